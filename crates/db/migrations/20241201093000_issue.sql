@@ -0,0 +1,52 @@
+-- migrate:up
+ALTER TABLE case_issue_relation
+    ADD COLUMN title VARCHAR,
+    ADD COLUMN status VARCHAR,
+    ADD COLUMN synced_at TIMESTAMP,
+    ADD COLUMN deleted_at TIMESTAMP,
+    ADD COLUMN deleted_by UUID;
+
+ALTER TABLE functional_case_execute_record
+    ADD COLUMN issue_relation_id INT;
+
+-- comments
+COMMENT ON COLUMN case_issue_relation.id IS '缺陷关联ID';
+
+COMMENT ON COLUMN case_issue_relation.case_id IS '关联用例ID';
+
+COMMENT ON COLUMN case_issue_relation.issue_id IS '缺陷平台中的缺陷编号';
+
+COMMENT ON COLUMN case_issue_relation.source IS '缺陷平台: JIRA/GITLAB/GITHUB';
+
+COMMENT ON COLUMN case_issue_relation.uri IS '缺陷链接';
+
+COMMENT ON COLUMN case_issue_relation.title IS '缺陷标题';
+
+COMMENT ON COLUMN case_issue_relation.status IS '缺陷状态';
+
+COMMENT ON COLUMN case_issue_relation.synced_at IS '最近同步时间';
+
+COMMENT ON COLUMN case_issue_relation.created_at IS '创建时间';
+
+COMMENT ON COLUMN case_issue_relation.created_by IS '创建人';
+
+COMMENT ON COLUMN case_issue_relation.updated_at IS '更新时间';
+
+COMMENT ON COLUMN case_issue_relation.updated_by IS '更新人';
+
+COMMENT ON COLUMN case_issue_relation.deleted_at IS '删除时间';
+
+COMMENT ON COLUMN case_issue_relation.deleted_by IS '删除人';
+
+COMMENT ON COLUMN functional_case_execute_record.issue_relation_id IS '由该执行记录创建的缺陷关联ID';
+
+-- migrate:down
+ALTER TABLE functional_case_execute_record
+    DROP COLUMN IF EXISTS issue_relation_id;
+
+ALTER TABLE case_issue_relation
+    DROP COLUMN IF EXISTS title,
+    DROP COLUMN IF EXISTS status,
+    DROP COLUMN IF EXISTS synced_at,
+    DROP COLUMN IF EXISTS deleted_at,
+    DROP COLUMN IF EXISTS deleted_by;
@@ -0,0 +1,91 @@
+-- migrate:up
+DROP TABLE IF EXISTS requirement;
+
+CREATE TABLE requirement (
+    id SERIAL PRIMARY KEY,
+    project_id INT NOT NULL,
+    name VARCHAR NOT NULL,
+    description VARCHAR,
+    source VARCHAR NOT NULL DEFAULT 'LOCAL',
+    external_id VARCHAR,
+    uri VARCHAR,
+    status VARCHAR,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    created_by UUID NOT NULL,
+    updated_at TIMESTAMP,
+    updated_by UUID,
+    deleted_at TIMESTAMP,
+    deleted_by UUID
+);
+
+-- create trigger: set updated_at field
+CREATE TRIGGER set_timestamp_requirement BEFORE
+UPDATE ON requirement FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp ();
+
+DROP TABLE IF EXISTS requirement_case_relation;
+
+CREATE TABLE requirement_case_relation (
+    id SERIAL PRIMARY KEY,
+    requirement_id INT NOT NULL,
+    case_id INT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    created_by UUID NOT NULL,
+    deleted_at TIMESTAMP,
+    deleted_by UUID
+);
+
+ALTER TABLE functional_case_execute_record
+    ADD COLUMN plan_id INT;
+
+-- comments
+COMMENT ON COLUMN requirement.id IS '需求ID';
+
+COMMENT ON COLUMN requirement.project_id IS '所属项目ID';
+
+COMMENT ON COLUMN requirement.name IS '需求名称';
+
+COMMENT ON COLUMN requirement.description IS '需求描述';
+
+COMMENT ON COLUMN requirement.source IS '需求来源: LOCAL/JIRA/GITLAB/GITHUB';
+
+COMMENT ON COLUMN requirement.external_id IS '外部平台需求编号';
+
+COMMENT ON COLUMN requirement.uri IS '外部平台需求链接';
+
+COMMENT ON COLUMN requirement.status IS '外部平台需求状态';
+
+COMMENT ON COLUMN requirement.created_at IS '创建时间';
+
+COMMENT ON COLUMN requirement.created_by IS '创建人';
+
+COMMENT ON COLUMN requirement.updated_at IS '更新时间';
+
+COMMENT ON COLUMN requirement.updated_by IS '更新人';
+
+COMMENT ON COLUMN requirement.deleted_at IS '删除时间';
+
+COMMENT ON COLUMN requirement.deleted_by IS '删除人';
+
+COMMENT ON COLUMN requirement_case_relation.id IS '需求-用例关联关系ID';
+
+COMMENT ON COLUMN requirement_case_relation.requirement_id IS '需求ID';
+
+COMMENT ON COLUMN requirement_case_relation.case_id IS '测试用例ID';
+
+COMMENT ON COLUMN requirement_case_relation.created_at IS '创建时间';
+
+COMMENT ON COLUMN requirement_case_relation.created_by IS '创建人';
+
+COMMENT ON COLUMN requirement_case_relation.deleted_at IS '删除时间';
+
+COMMENT ON COLUMN requirement_case_relation.deleted_by IS '删除人';
+
+COMMENT ON COLUMN functional_case_execute_record.plan_id IS '所属测试计划ID';
+
+-- migrate:down
+ALTER TABLE functional_case_execute_record
+    DROP COLUMN IF EXISTS plan_id;
+
+DROP TABLE IF EXISTS requirement_case_relation;
+
+DROP TABLE IF EXISTS requirement;
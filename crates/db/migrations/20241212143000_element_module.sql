@@ -0,0 +1,8 @@
+-- migrate:up
+-- selector value is unique within a module among alive elements
+CREATE UNIQUE INDEX elements_module_value_unique
+    ON elements (module_id, value)
+    WHERE deleted = FALSE;
+
+-- migrate:down
+DROP INDEX IF EXISTS elements_module_value_unique;
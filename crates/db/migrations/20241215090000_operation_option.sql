@@ -0,0 +1,96 @@
+-- migrate:up
+ALTER TABLE operation_option
+    ADD COLUMN template VARCHAR,
+    ADD COLUMN updated_at TIMESTAMP,
+    ADD COLUMN updated_by UUID;
+
+CREATE UNIQUE INDEX operation_option_exec_unique
+    ON operation_option (exec);
+
+-- create trigger: set updated_at field
+CREATE TRIGGER set_timestamp_operation_option BEFORE
+UPDATE ON operation_option FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp ();
+
+DROP TABLE IF EXISTS element_type_operation_option;
+
+CREATE TABLE element_type_operation_option (
+    id SERIAL PRIMARY KEY,
+    element_type VARCHAR NOT NULL,
+    option_id INT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    created_by UUID NOT NULL,
+    UNIQUE (element_type, option_id)
+);
+
+-- init internal operation options rendered by base.cy.js
+INSERT INTO
+    operation_option (name, internal, exec, created_by)
+SELECT
+    o.name,
+    TRUE,
+    o.exec,
+    (
+        SELECT
+            uuid
+        FROM
+            users
+        WHERE
+            username = '__system__'
+    )
+FROM
+    (
+        VALUES
+            ('点击', 'CLICK'),
+            ('输入', 'TYPE'),
+            ('访问', 'VISIT'),
+            ('清空', 'CLEAR'),
+            ('勾选', 'CHECK'),
+            ('取消勾选', 'UNCHECK'),
+            ('双击', 'DBLCLICK'),
+            ('右击', 'RCLICK'),
+            ('选择', 'SELECT'),
+            ('请求', 'REQUEST')
+    ) AS o (name, exec)
+WHERE
+    NOT EXISTS (
+        SELECT
+            1
+        FROM
+            operation_option
+        WHERE
+            exec = o.exec
+    );
+
+-- comments
+COMMENT ON COLUMN operation_option.internal IS '是否内置操作';
+
+COMMENT ON COLUMN operation_option.template IS '自定义操作渲染的脚本模板片段';
+
+COMMENT ON COLUMN operation_option.updated_at IS '更新时间';
+
+COMMENT ON COLUMN operation_option.updated_by IS '更新人';
+
+COMMENT ON COLUMN element_type_operation_option.id IS '元素类型可选操作关联关系ID';
+
+COMMENT ON COLUMN element_type_operation_option.element_type IS '元素类型';
+
+COMMENT ON COLUMN element_type_operation_option.option_id IS '可选操作ID';
+
+COMMENT ON COLUMN element_type_operation_option.created_at IS '创建时间';
+
+COMMENT ON COLUMN element_type_operation_option.created_by IS '创建人';
+
+-- migrate:down
+DROP TABLE IF EXISTS element_type_operation_option;
+
+DELETE FROM operation_option
+WHERE internal = TRUE;
+
+DROP TRIGGER IF EXISTS set_timestamp_operation_option ON operation_option;
+
+DROP INDEX IF EXISTS operation_option_exec_unique;
+
+ALTER TABLE operation_option
+    DROP COLUMN IF EXISTS template,
+    DROP COLUMN IF EXISTS updated_at,
+    DROP COLUMN IF EXISTS updated_by;
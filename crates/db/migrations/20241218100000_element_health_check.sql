@@ -0,0 +1,67 @@
+-- migrate:up
+DROP TABLE IF EXISTS element_health_check;
+
+CREATE TABLE element_health_check (
+    id SERIAL PRIMARY KEY,
+    module_id INT NOT NULL,
+    page_url VARCHAR NOT NULL,
+    machine_id INT,
+    status VARCHAR NOT NULL DEFAULT 'RUNNING',
+    error VARCHAR,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    created_by UUID NOT NULL,
+    finished_at TIMESTAMP
+);
+
+DROP TABLE IF EXISTS element_health_check_result;
+
+CREATE TABLE element_health_check_result (
+    id SERIAL PRIMARY KEY,
+    check_id INT NOT NULL,
+    element_id INT NOT NULL,
+    selector VARCHAR NOT NULL,
+    status VARCHAR NOT NULL,
+    match_count INT NOT NULL DEFAULT 0,
+    checked_at TIMESTAMP NOT NULL DEFAULT NOW ()
+);
+
+CREATE INDEX element_health_check_result_element_idx
+    ON element_health_check_result (element_id, checked_at DESC);
+
+-- comments
+COMMENT ON COLUMN element_health_check.id IS '元素健康检查ID';
+
+COMMENT ON COLUMN element_health_check.module_id IS '检查的元素模块ID';
+
+COMMENT ON COLUMN element_health_check.page_url IS '检查的页面地址';
+
+COMMENT ON COLUMN element_health_check.machine_id IS '执行检查的机器ID, 为空时由服务本地加载页面';
+
+COMMENT ON COLUMN element_health_check.status IS '检查状态: RUNNING/FINISHED/FAILED';
+
+COMMENT ON COLUMN element_health_check.error IS '检查失败原因';
+
+COMMENT ON COLUMN element_health_check.created_at IS '创建时间';
+
+COMMENT ON COLUMN element_health_check.created_by IS '创建人';
+
+COMMENT ON COLUMN element_health_check.finished_at IS '完成时间';
+
+COMMENT ON COLUMN element_health_check_result.id IS '元素检查结果ID';
+
+COMMENT ON COLUMN element_health_check_result.check_id IS '所属健康检查ID';
+
+COMMENT ON COLUMN element_health_check_result.element_id IS '元素ID';
+
+COMMENT ON COLUMN element_health_check_result.selector IS '检查时的元素选择器';
+
+COMMENT ON COLUMN element_health_check_result.status IS '检查结果: FOUND/MISSING/AMBIGUOUS/INVALID';
+
+COMMENT ON COLUMN element_health_check_result.match_count IS '页面中匹配的节点数量';
+
+COMMENT ON COLUMN element_health_check_result.checked_at IS '检查时间';
+
+-- migrate:down
+DROP TABLE IF EXISTS element_health_check_result;
+
+DROP TABLE IF EXISTS element_health_check;
@@ -0,0 +1,109 @@
+-- migrate:up
+DROP TABLE IF EXISTS plan_schedule;
+
+CREATE TABLE plan_schedule (
+    id SERIAL PRIMARY KEY,
+    plan_id INT NOT NULL,
+    script_id INT,
+    cron VARCHAR NOT NULL,
+    machine_id INT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    last_fired_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    created_by UUID NOT NULL,
+    updated_at TIMESTAMP,
+    updated_by UUID,
+    deleted_at TIMESTAMP,
+    deleted_by UUID
+);
+
+-- one alive schedule per plan
+CREATE UNIQUE INDEX plan_schedule_plan_unique
+    ON plan_schedule (plan_id)
+    WHERE deleted_at IS NULL;
+
+-- create trigger: set updated_at field
+CREATE TRIGGER set_timestamp_plan_schedule BEFORE
+UPDATE ON plan_schedule FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp ();
+
+DROP TABLE IF EXISTS plan_run;
+
+CREATE TABLE plan_run (
+    id SERIAL PRIMARY KEY,
+    plan_id INT NOT NULL,
+    schedule_id INT,
+    script_id INT,
+    trigger_type VARCHAR NOT NULL,
+    status VARCHAR NOT NULL DEFAULT 'RUNNING',
+    total INT NOT NULL DEFAULT 0,
+    passed INT NOT NULL DEFAULT 0,
+    failed INT NOT NULL DEFAULT 0,
+    error VARCHAR,
+    started_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    finished_at TIMESTAMP,
+    duration_ms BIGINT,
+    created_by UUID NOT NULL
+);
+
+CREATE INDEX plan_run_plan_idx
+    ON plan_run (plan_id, started_at DESC);
+
+-- comments
+COMMENT ON COLUMN plan_schedule.id IS '测试计划定时任务ID';
+
+COMMENT ON COLUMN plan_schedule.plan_id IS '测试计划ID';
+
+COMMENT ON COLUMN plan_schedule.script_id IS '仅执行的脚本ID, 为空时执行计划下全部用例脚本';
+
+COMMENT ON COLUMN plan_schedule.cron IS 'cron表达式(含秒)';
+
+COMMENT ON COLUMN plan_schedule.machine_id IS '执行机器ID';
+
+COMMENT ON COLUMN plan_schedule.enabled IS '是否启用';
+
+COMMENT ON COLUMN plan_schedule.last_fired_at IS '最近一次触发的调度时间点';
+
+COMMENT ON COLUMN plan_schedule.created_at IS '创建时间';
+
+COMMENT ON COLUMN plan_schedule.created_by IS '创建人';
+
+COMMENT ON COLUMN plan_schedule.updated_at IS '更新时间';
+
+COMMENT ON COLUMN plan_schedule.updated_by IS '更新人';
+
+COMMENT ON COLUMN plan_schedule.deleted_at IS '删除时间';
+
+COMMENT ON COLUMN plan_schedule.deleted_by IS '删除人';
+
+COMMENT ON COLUMN plan_run.id IS '测试计划执行记录ID';
+
+COMMENT ON COLUMN plan_run.plan_id IS '测试计划ID';
+
+COMMENT ON COLUMN plan_run.schedule_id IS '触发执行的定时任务ID';
+
+COMMENT ON COLUMN plan_run.script_id IS '仅执行的脚本ID';
+
+COMMENT ON COLUMN plan_run.trigger_type IS '触发方式: SCHEDULE/MANUAL';
+
+COMMENT ON COLUMN plan_run.status IS '执行状态: RUNNING/PASSED/FAILED/ERROR';
+
+COMMENT ON COLUMN plan_run.total IS '执行脚本数';
+
+COMMENT ON COLUMN plan_run.passed IS '通过脚本数';
+
+COMMENT ON COLUMN plan_run.failed IS '失败脚本数';
+
+COMMENT ON COLUMN plan_run.error IS '执行异常信息';
+
+COMMENT ON COLUMN plan_run.started_at IS '开始时间';
+
+COMMENT ON COLUMN plan_run.finished_at IS '结束时间';
+
+COMMENT ON COLUMN plan_run.duration_ms IS '执行耗时(毫秒)';
+
+COMMENT ON COLUMN plan_run.created_by IS '执行人';
+
+-- migrate:down
+DROP TABLE IF EXISTS plan_run;
+
+DROP TABLE IF EXISTS plan_schedule;
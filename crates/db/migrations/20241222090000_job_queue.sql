@@ -0,0 +1,69 @@
+-- migrate:up
+DROP TABLE IF EXISTS job;
+
+CREATE TABLE job (
+    id SERIAL PRIMARY KEY,
+    kind VARCHAR NOT NULL,
+    payload VARCHAR NOT NULL,
+    status VARCHAR NOT NULL DEFAULT 'PENDING',
+    progress INT NOT NULL DEFAULT 0,
+    result VARCHAR,
+    error VARCHAR,
+    attempts INT NOT NULL DEFAULT 0,
+    max_attempts INT NOT NULL DEFAULT 3,
+    run_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    locked_at TIMESTAMP,
+    locked_by VARCHAR,
+    cancel_requested BOOLEAN NOT NULL DEFAULT FALSE,
+    finished_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    created_by UUID NOT NULL,
+    updated_at TIMESTAMP
+);
+
+-- workers only look for due pending jobs
+CREATE INDEX job_pending_idx
+    ON job (run_at, id)
+    WHERE status = 'PENDING';
+
+-- create trigger: set updated_at field
+CREATE TRIGGER set_timestamp_job BEFORE
+UPDATE ON job FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp ();
+
+-- comments
+COMMENT ON COLUMN job.id IS '后台任务ID';
+
+COMMENT ON COLUMN job.kind IS '任务类型: GENERATE_SCRIPT/DIAGNOSE';
+
+COMMENT ON COLUMN job.payload IS '任务参数(JSON)';
+
+COMMENT ON COLUMN job.status IS '任务状态: PENDING/RUNNING/SUCCEEDED/FAILED/CANCELLED';
+
+COMMENT ON COLUMN job.progress IS '任务进度(0-100)';
+
+COMMENT ON COLUMN job.result IS '任务结果(JSON)';
+
+COMMENT ON COLUMN job.error IS '最近一次失败原因';
+
+COMMENT ON COLUMN job.attempts IS '已执行次数';
+
+COMMENT ON COLUMN job.max_attempts IS '最大执行次数';
+
+COMMENT ON COLUMN job.run_at IS '最早执行时间, 重试时按退避策略后移';
+
+COMMENT ON COLUMN job.locked_at IS '被领取时间';
+
+COMMENT ON COLUMN job.locked_by IS '领取任务的工作者';
+
+COMMENT ON COLUMN job.cancel_requested IS '是否已请求取消';
+
+COMMENT ON COLUMN job.finished_at IS '结束时间';
+
+COMMENT ON COLUMN job.created_at IS '创建时间';
+
+COMMENT ON COLUMN job.created_by IS '创建人';
+
+COMMENT ON COLUMN job.updated_at IS '更新时间';
+
+-- migrate:down
+DROP TABLE IF EXISTS job;
@@ -0,0 +1,67 @@
+-- migrate:up
+-- every project gets its own copy of the internal template and fields
+ALTER TABLE field DROP CONSTRAINT IF EXISTS field_name_key;
+
+ALTER TABLE field ADD CONSTRAINT field_project_name_key UNIQUE (project_id, name);
+
+ALTER TABLE template DROP CONSTRAINT IF EXISTS template_name_key;
+
+ALTER TABLE template ADD CONSTRAINT template_project_name_key UNIQUE (project_id, name);
+
+-- the creator of a project becomes its admin
+INSERT INTO
+    user_role (name, type, internal, description, created_by)
+SELECT
+    'admin',
+    'PROJECT',
+    false,
+    '项目管理员, 拥有项目内全部操作权限',
+    (
+        SELECT
+            uuid
+        FROM
+            users
+        WHERE
+            username = '__system__'
+    )
+WHERE
+    NOT EXISTS (
+        SELECT
+            1
+        FROM
+            user_role
+        WHERE
+            name = 'admin'
+    );
+
+INSERT INTO
+    role_permission_relation (role_id, permission_id)
+SELECT
+    ur.id,
+    p.id
+FROM
+    user_role ur
+    CROSS JOIN permission p
+WHERE
+    ur.name = 'admin'
+    AND p.module LIKE 'MANAGEMENT:%'
+    AND NOT EXISTS (
+        SELECT
+            1
+        FROM
+            role_permission_relation rpr
+        WHERE
+            rpr.role_id = ur.id
+            AND rpr.permission_id = p.id
+    );
+
+COMMENT ON COLUMN projects.enable IS '是否启用, 归档的项目为只读';
+
+-- migrate:down
+ALTER TABLE template DROP CONSTRAINT IF EXISTS template_project_name_key;
+
+ALTER TABLE template ADD CONSTRAINT template_name_key UNIQUE (name);
+
+ALTER TABLE field DROP CONSTRAINT IF EXISTS field_project_name_key;
+
+ALTER TABLE field ADD CONSTRAINT field_name_key UNIQUE (name);
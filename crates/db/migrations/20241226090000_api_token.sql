@@ -0,0 +1,46 @@
+-- migrate:up
+DROP TABLE IF EXISTS api_token;
+
+CREATE TABLE api_token (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL,
+    token_hash VARCHAR NOT NULL UNIQUE,
+    token_prefix VARCHAR NOT NULL,
+    user_id UUID NOT NULL,
+    project_id INT,
+    permissions INT[] NOT NULL,
+    expires_at TIMESTAMP,
+    last_used_at TIMESTAMP,
+    revoked_at TIMESTAMP,
+    revoked_by UUID,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW ()
+);
+
+CREATE INDEX api_token_user_idx ON api_token (user_id);
+
+COMMENT ON COLUMN api_token.id IS 'API令牌ID';
+
+COMMENT ON COLUMN api_token.name IS '令牌名称';
+
+COMMENT ON COLUMN api_token.token_hash IS '令牌的SHA-256摘要, 明文只在创建时返回一次';
+
+COMMENT ON COLUMN api_token.token_prefix IS '令牌前缀, 用于列表中辨认令牌';
+
+COMMENT ON COLUMN api_token.user_id IS '令牌所属用户';
+
+COMMENT ON COLUMN api_token.project_id IS '限定的项目ID, 为空时为个人令牌, 可用于用户所在的全部项目';
+
+COMMENT ON COLUMN api_token.permissions IS '令牌可使用的权限ID';
+
+COMMENT ON COLUMN api_token.expires_at IS '过期时间, 为空时永不过期';
+
+COMMENT ON COLUMN api_token.last_used_at IS '最近使用时间';
+
+COMMENT ON COLUMN api_token.revoked_at IS '吊销时间';
+
+COMMENT ON COLUMN api_token.revoked_by IS '吊销人';
+
+COMMENT ON COLUMN api_token.created_at IS '创建时间';
+
+-- migrate:down
+DROP TABLE IF EXISTS api_token;
@@ -0,0 +1,16 @@
+-- migrate:up
+ALTER TABLE users
+ADD COLUMN password_change_required BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN users.password_change_required IS '是否需要修改密码, 邀请未接受或密码曾以明文邮件发送';
+
+-- passwords of existing users were mailed in plaintext
+UPDATE users
+SET
+    password_change_required = TRUE
+WHERE
+    username != '__system__';
+
+-- migrate:down
+ALTER TABLE users
+DROP COLUMN password_change_required;
@@ -0,0 +1,31 @@
+-- migrate:up
+DROP TABLE IF EXISTS audit_log;
+
+CREATE TABLE audit_log (
+    id SERIAL PRIMARY KEY,
+    action VARCHAR NOT NULL,
+    target VARCHAR NOT NULL,
+    detail VARCHAR,
+    ip VARCHAR,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    created_by UUID
+);
+
+CREATE INDEX audit_log_action_idx ON audit_log (action, created_at);
+
+COMMENT ON COLUMN audit_log.id IS '审计日志ID';
+
+COMMENT ON COLUMN audit_log.action IS '操作类型: LOGIN_LOCKOUT/LOGIN_UNLOCK';
+
+COMMENT ON COLUMN audit_log.target IS '操作对象, 如用户名或IP';
+
+COMMENT ON COLUMN audit_log.detail IS '详细信息';
+
+COMMENT ON COLUMN audit_log.ip IS '请求来源IP';
+
+COMMENT ON COLUMN audit_log.created_at IS '创建时间';
+
+COMMENT ON COLUMN audit_log.created_by IS '操作人, 系统触发时为空';
+
+-- migrate:down
+DROP TABLE IF EXISTS audit_log;
@@ -0,0 +1,35 @@
+-- migrate:up
+DROP TABLE IF EXISTS user_mfa;
+
+CREATE TABLE user_mfa (
+    user_id UUID PRIMARY KEY,
+    secret VARCHAR NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    last_step BIGINT NOT NULL DEFAULT 0,
+    recovery_codes VARCHAR[] NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    enabled_at TIMESTAMP
+);
+
+ALTER TABLE user_role ADD COLUMN IF NOT EXISTS mfa_required BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN user_mfa.user_id IS '用户ID';
+
+COMMENT ON COLUMN user_mfa.secret IS 'TOTP密钥, 十六进制';
+
+COMMENT ON COLUMN user_mfa.enabled IS '是否已启用, 首次校验验证码后启用';
+
+COMMENT ON COLUMN user_mfa.last_step IS '最近一次使用的TOTP时间步, 防止验证码重放';
+
+COMMENT ON COLUMN user_mfa.recovery_codes IS '未使用的恢复码的SHA-256摘要';
+
+COMMENT ON COLUMN user_mfa.created_at IS '创建时间';
+
+COMMENT ON COLUMN user_mfa.enabled_at IS '启用时间';
+
+COMMENT ON COLUMN user_role.mfa_required IS '拥有该角色的用户是否必须启用两步验证';
+
+-- migrate:down
+ALTER TABLE user_role DROP COLUMN IF EXISTS mfa_required;
+
+DROP TABLE IF EXISTS user_mfa;
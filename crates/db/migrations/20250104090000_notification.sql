@@ -0,0 +1,57 @@
+-- migrate:up
+DROP TABLE IF EXISTS notification_preference;
+
+DROP TABLE IF EXISTS notification_channel;
+
+CREATE TABLE notification_channel (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL,
+    name VARCHAR NOT NULL,
+    channel_type VARCHAR NOT NULL,
+    url VARCHAR NOT NULL,
+    secret VARCHAR,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    deleted_at TIMESTAMP
+);
+
+CREATE INDEX notification_channel_user_idx ON notification_channel (user_id);
+
+CREATE TABLE notification_preference (
+    user_id UUID NOT NULL,
+    event_type VARCHAR NOT NULL,
+    email BOOLEAN NOT NULL DEFAULT TRUE,
+    channel_ids INT[] NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    PRIMARY KEY (user_id, event_type)
+);
+
+COMMENT ON COLUMN notification_channel.id IS '通知渠道ID';
+
+COMMENT ON COLUMN notification_channel.user_id IS '渠道所属用户';
+
+COMMENT ON COLUMN notification_channel.name IS '渠道名称';
+
+COMMENT ON COLUMN notification_channel.channel_type IS '渠道类型: WEBHOOK/SLACK/DINGTALK/FEISHU';
+
+COMMENT ON COLUMN notification_channel.url IS '接收通知的webhook地址';
+
+COMMENT ON COLUMN notification_channel.secret IS '签名密钥, 为空时不签名';
+
+COMMENT ON COLUMN notification_channel.created_at IS '创建时间';
+
+COMMENT ON COLUMN notification_channel.deleted_at IS '删除时间';
+
+COMMENT ON COLUMN notification_preference.user_id IS '用户ID';
+
+COMMENT ON COLUMN notification_preference.event_type IS '事件类型: CASE_REVIEW_ASSIGNED/PLAN_RUN_FINISHED/EXECUTION_FAILED/COMMENT_MENTION';
+
+COMMENT ON COLUMN notification_preference.email IS '是否发送邮件, 没有记录时默认发送';
+
+COMMENT ON COLUMN notification_preference.channel_ids IS '同时推送的通知渠道ID';
+
+COMMENT ON COLUMN notification_preference.updated_at IS '更新时间';
+
+-- migrate:down
+DROP TABLE IF EXISTS notification_preference;
+
+DROP TABLE IF EXISTS notification_channel;
@@ -0,0 +1,29 @@
+-- migrate:up
+DROP TABLE IF EXISTS sso_identity;
+
+CREATE TABLE sso_identity (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL,
+    provider VARCHAR NOT NULL,
+    issuer VARCHAR NOT NULL,
+    subject VARCHAR NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW (),
+    UNIQUE (provider, issuer, subject)
+);
+
+CREATE INDEX sso_identity_user_idx ON sso_identity (user_id);
+
+COMMENT ON COLUMN sso_identity.id IS '单点登录身份ID';
+
+COMMENT ON COLUMN sso_identity.user_id IS '关联的用户';
+
+COMMENT ON COLUMN sso_identity.provider IS '身份来源, OIDC/LDAP';
+
+COMMENT ON COLUMN sso_identity.issuer IS 'OIDC为签发者地址, LDAP为服务器地址';
+
+COMMENT ON COLUMN sso_identity.subject IS 'OIDC为sub声明, LDAP为用户条目的DN';
+
+COMMENT ON COLUMN sso_identity.created_at IS '关联时间';
+
+-- migrate:down
+DROP TABLE IF EXISTS sso_identity;
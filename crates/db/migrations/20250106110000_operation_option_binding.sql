@@ -0,0 +1,52 @@
+-- migrate:up
+-- bind the internal operation options to every element type in use, steps of existing scripts stay allowed
+INSERT INTO
+    element_type_operation_option (element_type, option_id, created_by)
+SELECT
+    t.element_type,
+    oo.id,
+    (
+        SELECT
+            uuid
+        FROM
+            users
+        WHERE
+            username = '__system__'
+    )
+FROM
+    (
+        SELECT DISTINCT
+            type AS element_type
+        FROM
+            elements
+        WHERE
+            type IS NOT NULL
+    ) AS t
+    CROSS JOIN operation_option oo
+WHERE
+    oo.internal = TRUE
+ON CONFLICT (element_type, option_id) DO NOTHING;
+
+-- custom options already used by an element stay allowed for its type
+INSERT INTO
+    element_type_operation_option (element_type, option_id, created_by)
+SELECT DISTINCT
+    e.type,
+    eoo.option_id,
+    (
+        SELECT
+            uuid
+        FROM
+            users
+        WHERE
+            username = '__system__'
+    )
+FROM
+    element_operation_option eoo
+    INNER JOIN elements e ON e.id = eoo.element_id
+WHERE
+    e.type IS NOT NULL
+ON CONFLICT (element_type, option_id) DO NOTHING;
+
+-- migrate:down
+-- the seeded bindings can't be told apart from the ones made later, they are kept
@@ -0,0 +1,10 @@
+-- migrate:up
+ALTER TABLE plan_run
+    ADD COLUMN job_id INT;
+
+-- comments
+COMMENT ON COLUMN plan_run.job_id IS '执行任务ID, 任务结束而执行仍为RUNNING时置为ERROR';
+
+-- migrate:down
+ALTER TABLE plan_run
+    DROP COLUMN IF EXISTS job_id;
@@ -0,0 +1,14 @@
+-- migrate:up
+ALTER TABLE projects
+    DROP CONSTRAINT IF EXISTS projects_name_key;
+
+-- the name of a deleted project can be reused
+CREATE UNIQUE INDEX projects_name_unique
+    ON projects (name)
+    WHERE deleted_at IS NULL;
+
+-- migrate:down
+DROP INDEX IF EXISTS projects_name_unique;
+
+ALTER TABLE projects
+    ADD CONSTRAINT projects_name_key UNIQUE (name);
@@ -0,0 +1,10 @@
+-- migrate:up
+ALTER TABLE functional_case_execute_record
+    ADD COLUMN reporting_at TIMESTAMP;
+
+-- comments
+COMMENT ON COLUMN functional_case_execute_record.reporting_at IS '缺陷提交中的时间, 提交完成或失败后清空, 超时后可重新提交';
+
+-- migrate:down
+ALTER TABLE functional_case_execute_record
+    DROP COLUMN IF EXISTS reporting_at;
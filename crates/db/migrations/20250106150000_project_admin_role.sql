@@ -0,0 +1,18 @@
+-- migrate:up
+-- the project admin role is looked up by name, it can neither be renamed nor deleted
+UPDATE user_role
+SET
+    internal = TRUE,
+    deleted_at = NULL,
+    deleted_by = NULL
+WHERE
+    name = 'admin'
+    AND type = 'PROJECT';
+
+-- migrate:down
+UPDATE user_role
+SET
+    internal = FALSE
+WHERE
+    name = 'admin'
+    AND type = 'PROJECT';
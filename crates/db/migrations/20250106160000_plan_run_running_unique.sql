@@ -0,0 +1,27 @@
+-- migrate:up
+-- runs started twice by a race, only the latest one is kept running
+UPDATE plan_run pr
+SET
+    status = 'ERROR',
+    error = 'duplicate run',
+    finished_at = NOW()
+WHERE
+    pr.status = 'RUNNING'
+    AND EXISTS (
+        SELECT
+            1
+        FROM
+            plan_run o
+        WHERE
+            o.plan_id = pr.plan_id
+            AND o.status = 'RUNNING'
+            AND o.id > pr.id
+    );
+
+-- one run in progress per plan, across replicas
+CREATE UNIQUE INDEX plan_run_running_unique
+    ON plan_run (plan_id)
+    WHERE status = 'RUNNING';
+
+-- migrate:down
+DROP INDEX IF EXISTS plan_run_running_unique;
@@ -0,0 +1,36 @@
+--! insert_api_token (project_id?, expires_at?)
+INSERT INTO api_token
+(name, token_hash, token_prefix, user_id, project_id, permissions, expires_at)
+VALUES (:name, :token_hash, :token_prefix, :user_id, :project_id, :permissions, :expires_at)
+RETURNING id;
+
+--! get_api_tokens_by_user_id : (project_id?, expires_at?, last_used_at?, revoked_at?)
+SELECT id,
+       name,
+       token_prefix,
+       project_id,
+       permissions,
+       expires_at,
+       last_used_at,
+       revoked_at,
+       created_at
+FROM api_token
+WHERE user_id = :user_id
+ORDER BY id DESC;
+
+--! revoke_api_token
+UPDATE api_token
+SET revoked_at = NOW(),
+    revoked_by = :revoked_by
+WHERE id = :id
+  AND user_id = :user_id
+  AND revoked_at IS NULL
+RETURNING id;
+
+--! use_api_token : (project_id?, expires_at?)
+UPDATE api_token
+SET last_used_at = NOW()
+WHERE token_hash = :token_hash
+  AND revoked_at IS NULL
+  AND (expires_at IS NULL OR expires_at > NOW())
+RETURNING id, user_id, project_id, permissions, expires_at;
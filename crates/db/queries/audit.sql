@@ -0,0 +1,5 @@
+--! insert_audit_log (detail?, ip?, created_by?)
+INSERT INTO audit_log
+(action, target, detail, ip, created_by)
+VALUES (:action, :target, :detail, :ip, :created_by)
+RETURNING id;
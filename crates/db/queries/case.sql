@@ -27,24 +27,27 @@ INSERT INTO functional_case_field_relation (
     :created_by
 ) RETURNING id;
 
---! insert_case_execute_record
+--! insert_case_execute_record (attach_info?, plan_id?)
 INSERT INTO functional_case_execute_record (
     case_id,
     result,
     attach_info,
+    plan_id,
     created_by
 ) VALUES (
     :case_id,
     :result,
     :attach_info,
+    :plan_id,
     :created_by
 ) RETURNING id;
 
---! get_last_execute_record_by_case_id : (updated_at?, updated_by?)
+--! get_last_execute_record_by_case_id : (issue_relation_id?, updated_at?, updated_by?)
 SELECT  fcer.id,
         fcer.case_id,
         fcer.result,
         fcer.attach_info,
+        fcer.issue_relation_id,
         fcer.created_at,
         uc.username AS created_by,
         fcer.updated_at,
@@ -56,20 +59,126 @@ WHERE case_id = :case_id
 ORDER BY fcer.created_at
 DESC LIMIT 1;
 
---! insert_case_issue_relation
+--! insert_case_issue_relation (title?, status?)
 INSERT INTO case_issue_relation (
     case_id,
     issue_id,
     source,
     uri,
+    title,
+    status,
+    synced_at,
     created_by
 ) VALUES (
     :case_id,
-    :field_id,
+    :issue_id,
     :source,
     :uri,
+    :title,
+    :status,
+    NOW(),
     :created_by
-);
+) RETURNING id;
+
+--! get_issue_relations_by_case_id : (title?, status?, synced_at?, updated_at?, updated_by?)
+SELECT
+    cir.id,
+    cir.case_id,
+    cir.issue_id,
+    cir.source,
+    cir.uri,
+    cir.title,
+    cir.status,
+    cir.synced_at,
+    cir.created_at,
+    uc.username AS created_by,
+    cir.updated_at,
+    uu.username AS updated_by
+FROM case_issue_relation cir
+LEFT JOIN users uc ON uc.uuid = cir.created_by
+LEFT JOIN users uu ON uu.uuid = cir.updated_by
+WHERE cir.case_id = :case_id
+AND cir.deleted_at IS NULL AND cir.deleted_by IS NULL
+ORDER BY cir.id;
+
+--! get_issue_relation_by_id : (title?, status?, synced_at?, updated_at?, updated_by?)
+SELECT
+    cir.id,
+    cir.case_id,
+    cir.issue_id,
+    cir.source,
+    cir.uri,
+    cir.title,
+    cir.status,
+    cir.synced_at,
+    cir.created_at,
+    uc.username AS created_by,
+    cir.updated_at,
+    uu.username AS updated_by
+FROM case_issue_relation cir
+LEFT JOIN users uc ON uc.uuid = cir.created_by
+LEFT JOIN users uu ON uu.uuid = cir.updated_by
+WHERE cir.id = :id
+AND cir.deleted_at IS NULL AND cir.deleted_by IS NULL;
+
+--! get_issue_relation_by_case_and_issue
+SELECT id
+FROM case_issue_relation
+WHERE case_id = :case_id
+AND issue_id = :issue_id
+AND source = :source
+AND deleted_at IS NULL AND deleted_by IS NULL;
+
+--! sync_issue_relation (title?, status?)
+UPDATE case_issue_relation
+SET title = :title,
+    status = :status,
+    synced_at = NOW()
+WHERE id = :id;
+
+--! soft_delete_issue_relation
+UPDATE case_issue_relation
+SET deleted_at = NOW(),
+    deleted_by = :deleted_by,
+    updated_by = :deleted_by
+WHERE id = :id;
+
+--! get_execute_record_by_id : (attach_info?, issue_relation_id?, updated_at?, updated_by?)
+SELECT  fcer.id,
+        fcer.case_id,
+        fcer.result,
+        fcer.attach_info,
+        fcer.issue_relation_id,
+        fcer.created_at,
+        uc.username AS created_by,
+        fcer.updated_at,
+        uu.username AS updated_by
+FROM functional_case_execute_record fcer
+LEFT JOIN users uc ON uc.uuid = fcer.created_by
+LEFT JOIN users uu ON uu.uuid = fcer.updated_by
+WHERE fcer.id = :id
+AND fcer.deleted_at IS NULL AND fcer.deleted_by IS NULL;
+
+--! claim_execute_record_report
+UPDATE functional_case_execute_record
+SET reporting_at = NOW()
+WHERE id = :id
+AND issue_relation_id IS NULL
+AND (reporting_at IS NULL OR reporting_at < NOW() - INTERVAL '10 minutes')
+AND deleted_at IS NULL AND deleted_by IS NULL
+RETURNING id;
+
+--! release_execute_record_report
+UPDATE functional_case_execute_record
+SET reporting_at = NULL
+WHERE id = :id;
+
+--! bind_execute_record_issue
+UPDATE functional_case_execute_record
+SET issue_relation_id = :issue_relation_id,
+    reporting_at = NULL,
+    updated_by = :updated_by
+WHERE id = :id;
 
 --! get_functional_case_list : (updated_at?, updated_by?, attach_info?)
 SELECT fc.id,
@@ -101,9 +210,13 @@ ORDER BY fc.id
 LIMIT :page_size;
 
 --! get_query_cursor
-SELECT id
-FROM functional_cases
-ORDER BY id
+SELECT fc.id
+FROM functional_cases fc
+WHERE
+fc.module_id = ANY(:module_id)
+AND ((:deleted AND fc.deleted_at IS NOT NULL AND fc.deleted_by IS NOT NULL)
+    OR (NOT :deleted AND fc.deleted_at IS NULL AND fc.deleted_by IS NULL))
+ORDER BY fc.id
 LIMIT 1 OFFSET :offset;
 
 --! count
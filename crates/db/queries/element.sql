@@ -1,7 +1,7 @@
 --! insert (description?)
 INSERT INTO elements
-(name, value, type, description, created_by)
-VALUES(:name, :value, :type, :description, :created_by)
+(name, module_id, value, type, description, created_by)
+VALUES(:name, :module_id, :value, :type, :description, :created_by)
 RETURNING id;
 
 --! update (description?) :
@@ -15,24 +15,128 @@ SET
 WHERE
     id = :id;
 
---! get_query_cursor
+--! update_module
+UPDATE elements
+SET
+    module_id = :module_id,
+    updated_by = :updated_by
+WHERE
+    id = :id;
+
+--! soft_delete
+UPDATE elements
+SET
+    deleted = TRUE,
+    deleted_at = NOW(),
+    deleted_by = :deleted_by,
+    updated_by = :deleted_by
+WHERE
+    id = :id;
+
+--! restore
+UPDATE elements
+SET
+    deleted = FALSE,
+    deleted_at = NULL,
+    deleted_by = NULL,
+    updated_by = :updated_by
+WHERE
+    id = :id;
+
+--! get_element_by_id : (description?, updated_at?, updated_by?, health_status?, health_checked_at?)
+SELECT
+    e.id,
+    e.name,
+    e.module_id,
+    fm.name AS module_name,
+    e.value,
+    e.description,
+    e.type AS element_type,
+    e.deleted,
+    e.created_at,
+    (SELECT username FROM users WHERE users.uuid = e.created_by) AS created_by,
+    e.updated_at,
+    (SELECT username FROM users WHERE users.uuid = e.updated_by) AS updated_by,
+    hr.status AS health_status,
+    hr.checked_at AS health_checked_at
+FROM elements e
+INNER JOIN file_module fm ON fm.id = e.module_id
+LEFT JOIN LATERAL (
+    SELECT ehcr.status, ehcr.checked_at
+    FROM element_health_check_result ehcr
+    WHERE ehcr.element_id = e.id
+    ORDER BY ehcr.checked_at DESC
+    LIMIT 1
+) hr ON TRUE
+WHERE e.id = :id;
+
+--! get_element_by_module_and_value
 SELECT id
 FROM elements
-ORDER BY id
+WHERE module_id = :module_id
+AND value = :value
+AND deleted = FALSE;
+
+--! get_referenced_scripts
+SELECT DISTINCT
+    s.id,
+    s.case_id,
+    fc.name AS case_name,
+    s.path,
+    s.environment,
+    s.created_at
+FROM script_element_relation ser
+INNER JOIN element_operation_option eoo ON eoo.id = ser.element_operation_id
+INNER JOIN script s ON s.id = ser.script_id
+INNER JOIN functional_cases fc ON fc.id = s.case_id
+WHERE eoo.element_id = :element_id
+AND fc.deleted_at IS NULL
+ORDER BY s.id;
+
+--! detach_element_scripts
+DELETE FROM script_element_relation
+WHERE element_operation_id IN (
+    SELECT eoo.id
+    FROM element_operation_option eoo
+    WHERE eoo.element_id = :element_id
+);
+
+--! get_query_cursor
+SELECT e.id
+FROM elements e
+WHERE e.module_id = ANY(SELECT fm.id FROM file_module fm WHERE fm.id = ANY(:module_id) OR fm.parent_id = ANY(:module_id))
+AND e.deleted = :deleted
+ORDER BY e.id
 LIMIT 1 OFFSET :offset;
 
 
---! get_element : (value?)
+--! get_element : (value?, template?)
 SELECT
     e.id,
     e.name,
     e.type AS element_type,
     e.value,
     oo.name AS option,
-    oo.exec AS action
+    oo.exec AS action,
+    oo.template,
+    EXISTS (
+        SELECT 1 FROM element_type_operation_option etoo
+        WHERE etoo.element_type = e.type
+        AND etoo.option_id = oo.id
+    ) AS allowed
 FROM elements e
 INNER JOIN operation_option oo ON oo.id = :operation_option_id
-WHERE e.id = :id;
+WHERE e.id = :id
+AND e.deleted = FALSE;
+
+--! insert_element_operation_option
+INSERT INTO element_operation_option (option_id, element_id)
+SELECT :option_id, :element_id
+WHERE NOT EXISTS (
+    SELECT 1 FROM element_operation_option
+    WHERE option_id = :option_id
+    AND element_id = :element_id
+);
 
 --! count
 SELECT
@@ -54,19 +158,159 @@ WHERE
     e.module_id = :module_id
 AND deleted = :is_deleted;
 
---! get_element_list : (updated_at?, updated_by?, description?)
+--! get_element_list : (updated_at?, updated_by?, description?, health_status?, health_checked_at?)
 SELECT  e.id,
         e.name,
+        e.module_id,
         (SELECT name FROM file_module WHERE file_module.id = e.module_id) AS module_name,
         e.value,
         e.description,
         e.type AS element_type,
+        e.deleted,
         e.created_at,
-        (SELECT name FROM users WHERE users.uuid = e.created_by) AS created_by,
+        (SELECT username FROM users WHERE users.uuid = e.created_by) AS created_by,
         e.updated_at,
-        (SELECT name FROM users WHERE users.uuid = e.updated_by) AS updated_by
+        (SELECT username FROM users WHERE users.uuid = e.updated_by) AS updated_by,
+        hr.status AS health_status,
+        hr.checked_at AS health_checked_at
 FROM elements e
+LEFT JOIN LATERAL (
+    SELECT ehcr.status, ehcr.checked_at
+    FROM element_health_check_result ehcr
+    WHERE ehcr.element_id = e.id
+    ORDER BY ehcr.checked_at DESC
+    LIMIT 1
+) hr ON TRUE
 WHERE e.module_id = ANY(SELECT fm.id FROM file_module fm WHERE fm.id = ANY(:module_id) OR fm.parent_id = ANY(:module_id))
-AND e.deleted = FALSE
-AND e.id >= :start_id
+AND e.deleted = :deleted
+AND e.id > :start_id
+ORDER BY e.id
 LIMIT :page_size;
+
+--! insert_operation_option (template?)
+INSERT INTO operation_option
+(name, exec, template, created_by)
+VALUES(:name, :exec, :template, :created_by)
+RETURNING id;
+
+--! update_operation_option (template?)
+UPDATE operation_option
+SET
+    name = :name,
+    exec = :exec,
+    template = :template,
+    updated_by = :updated_by
+WHERE
+    id = :id;
+
+--! delete_operation_option
+WITH type_binding_delete AS (
+    DELETE FROM element_type_operation_option
+    WHERE option_id = :id
+)
+DELETE FROM operation_option
+WHERE id = :id
+AND internal = FALSE;
+
+--! get_operation_option_by_id : (template?)
+SELECT id, name, internal, exec, template
+FROM operation_option
+WHERE id = :id;
+
+--! get_operation_option_by_exec
+SELECT id
+FROM operation_option
+WHERE exec = :exec;
+
+--! get_operation_options : (template?)
+SELECT id, name, internal, exec, template
+FROM operation_option
+ORDER BY id;
+
+--! get_operation_options_by_element_type : (template?)
+SELECT oo.id, oo.name, oo.internal, oo.exec, oo.template
+FROM operation_option oo
+INNER JOIN element_type_operation_option etoo
+    ON etoo.option_id = oo.id
+WHERE etoo.element_type = :element_type
+ORDER BY oo.id;
+
+--! count_operation_option_references
+SELECT COUNT(ser.id)
+FROM script_element_relation ser
+INNER JOIN element_operation_option eoo ON eoo.id = ser.element_operation_id
+WHERE eoo.option_id = :option_id;
+
+--! insert_type_operation_option
+INSERT INTO element_type_operation_option (element_type, option_id, created_by)
+VALUES (:element_type, :option_id, :created_by)
+ON CONFLICT (element_type, option_id) DO NOTHING;
+
+--! delete_type_operation_option
+DELETE FROM element_type_operation_option
+WHERE element_type = :element_type
+AND option_id = :option_id;
+
+--! get_selectors_by_module_id
+SELECT id, value
+FROM elements
+WHERE module_id = :module_id
+AND deleted = FALSE
+ORDER BY id;
+
+--! insert_health_check (machine_id?)
+INSERT INTO element_health_check
+(module_id, page_url, machine_id, created_by)
+VALUES(:module_id, :page_url, :machine_id, :created_by)
+RETURNING id;
+
+--! finish_health_check (error?)
+UPDATE element_health_check
+SET
+    status = :status,
+    error = :error,
+    finished_at = NOW()
+WHERE
+    id = :id;
+
+--! get_health_check_by_id : (machine_id?, error?, finished_at?)
+SELECT
+    ehc.id,
+    ehc.module_id,
+    ehc.page_url,
+    ehc.machine_id,
+    ehc.status,
+    ehc.error,
+    ehc.created_at,
+    (SELECT username FROM users WHERE users.uuid = ehc.created_by) AS created_by,
+    ehc.finished_at
+FROM element_health_check ehc
+WHERE ehc.id = :id;
+
+--! insert_health_check_result
+INSERT INTO element_health_check_result
+(check_id, element_id, selector, status, match_count)
+VALUES(:check_id, :element_id, :selector, :status, :match_count);
+
+--! get_health_check_results
+SELECT
+    ehcr.element_id,
+    e.name AS element_name,
+    ehcr.selector,
+    ehcr.status,
+    ehcr.match_count,
+    ehcr.checked_at
+FROM element_health_check_result ehcr
+INNER JOIN elements e ON e.id = ehcr.element_id
+WHERE ehcr.check_id = :check_id
+ORDER BY ehcr.element_id;
+
+--! expire_health_checks
+UPDATE element_health_check
+SET
+    status = 'FAILED',
+    error = 'health check timed out',
+    finished_at = NOW()
+WHERE
+    status = 'RUNNING'
+AND created_at < NOW() - INTERVAL '30 minutes';
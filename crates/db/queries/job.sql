@@ -0,0 +1,103 @@
+--! insert
+INSERT INTO job
+(kind, payload, max_attempts, created_by)
+VALUES(:kind, :payload, :max_attempts, :created_by)
+RETURNING id;
+
+--! claim_job
+UPDATE job
+SET status = 'RUNNING',
+    attempts = attempts + 1,
+    locked_at = NOW(),
+    locked_by = :worker
+WHERE id = (
+    SELECT id
+    FROM job
+    WHERE status = 'PENDING'
+    AND run_at <= NOW()
+    ORDER BY run_at, id
+    FOR UPDATE SKIP LOCKED
+    LIMIT 1
+)
+RETURNING id, kind, payload, attempts, max_attempts, created_by;
+
+--! update_progress
+UPDATE job
+SET progress = :progress,
+    locked_at = NOW()
+WHERE id = :id
+AND status = 'RUNNING'
+RETURNING cancel_requested;
+
+--! heartbeat_job
+UPDATE job
+SET locked_at = NOW()
+WHERE id = :id
+AND status = 'RUNNING'
+AND locked_by = :worker;
+
+--! finish_job (result?, error?)
+UPDATE job
+SET status = :status,
+    progress = CASE WHEN :status = 'SUCCEEDED' THEN 100 ELSE progress END,
+    result = :result,
+    error = :error,
+    locked_at = NULL,
+    locked_by = NULL,
+    finished_at = NOW()
+WHERE id = :id
+AND locked_by = :worker;
+
+--! retry_job
+UPDATE job
+SET status = 'PENDING',
+    error = :error,
+    run_at = :run_at,
+    locked_at = NULL,
+    locked_by = NULL
+WHERE id = :id
+AND locked_by = :worker
+AND cancel_requested = FALSE;
+
+--! cancel_job
+UPDATE job
+SET cancel_requested = TRUE,
+    status = CASE WHEN status = 'PENDING' THEN 'CANCELLED' ELSE status END,
+    finished_at = CASE WHEN status = 'PENDING' THEN NOW() ELSE finished_at END
+WHERE id = :id
+AND status IN ('PENDING', 'RUNNING')
+RETURNING status;
+
+--! requeue_stale_jobs
+UPDATE job
+SET status = CASE
+        WHEN cancel_requested THEN 'CANCELLED'
+        WHEN attempts < max_attempts THEN 'PENDING'
+        ELSE 'FAILED'
+    END,
+    error = 'worker lost',
+    finished_at = CASE WHEN attempts < max_attempts AND NOT cancel_requested THEN NULL ELSE NOW() END,
+    locked_at = NULL,
+    locked_by = NULL
+WHERE status = 'RUNNING'
+AND locked_at < :stale_before;
+
+--! get_job_by_id : (result?, error?, locked_by?, finished_at?, updated_at?)
+SELECT
+    id,
+    kind,
+    status,
+    progress,
+    result,
+    error,
+    attempts,
+    max_attempts,
+    run_at,
+    locked_by,
+    cancel_requested,
+    finished_at,
+    created_at,
+    created_by,
+    updated_at
+FROM job
+WHERE id = :id;
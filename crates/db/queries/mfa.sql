@@ -0,0 +1,70 @@
+--! get_user_mfa
+SELECT user_id,
+       secret,
+       enabled,
+       last_step
+FROM user_mfa
+WHERE user_id = :user_id;
+
+--! upsert_user_mfa
+INSERT INTO user_mfa (user_id, secret, recovery_codes)
+VALUES (:user_id, :secret, :recovery_codes)
+ON CONFLICT (user_id) DO UPDATE
+    SET secret         = EXCLUDED.secret,
+        recovery_codes = EXCLUDED.recovery_codes,
+        last_step      = 0,
+        created_at     = NOW()
+WHERE user_mfa.enabled = FALSE
+RETURNING user_id;
+
+--! enable_user_mfa
+UPDATE user_mfa
+SET enabled    = TRUE,
+    enabled_at = NOW()
+WHERE user_id = :user_id
+  AND enabled = FALSE
+RETURNING user_id;
+
+--! use_user_mfa_step
+UPDATE user_mfa
+SET last_step = :step
+WHERE user_id = :user_id
+  AND last_step < :step
+RETURNING user_id;
+
+--! use_user_mfa_recovery_code
+UPDATE user_mfa
+SET recovery_codes = array_remove(recovery_codes, :code_hash)
+WHERE user_id = :user_id
+  AND enabled = TRUE
+  AND :code_hash = ANY (recovery_codes)
+RETURNING user_id;
+
+--! update_user_mfa_recovery_codes
+UPDATE user_mfa
+SET recovery_codes = :recovery_codes
+WHERE user_id = :user_id
+  AND enabled = TRUE
+RETURNING user_id;
+
+--! delete_user_mfa
+DELETE
+FROM user_mfa
+WHERE user_id = :user_id;
+
+--! is_user_mfa_required
+SELECT EXISTS (SELECT 1
+               FROM user_role_relation urr
+                        JOIN user_role ur ON urr.role_id = ur.id
+               WHERE urr.user_id = :user_id
+                 AND ur.mfa_required = TRUE
+                 AND ur.deleted_at IS NULL) AS required;
+
+--! update_role_mfa_required
+UPDATE user_role
+SET mfa_required = :mfa_required,
+    updated_by   = :updated_by,
+    updated_at   = NOW()
+WHERE id = :id
+  AND deleted_at IS NULL
+RETURNING id;
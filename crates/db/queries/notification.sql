@@ -0,0 +1,58 @@
+--! insert_notification_channel (secret?)
+INSERT INTO notification_channel (user_id, name, channel_type, url, secret)
+VALUES (:user_id, :name, :channel_type, :url, :secret)
+RETURNING id;
+
+--! get_notification_channels_by_user_id : (secret?)
+SELECT id,
+       name,
+       channel_type,
+       url,
+       secret,
+       created_at
+FROM notification_channel
+WHERE user_id = :user_id
+  AND deleted_at IS NULL
+ORDER BY id;
+
+--! get_notification_channel : (secret?)
+SELECT id,
+       name,
+       channel_type,
+       url,
+       secret,
+       created_at
+FROM notification_channel
+WHERE id = :id
+  AND user_id = :user_id
+  AND deleted_at IS NULL;
+
+--! delete_notification_channel
+UPDATE notification_channel
+SET deleted_at = NOW()
+WHERE id = :id
+  AND user_id = :user_id
+  AND deleted_at IS NULL
+RETURNING id;
+
+--! remove_channel_from_preferences
+UPDATE notification_preference
+SET channel_ids = array_remove(channel_ids, :channel_id),
+    updated_at  = NOW()
+WHERE user_id = :user_id
+  AND :channel_id = ANY (channel_ids);
+
+--! get_notification_preferences_by_user_id
+SELECT event_type,
+       email,
+       channel_ids
+FROM notification_preference
+WHERE user_id = :user_id;
+
+--! upsert_notification_preference
+INSERT INTO notification_preference (user_id, event_type, email, channel_ids)
+VALUES (:user_id, :event_type, :email, :channel_ids)
+ON CONFLICT (user_id, event_type) DO UPDATE
+    SET email       = EXCLUDED.email,
+        channel_ids = EXCLUDED.channel_ids,
+        updated_at  = NOW();
@@ -52,3 +52,46 @@ GROUP BY r.id;
 INSERT INTO role_permission_relation (role_id, permission_id)
 VALUES (:role_id, :permission_id)
 RETURNING id;
+
+--! delete_role_permission_relation
+DELETE
+FROM role_permission_relation
+WHERE role_id = :role_id
+  AND permission_id = ANY (:permission_ids);
+
+--! insert_permission
+WITH inserted AS (
+    INSERT INTO permission (module, scope)
+    SELECT :module, :scope
+    WHERE NOT EXISTS (SELECT 1 FROM permission WHERE module = :module AND scope = :scope)
+    RETURNING id
+)
+SELECT id, TRUE AS created
+FROM inserted
+UNION ALL
+SELECT id, FALSE AS created
+FROM permission
+WHERE module = :module
+  AND scope = :scope;
+
+--! grant_internal_role_permission
+INSERT INTO role_permission_relation (role_id, permission_id)
+SELECT id, :permission_id
+FROM user_role
+WHERE internal = TRUE
+  AND name <> :except_name;
+
+--! grant_role_permission_by_name
+INSERT INTO role_permission_relation (role_id, permission_id)
+SELECT id, :permission_id
+FROM user_role
+WHERE name = :name
+  AND deleted_at IS NULL;
+
+--! delete_api_permission_relation
+DELETE
+FROM api_permission_relation;
+
+--! insert_api_permission_relation
+INSERT INTO api_permission_relation (uri, method, permission_id)
+VALUES (:uri, :method, :permission_id);
@@ -5,9 +5,11 @@ VALUES(:name, :project_id, :description, :module_id, :created_by, :start_date, :
 RETURNING id;
 
 --! get_query_cursor
-SELECT id
-FROM plans
-ORDER BY id
+SELECT p.id
+FROM plans p
+WHERE p.module_id = ANY(SELECT fm.id FROM file_module fm WHERE fm.id = ANY(:module_id) OR fm.parent_id = ANY(:module_id))
+AND p.deleted = FALSE
+ORDER BY p.id
 LIMIT 1 OFFSET :offset;
 
 --! count_by_module_id
@@ -49,6 +51,185 @@ SELECT
 FROM plans p
 WHERE p.module_id = ANY(SELECT fm.id FROM file_module fm WHERE fm.id = ANY(:module_id) OR fm.parent_id = ANY(:module_id))
 AND p.deleted = FALSE
-AND p.id >= :start_id
+AND p.id > :start_id
 ORDER BY p.id
 LIMIT :page_size;
+
+--! get_plan_by_id : (description?, updated_at?, updated_by?, start_date?, end_date?)
+SELECT
+    p.id,
+    p.name,
+    p.status,
+    p.module_id,
+    p.description,
+    p.project_id,
+    p.created_at,
+    p.created_by,
+    p.updated_at,
+    (SELECT username FROM users WHERE users.uuid = p.updated_by) AS updated_by,
+    p.start_date,
+    p.end_date
+FROM plans p
+WHERE p.id = :id
+AND p.deleted = FALSE;
+
+--! get_plan_scripts
+SELECT DISTINCT ON (s.case_id)
+    s.id,
+    s.case_id,
+    s.path
+FROM plan_case_relation pcr
+INNER JOIN functional_cases fc
+    ON fc.id = pcr.case_id
+    AND fc.deleted_at IS NULL
+INNER JOIN script s ON s.case_id = pcr.case_id
+WHERE pcr.plan_id = :plan_id
+ORDER BY s.case_id, s.created_at DESC;
+
+--! get_plan_script_by_id
+SELECT
+    s.id,
+    s.case_id,
+    s.path
+FROM script s
+INNER JOIN plan_case_relation pcr
+    ON pcr.case_id = s.case_id
+    AND pcr.plan_id = :plan_id
+WHERE s.id = :id;
+
+--! upsert_schedule (script_id?)
+INSERT INTO plan_schedule
+(plan_id, script_id, cron, machine_id, enabled, created_by)
+VALUES(:plan_id, :script_id, :cron, :machine_id, :enabled, :updated_by)
+ON CONFLICT (plan_id) WHERE deleted_at IS NULL
+DO UPDATE SET
+    script_id = EXCLUDED.script_id,
+    cron = EXCLUDED.cron,
+    machine_id = EXCLUDED.machine_id,
+    enabled = EXCLUDED.enabled,
+    last_fired_at = NULL,
+    updated_by = :updated_by
+RETURNING id;
+
+--! soft_delete_schedule
+UPDATE plan_schedule
+SET
+    deleted_at = NOW(),
+    deleted_by = :deleted_by
+WHERE plan_id = :plan_id
+AND deleted_at IS NULL;
+
+--! get_schedule_by_plan_id : (script_id?, last_fired_at?, updated_at?)
+SELECT
+    id,
+    plan_id,
+    script_id,
+    cron,
+    machine_id,
+    enabled,
+    last_fired_at,
+    created_at,
+    created_by,
+    updated_at
+FROM plan_schedule
+WHERE plan_id = :plan_id
+AND deleted_at IS NULL;
+
+--! lock_schedule_by_plan_id
+SELECT id
+FROM plan_schedule
+WHERE plan_id = :plan_id
+AND deleted_at IS NULL
+FOR UPDATE;
+
+--! get_enabled_schedules : (script_id?, last_fired_at?, updated_at?)
+SELECT
+    ps.id,
+    ps.plan_id,
+    ps.script_id,
+    ps.cron,
+    ps.machine_id,
+    ps.enabled,
+    ps.last_fired_at,
+    ps.created_at,
+    ps.created_by,
+    ps.updated_at
+FROM plan_schedule ps
+INNER JOIN plans p
+    ON p.id = ps.plan_id
+    AND p.deleted = FALSE
+WHERE ps.enabled = TRUE
+AND ps.deleted_at IS NULL
+ORDER BY ps.id;
+
+--! claim_schedule
+UPDATE plan_schedule
+SET last_fired_at = :fired_at
+WHERE id = :id
+AND (last_fired_at IS NULL OR last_fired_at < :fired_at)
+RETURNING id;
+
+--! insert_run (schedule_id?, script_id?)
+INSERT INTO plan_run
+(plan_id, schedule_id, script_id, trigger_type, created_by)
+VALUES(:plan_id, :schedule_id, :script_id, :trigger_type, :created_by)
+RETURNING id;
+
+--! finish_run (error?)
+UPDATE plan_run
+SET
+    status = :status,
+    total = :total,
+    passed = :passed,
+    failed = :failed,
+    error = :error,
+    finished_at = NOW(),
+    duration_ms = (EXTRACT(EPOCH FROM (NOW() - started_at)) * 1000)::BIGINT
+WHERE id = :id;
+
+--! set_run_job
+UPDATE plan_run
+SET job_id = :job_id
+WHERE id = :id;
+
+--! expire_lost_runs
+UPDATE plan_run pr
+SET
+    status = 'ERROR',
+    error = 'run lost',
+    finished_at = NOW(),
+    duration_ms = (EXTRACT(EPOCH FROM (NOW() - pr.started_at)) * 1000)::BIGINT
+WHERE pr.status = 'RUNNING'
+AND NOT EXISTS (
+    SELECT 1 FROM job j
+    WHERE j.id = pr.job_id
+    AND j.status IN ('PENDING', 'RUNNING')
+);
+
+--! count_running_runs
+SELECT COUNT(id)
+FROM plan_run
+WHERE plan_id = :plan_id
+AND status = 'RUNNING';
+
+--! get_runs_by_plan_id : (schedule_id?, script_id?, job_id?, error?, finished_at?, duration_ms?)
+SELECT
+    pr.id,
+    pr.plan_id,
+    pr.schedule_id,
+    pr.script_id,
+    pr.job_id,
+    pr.trigger_type,
+    pr.status,
+    pr.total,
+    pr.passed,
+    pr.failed,
+    pr.error,
+    pr.started_at,
+    pr.finished_at,
+    pr.duration_ms,
+    (SELECT username FROM users WHERE users.uuid = pr.created_by) AS created_by
+FROM plan_run pr
+WHERE pr.plan_id = :plan_id
+ORDER BY pr.started_at DESC
+LIMIT :limit;
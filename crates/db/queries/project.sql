@@ -17,7 +17,8 @@ FROM projects p
          LEFT JOIN users uc ON p.created_by = uc.uuid
          LEFT JOIN users uu ON p.updated_by = uu.uuid
          LEFT JOIN users ud ON p.deleted_by = ud.uuid
-WHERE p.id = :id;
+WHERE p.id = :id
+  AND p.deleted_at IS NULL;
 
 --! find_project_by_name : (updated_at?, updated_by?, deleted_at?, deleted_by?, description?, module_setting?)
 SELECT p.id,
@@ -38,7 +39,8 @@ FROM projects p
          LEFT JOIN users uc ON p.created_by = uc.uuid
          LEFT JOIN users uu ON p.updated_by = uu.uuid
          LEFT JOIN users ud ON p.deleted_by = ud.uuid
-WHERE p.name = :name;
+WHERE p.name = :name
+  AND p.deleted_at IS NULL;
 
 --! find_projects_by_uid : (updated_at?, updated_by?, deleted_at?, deleted_by?, description?, module_setting?)
 SELECT p.id,
@@ -61,7 +63,8 @@ FROM projects p
          LEFT JOIN users uc ON p.created_by = uc.uuid
          LEFT JOIN users uu ON p.updated_by = uu.uuid
          LEFT JOIN users ud ON p.deleted_by = ud.uuid
-WHERE p.created_by = :uid;
+WHERE p.created_by = :uid
+  AND p.deleted_at IS NULL;
 
 --! get_projects_by_uid : (updated_at?, updated_by?, description?, module_setting?)
 SELECT p.id,
@@ -79,6 +82,7 @@ FROM projects p
     LEFT JOIN users uu ON p.updated_by = uu.uuid
     LEFT JOIN user_role_relation urr ON urr.project_id = p.id
 WHERE urr.user_id = :uid
+  AND p.deleted_at IS NULL
 GROUP BY p.id, p.name, uc.username, uu.username;
 
 
@@ -104,3 +108,117 @@ VALUES (:name,
         :created_by,
         :description,
         :module_setting) RETURNING id;
+
+--! update_project (description?)
+UPDATE projects
+SET name        = :name,
+    description = :description,
+    updated_by  = :updated_by
+WHERE id = :id
+  AND deleted_at IS NULL;
+
+--! update_project_module_setting
+UPDATE projects
+SET module_setting = :module_setting,
+    updated_by     = :updated_by
+WHERE id = :id
+  AND deleted_at IS NULL;
+
+--! update_project_enable
+UPDATE projects
+SET enable     = :enable,
+    updated_by = :updated_by
+WHERE id = :id
+  AND deleted_at IS NULL;
+
+--! soft_delete_project
+UPDATE projects
+SET deleted_at = NOW(),
+    deleted_by = :deleted_by
+WHERE id = :id
+  AND deleted_at IS NULL;
+
+--! get_project_member_uuids
+SELECT user_id
+FROM user_role_relation
+WHERE project_id = :project_id;
+
+--! seed_project_template
+WITH source AS (SELECT id, project_id
+                FROM template
+                WHERE internal = TRUE
+                  AND deleted_at IS NULL
+                ORDER BY id
+                LIMIT 1),
+     new_template AS (
+         INSERT INTO template (name, project_id, description, internal, created_by)
+             SELECT t.name, :project_id, t.description, TRUE, :created_by
+             FROM template t
+                      INNER JOIN source s ON s.id = t.id
+             RETURNING id),
+     new_field AS (
+         INSERT INTO field (name, label, field_type, project_id, remark, internal, created_by)
+             SELECT f.name, f.label, f.field_type, :project_id, f.remark, TRUE, :created_by
+             FROM field f
+                      INNER JOIN source s ON s.project_id = f.project_id
+             WHERE f.internal = TRUE
+               AND f.deleted_at IS NULL
+             RETURNING id, name),
+     new_option AS (
+         INSERT INTO field_option (field_id, value, position, created_by)
+             SELECT nf.id, fo.value, fo.position, :created_by
+             FROM field_option fo
+                      INNER JOIN field f ON f.id = fo.field_id
+                      INNER JOIN source s ON s.project_id = f.project_id
+                      INNER JOIN new_field nf ON nf.name = f.name
+             WHERE f.internal = TRUE
+               AND fo.deleted_at IS NULL)
+INSERT
+INTO template_field_relation (template_id, field_id, required, unique_required, default_value, created_by)
+SELECT nt.id, nf.id, tfr.required, tfr.unique_required, tfr.default_value, :created_by
+FROM template_field_relation tfr
+         INNER JOIN source s ON s.id = tfr.template_id
+         INNER JOIN field f ON f.id = tfr.field_id
+         INNER JOIN new_field nf ON nf.name = f.name
+         CROSS JOIN new_template nt
+WHERE tfr.deleted_at IS NULL;
+
+--! get_module_owner
+SELECT project_id
+FROM file_module
+WHERE id = :id;
+
+--! get_case_owner
+SELECT fm.project_id
+FROM functional_cases fc
+         INNER JOIN file_module fm ON fm.id = fc.module_id
+WHERE fc.id = :id;
+
+--! get_element_owner
+SELECT fm.project_id
+FROM elements e
+         INNER JOIN file_module fm ON fm.id = e.module_id
+WHERE e.id = :id;
+
+--! get_template_owner
+SELECT project_id
+FROM template
+WHERE id = :id;
+
+--! get_field_owner
+SELECT project_id
+FROM field
+WHERE id = :id;
+
+--! get_issue_relation_owner
+SELECT fm.project_id
+FROM case_issue_relation cir
+         INNER JOIN functional_cases fc ON fc.id = cir.case_id
+         INNER JOIN file_module fm ON fm.id = fc.module_id
+WHERE cir.id = :id;
+
+--! get_health_check_owner
+SELECT fm.project_id
+FROM element_health_check ehc
+         INNER JOIN file_module fm ON fm.id = ehc.module_id
+WHERE ehc.id = :id;
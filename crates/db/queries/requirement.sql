@@ -0,0 +1,164 @@
+--! insert (description?, external_id?, uri?, status?)
+INSERT INTO requirement
+(
+    project_id,
+    name,
+    description,
+    source,
+    external_id,
+    uri,
+    status,
+    created_by
+) VALUES (
+    :project_id,
+    :name,
+    :description,
+    :source,
+    :external_id,
+    :uri,
+    :status,
+    :created_by
+) RETURNING id;
+
+--! update (description?)
+UPDATE requirement
+SET name = :name,
+    description = :description,
+    updated_by = :updated_by
+WHERE id = :id;
+
+--! soft_delete
+WITH requirement_soft_delete AS (UPDATE requirement
+SET deleted_at = NOW(),
+    deleted_by = :deleted_by,
+    updated_by = :deleted_by
+WHERE id = :id
+RETURNING id)
+UPDATE requirement_case_relation
+SET deleted_at = NOW(),
+    deleted_by = :deleted_by
+WHERE requirement_id IN (SELECT id FROM requirement_soft_delete)
+AND deleted_at IS NULL;
+
+--! get_requirement_by_id : (description?, external_id?, uri?, status?, updated_at?, updated_by?)
+SELECT
+    r.id,
+    r.project_id,
+    r.name,
+    r.description,
+    r.source,
+    r.external_id,
+    r.uri,
+    r.status,
+    r.created_at,
+    uc.username AS created_by,
+    r.updated_at,
+    uu.username AS updated_by
+FROM requirement r
+LEFT JOIN users uc ON uc.uuid = r.created_by
+LEFT JOIN users uu ON uu.uuid = r.updated_by
+WHERE r.id = :id
+AND r.deleted_at IS NULL AND r.deleted_by IS NULL;
+
+--! get_requirement_by_external_id
+SELECT id
+FROM requirement
+WHERE project_id = :project_id
+AND source = :source
+AND external_id = :external_id
+AND deleted_at IS NULL AND deleted_by IS NULL;
+
+--! get_requirement_list : (description?, external_id?, uri?, status?, updated_at?, updated_by?)
+SELECT
+    r.id,
+    r.project_id,
+    r.name,
+    r.description,
+    r.source,
+    r.external_id,
+    r.uri,
+    r.status,
+    r.created_at,
+    uc.username AS created_by,
+    r.updated_at,
+    uu.username AS updated_by
+FROM requirement r
+LEFT JOIN users uc ON uc.uuid = r.created_by
+LEFT JOIN users uu ON uu.uuid = r.updated_by
+WHERE r.project_id = :project_id
+AND r.deleted_at IS NULL AND r.deleted_by IS NULL
+ORDER BY r.id;
+
+--! insert_case_relation
+INSERT INTO requirement_case_relation (
+    requirement_id,
+    case_id,
+    created_by
+)
+SELECT :requirement_id, :case_id, :created_by
+WHERE NOT EXISTS (
+    SELECT 1 FROM requirement_case_relation
+    WHERE requirement_id = :requirement_id
+    AND case_id = :case_id
+    AND deleted_at IS NULL
+);
+
+--! soft_delete_case_relation
+UPDATE requirement_case_relation
+SET deleted_at = NOW(),
+    deleted_by = :deleted_by
+WHERE requirement_id = :requirement_id
+AND case_id = :case_id
+AND deleted_at IS NULL;
+
+--! get_case_ids_by_requirement_id
+SELECT rcr.case_id
+FROM requirement_case_relation rcr
+INNER JOIN functional_cases fc
+    ON fc.id = rcr.case_id
+    AND fc.deleted_at IS NULL
+WHERE rcr.requirement_id = :requirement_id
+AND rcr.deleted_at IS NULL
+ORDER BY rcr.case_id;
+
+--! get_latest_executed_plan_id
+SELECT fcer.plan_id AS id
+FROM functional_case_execute_record fcer
+INNER JOIN plans p
+    ON p.id = fcer.plan_id
+    AND p.deleted = FALSE
+WHERE p.project_id = :project_id
+AND fcer.deleted_at IS NULL
+ORDER BY fcer.created_at DESC
+LIMIT 1;
+
+--! get_requirement_coverage (plan_id?) : (external_id?, uri?, status?)
+SELECT
+    r.id,
+    r.name,
+    r.source,
+    r.external_id,
+    r.uri,
+    r.status,
+    COUNT(DISTINCT fc.id) AS case_count,
+    COUNT(DISTINCT lr.case_id) FILTER (WHERE lr.result = 'PASSED') AS passed_count
+FROM requirement r
+LEFT JOIN requirement_case_relation rcr
+    ON rcr.requirement_id = r.id
+    AND rcr.deleted_at IS NULL
+LEFT JOIN functional_cases fc
+    ON fc.id = rcr.case_id
+    AND fc.deleted_at IS NULL
+LEFT JOIN LATERAL (
+    SELECT fcer.case_id, fcer.result
+    FROM functional_case_execute_record fcer
+    WHERE fcer.case_id = fc.id
+    AND fcer.plan_id = :plan_id
+    AND fcer.deleted_at IS NULL
+    ORDER BY fcer.created_at DESC
+    LIMIT 1
+) lr ON TRUE
+WHERE r.project_id = :project_id
+AND r.deleted_at IS NULL AND r.deleted_by IS NULL
+GROUP BY r.id
+ORDER BY r.id;
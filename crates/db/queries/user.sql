@@ -109,6 +109,20 @@ SELECT id,
 FROM users
 WHERE email = :email;
 
+--! get_active_user_by_email : (updated_at?, last_project_id?)
+SELECT id,
+       uuid,
+       username,
+       hashed_password,
+       email,
+       enable,
+       created_at,
+       created_by,
+       updated_at,
+       last_project_id
+FROM users
+WHERE email = :email AND deleted_at IS NULL AND deleted_by IS NULL;
+
 --! get_user_by_uuid : (updated_at?, last_project_id?)
 SELECT id,
        uuid,
@@ -238,3 +252,91 @@ FROM users u
          JOIN user_role ur ON urr.role_id = ur.id
 WHERE p.id = :project_id
   AND ur.name = :role_name;
+
+--! update_user_role (description?)
+UPDATE user_role
+SET name = :name,
+    description = :description,
+    updated_by = :updated_by
+WHERE id = :id
+  AND deleted_at IS NULL
+  AND deleted_by IS NULL;
+
+--! get_role_id_by_name
+SELECT id
+FROM user_role
+WHERE name = :name
+  AND deleted_at IS NULL
+  AND deleted_by IS NULL;
+
+--! get_member_role
+SELECT urr.id,
+       urr.role_id,
+       ur.name AS role_name
+FROM user_role_relation urr
+         JOIN user_role ur ON urr.role_id = ur.id
+WHERE urr.user_id = :user_id
+  AND urr.project_id = :project_id;
+
+--! update_user_role_relation
+UPDATE user_role_relation
+SET role_id = :role_id,
+    updated_by = :updated_by
+WHERE user_id = :user_id
+  AND project_id = :project_id;
+
+--! delete_user_role_relation
+DELETE
+FROM user_role_relation
+WHERE user_id = :user_id
+  AND project_id = :project_id;
+
+--! get_project_ids_by_uuid
+SELECT project_id
+FROM user_role_relation
+WHERE user_id = :user_id;
+
+--! get_members_by_role_id
+SELECT user_id,
+       project_id
+FROM user_role_relation
+WHERE role_id = :role_id;
+
+--! get_password_change_required
+SELECT password_change_required
+FROM users
+WHERE uuid = :uuid;
+
+--! update_password_change_required
+UPDATE users
+SET password_change_required = :password_change_required
+WHERE uuid = :uuid;
+
+--! update_password
+UPDATE users
+SET hashed_password          = :hashed_password,
+    password_change_required = :password_change_required,
+    updated_at               = NOW()
+WHERE uuid = :uuid;
+
+--! get_user_by_sso_identity : (updated_at?, last_project_id?)
+SELECT u.id,
+       u.uuid,
+       u.username,
+       u.hashed_password,
+       u.email,
+       u.enable,
+       u.created_at,
+       u.created_by,
+       u.updated_at,
+       u.last_project_id,
+       (u.deleted_at IS NOT NULL OR u.deleted_by IS NOT NULL) AS deleted
+FROM sso_identity i
+         JOIN users u ON u.uuid = i.user_id
+WHERE i.provider = :provider
+  AND i.issuer = :issuer
+  AND i.subject = :subject;
+
+--! insert_sso_identity
+INSERT INTO sso_identity (user_id, provider, issuer, subject)
+VALUES (:user_id, :provider, :issuer, :subject);
@@ -39,9 +39,15 @@ pub trait RedisClientExt {
 
     fn get(&self, key: &str) -> impl Future<Output = Result<Option<String>, RedisError>>;
 
+    fn mget(&self, keys: &[&str]) -> impl Future<Output = Result<Vec<Option<String>>, RedisError>>;
+
     fn del(&self, key: &str) -> impl Future<Output = Result<bool, RedisError>>;
 
     fn ttl(&self, key: &str) -> impl Future<Output = Result<i64, RedisError>>;
+
+    fn incr(&self, key: &str, expire: Duration) -> impl Future<Output = Result<i64, RedisError>>;
+
+    fn expire(&self, key: &str, expire: Duration) -> impl Future<Output = Result<(), RedisError>>;
 }
 
 impl RedisClientExt for Client {
@@ -114,6 +120,12 @@ impl RedisClientExt for Client {
         Ok(value)
     }
 
+    async fn mget(&self, keys: &[&str]) -> Result<Vec<Option<String>>, RedisError> {
+        let mut conn = self.get_multiplexed_async_connection().await?;
+        let value = redis::cmd("MGET").arg(keys).query_async(&mut conn).await?;
+        Ok(value)
+    }
+
     async fn del(&self, key: &str) -> Result<bool, RedisError> {
         let mut conn = self.get_multiplexed_async_connection().await?;
         let value: i32 = redis::cmd("DEL").arg(key).query_async(&mut conn).await?;
@@ -125,4 +137,29 @@ impl RedisClientExt for Client {
         let value = redis::cmd("TTL").arg(&[key]).query_async(&mut conn).await?;
         Ok(value)
     }
+
+    /* the expiry is renewed on every increment, in one transaction so the counter never lacks it */
+    async fn incr(&self, key: &str, expire: Duration) -> Result<i64, RedisError> {
+        let mut conn = self.get_multiplexed_async_connection().await?;
+        let (value,): (i64,) = redis::pipe()
+            .atomic()
+            .cmd("INCR")
+            .arg(key)
+            .cmd("EXPIRE")
+            .arg(key)
+            .arg(expire.as_secs())
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+        Ok(value)
+    }
+
+    async fn expire(&self, key: &str, expire: Duration) -> Result<(), RedisError> {
+        let mut conn = self.get_multiplexed_async_connection().await?;
+        let _msg: i32 = redis::cmd("EXPIRE")
+            .arg(&[key, &expire.as_secs().to_string()])
+            .query_async(&mut conn)
+            .await?;
+        Ok(())
+    }
 }
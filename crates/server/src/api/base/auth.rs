@@ -1,19 +1,35 @@
 use crate::{
+    constant::OIDC_BINDING_COOKIE,
     dto::{
-        request::{user::LoginRequest, *},
+        request::{
+            user::{
+                AcceptInvitationRequest, ForgotPasswordRequest, LdapLoginRequest, LoginRequest,
+                MfaEnrollChallengeRequest, MfaLoginRequest, OidcCallbackParam,
+                ResetPasswordRequest,
+            },
+            *,
+        },
         response::{
-            user::{LoginResponse, TokenResponse},
+            user::{LoginResponse, MfaEnrollResponse, OidcAuthorizeResponse, TokenResponse},
             MessageResponse,
         },
     },
-    errors::{AppResponseError, AppResult},
+    errors::{invalid_input_error, AppResponseError, AppResult},
     service,
     state::AppState,
-    utils::claim::UserClaims,
+    utils::{
+        claim::UserClaims,
+        header::{extract_cookie, ClientInfo},
+    },
+};
+use axum::extract::{Extension, Multipart, Query};
+use axum::http::{
+    header::{HeaderName, SET_COOKIE},
+    HeaderMap,
 };
-use axum::extract::Extension;
 use axum::Json;
 use garde::Validate;
+use jsonwebtoken::jwk::JwkSet;
 use tracing::{info, warn};
 
 /// User Register
@@ -45,6 +61,43 @@ pub async fn register(
     }
 }
 
+/* csv with one `username,email` per line, the header line is optional */
+#[utoipa::path(
+    post,
+    request_body(content_type = "multipart/form-data"),
+    path = "/auth/register/csv",
+    responses(
+    (status = 200, description = "Success register user", body = [MessageResponse]),
+    (status = 400, description = "Invalid data input", body = [AppResponseError]),
+    (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn register_csv(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    mut multipart: Multipart,
+) -> AppResult<Json<MessageResponse>> {
+    let mut content = None;
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() == Some("file") {
+            content = Some(field.text().await?);
+        }
+    }
+    let content = content.ok_or_else(|| invalid_input_error("file", "csv file is required"))?;
+    let user_info_list = service::user::parse_user_csv(&content)?;
+    info!("Register {} users from csv", user_info_list.len());
+    user_info_list.validate()?;
+    let request = RegisterRequest { user_info_list };
+    match service::user::batch_register(&state, user.uid, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success register user"))),
+        Err(e) => {
+            warn!("Failed to register user from csv: {e:?}");
+            Err(e)
+        }
+    }
+}
+
 /// User Login
 #[utoipa::path(
     post,
@@ -61,11 +114,12 @@ pub async fn register(
 )]
 pub async fn login(
     Extension(state): Extension<AppState>,
+    client_info: ClientInfo,
     Json(request): Json<LoginRequest>,
 ) -> AppResult<Json<LoginResponse>> {
     info!("Login user with request: {request:?}.");
     request.validate()?;
-    match service::user::login(&state, request).await {
+    match service::user::login(&state, request, &client_info).await {
         Ok(resp) => {
             info!("Success login user");
             Ok(Json(resp))
@@ -77,6 +131,64 @@ pub async fn login(
     }
 }
 
+/// Mfa Login
+#[utoipa::path(
+    post,
+    request_body = MfaLoginRequest,
+    path = "/auth/login/mfa",
+    responses(
+    (status = 200, description = "Login success", body = [LoginResponse]),
+    (status = 400, description = "Invalid challenge or code", body = [AppResponseError]),
+    (status = 403, description = "Disabled user forbidden", body = [AppResponseError]),
+    (status = 429, description = "Too many failed codes", body = [AppResponseError]),
+    (status = 500, description = "Internal server error", body = [AppResponseError])
+    )
+)]
+pub async fn login_mfa(
+    Extension(state): Extension<AppState>,
+    client_info: ClientInfo,
+    Json(request): Json<MfaLoginRequest>,
+) -> AppResult<Json<LoginResponse>> {
+    info!("Login user with second factor");
+    request.validate()?;
+    match service::mfa::login(&state, request, &client_info).await {
+        Ok(resp) => {
+            info!("Success login user with second factor");
+            Ok(Json(resp))
+        }
+        Err(e) => {
+            warn!("Failed to login user with second factor: {e:?}");
+            Err(e)
+        }
+    }
+}
+
+/// Mfa Enroll During Login
+#[utoipa::path(
+    post,
+    request_body = MfaEnrollChallengeRequest,
+    path = "/auth/login/mfa/enroll",
+    responses(
+    (status = 200, description = "Secret and recovery codes of the enrollment", body = [MfaEnrollResponse]),
+    (status = 400, description = "Invalid challenge or mfa already enabled", body = [AppResponseError]),
+    (status = 500, description = "Internal server error", body = [AppResponseError])
+    )
+)]
+pub async fn login_mfa_enroll(
+    Extension(state): Extension<AppState>,
+    Json(request): Json<MfaEnrollChallengeRequest>,
+) -> AppResult<Json<MfaEnrollResponse>> {
+    info!("Enroll mfa of a user during login");
+    request.validate()?;
+    match service::mfa::enroll_by_challenge(&state, request).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => {
+            warn!("Failed to enroll mfa: {e:?}");
+            Err(e)
+        }
+    }
+}
+
 /// User Logout
 #[utoipa::path(
     get,
@@ -110,7 +222,7 @@ pub async fn logout(
     get,
     path = "/auth/is-login",
     responses(
-        (status = 200, description = "User is login", body = [LoginResponse]),
+        (status = 200, description = "User is login", body = [MessageResponse]),
         (status = 401, description = "User Unauthorized", body = [AppResponseError]),
         (status = 500, description = "Internal server error", body = [AppResponseError])
     ),
@@ -119,14 +231,12 @@ pub async fn logout(
 pub async fn is_login(
     Extension(state): Extension<AppState>,
     user: UserClaims,
-) -> AppResult<Json<LoginResponse>> {
+) -> AppResult<Json<MessageResponse>> {
     info!("Check if user is already login: {}", user.uid);
-    /* 获取用户的access token
-     * 检验成功后返回刷新的access token
-     */
+    /* 仅校验会话是否有效, 续期请使用 refresh token */
     match service::user::is_login(&state, user).await {
         Ok(resp) => {
-            info!("User is already login, refresh token: {resp:?}");
+            info!("User is already login");
             Ok(Json(resp))
         }
         Err(e) => {
@@ -164,3 +274,189 @@ pub async fn token_refresh(
         }
     }
 }
+
+/// Json Web Key Set
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    responses(
+        (status = 200, description = "Public keys verifying access tokens, keyed by the `kid` header"),
+    )
+)]
+pub async fn jwks() -> AppResult<Json<JwkSet>> {
+    Ok(Json(service::token::jwks()))
+}
+
+/// OIDC Authorize
+#[utoipa::path(
+    get,
+    path = "/auth/sso/oidc/authorize",
+    responses(
+        (status = 200, description = "Authorization url of the provider, sets the cookie binding the state to the browser", body = [OidcAuthorizeResponse]),
+        (status = 400, description = "Provider not configured", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    )
+)]
+pub async fn oidc_authorize(
+    Extension(state): Extension<AppState>,
+) -> AppResult<([(HeaderName, String); 1], Json<OidcAuthorizeResponse>)> {
+    info!("Start oidc authorization");
+    match service::sso::oidc_authorize(&state).await {
+        Ok((resp, binding)) => {
+            let cookie = service::sso::binding_cookie(&state, &binding);
+            Ok(([(SET_COOKIE, cookie)], Json(resp)))
+        }
+        Err(e) => {
+            warn!("Failed to start oidc authorization: {e:?}");
+            Err(e)
+        }
+    }
+}
+
+/// OIDC Callback
+#[utoipa::path(
+    get,
+    path = "/auth/sso/oidc/callback",
+    params(OidcCallbackParam),
+    responses(
+        (status = 200, description = "Login success", body = [LoginResponse]),
+        (status = 400, description = "Unknown, expired or used state, or state of another browser", body = [AppResponseError]),
+        (status = 401, description = "Invalid id token", body = [AppResponseError]),
+        (status = 403, description = "Disabled user forbidden", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    )
+)]
+pub async fn oidc_callback(
+    Extension(state): Extension<AppState>,
+    client_info: ClientInfo,
+    headers: HeaderMap,
+    Query(param): Query<OidcCallbackParam>,
+) -> AppResult<Json<LoginResponse>> {
+    info!("Oidc callback with state: {}", param.state);
+    let binding = extract_cookie(&headers, OIDC_BINDING_COOKIE);
+    match service::sso::oidc_callback(&state, param, binding.as_deref(), &client_info).await {
+        Ok(resp) => {
+            info!("Success login user by oidc");
+            Ok(Json(resp))
+        }
+        Err(e) => {
+            warn!("Failed to login user by oidc: {e:?}");
+            Err(e)
+        }
+    }
+}
+
+/// LDAP Login
+#[utoipa::path(
+    post,
+    request_body = LdapLoginRequest,
+    path = "/auth/sso/ldap/login",
+    responses(
+        (status = 200, description = "Login success", body = [LoginResponse]),
+        (status = 400, description = "Invalid data input", body = [AppResponseError]),
+        (status = 401, description = "Invalid username/password", body = [AppResponseError]),
+        (status = 403, description = "Disabled user forbidden", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    )
+)]
+pub async fn ldap_login(
+    Extension(state): Extension<AppState>,
+    client_info: ClientInfo,
+    Json(request): Json<LdapLoginRequest>,
+) -> AppResult<Json<LoginResponse>> {
+    info!("Ldap login user: {}", request.username);
+    request.validate()?;
+    match service::sso::ldap_login(&state, request, &client_info).await {
+        Ok(resp) => {
+            info!("Success login user by ldap");
+            Ok(Json(resp))
+        }
+        Err(e) => {
+            warn!("Failed to login user by ldap: {e:?}");
+            Err(e)
+        }
+    }
+}
+
+/// Accept Invitation
+#[utoipa::path(
+    post,
+    request_body = AcceptInvitationRequest,
+    path = "/auth/invitation/accept",
+    responses(
+        (status = 200, description = "Password set and logged in", body = [LoginResponse]),
+        (status = 400, description = "Invalid or expired invitation", body = [AppResponseError]),
+        (status = 403, description = "Disabled user forbidden", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    )
+)]
+pub async fn accept_invitation(
+    Extension(state): Extension<AppState>,
+    client_info: ClientInfo,
+    Json(request): Json<AcceptInvitationRequest>,
+) -> AppResult<Json<LoginResponse>> {
+    info!("Accept invitation");
+    request.validate()?;
+    match service::invitation::accept(&state, request, &client_info).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => {
+            warn!("Failed to accept invitation: {e:?}");
+            Err(e)
+        }
+    }
+}
+
+/// Forgot Password
+#[utoipa::path(
+    post,
+    request_body = ForgotPasswordRequest,
+    path = "/auth/password/forgot",
+    responses(
+        (status = 200, description = "Reset email sent if the address is known", body = [MessageResponse]),
+        (status = 400, description = "Invalid data input", body = [AppResponseError]),
+        (status = 429, description = "Reset email sent recently", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    )
+)]
+pub async fn forgot_password(
+    Extension(state): Extension<AppState>,
+    Json(request): Json<ForgotPasswordRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("Forgot password with email: {}", request.email);
+    request.validate()?;
+    match service::user::forgot_password(&state, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new(
+            "Reset email is sent if the address is registered",
+        ))),
+        Err(e) => {
+            warn!("Failed to send reset email: {e:?}");
+            Err(e)
+        }
+    }
+}
+
+/// Reset Password
+#[utoipa::path(
+    post,
+    request_body = ResetPasswordRequest,
+    path = "/auth/password/reset",
+    responses(
+        (status = 200, description = "Password reset", body = [MessageResponse]),
+        (status = 400, description = "Invalid token or weak password", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    )
+)]
+pub async fn reset_password(
+    Extension(state): Extension<AppState>,
+    Json(request): Json<ResetPasswordRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("Reset password");
+    request.validate()?;
+    match service::user::reset_password(&state, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success reset password"))),
+        Err(e) => {
+            warn!("Failed to reset password: {e:?}");
+            Err(e)
+        }
+    }
+}
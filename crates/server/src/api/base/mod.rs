@@ -2,18 +2,95 @@ mod auth;
 pub mod openapi;
 pub mod user;
 
-use axum::routing::{get, post, put};
+use axum::routing::{delete, get, post, put};
 use axum::Router;
 
+use crate::{constant::SYSTEM_USER, entity::permission::Access};
+
 pub fn app() -> Router {
     Router::new()
         .route("/auth/register", post(auth::register))
+        .route("/auth/register/csv", post(auth::register_csv))
         .route("/auth/login", post(auth::login))
+        .route("/auth/login/mfa", post(auth::login_mfa))
+        .route("/auth/login/mfa/enroll", post(auth::login_mfa_enroll))
         .route("/auth/logout", get(auth::logout))
         .route("/auth/is-login", get(auth::is_login))
         .route("/auth/token/refresh", post(auth::token_refresh))
+        .route("/.well-known/jwks.json", get(auth::jwks))
+        .route("/auth/sso/oidc/authorize", get(auth::oidc_authorize))
+        .route("/auth/sso/oidc/callback", get(auth::oidc_callback))
+        .route("/auth/sso/ldap/login", post(auth::ldap_login))
+        .route("/auth/invitation/accept", post(auth::accept_invitation))
+        .route("/auth/password/forgot", post(auth::forgot_password))
+        .route("/auth/password/reset", post(auth::reset_password))
         .route("/user/info", get(user::info))
         .route("/user/info", put(user::update))
+        .route("/user/password", put(user::change_password))
+        .route("/user/sessions", get(user::list_sessions))
+        .route("/user/sessions/{sid}", delete(user::revoke_session))
+        .route("/user/mfa", delete(user::disable_mfa))
+        .route("/user/mfa/enroll", post(user::enroll_mfa))
+        .route("/user/mfa/confirm", post(user::confirm_mfa))
+        .route(
+            "/user/mfa/recovery-codes",
+            post(user::regenerate_recovery_codes),
+        )
+        .route(
+            "/user/notification/preferences",
+            get(user::list_notification_preferences).put(user::update_notification_preference),
+        )
+        .route(
+            "/user/notification/channels",
+            get(user::list_notification_channels).post(user::create_notification_channel),
+        )
+        .route(
+            "/user/notification/channels/{id}",
+            delete(user::delete_notification_channel),
+        )
         .route("/user/list/{project_id}", get(user::list))
         .route("/user/role/list/{project_id}", get(user::role_list))
+        .route(
+            "/user/api-token",
+            get(user::list_api_tokens)
+                .post(user::create_api_token)
+                .delete(user::revoke_api_token),
+        )
 }
+
+pub const PERMISSIONS: &[(&str, &str, Access)] = &[
+    ("POST", "/auth/register", Access::write(SYSTEM_USER)),
+    ("POST", "/auth/register/csv", Access::write(SYSTEM_USER)),
+    ("POST", "/auth/login", Access::Open),
+    ("POST", "/auth/login/mfa", Access::Open),
+    ("POST", "/auth/login/mfa/enroll", Access::Open),
+    ("GET", "/auth/logout", Access::Open),
+    ("GET", "/auth/is-login", Access::Open),
+    ("POST", "/auth/token/refresh", Access::Open),
+    ("GET", "/.well-known/jwks.json", Access::Open),
+    ("GET", "/auth/sso/oidc/authorize", Access::Open),
+    ("GET", "/auth/sso/oidc/callback", Access::Open),
+    ("POST", "/auth/sso/ldap/login", Access::Open),
+    ("POST", "/auth/invitation/accept", Access::Open),
+    ("POST", "/auth/password/forgot", Access::Open),
+    ("POST", "/auth/password/reset", Access::Open),
+    ("GET", "/user/info", Access::Open),
+    ("PUT", "/user/info", Access::Open),
+    ("PUT", "/user/password", Access::Open),
+    ("GET", "/user/sessions", Access::Open),
+    ("DELETE", "/user/sessions/{sid}", Access::Open),
+    ("DELETE", "/user/mfa", Access::Open),
+    ("POST", "/user/mfa/enroll", Access::Open),
+    ("POST", "/user/mfa/confirm", Access::Open),
+    ("POST", "/user/mfa/recovery-codes", Access::Open),
+    ("GET", "/user/notification/preferences", Access::Open),
+    ("PUT", "/user/notification/preferences", Access::Open),
+    ("GET", "/user/notification/channels", Access::Open),
+    ("POST", "/user/notification/channels", Access::Open),
+    ("DELETE", "/user/notification/channels/{id}", Access::Open),
+    ("GET", "/user/list/{project_id}", Access::Member),
+    ("GET", "/user/role/list/{project_id}", Access::Member),
+    ("GET", "/user/api-token", Access::Open),
+    ("POST", "/user/api-token", Access::Open),
+    ("DELETE", "/user/api-token", Access::Open),
+];
@@ -1,20 +1,42 @@
 use crate::{
     dto::{
-        request::{UserInfoUpdateRequest, UserQueryParam},
-        response::user::GetUserInfoResponse,
+        request::{
+            user::{
+                ChangePasswordRequest, CreateApiTokenRequest, CreateNotificationChannelRequest,
+                MfaCodeRequest, UpdateNotificationPreferenceRequest,
+            },
+            DeleteEntityRequest, UserInfoUpdateRequest, UserQueryParam,
+        },
+        response::{
+            user::{
+                CreateApiTokenResponse, GetUserInfoResponse, ListSessionResponse,
+                MfaEnrollResponse, RecoveryCodesResponse,
+            },
+            CreateEntityResponse, MessageResponse,
+        },
+    },
+    entity::{
+        api_token::ApiToken,
+        notification::{NotificationChannel, NotificationPreference},
+        user::{User, UserRoleOption},
     },
-    entity::user::{User, UserRoleOption},
     errors::{AppResponseError, AppResult},
     service,
     state::AppState,
-    utils::claim::UserClaims,
+    utils::{
+        claim::UserClaims,
+        header::{validate_project_id, ClientInfo},
+    },
 };
 
 use axum::{
     extract::{Path, Query},
+    http::HeaderMap,
     Extension, Json,
 };
+use garde::Validate;
 use tracing::{info, warn};
+use uuid::Uuid;
 
 #[utoipa::path(
     get,
@@ -63,6 +85,73 @@ pub async fn update(
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/user/password",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 200, description = "Success change password", body = [MessageResponse]),
+        (status = 400, description = "Wrong password or weak new password", body = [AppResponseError]),
+        (status = 401, description = "User Unauthorized", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn change_password(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Json(request): Json<ChangePasswordRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer change password of user: {}", user.uid);
+    request.validate()?;
+    match service::user::change_password(&state, user.uid, user.sid, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success change password"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/sessions",
+    responses(
+        (status = 200, description = "Active sessions of the user", body = [ListSessionResponse]),
+        (status = 401, description = "User Unauthorized", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn list_sessions(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+) -> AppResult<Json<ListSessionResponse>> {
+    match service::user::list_sessions(&state, user.uid, user.sid).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/user/sessions/{sid}",
+    params(
+        ("sid", description = "Session id"),
+    ),
+    responses(
+        (status = 200, description = "Success revoke session", body = [MessageResponse]),
+        (status = 401, description = "User Unauthorized", body = [AppResponseError]),
+        (status = 404, description = "Session not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn revoke_session(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Path(sid): Path<Uuid>,
+) -> AppResult<Json<MessageResponse>> {
+    match service::user::revoke_session(&state, user.uid, sid).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success revoke session"))),
+        Err(e) => Err(e),
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/user/list/:project_id",
@@ -90,12 +179,295 @@ pub async fn list(
 )]
 pub async fn role_list(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     Path(project_id): Path<i32>,
     _user: UserClaims,
 ) -> AppResult<Json<Vec<UserRoleOption>>> {
     info!("controller layer get user role list with project_id: {project_id}");
+    validate_project_id(&headers, project_id)?;
     match service::user::role_list(&state, project_id).await {
         Ok(resp) => Ok(Json(resp)),
         Err(e) => Err(e),
     }
 }
+
+#[utoipa::path(
+    post,
+    path = "/user/api-token",
+    request_body = CreateApiTokenRequest,
+    responses(
+        (status = 200, description = "Success create api token", body = [CreateApiTokenResponse]),
+        (status = 400, description = "Invalid expiry or permissions", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn create_api_token(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Json(request): Json<CreateApiTokenRequest>,
+) -> AppResult<Json<CreateApiTokenResponse>> {
+    info!("controller layer create api token with request: {request:?}");
+    request.validate()?;
+    match service::api_token::create(&state, user.uid, request).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/api-token",
+    responses(
+        (status = 200, description = "Success get api tokens", body = [Vec<ApiToken>]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn list_api_tokens(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+) -> AppResult<Json<Vec<ApiToken>>> {
+    match service::api_token::list(&state, user.uid).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/user/api-token",
+    request_body = DeleteEntityRequest,
+    responses(
+        (status = 200, description = "Success revoke api token", body = [MessageResponse]),
+        (status = 404, description = "Api token not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn revoke_api_token(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Json(request): Json<DeleteEntityRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer revoke api token with request: {request:?}");
+    match service::api_token::revoke(&state, user.uid, request.id).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success revoke api token"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/user/mfa/enroll",
+    responses(
+        (status = 200, description = "Secret and recovery codes, confirm with a code to enable", body = [MfaEnrollResponse]),
+        (status = 400, description = "Mfa already enabled", body = [AppResponseError]),
+        (status = 401, description = "User Unauthorized", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn enroll_mfa(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+) -> AppResult<Json<MfaEnrollResponse>> {
+    info!("controller layer enroll mfa of user: {}", user.uid);
+    match service::mfa::enroll(&state, user.uid).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/user/mfa/confirm",
+    request_body = MfaCodeRequest,
+    responses(
+        (status = 200, description = "Success enable mfa", body = [MessageResponse]),
+        (status = 400, description = "Invalid code or no pending enrollment", body = [AppResponseError]),
+        (status = 401, description = "User Unauthorized", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn confirm_mfa(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    client_info: ClientInfo,
+    Json(request): Json<MfaCodeRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer confirm mfa of user: {}", user.uid);
+    request.validate()?;
+    match service::mfa::confirm(&state, user.uid, request, &client_info).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success enable mfa"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/user/mfa",
+    request_body = MfaCodeRequest,
+    responses(
+        (status = 200, description = "Success disable mfa", body = [MessageResponse]),
+        (status = 400, description = "Invalid code or mfa not enabled", body = [AppResponseError]),
+        (status = 401, description = "User Unauthorized", body = [AppResponseError]),
+        (status = 403, description = "Mfa required by a role of the user", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn disable_mfa(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    client_info: ClientInfo,
+    Json(request): Json<MfaCodeRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer disable mfa of user: {}", user.uid);
+    request.validate()?;
+    match service::mfa::disable(&state, user.uid, request, &client_info).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success disable mfa"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/user/mfa/recovery-codes",
+    request_body = MfaCodeRequest,
+    responses(
+        (status = 200, description = "New recovery codes, the previous ones stop working", body = [RecoveryCodesResponse]),
+        (status = 400, description = "Invalid code or mfa not enabled", body = [AppResponseError]),
+        (status = 401, description = "User Unauthorized", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn regenerate_recovery_codes(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    client_info: ClientInfo,
+    Json(request): Json<MfaCodeRequest>,
+) -> AppResult<Json<RecoveryCodesResponse>> {
+    info!(
+        "controller layer regenerate recovery codes of user: {}",
+        user.uid
+    );
+    request.validate()?;
+    match service::mfa::regenerate_recovery_codes(&state, user.uid, request, &client_info).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/notification/preferences",
+    responses(
+        (status = 200, description = "Channels notified of each event type", body = [Vec<NotificationPreference>]),
+        (status = 401, description = "User Unauthorized", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn list_notification_preferences(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+) -> AppResult<Json<Vec<NotificationPreference>>> {
+    match service::notification::list_preferences(&state, user.uid).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/user/notification/preferences",
+    request_body = UpdateNotificationPreferenceRequest,
+    responses(
+        (status = 200, description = "Success update notification preference", body = [MessageResponse]),
+        (status = 400, description = "Channel not found", body = [AppResponseError]),
+        (status = 401, description = "User Unauthorized", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn update_notification_preference(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Json(request): Json<UpdateNotificationPreferenceRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer update notification preference with request: {request:?}");
+    request.validate()?;
+    match service::notification::update_preference(&state, user.uid, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new(
+            "Success update notification preference",
+        ))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/notification/channels",
+    responses(
+        (status = 200, description = "Success get notification channels", body = [Vec<NotificationChannel>]),
+        (status = 401, description = "User Unauthorized", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn list_notification_channels(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+) -> AppResult<Json<Vec<NotificationChannel>>> {
+    match service::notification::list_channels(&state, user.uid).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/user/notification/channels",
+    request_body = CreateNotificationChannelRequest,
+    responses(
+        (status = 200, description = "Success create notification channel", body = [CreateEntityResponse]),
+        (status = 400, description = "Invalid channel type or url", body = [AppResponseError]),
+        (status = 401, description = "User Unauthorized", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn create_notification_channel(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Json(request): Json<CreateNotificationChannelRequest>,
+) -> AppResult<Json<CreateEntityResponse>> {
+    info!(
+        "controller layer create notification channel: {}",
+        request.name
+    );
+    request.validate()?;
+    match service::notification::create_channel(&state, user.uid, request).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/user/notification/channels/{id}",
+    params(
+        ("id", description = "Notification channel id"),
+    ),
+    responses(
+        (status = 200, description = "Success delete notification channel", body = [MessageResponse]),
+        (status = 401, description = "User Unauthorized", body = [AppResponseError]),
+        (status = 404, description = "Notification channel not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn delete_notification_channel(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Path(id): Path<i32>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer delete notification channel: {id}");
+    match service::notification::delete_channel(&state, user.uid, id).await {
+        Ok(_) => Ok(Json(MessageResponse::new(
+            "Success delete notification channel",
+        ))),
+        Err(e) => Err(e),
+    }
+}
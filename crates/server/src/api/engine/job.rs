@@ -0,0 +1,56 @@
+use axum::{extract::Path, Extension, Json};
+use tracing::info;
+
+use crate::{
+    dto::response::MessageResponse,
+    entity::job::BackgroundJob,
+    errors::{AppResponseError, AppResult},
+    service::job,
+    state::AppState,
+    utils::claim::UserClaims,
+};
+
+#[utoipa::path(
+    get,
+    path = "/engine/jobs/{job_id}",
+    responses(
+        (status = 200, description = "Success get job", body = [BackgroundJob]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "Job not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn info(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Path(job_id): Path<i32>,
+) -> AppResult<Json<BackgroundJob>> {
+    info!("controller layer get job with job_id: {job_id}");
+    match job::get_job(&state, user.uid, job_id).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/engine/jobs/{job_id}/cancel",
+    responses(
+        (status = 200, description = "Success cancel job", body = [MessageResponse]),
+        (status = 400, description = "Job already finished", body = [AppResponseError]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "Job not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn cancel(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Path(job_id): Path<i32>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer cancel job with job_id: {job_id}");
+    match job::cancel(&state, user.uid, job_id).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success cancel job"))),
+        Err(e) => Err(e),
+    }
+}
@@ -1,5 +1,18 @@
+use axum::routing::{get, post};
 use axum::Router;
 
+use crate::entity::permission::Access;
+
+mod job;
+
 pub fn app() -> Router {
     Router::new()
+        .route("/jobs/{job_id}", get(job::info))
+        .route("/jobs/{job_id}/cancel", post(job::cancel))
 }
+
+/* jobs are only visible to their creator, checked in the service layer */
+pub const PERMISSIONS: &[(&str, &str, Access)] = &[
+    ("GET", "/jobs/{job_id}", Access::Member),
+    ("POST", "/jobs/{job_id}/cancel", Access::Member),
+];
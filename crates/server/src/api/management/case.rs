@@ -13,23 +13,27 @@ use crate::{
         request::{
             case::{
                 CreateFieldRequest, CreateFunctionalCaseRequest, DeleteFieldRequest,
-                QueryFieldParam, UpdateFieldRequest, UpdateFunctionalCaseRequest,
+                QueryFieldParam, RequirementInfoParam, UpdateFieldRequest,
+                UpdateFunctionalCaseRequest,
             },
             file::{
                 CreateModuleRequest, DeleteModuleRequest, QueryModuleParam, UpdateModuleRequest,
             },
             CaseQueryParam, CreateScriptRequest, DeleteEntityRequest, DiagnoseRequest,
-            IssueRelationRequest, ListQueryParam, QueryTemplateParam,
+            IssueRelationRequest, ListQueryParam, QueryTemplateParam, ReportIssueRequest,
         },
         response::{
             case::{FunctionalCaseResponse, GetTemplateResponse, ListFunctionalCaseResponse},
-            CreateEntityResponse, CreateScriptResponse, DiagnoseResponse, FileModuleResponse,
-            MessageResponse, RequirementInfoResponse,
+            CreateEntityResponse, FileModuleResponse, MessageResponse, RequirementInfoResponse,
         },
     },
-    entity::{case::Field, file::ModuleType},
+    entity::{
+        case::{Field, IssueRelation},
+        file::ModuleType,
+        job::JobKind,
+    },
     errors::{AppError, AppResponseError, AppResult},
-    service::{self, case, file},
+    service::{self, case, file, job},
     state::AppState,
     utils::{
         claim::UserClaims,
@@ -101,11 +105,13 @@ pub async fn create_module(
 )]
 pub async fn update_module(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     user: UserClaims,
     Json(request): Json<UpdateModuleRequest>,
 ) -> AppResult<Json<MessageResponse>> {
     info!("case controller layer update module with {request:?}");
-    match file::update_file_module(&state, user.uid, ModuleType::Case, request).await {
+    let project_id = extract_project_id(&headers)?;
+    match file::update_file_module(&state, user.uid, project_id, ModuleType::Case, request).await {
         Ok(_) => Ok(Json(MessageResponse::new("success update module"))),
         Err(e) => Err(e),
     }
@@ -121,11 +127,13 @@ pub async fn update_module(
 )]
 pub async fn delete_module(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     user: UserClaims,
     Json(request): Json<DeleteModuleRequest>,
 ) -> AppResult<Json<MessageResponse>> {
     info!("controller layer delete case module with module_id: {request:?}",);
-    match service::case::delete_by_module_id(&state, user.uid, request.id).await {
+    let project_id = extract_project_id(&headers)?;
+    match service::case::delete_by_module_id(&state, user.uid, project_id, request.id).await {
         Ok(_) => Ok(Json(MessageResponse::new("Success delete case module"))),
         Err(e) => Err(e),
     }
@@ -168,12 +176,14 @@ pub async fn get_template(
 )]
 pub async fn create_functional_case(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     user: UserClaims,
     Json(request): Json<CreateFunctionalCaseRequest>,
 ) -> AppResult<Json<CreateEntityResponse>> {
     info!("create functional case with request: {request:?}");
     request.validate()?;
-    match case::create_functional_case(&state, user.uid, request).await {
+    let project_id = extract_project_id(&headers)?;
+    match case::create_functional_case(&state, user.uid, project_id, request).await {
         Ok(resp) => Ok(Json(CreateEntityResponse { id: resp })),
         Err(e) => Err(e),
     }
@@ -190,11 +200,13 @@ pub async fn create_functional_case(
 )]
 pub async fn get_functional_case(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     Path(case_id): Path<i32>,
     _user: UserClaims,
 ) -> AppResult<Json<FunctionalCaseResponse>> {
     info!("query functional case with path case_id: {case_id:?}");
-    match case::get_functional_case(&state, case_id).await {
+    let project_id = extract_project_id(&headers)?;
+    match case::get_functional_case(&state, project_id, case_id).await {
         Ok(resp) => Ok(Json(resp)),
         Err(e) => Err(e),
     }
@@ -250,18 +262,98 @@ pub async fn delete_functional_case(
 
 #[utoipa::path(
     post,
-    path = "case/functional-case/related-issue",
+    path = "/management/case/functional-case/issue-relation",
     request_body = IssueRelationRequest,
-    responses(),
+    responses(
+        (status = 200, description = "Success link issues with case", body = [MessageResponse]),
+        (status = 400, description = "Unknown or unconfigured issue tracker", body = [AppResponseError]),
+        (status = 404, description = "Case or issue not found", body = [AppResponseError]),
+        (status = 409, description = "Issue already linked", body = [AppResponseError]),
+    ),
     security(("jwt" = []))
 )]
 pub async fn create_issue_relation(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     user: UserClaims,
     Json(request): Json<IssueRelationRequest>,
-) -> AppResult {
-    match case::create_issue_relation(&state, user.uid, request).await {
-        Ok(_) => Ok(()),
+) -> AppResult<Json<MessageResponse>> {
+    info!("case controller layer create issue relation with {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match case::create_issue_relation(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success link issues"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/management/case/functional-case/issue-relation/{case_id}",
+    responses(
+        (status = 200, description = "Success get issue relation list", body = [Vec<IssueRelation>]),
+        (status = 404, description = "Case not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_issue_relation_list(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    Path(case_id): Path<i32>,
+    _user: UserClaims,
+) -> AppResult<Json<Vec<IssueRelation>>> {
+    info!("case controller layer query issue relation with case_id: {case_id}");
+    let project_id = extract_project_id(&headers)?;
+    match case::get_issue_relation_list(&state, project_id, case_id).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/management/case/functional-case/issue-relation",
+    request_body = DeleteEntityRequest,
+    responses(
+        (status = 200, description = "Success remove issue relation", body = [MessageResponse]),
+        (status = 404, description = "Issue relation not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn delete_issue_relation(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<DeleteEntityRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("case controller layer delete issue relation with {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match case::delete_issue_relation(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success remove issue relation"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/management/case/functional-case/issue-relation/report",
+    request_body = ReportIssueRequest,
+    responses(
+        (status = 200, description = "Success create issue from execute record", body = [CreateEntityResponse]),
+        (status = 400, description = "Record not failed or already reported", body = [AppResponseError]),
+        (status = 404, description = "Execute record not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn report_issue(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<ReportIssueRequest>,
+) -> AppResult<Json<CreateEntityResponse>> {
+    info!("case controller layer report issue with {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match case::report_issue(&state, user.uid, project_id, request).await {
+        Ok(resp) => Ok(Json(resp)),
         Err(e) => Err(e),
     }
 }
@@ -358,9 +450,10 @@ pub async fn get_field_list(
 
 #[utoipa::path(
     get,
-    path = "/case/info/requirement",
+    path = "/management/case/info/requirement",
+    params(RequirementInfoParam),
     responses(
-        (status = 200, description = "Get related pr info"),
+        (status = 200, description = "Get requirement coverage info", body = [RequirementInfoResponse]),
         (status = 401, description = "Unauthorized user", body = [AppResponseError]),
         (status = 404, description = "case tree not found", body = [AppResponseError]),
         (status = 500, description = "Internal server error", body = [AppResponseError]),
@@ -370,10 +463,11 @@ pub async fn get_field_list(
 pub async fn info(
     Extension(state): Extension<AppState>,
     headers: HeaderMap,
+    Query(param): Query<RequirementInfoParam>,
 ) -> AppResult<Json<RequirementInfoResponse>> {
-    info!("case controller layer query related information");
+    info!("case controller layer query requirement coverage with param: {param:?}");
     let project_id = extract_project_id(&headers)?;
-    match case::info(&state, &project_id).await {
+    match service::requirement::info(&state, &project_id, param).await {
         Ok(resp) => Ok(Json(resp)),
         Err(e) => Err(e),
     }
@@ -433,37 +527,47 @@ pub async fn count(
     }
 }
 
+/* generated in background, poll `/engine/jobs/{job_id}` for the script */
 #[utoipa::path(
     post,
     path = "/management/case/script/generate",
     request_body=CreateScriptRequest,
-    responses()
+    responses(
+        (status = 200, description = "Script generation job created", body = [CreateEntityResponse]),
+    )
 )]
 pub async fn create_script(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     user: UserClaims,
     WithRejection(Json(request), _): WithRejection<Json<CreateScriptRequest>, AppError>,
-) -> AppResult<Json<CreateScriptResponse>> {
+) -> AppResult<Json<CreateEntityResponse>> {
     info!("controller layer create script with request: {request:?}");
-    match service::case::gen_script(&state, user.uid, request).await {
-        Ok(resp) => Ok(Json(resp)),
+    let project_id = extract_project_id(&headers)?;
+    case::check_script_request(&state, project_id, &request).await?;
+    match job::enqueue(&state, user.uid, JobKind::GenerateScript, &request).await {
+        Ok(id) => Ok(Json(CreateEntityResponse { id })),
         Err(e) => Err(e),
     }
 }
 
+/* diagnosed in background, poll `/engine/jobs/{job_id}` for the output */
 #[utoipa::path(
     post,
     path = "/management/case/environment/diagnose",
     request_body=DiagnoseRequest,
-    responses()
+    responses(
+        (status = 200, description = "Diagnose job created", body = [CreateEntityResponse]),
+    )
 )]
 pub async fn env_diagnose(
     Extension(state): Extension<AppState>,
+    user: UserClaims,
     WithRejection(Json(request), _): WithRejection<Json<DiagnoseRequest>, AppError>,
-) -> AppResult<Json<DiagnoseResponse>> {
+) -> AppResult<Json<CreateEntityResponse>> {
     info!("controller layer diagnose environment with request");
-    match service::case::env_diagnose(&state, request).await {
-        Ok(resp) => Ok(Json(resp)),
+    match job::enqueue(&state, user.uid, JobKind::Diagnose, &request).await {
+        Ok(id) => Ok(Json(CreateEntityResponse { id })),
         Err(e) => Err(e),
     }
 }
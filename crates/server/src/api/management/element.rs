@@ -1,20 +1,31 @@
 use std::collections::HashMap;
 
 use crate::{
+    dao::entity::{ElementDetail, ElementReference, OperationOption},
     dto::{
         request::{
-            file::QueryModuleParam, CreateElementRequest, ElementQueryParam, ListQueryParam,
+            file::QueryModuleParam, CreateElementRequest, CreateOperationOptionRequest,
+            DeleteElementRequest, DeleteEntityRequest, ElementQueryParam, HealthCheckRequest,
+            ListQueryParam, MoveElementRequest, OperationOptionBindingRequest,
+            OperationOptionQueryParam, UpdateElementRequest, UpdateOperationOptionRequest,
+        },
+        response::{
+            CreateEntityResponse, FileModuleResponse, HealthCheckResponse, ListElementResponse,
+            MessageResponse,
         },
-        response::{FileModuleResponse, ListElementResponse},
     },
     entity::file::ModuleType,
-    errors::AppResult,
+    errors::{AppResponseError, AppResult},
     service::{element, file},
     state::AppState,
-    utils::claim::UserClaims,
+    utils::{
+        claim::UserClaims,
+        header::{extract_project_id, validate_project_id},
+    },
 };
 use axum::{
     extract::{Path, Query},
+    http::HeaderMap,
     Extension, Json,
 };
 use tracing::{info, warn};
@@ -24,25 +35,170 @@ use tracing::{info, warn};
     path = "/management/element",
     request_body = CreateElementRequest,
     responses(
-        (status = 200, description = "Element created")
+        (status = 200, description = "Element created", body = [CreateEntityResponse]),
+        (status = 400, description = "Module is not an element module", body = [AppResponseError]),
+        (status = 409, description = "Selector value already exists in module", body = [AppResponseError]),
     ),
     security(("jwt" = []))
 )]
 pub async fn create(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     user: UserClaims,
     Json(request): Json<CreateElementRequest>,
-) -> AppResult {
+) -> AppResult<Json<CreateEntityResponse>> {
     info!("controller layer create element with request: {request:?}");
-    match element::create(&state, user.uid, request).await {
-        Ok(resp) => Ok(resp),
+    let project_id = extract_project_id(&headers)?;
+    match element::create(&state, user.uid, project_id, request).await {
+        Ok(id) => Ok(Json(CreateEntityResponse { id })),
         Err(e) => Err(e),
     }
 }
 
-pub async fn info(Extension(_state): Extension<AppState>) -> AppResult<Json<()>> {
-    info!("controller layer query element information with params");
-    Ok(Json(()))
+#[utoipa::path(
+    get,
+    path = "/management/element/{element_id}",
+    responses(
+        (status = 200, description = "Get element information", body = [ElementDetail]),
+        (status = 404, description = "Element not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn info(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    Path(element_id): Path<i32>,
+    _user: UserClaims,
+) -> AppResult<Json<ElementDetail>> {
+    info!("controller layer query element information with id: {element_id}");
+    let project_id = extract_project_id(&headers)?;
+    match element::info(&state, project_id, element_id).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/management/element",
+    request_body = UpdateElementRequest,
+    responses(
+        (status = 200, description = "Success update element", body = [MessageResponse]),
+        (status = 404, description = "Element not found", body = [AppResponseError]),
+        (status = 409, description = "Selector value already exists in module", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn update(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<UpdateElementRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer update element with request: {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match element::update(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success update element"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/management/element/module",
+    request_body = MoveElementRequest,
+    responses(
+        (status = 200, description = "Success move element", body = [MessageResponse]),
+        (status = 400, description = "Module is not an element module", body = [AppResponseError]),
+        (status = 404, description = "Element or module not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn move_to(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<MoveElementRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer move element with request: {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match element::move_to(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success move element"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/management/element",
+    request_body = DeleteElementRequest,
+    responses(
+        (status = 200, description = "Success delete element", body = [MessageResponse]),
+        (status = 400, description = "Element referenced by scripts", body = [AppResponseError]),
+        (status = 404, description = "Element not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn delete(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<DeleteElementRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer delete element with request: {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match element::delete(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success delete element"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/management/element/restore",
+    request_body = DeleteEntityRequest,
+    responses(
+        (status = 200, description = "Success restore element", body = [MessageResponse]),
+        (status = 404, description = "Element not found", body = [AppResponseError]),
+        (status = 409, description = "Selector value already exists in module", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn restore(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<DeleteEntityRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer restore element with request: {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match element::restore(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success restore element"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/management/element/{element_id}/reference",
+    responses(
+        (status = 200, description = "Scripts referencing the element", body = [Vec<ElementReference>]),
+        (status = 404, description = "Element not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn references(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    Path(element_id): Path<i32>,
+    _user: UserClaims,
+) -> AppResult<Json<Vec<ElementReference>>> {
+    info!("controller layer query element references with id: {element_id}");
+    let project_id = extract_project_id(&headers)?;
+    match element::references(&state, project_id, element_id).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
 }
 
 #[utoipa::path(
@@ -53,6 +209,7 @@ pub async fn info(Extension(_state): Extension<AppState>) -> AppResult<Json<()>>
 )]
 pub async fn tree(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     Path(project_id): Path<i32>,
     Query(params): Query<QueryModuleParam>,
 ) -> AppResult<Json<Vec<FileModuleResponse>>> {
@@ -60,6 +217,7 @@ pub async fn tree(
         "controller layer query element list with params: {}",
         project_id
     );
+    validate_project_id(&headers, project_id)?;
     match file::get_file_module(&state, &project_id, ModuleType::Element, params).await {
         Ok(resp) => Ok(Json(resp)),
         Err(e) => {
@@ -77,6 +235,7 @@ pub async fn tree(
 )]
 pub async fn list(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     Path(project_id): Path<i32>,
     Query(param): Query<ListQueryParam>,
 ) -> AppResult<Json<ListElementResponse>> {
@@ -84,6 +243,7 @@ pub async fn list(
         "controller layer query element list with params: {}",
         project_id
     );
+    validate_project_id(&headers, project_id)?;
     match element::get_element_list(&state, &project_id, param).await {
         Ok(resp) => Ok(Json(resp)),
         Err(e) => {
@@ -102,12 +262,202 @@ pub async fn list(
 )]
 pub async fn count(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     Path(project_id): Path<i32>,
     Query(param): Query<ElementQueryParam>,
 ) -> AppResult<Json<HashMap<String, i64>>> {
     info!("controller layer element count group by module in project: {project_id:?}");
+    validate_project_id(&headers, project_id)?;
     match element::count(&state, &project_id, &param).await {
         Ok(resp) => Ok(Json(resp)),
         Err(e) => Err(e),
     }
 }
+
+#[utoipa::path(
+    post,
+    path = "/management/element/operation-option",
+    request_body = CreateOperationOptionRequest,
+    responses(
+        (status = 200, description = "Operation option created", body = [CreateEntityResponse]),
+        (status = 409, description = "Operation exec already exists", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn create_operation_option(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Json(request): Json<CreateOperationOptionRequest>,
+) -> AppResult<Json<CreateEntityResponse>> {
+    info!("controller layer create operation option with request: {request:?}");
+    match element::create_operation_option(&state, user.uid, request).await {
+        Ok(id) => Ok(Json(CreateEntityResponse { id })),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/management/element/operation-option",
+    params(OperationOptionQueryParam),
+    responses(
+        (status = 200, description = "Get operation options", body = [Vec<OperationOption>]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_operation_options(
+    Extension(state): Extension<AppState>,
+    _user: UserClaims,
+    Query(param): Query<OperationOptionQueryParam>,
+) -> AppResult<Json<Vec<OperationOption>>> {
+    info!("controller layer query operation options with param: {param:?}");
+    match element::get_operation_options(&state, param).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/management/element/operation-option",
+    request_body = UpdateOperationOptionRequest,
+    responses(
+        (status = 200, description = "Success update operation option", body = [MessageResponse]),
+        (status = 400, description = "Internal operation can not be modified", body = [AppResponseError]),
+        (status = 404, description = "Operation option not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn update_operation_option(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Json(request): Json<UpdateOperationOptionRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer update operation option with request: {request:?}");
+    match element::update_operation_option(&state, user.uid, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new(
+            "Success update operation option",
+        ))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/management/element/operation-option",
+    request_body = DeleteEntityRequest,
+    responses(
+        (status = 200, description = "Success delete operation option", body = [MessageResponse]),
+        (status = 400, description = "Operation is internal or referenced by scripts", body = [AppResponseError]),
+        (status = 404, description = "Operation option not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn delete_operation_option(
+    Extension(state): Extension<AppState>,
+    _user: UserClaims,
+    Json(request): Json<DeleteEntityRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer delete operation option with request: {request:?}");
+    match element::delete_operation_option(&state, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new(
+            "Success delete operation option",
+        ))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/management/element/operation-option/binding",
+    request_body = OperationOptionBindingRequest,
+    responses(
+        (status = 200, description = "Success bind operation options to element type", body = [MessageResponse]),
+        (status = 404, description = "Operation option not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn bind_operation_options(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Json(request): Json<OperationOptionBindingRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer bind operation options with request: {request:?}");
+    match element::bind_operation_options(&state, user.uid, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new(
+            "Success bind operation options to element type",
+        ))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/management/element/operation-option/binding",
+    request_body = OperationOptionBindingRequest,
+    responses(
+        (status = 200, description = "Success unbind operation options from element type", body = [MessageResponse]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn unbind_operation_options(
+    Extension(state): Extension<AppState>,
+    _user: UserClaims,
+    Json(request): Json<OperationOptionBindingRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer unbind operation options with request: {request:?}");
+    match element::unbind_operation_options(&state, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new(
+            "Success unbind operation options from element type",
+        ))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/management/element/health-check",
+    request_body = HealthCheckRequest,
+    responses(
+        (status = 200, description = "Element health check started", body = [CreateEntityResponse]),
+        (status = 400, description = "Module is not an element module", body = [AppResponseError]),
+        (status = 404, description = "Module or machine not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn health_check(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<HealthCheckRequest>,
+) -> AppResult<Json<CreateEntityResponse>> {
+    info!("controller layer create element health check with request: {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match element::health_check(&state, user.uid, project_id, request).await {
+        Ok(id) => Ok(Json(CreateEntityResponse { id })),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/management/element/health-check/{check_id}",
+    responses(
+        (status = 200, description = "Get element health check results", body = [HealthCheckResponse]),
+        (status = 404, description = "Health check not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_health_check(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    Path(check_id): Path<i32>,
+    _user: UserClaims,
+) -> AppResult<Json<HealthCheckResponse>> {
+    info!("controller layer query element health check with id: {check_id}");
+    let project_id = extract_project_id(&headers)?;
+    match element::get_health_check(&state, project_id, check_id).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
@@ -1,20 +1,43 @@
 use axum::routing::{delete, get, post, put};
 use axum::Router;
 
+use crate::{
+    constant::{
+        MANAGEMENT_ELEMENT, MANAGEMENT_FUNCTIONAL_CASE, MANAGEMENT_PROJECT, MANAGEMENT_REQUIREMENT,
+        MANAGEMENT_TEST_PLAN, SYSTEM_PROJECT,
+    },
+    entity::permission::Access,
+};
+
 mod case;
 mod element;
 mod plan;
 mod project;
+mod requirement;
 
 pub fn app() -> Router {
     Router::new()
         .route("/project/{project_id}", get(project::info))
-        .route("/project", get(project::get_project_list))
+        .route(
+            "/project",
+            get(project::get_project_list)
+                .post(project::create)
+                .put(project::update)
+                .delete(project::delete),
+        )
+        .route("/project/module-setting", put(project::update_module_setting))
+        .route("/project/archive", put(project::archive))
         .route(
             "/project/has-permission/{project_id}",
             get(project::permission),
         )
         .route("/project/member/list/{project_id}", get(project::members))
+        .route(
+            "/project/member",
+            post(project::add_member)
+                .put(project::update_member)
+                .delete(project::remove_member),
+        )
         .route("/case/module", get(case::get_module_list))
         .route("/case/module", put(case::update_module))
         .route("/case/module", post(case::create_module))
@@ -40,11 +63,55 @@ pub fn app() -> Router {
             "/case/functional-case/issue-relation",
             post(case::create_issue_relation),
         )
+        .route(
+            "/case/functional-case/issue-relation",
+            delete(case::delete_issue_relation),
+        )
+        .route(
+            "/case/functional-case/issue-relation/{case_id}",
+            get(case::get_issue_relation_list),
+        )
+        .route(
+            "/case/functional-case/issue-relation/report",
+            post(case::report_issue),
+        )
         .route("/case/script/generate", post(case::create_script))
         .route("/case/environment/diagnose", post(case::env_diagnose))
         .route("/case/info/requirement", get(case::info))
-        .route("/element", post(element::create))
+        .route("/requirement", post(requirement::create))
+        .route("/requirement", get(requirement::list))
+        .route("/requirement", put(requirement::update))
+        .route("/requirement", delete(requirement::delete))
+        .route("/requirement/import", post(requirement::import))
+        .route("/requirement/{requirement_id}", get(requirement::info))
+        .route("/requirement/case", post(requirement::link_cases))
+        .route("/requirement/case", delete(requirement::unlink_cases))
+        .route(
+            "/element",
+            post(element::create)
+                .put(element::update)
+                .delete(element::delete),
+        )
+        .route("/element/module", put(element::move_to))
+        .route("/element/health-check", post(element::health_check))
+        .route(
+            "/element/health-check/{check_id}",
+            get(element::get_health_check),
+        )
+        .route(
+            "/element/operation-option",
+            post(element::create_operation_option)
+                .get(element::get_operation_options)
+                .put(element::update_operation_option)
+                .delete(element::delete_operation_option),
+        )
+        .route(
+            "/element/operation-option/binding",
+            post(element::bind_operation_options).delete(element::unbind_operation_options),
+        )
+        .route("/element/restore", put(element::restore))
         .route("/element/{element_id}", get(element::info))
+        .route("/element/{element_id}/reference", get(element::references))
         .route("/element/module/tree/{project}", get(element::tree))
         .route("/element/list/{project_id}", get(element::list))
         .route("/element/count/{project_id}", get(element::count))
@@ -53,4 +120,104 @@ pub fn app() -> Router {
         .route("/test-plan/module/count/{project_id}", get(plan::count))
         .route("/test-plan/module", post(plan::create_module))
         .route("/test-plan/list/{project_id}", get(plan::list))
+        .route(
+            "/test-plan/{plan_id}/schedule",
+            get(plan::get_schedule)
+                .put(plan::save_schedule)
+                .delete(plan::delete_schedule),
+        )
+        .route("/test-plan/{plan_id}/schedule/run", post(plan::run_now))
+        .route("/test-plan/{plan_id}/schedule/runs", get(plan::runs))
 }
+
+const SYSTEM_PROJECT_WRITE: Access = Access::write(SYSTEM_PROJECT);
+const PROJECT_WRITE: Access = Access::write(MANAGEMENT_PROJECT);
+const CASE_READ: Access = Access::read(MANAGEMENT_FUNCTIONAL_CASE);
+const CASE_WRITE: Access = Access::write(MANAGEMENT_FUNCTIONAL_CASE);
+const REQUIREMENT_READ: Access = Access::read(MANAGEMENT_REQUIREMENT);
+const REQUIREMENT_WRITE: Access = Access::write(MANAGEMENT_REQUIREMENT);
+const ELEMENT_READ: Access = Access::read(MANAGEMENT_ELEMENT);
+const ELEMENT_WRITE: Access = Access::write(MANAGEMENT_ELEMENT);
+const PLAN_READ: Access = Access::read(MANAGEMENT_TEST_PLAN);
+const PLAN_WRITE: Access = Access::write(MANAGEMENT_TEST_PLAN);
+
+pub const PERMISSIONS: &[(&str, &str, Access)] = &[
+    ("GET", "/project/{project_id}", Access::Member),
+    ("GET", "/project", Access::Open),
+    ("POST", "/project", SYSTEM_PROJECT_WRITE),
+    ("PUT", "/project", PROJECT_WRITE),
+    ("DELETE", "/project", SYSTEM_PROJECT_WRITE),
+    ("PUT", "/project/module-setting", PROJECT_WRITE),
+    ("PUT", "/project/archive", SYSTEM_PROJECT_WRITE),
+    ("GET", "/project/has-permission/{project_id}", Access::Open),
+    ("GET", "/project/member/list/{project_id}", Access::Member),
+    ("POST", "/project/member", PROJECT_WRITE),
+    ("PUT", "/project/member", PROJECT_WRITE),
+    ("DELETE", "/project/member", PROJECT_WRITE),
+    ("GET", "/case/module", CASE_READ),
+    ("PUT", "/case/module", CASE_WRITE),
+    ("POST", "/case/module", CASE_WRITE),
+    ("DELETE", "/case/module", CASE_WRITE),
+    ("GET", "/case/count", CASE_READ),
+    ("GET", "/case/functional-case/template", CASE_READ),
+    ("GET", "/case/field/{project_id}", CASE_READ),
+    ("POST", "/case/field", CASE_WRITE),
+    ("PUT", "/case/field", CASE_WRITE),
+    ("DELETE", "/case/field", CASE_WRITE),
+    ("POST", "/case/functional-case", CASE_WRITE),
+    ("GET", "/case/functional-case", CASE_READ),
+    ("GET", "/case/functional-case/{case_id}", CASE_READ),
+    ("PUT", "/case/functional-case", CASE_WRITE),
+    ("DELETE", "/case/functional-case", CASE_WRITE),
+    ("POST", "/case/functional-case/issue-relation", CASE_WRITE),
+    ("DELETE", "/case/functional-case/issue-relation", CASE_WRITE),
+    (
+        "GET",
+        "/case/functional-case/issue-relation/{case_id}",
+        CASE_READ,
+    ),
+    (
+        "POST",
+        "/case/functional-case/issue-relation/report",
+        CASE_WRITE,
+    ),
+    ("POST", "/case/script/generate", CASE_WRITE),
+    ("POST", "/case/environment/diagnose", CASE_WRITE),
+    ("GET", "/case/info/requirement", CASE_READ),
+    ("POST", "/requirement", REQUIREMENT_WRITE),
+    ("GET", "/requirement", REQUIREMENT_READ),
+    ("PUT", "/requirement", REQUIREMENT_WRITE),
+    ("DELETE", "/requirement", REQUIREMENT_WRITE),
+    ("POST", "/requirement/import", REQUIREMENT_WRITE),
+    ("GET", "/requirement/{requirement_id}", REQUIREMENT_READ),
+    ("POST", "/requirement/case", REQUIREMENT_WRITE),
+    ("DELETE", "/requirement/case", REQUIREMENT_WRITE),
+    ("POST", "/element", ELEMENT_WRITE),
+    ("PUT", "/element", ELEMENT_WRITE),
+    ("DELETE", "/element", ELEMENT_WRITE),
+    ("PUT", "/element/module", ELEMENT_WRITE),
+    ("POST", "/element/health-check", ELEMENT_WRITE),
+    ("GET", "/element/health-check/{check_id}", ELEMENT_READ),
+    ("POST", "/element/operation-option", ELEMENT_WRITE),
+    ("GET", "/element/operation-option", ELEMENT_READ),
+    ("PUT", "/element/operation-option", ELEMENT_WRITE),
+    ("DELETE", "/element/operation-option", ELEMENT_WRITE),
+    ("POST", "/element/operation-option/binding", ELEMENT_WRITE),
+    ("DELETE", "/element/operation-option/binding", ELEMENT_WRITE),
+    ("PUT", "/element/restore", ELEMENT_WRITE),
+    ("GET", "/element/{element_id}", ELEMENT_READ),
+    ("GET", "/element/{element_id}/reference", ELEMENT_READ),
+    ("GET", "/element/module/tree/{project}", ELEMENT_READ),
+    ("GET", "/element/list/{project_id}", ELEMENT_READ),
+    ("GET", "/element/count/{project_id}", ELEMENT_READ),
+    ("POST", "/test-plan", PLAN_WRITE),
+    ("GET", "/test-plan/module/tree/{project_id}", PLAN_READ),
+    ("GET", "/test-plan/module/count/{project_id}", PLAN_READ),
+    ("POST", "/test-plan/module", PLAN_WRITE),
+    ("GET", "/test-plan/list/{project_id}", PLAN_READ),
+    ("GET", "/test-plan/{plan_id}/schedule", PLAN_READ),
+    ("PUT", "/test-plan/{plan_id}/schedule", PLAN_WRITE),
+    ("DELETE", "/test-plan/{plan_id}/schedule", PLAN_WRITE),
+    ("POST", "/test-plan/{plan_id}/schedule/run", PLAN_WRITE),
+    ("GET", "/test-plan/{plan_id}/schedule/runs", PLAN_READ),
+];
@@ -10,15 +10,22 @@ use crate::{
     dto::{
         request::{
             file::{CreateModuleRequest, QueryModuleParam},
-            CreatePlanRequest, ListQueryParam, PlanQueryParam,
+            CreatePlanRequest, ListQueryParam, PlanQueryParam, PlanRunQueryParam,
+            SaveScheduleRequest,
         },
-        response::{CreateEntityResponse, FileModuleResponse, ListPlanResponse},
+        response::{CreateEntityResponse, FileModuleResponse, ListPlanResponse, MessageResponse},
+    },
+    entity::{
+        file::ModuleType,
+        plan::{PlanRun, PlanSchedule},
     },
-    entity::file::ModuleType,
     errors::{AppResponseError, AppResult},
     service::{file, plan},
     state::AppState,
-    utils::{claim::UserClaims, header::extract_project_id},
+    utils::{
+        claim::UserClaims,
+        header::{extract_project_id, validate_project_id},
+    },
 };
 
 use tracing::info;
@@ -31,11 +38,13 @@ use tracing::info;
 )]
 pub async fn create(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     user: UserClaims,
     Json(request): Json<CreatePlanRequest>,
 ) -> AppResult {
     info!("controller layer create plan with request: {request:?}");
-    match plan::create(&state, user.uid, request).await {
+    let project_id = extract_project_id(&headers)?;
+    match plan::create(&state, user.uid, project_id, request).await {
         Ok(resp) => Ok(resp),
         Err(e) => Err(e),
     }
@@ -48,10 +57,12 @@ pub async fn create(
 )]
 pub async fn tree(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     Path(project_id): Path<i32>,
     Query(params): Query<QueryModuleParam>,
 ) -> AppResult<Json<Vec<FileModuleResponse>>> {
     info!("controller layer query with param: {project_id:?}");
+    validate_project_id(&headers, project_id)?;
     match file::get_file_module(&state, &project_id, ModuleType::Plan, params).await {
         Ok(resp) => Ok(Json(resp)),
         Err(e) => {
@@ -75,10 +86,12 @@ pub async fn tree(
 )]
 pub async fn count(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     Path(project_id): Path<i32>,
     Query(param): Query<PlanQueryParam>,
 ) -> AppResult<Json<HashMap<String, i64>>> {
     info!("controller layer case count group by module in project: {project_id:?}");
+    validate_project_id(&headers, project_id)?;
     match plan::count(&state, &project_id, &param).await {
         Ok(resp) => Ok(Json(resp)),
         Err(e) => Err(e),
@@ -93,11 +106,13 @@ pub async fn count(
 )]
 pub async fn list(
     Extension(state): Extension<AppState>,
+    headers: HeaderMap,
     _user: UserClaims,
     Path(project_id): Path<i32>,
     Query(param): Query<ListQueryParam>,
 ) -> AppResult<Json<ListPlanResponse>> {
     info!("controller layer plan list query with project_id: {project_id:?}");
+    validate_project_id(&headers, project_id)?;
     match plan::get_plan_list(&state, &project_id, param).await {
         Ok(resp) => Ok(Json(resp)),
         Err(e) => Err(e),
@@ -124,3 +139,129 @@ pub async fn create_module(
         Err(e) => Err(e),
     }
 }
+
+#[utoipa::path(
+    get,
+    path = "/management/test-plan/{plan_id}/schedule",
+    responses(
+        (status = 200, description = "Success get plan schedule", body = [PlanSchedule]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "Plan schedule not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_schedule(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    _user: UserClaims,
+    Path(plan_id): Path<i32>,
+) -> AppResult<Json<PlanSchedule>> {
+    info!("controller layer get plan schedule with plan_id: {plan_id}");
+    let project_id = extract_project_id(&headers)?;
+    match plan::get_schedule(&state, project_id, plan_id).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/management/test-plan/{plan_id}/schedule",
+    request_body = SaveScheduleRequest,
+    responses(
+        (status = 200, description = "Success save plan schedule", body = [CreateEntityResponse]),
+        (status = 400, description = "Invalid cron expression", body = [AppResponseError]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "Plan, machine or script not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn save_schedule(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Path(plan_id): Path<i32>,
+    Json(request): Json<SaveScheduleRequest>,
+) -> AppResult<Json<CreateEntityResponse>> {
+    info!("controller layer save plan schedule with plan_id: {plan_id}, request: {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match plan::save_schedule(&state, user.uid, project_id, plan_id, request).await {
+        Ok(id) => Ok(Json(CreateEntityResponse { id })),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/management/test-plan/{plan_id}/schedule",
+    responses(
+        (status = 200, description = "Success delete plan schedule", body = [MessageResponse]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "Plan schedule not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn delete_schedule(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Path(plan_id): Path<i32>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer delete plan schedule with plan_id: {plan_id}");
+    let project_id = extract_project_id(&headers)?;
+    match plan::delete_schedule(&state, user.uid, project_id, plan_id).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success delete plan schedule"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/management/test-plan/{plan_id}/schedule/run",
+    responses(
+        (status = 200, description = "Plan run started", body = [CreateEntityResponse]),
+        (status = 400, description = "Plan run already in progress", body = [AppResponseError]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "Plan schedule not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn run_now(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Path(plan_id): Path<i32>,
+) -> AppResult<Json<CreateEntityResponse>> {
+    info!("controller layer run plan schedule now with plan_id: {plan_id}");
+    let project_id = extract_project_id(&headers)?;
+    match plan::run_now(&state, user.uid, project_id, plan_id).await {
+        Ok(id) => Ok(Json(CreateEntityResponse { id })),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/management/test-plan/{plan_id}/schedule/runs",
+    params(PlanRunQueryParam),
+    responses(
+        (status = 200, description = "Success get plan runs", body = [Vec<PlanRun>]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "Plan not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn runs(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    _user: UserClaims,
+    Path(plan_id): Path<i32>,
+    Query(param): Query<PlanRunQueryParam>,
+) -> AppResult<Json<Vec<PlanRun>>> {
+    info!("controller layer get plan runs with plan_id: {plan_id}, param: {param:?}");
+    let project_id = extract_project_id(&headers)?;
+    match plan::get_runs(&state, project_id, plan_id, param).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
@@ -1,112 +1,315 @@
-use crate::{dao::entity::ProjectMember, entity::project::ProjectInfo};
-
-use axum::extract::Path;
-use axum::{Extension, Json};
-use tracing::info;
-
-use crate::dto::response::{MessageResponse, ProjectInfoResponse};
-use crate::errors::{AppResponseError, AppResult};
-use crate::service::project;
-use crate::state::AppState;
-use crate::utils::claim::UserClaims;
-
-#[utoipa::path(
-    get,
-    path = "/project/:project_id",
-    responses(
-        (status = 200, description = "Get project info", body = [ProjectInfoResponse]),
-        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
-        (status = 404, description = "Project not found", body = [AppResponseError]),
-        (status = 500, description = "Internal server error", body = [AppResponseError])
-    ),
-    security(("jwt" = []))
-)]
-pub async fn info(
-    Extension(state): Extension<AppState>,
-    Path(project_id): Path<i32>,
-) -> AppResult<Json<ProjectInfoResponse>> {
-    info!("Project info with path param: {project_id:?}");
-    match project::info(&state, project_id).await {
-        Ok(resp) => {
-            info!("Get Project info successfully.");
-            Ok(Json(resp))
-        }
-        Err(e) => {
-            info!("Failed to get project information");
-            Err(e)
-        }
-    }
-}
-
-#[utoipa::path(
-    get,
-    path = "/management/project",
-    responses(
-        (status = 200, description = "Success get project list", body = [ProjectInfo]),
-        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
-        (status = 404, description = "Project not found", body = [AppResponseError]),
-        (status = 500, description = "Internal server error", body = [AppResponseError])
-    ),
-    security(("jwt" = []))
-)]
-pub async fn get_project_list(
-    Extension(state): Extension<AppState>,
-    user: UserClaims,
-) -> AppResult<Json<Vec<ProjectInfo>>> {
-    info!("project controller layer get project list by user: {user:?}");
-    match project::list(&state, user.uid).await {
-        Ok(resp) => {
-            info!("Get Project list successfully.");
-            Ok(Json(resp))
-        }
-        Err(e) => {
-            info!("Failed to get project list");
-            Err(e)
-        }
-    }
-}
-
-#[utoipa::path(
-    get,
-    path = "/project/has-permission/:project_id",
-    responses(
-        (status = 200, description = "Get project list", body = [MessageResponse]),
-        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
-        (status = 404, description = "Project not found", body = [AppResponseError]),
-        (status = 500, description = "Internal server error", body = [AppResponseError])
-    ),
-    security(("jwt" = []))
-)]
-pub async fn permission(
-    Extension(state): Extension<AppState>,
-    user: UserClaims,
-    Path(project_id): Path<i32>,
-) -> AppResult<Json<MessageResponse>> {
-    info!("Project list with path param: {:?}", user.uid);
-    match project::permission(&state, project_id, user.uid).await {
-        Ok(resp) => {
-            info!("Get Project list successfully.");
-            Ok(Json(resp))
-        }
-        Err(e) => {
-            info!("Failed to get project list");
-            Err(e)
-        }
-    }
-}
-
-#[utoipa::path(
-    get,
-    path = "/project/member/list/:project_id",
-    responses(),
-    security(("jwt" = []))
-)]
-pub async fn members(
-    Extension(state): Extension<AppState>,
-    Path(project_id): Path<i32>,
-) -> AppResult<Json<Vec<ProjectMember>>> {
-    match project::members(&state, &project_id).await {
-        Ok(resp) => Ok(Json(resp)),
-        Err(e) => Err(e),
-    }
-}
+use crate::{dao::entity::ProjectMember, entity::project::ProjectInfo};
+
+use axum::extract::Path;
+use axum::http::HeaderMap;
+use axum::{Extension, Json};
+use garde::Validate;
+use tracing::info;
+
+use crate::dto::request::{
+    AddMemberRequest, ArchiveProjectRequest, CreateProjectRequest, DeleteEntityRequest,
+    RemoveMemberRequest, UpdateMemberRequest, UpdateModuleSettingRequest, UpdateProjectRequest,
+};
+use crate::dto::response::{CreateEntityResponse, MessageResponse, ProjectInfoResponse};
+use crate::errors::{AppResponseError, AppResult};
+use crate::service::project;
+use crate::state::AppState;
+use crate::utils::claim::UserClaims;
+use crate::utils::header::{extract_project_id, validate_project_id};
+
+#[utoipa::path(
+    get,
+    path = "/project/:project_id",
+    responses(
+        (status = 200, description = "Get project info", body = [ProjectInfoResponse]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 404, description = "Project not found", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn info(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    Path(project_id): Path<i32>,
+) -> AppResult<Json<ProjectInfoResponse>> {
+    info!("Project info with path param: {project_id:?}");
+    validate_project_id(&headers, project_id)?;
+    match project::info(&state, project_id).await {
+        Ok(resp) => {
+            info!("Get Project info successfully.");
+            Ok(Json(resp))
+        }
+        Err(e) => {
+            info!("Failed to get project information");
+            Err(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/management/project",
+    responses(
+        (status = 200, description = "Success get project list", body = [ProjectInfo]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 404, description = "Project not found", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_project_list(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+) -> AppResult<Json<Vec<ProjectInfo>>> {
+    info!("project controller layer get project list by user: {user:?}");
+    match project::list(&state, user.uid).await {
+        Ok(resp) => {
+            info!("Get Project list successfully.");
+            Ok(Json(resp))
+        }
+        Err(e) => {
+            info!("Failed to get project list");
+            Err(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/management/project",
+    request_body = CreateProjectRequest,
+    responses(
+        (status = 200, description = "Success create project", body = [CreateEntityResponse]),
+        (status = 400, description = "Invalid parameters or name exists", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn create(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Json(request): Json<CreateProjectRequest>,
+) -> AppResult<Json<CreateEntityResponse>> {
+    info!("controller layer create project with request: {request:?}");
+    request.validate()?;
+    match project::create(&state, user.uid, request).await {
+        Ok(id) => Ok(Json(CreateEntityResponse { id })),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/management/project",
+    request_body = UpdateProjectRequest,
+    responses(
+        (status = 200, description = "Success update project", body = [MessageResponse]),
+        (status = 400, description = "Invalid parameters or name exists", body = [AppResponseError]),
+        (status = 404, description = "Project not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn update(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<UpdateProjectRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer update project with request: {request:?}");
+    request.validate()?;
+    let project_id = extract_project_id(&headers)?;
+    match project::update(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success update project"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/management/project/module-setting",
+    request_body = UpdateModuleSettingRequest,
+    responses(
+        (status = 200, description = "Success update module setting", body = [MessageResponse]),
+        (status = 404, description = "Project not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn update_module_setting(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<UpdateModuleSettingRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer update project module setting with request: {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match project::update_module_setting(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success update module setting"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/management/project/archive",
+    request_body = ArchiveProjectRequest,
+    responses(
+        (status = 200, description = "Success archive project", body = [MessageResponse]),
+        (status = 404, description = "Project not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn archive(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Json(request): Json<ArchiveProjectRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer archive project with request: {request:?}");
+    match project::archive(&state, user.uid, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success archive project"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/management/project",
+    request_body = DeleteEntityRequest,
+    responses(
+        (status = 200, description = "Success delete project", body = [MessageResponse]),
+        (status = 404, description = "Project not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn delete(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Json(request): Json<DeleteEntityRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer delete project with request: {request:?}");
+    match project::delete(&state, user.uid, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success delete project"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/project/has-permission/:project_id",
+    responses(
+        (status = 200, description = "Get project list", body = [MessageResponse]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 404, description = "Project not found", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn permission(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Path(project_id): Path<i32>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("Project list with path param: {:?}", user.uid);
+    match project::permission(&state, project_id, user.uid).await {
+        Ok(resp) => {
+            info!("Get Project list successfully.");
+            Ok(Json(resp))
+        }
+        Err(e) => {
+            info!("Failed to get project list");
+            Err(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/project/member/list/:project_id",
+    responses(),
+    security(("jwt" = []))
+)]
+pub async fn members(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    Path(project_id): Path<i32>,
+) -> AppResult<Json<Vec<ProjectMember>>> {
+    validate_project_id(&headers, project_id)?;
+    match project::members(&state, &project_id).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/management/project/member",
+    request_body = AddMemberRequest,
+    responses(
+        (status = 200, description = "Success add project member", body = [MessageResponse]),
+        (status = 400, description = "User is already a member", body = [AppResponseError]),
+        (status = 404, description = "User or role not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn add_member(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<AddMemberRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer add project member with request: {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match project::add_member(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success add project member"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/management/project/member",
+    request_body = UpdateMemberRequest,
+    responses(
+        (status = 200, description = "Success change member role", body = [MessageResponse]),
+        (status = 400, description = "Not a member or last admin", body = [AppResponseError]),
+        (status = 404, description = "User or role not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn update_member(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<UpdateMemberRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer update project member with request: {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match project::update_member(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success change member role"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/management/project/member",
+    request_body = RemoveMemberRequest,
+    responses(
+        (status = 200, description = "Success remove project member", body = [MessageResponse]),
+        (status = 400, description = "Not a member or last admin", body = [AppResponseError]),
+        (status = 404, description = "User not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn remove_member(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    _user: UserClaims,
+    Json(request): Json<RemoveMemberRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer remove project member with request: {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match project::remove_member(&state, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success remove project member"))),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,216 @@
+use axum::{extract::Path, http::HeaderMap, Extension, Json};
+use garde::Validate;
+use tracing::info;
+
+use crate::{
+    dto::{
+        request::{
+            case::{
+                CreateRequirementRequest, ImportRequirementRequest, RequirementCaseRequest,
+                UpdateRequirementRequest,
+            },
+            DeleteEntityRequest,
+        },
+        response::{CreateEntityResponse, MessageResponse, RequirementResponse},
+    },
+    entity::requirement::Requirement,
+    errors::{AppResponseError, AppResult},
+    service::requirement,
+    state::AppState,
+    utils::{claim::UserClaims, header::extract_project_id},
+};
+
+#[utoipa::path(
+    post,
+    path = "/management/requirement",
+    request_body = CreateRequirementRequest,
+    responses(
+        (status = 200, description = "Success create requirement", body = [CreateEntityResponse]),
+        (status = 400, description = "Invalid parameters", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn create(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<CreateRequirementRequest>,
+) -> AppResult<Json<CreateEntityResponse>> {
+    info!("requirement controller layer create with {request:?}");
+    request.validate()?;
+    let project_id = extract_project_id(&headers)?;
+    match requirement::create(&state, user.uid, project_id, request).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/management/requirement/import",
+    request_body = ImportRequirementRequest,
+    responses(
+        (status = 200, description = "Success import requirement from tracker", body = [CreateEntityResponse]),
+        (status = 400, description = "Unknown or unconfigured tracker", body = [AppResponseError]),
+        (status = 404, description = "Requirement not found on tracker", body = [AppResponseError]),
+        (status = 409, description = "Requirement already imported", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn import(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<ImportRequirementRequest>,
+) -> AppResult<Json<CreateEntityResponse>> {
+    info!("requirement controller layer import with {request:?}");
+    request.validate()?;
+    let project_id = extract_project_id(&headers)?;
+    match requirement::import(&state, user.uid, project_id, request).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/management/requirement",
+    request_body = UpdateRequirementRequest,
+    responses(
+        (status = 200, description = "Success update requirement", body = [MessageResponse]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "Requirement not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn update(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<UpdateRequirementRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("requirement controller layer update with {request:?}");
+    request.validate()?;
+    let project_id = extract_project_id(&headers)?;
+    match requirement::update(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success update requirement"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/management/requirement",
+    request_body = DeleteEntityRequest,
+    responses(
+        (status = 200, description = "Success delete requirement", body = [MessageResponse]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "Requirement not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn delete(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<DeleteEntityRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("requirement controller layer delete with {request:?}");
+    let project_id = extract_project_id(&headers)?;
+    match requirement::delete(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success delete requirement"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/management/requirement/{requirement_id}",
+    responses(
+        (status = 200, description = "Success get requirement", body = [RequirementResponse]),
+        (status = 404, description = "Requirement not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn info(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    Path(requirement_id): Path<i32>,
+) -> AppResult<Json<RequirementResponse>> {
+    info!("requirement controller layer get with requirement_id: {requirement_id}");
+    let project_id = extract_project_id(&headers)?;
+    match requirement::get(&state, project_id, requirement_id).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/management/requirement",
+    responses(
+        (status = 200, description = "Success get requirement list", body = [Vec<Requirement>]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn list(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+) -> AppResult<Json<Vec<Requirement>>> {
+    info!("requirement controller layer list");
+    let project_id = extract_project_id(&headers)?;
+    match requirement::list(&state, project_id).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/management/requirement/case",
+    request_body = RequirementCaseRequest,
+    responses(
+        (status = 200, description = "Success link cases with requirement", body = [MessageResponse]),
+        (status = 404, description = "Requirement or case not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn link_cases(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<RequirementCaseRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("requirement controller layer link cases with {request:?}");
+    request.validate()?;
+    let project_id = extract_project_id(&headers)?;
+    match requirement::link_cases(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success link cases"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/management/requirement/case",
+    request_body = RequirementCaseRequest,
+    responses(
+        (status = 200, description = "Success unlink cases from requirement", body = [MessageResponse]),
+        (status = 404, description = "Requirement not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn unlink_cases(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    user: UserClaims,
+    Json(request): Json<RequirementCaseRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("requirement controller layer unlink cases with {request:?}");
+    request.validate()?;
+    let project_id = extract_project_id(&headers)?;
+    match requirement::unlink_cases(&state, user.uid, project_id, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success unlink cases"))),
+        Err(e) => Err(e),
+    }
+}
@@ -3,6 +3,9 @@ mod engine;
 mod management;
 mod system;
 use axum::Router;
+use once_cell::sync::Lazy;
+
+use crate::entity::permission::{Access, ApiPermission};
 // use base::openapi::ApiDoc;
 // use utoipa::OpenApi;
 // use utoipa_swagger_ui::SwaggerUi;
@@ -14,7 +17,40 @@ pub fn create_router() -> Router {
     // .merge(SwaggerUi::new("swagger-ui").url("/api-docs/openapi.jsoin", api.clone()))
     Router::new()
         .merge(base::app())
-        .nest("/system", system::app())
-        .nest("/management", management::app())
-        .nest("/engine", engine::app())
+        .nest(SYSTEM, system::app())
+        .nest(MANAGEMENT, management::app())
+        .nest(ENGINE, engine::app())
+}
+
+const SYSTEM: &str = "/system";
+const MANAGEMENT: &str = "/management";
+const ENGINE: &str = "/engine";
+
+/* declared access of every route, synced to `api_permission_relation` at startup */
+pub static PERMISSIONS: Lazy<Vec<ApiPermission>> = Lazy::new(|| {
+    [
+        ("", base::PERMISSIONS),
+        (SYSTEM, system::PERMISSIONS),
+        (MANAGEMENT, management::PERMISSIONS),
+        (ENGINE, engine::PERMISSIONS),
+    ]
+    .into_iter()
+    .flat_map(|(prefix, permissions)| {
+        permissions
+            .iter()
+            .map(move |&(method, path, access)| ApiPermission {
+                method,
+                path: format!("{prefix}{path}"),
+                access,
+            })
+    })
+    .collect()
+});
+
+/* `None` for paths without a declared route */
+pub fn access(method: &str, path: &str) -> Option<Access> {
+    PERMISSIONS
+        .iter()
+        .find(|p| p.method == method && p.path == path)
+        .map(|p| p.access)
 }
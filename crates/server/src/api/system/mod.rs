@@ -3,6 +3,11 @@ use axum::{
     Router,
 };
 
+use crate::{
+    constant::{SYSTEM_PARAMETER, SYSTEM_ROLE, SYSTEM_USER},
+    entity::permission::Access,
+};
+
 mod parameter;
 mod user;
 
@@ -12,15 +17,51 @@ pub fn app() -> Router {
         .route("/user/list", get(user::list))
         .route("/user/status", put(user::update_status))
         .route("/user", delete(user::delete))
+        .route("/user/session", delete(user::force_logout))
+        .route("/user/unlock", put(user::unlock))
+        .route("/user/mfa", delete(user::reset_mfa))
+        .route(
+            "/user/invitation",
+            post(user::resend_invitation).delete(user::revoke_invitation),
+        )
         .route(
             "/user/role/permission/list",
             get(user::role_permission_list),
         )
-        .route("/user/role", post(user::create_role))
+        .route("/user/role", post(user::create_role).put(user::update_role))
         .route("/user/role/{role_id}", get(user::get_role))
+        .route("/user/role/mfa", put(user::update_role_mfa))
         .route(
             "/user/role/permission/{role_id}",
             get(user::role_permission),
         )
         .route("/user/role", delete(user::delete_role))
 }
+
+const USER_READ: Access = Access::read(SYSTEM_USER);
+const USER_WRITE: Access = Access::write(SYSTEM_USER);
+const ROLE_READ: Access = Access::read(SYSTEM_ROLE);
+const ROLE_WRITE: Access = Access::write(SYSTEM_ROLE);
+
+pub const PERMISSIONS: &[(&str, &str, Access)] = &[
+    (
+        "GET",
+        "/parameter/save/base-url",
+        Access::write(SYSTEM_PARAMETER),
+    ),
+    ("GET", "/user/list", USER_READ),
+    ("PUT", "/user/status", USER_WRITE),
+    ("DELETE", "/user", USER_WRITE),
+    ("DELETE", "/user/session", USER_WRITE),
+    ("PUT", "/user/unlock", USER_WRITE),
+    ("DELETE", "/user/mfa", USER_WRITE),
+    ("POST", "/user/invitation", USER_WRITE),
+    ("DELETE", "/user/invitation", USER_WRITE),
+    ("GET", "/user/role/permission/list", ROLE_READ),
+    ("POST", "/user/role", ROLE_WRITE),
+    ("PUT", "/user/role", ROLE_WRITE),
+    ("GET", "/user/role/{role_id}", ROLE_READ),
+    ("PUT", "/user/role/mfa", ROLE_WRITE),
+    ("GET", "/user/role/permission/{role_id}", ROLE_READ),
+    ("DELETE", "/user/role", ROLE_WRITE),
+];
@@ -1,10 +1,13 @@
 use crate::{
     dto::{
         request::{
-            user::{DeleteUserRequest, UpdateUserStatusRequest},
-            CreateRoleRequest, DeleteRoleRequest, UserQueryParam,
+            user::{
+                DeleteUserRequest, ForceLogoutRequest, InvitationRequest, ResetMfaRequest,
+                UnlockUserRequest, UpdateRoleMfaRequest, UpdateUserStatusRequest,
+            },
+            CreateRoleRequest, DeleteRoleRequest, UpdateRoleRequest, UserQueryParam,
         },
-        response::{CreateEntityResponse, ListUserResponse, MessageResponse},
+        response::{CreateEntityResponse, ListUserResponse, MessageResponse, UpdateRoleResponse},
     },
     entity::{
         permission::Permission,
@@ -13,7 +16,7 @@ use crate::{
     errors::{AppResponseError, AppResult},
     service,
     state::AppState,
-    utils::claim::UserClaims,
+    utils::{claim::UserClaims, header::ClientInfo},
 };
 use axum::{extract::Path, Extension, Json};
 use garde::Validate;
@@ -44,6 +47,32 @@ pub async fn create_role(
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/system/user/role",
+    request_body = UpdateRoleRequest,
+    responses(
+        (status = 200, description = "Success update user role", body = [UpdateRoleResponse]),
+        (status = 400, description = "INVALID_INPUT_ERROR", body = [AppResponseError]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 403, description = "Internal role can not be modified", body = [AppResponseError]),
+        (status = 404, description = "Role not found", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn update_role(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    Json(request): Json<UpdateRoleRequest>,
+) -> AppResult<Json<UpdateRoleResponse>> {
+    info!("controller layer update role with request: {request:?}");
+    request.validate()?;
+    match service::user::update_role(&state, request, user.uid).await {
+        Ok(resp) => Ok(Json(resp)),
+        Err(err) => Err(err),
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/system/user/role/{role_id}",
@@ -196,3 +225,164 @@ pub async fn role_permission_list(
         Err(e) => Err(e),
     }
 }
+
+#[utoipa::path(
+    post,
+    path = "/system/user/invitation",
+    request_body = InvitationRequest,
+    responses(
+        (status = 200, description = "Success resend invitation", body = [MessageResponse]),
+        (status = 400, description = "Invitation already accepted", body = [AppResponseError]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "User not found", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn resend_invitation(
+    Extension(state): Extension<AppState>,
+    Json(request): Json<InvitationRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer resend invitation with request: {request:?}");
+    request.validate()?;
+    match service::invitation::resend(&state, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success resend invitation"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/system/user/invitation",
+    request_body = InvitationRequest,
+    responses(
+        (status = 200, description = "Success revoke invitation", body = [MessageResponse]),
+        (status = 400, description = "Invitation already accepted", body = [AppResponseError]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "User not found", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn revoke_invitation(
+    Extension(state): Extension<AppState>,
+    Json(request): Json<InvitationRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer revoke invitation with request: {request:?}");
+    request.validate()?;
+    match service::invitation::revoke(&state, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success revoke invitation"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/system/user/session",
+    request_body = ForceLogoutRequest,
+    responses(
+        (status = 200, description = "Success force logout", body = [MessageResponse]),
+        (status = 400, description = "INVALID_INPUT_ERROR", body = [AppResponseError]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "User not found", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn force_logout(
+    Extension(state): Extension<AppState>,
+    Json(request): Json<ForceLogoutRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer force logout with request: {request:?}");
+    request.validate()?;
+    match service::user::force_logout(&state, request).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success force logout"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/system/user/unlock",
+    request_body = UnlockUserRequest,
+    responses(
+        (status = 200, description = "Success unlock user login", body = [MessageResponse]),
+        (status = 400, description = "INVALID_INPUT_ERROR", body = [AppResponseError]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "User not found", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn unlock(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    client_info: ClientInfo,
+    Json(request): Json<UnlockUserRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer unlock user login with request: {request:?}");
+    request.validate()?;
+    match service::lockout::unlock(&state, user.uid, request, &client_info).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success unlock user login"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/system/user/mfa",
+    request_body = ResetMfaRequest,
+    responses(
+        (status = 200, description = "Success reset user mfa", body = [MessageResponse]),
+        (status = 400, description = "INVALID_INPUT_ERROR", body = [AppResponseError]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "User not found", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn reset_mfa(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    client_info: ClientInfo,
+    Json(request): Json<ResetMfaRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer reset user mfa with request: {request:?}");
+    request.validate()?;
+    match service::mfa::reset(&state, user.uid, request, &client_info).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success reset user mfa"))),
+        Err(e) => Err(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/system/user/role/mfa",
+    request_body = UpdateRoleMfaRequest,
+    responses(
+        (status = 200, description = "Success update role mfa policy", body = [MessageResponse]),
+        (status = 401, description = "Unauthorized user", body = [AppResponseError]),
+        (status = 403, description = "Forbidden", body = [AppResponseError]),
+        (status = 404, description = "Role not found", body = [AppResponseError]),
+        (status = 500, description = "Internal server error", body = [AppResponseError]),
+    ),
+    security(("jwt" = []))
+)]
+pub async fn update_role_mfa(
+    Extension(state): Extension<AppState>,
+    user: UserClaims,
+    client_info: ClientInfo,
+    Json(request): Json<UpdateRoleMfaRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    info!("controller layer update role mfa policy with request: {request:?}");
+    request.validate()?;
+    match service::mfa::set_role_required(&state, user.uid, request, &client_info).await {
+        Ok(_) => Ok(Json(MessageResponse::new("Success update role mfa policy"))),
+        Err(e) => Err(e),
+    }
+}
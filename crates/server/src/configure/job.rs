@@ -0,0 +1,28 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConfigJob {
+    /* workers polling the job queue in every server process */
+    pub workers: usize,
+    /* total runs of a failing job, including the first one */
+    pub max_attempts: i32,
+    /* seconds before the first retry, doubled on every further attempt */
+    pub backoff: u64,
+    /* seconds an idle worker waits before polling again */
+    pub poll_interval: u64,
+    /* seconds without a heartbeat before a running job is considered lost, renewed every third of it */
+    pub stale_after: u64,
+}
+
+impl Default for ConfigJob {
+    fn default() -> Self {
+        ConfigJob {
+            workers: 2,
+            max_attempts: 3,
+            backoff: 10,
+            poll_interval: 1,
+            stale_after: 600,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/* failed password logins counted per username and per client ip */
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfigLockout {
+    /* failures of one username before it is locked */
+    #[serde(default = "default_max_failures")]
+    pub max_failures: i64,
+    /* failures from one ip before it is locked, shared by every username tried from it */
+    #[serde(default = "default_ip_max_failures")]
+    pub ip_max_failures: i64,
+    /* failures older than this are forgotten */
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+    #[serde(default = "default_lockout_secs")]
+    pub lockout_secs: u64,
+    /* from this many failures on, the next attempt waits `backoff_base_secs * 2^n` */
+    #[serde(default = "default_backoff_after")]
+    pub backoff_after: i64,
+    #[serde(default = "default_backoff_base_secs")]
+    pub backoff_base_secs: u64,
+}
+
+impl ConfigLockout {
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(self.window_secs)
+    }
+
+    pub fn lockout(&self) -> Duration {
+        Duration::from_secs(self.lockout_secs)
+    }
+
+    /* delay required after `failures` consecutive failures */
+    pub fn backoff(&self, failures: i64) -> u64 {
+        if failures < self.backoff_after {
+            return 0;
+        }
+        let exp = (failures - self.backoff_after).min(16) as u32;
+        self.backoff_base_secs
+            .saturating_mul(2u64.pow(exp))
+            .min(self.lockout_secs)
+    }
+}
+
+impl Default for ConfigLockout {
+    fn default() -> Self {
+        Self {
+            max_failures: default_max_failures(),
+            ip_max_failures: default_ip_max_failures(),
+            window_secs: default_window_secs(),
+            lockout_secs: default_lockout_secs(),
+            backoff_after: default_backoff_after(),
+            backoff_base_secs: default_backoff_base_secs(),
+        }
+    }
+}
+
+fn default_max_failures() -> i64 {
+    5
+}
+
+fn default_ip_max_failures() -> i64 {
+    50
+}
+
+fn default_window_secs() -> u64 {
+    900
+}
+
+fn default_lockout_secs() -> u64 {
+    900
+}
+
+fn default_backoff_after() -> i64 {
+    2
+}
+
+fn default_backoff_base_secs() -> u64 {
+    1
+}
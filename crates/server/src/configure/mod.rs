@@ -1,21 +1,33 @@
 use serde::Deserialize;
-use std::fs;
+use std::{collections::HashMap, fs};
 
 use crate::configure::env::get_profile;
 use crate::utils::dir::get_project_root;
 use config::{ConfigError, Environment};
+use job::ConfigJob;
+use lockout::ConfigLockout;
+use page::ConfigPage;
+use password::ConfigPassword;
 use secret::ConfigJWT;
 use server::ConfigHTTP;
 use smtp::ConfigSMTP;
+use sso::ConfigSSO;
 use storage::ConfigStorage;
+use tracker::ConfigTracker;
 
 pub mod env;
+pub mod job;
+pub mod lockout;
+pub mod page;
+pub mod password;
 pub mod secret;
 pub mod server;
 pub mod smtp;
+pub mod sso;
 pub mod storage;
 pub mod template;
 pub mod tracing;
+pub mod tracker;
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Config {
@@ -23,6 +35,21 @@ pub struct Config {
     pub jwt: ConfigJWT,
     pub storage: ConfigStorage,
     pub smtp: ConfigSMTP,
+    #[serde(default)]
+    pub password: ConfigPassword,
+    #[serde(default)]
+    pub lockout: ConfigLockout,
+    #[serde(default)]
+    pub page: ConfigPage,
+    #[serde(default)]
+    pub sso: ConfigSSO,
+    #[serde(default)]
+    pub tracker: ConfigTracker,
+    #[serde(default)]
+    pub job: ConfigJob,
+    /* scheduled job settings keyed by job name, see `service::schedule::Job::with_config` */
+    #[serde(default)]
+    pub schedule: HashMap<String, toml::Table>,
 }
 
 impl Config {
@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/* page tokens returned by the list apis */
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfigPage {
+    /* must be the same on every instance, a random one per process is used when unset */
+    #[serde(default)]
+    pub token_secret: Option<String>,
+    #[serde(default = "default_token_expire_secs")]
+    pub token_expire_secs: u64,
+}
+
+impl Default for ConfigPage {
+    fn default() -> Self {
+        Self {
+            token_secret: None,
+            token_expire_secs: default_token_expire_secs(),
+        }
+    }
+}
+
+impl ConfigPage {
+    pub fn token_expire(&self) -> Duration {
+        Duration::from_secs(self.token_expire_secs)
+    }
+}
+
+fn default_token_expire_secs() -> u64 {
+    3600
+}
@@ -0,0 +1,32 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfigPassword {
+    #[serde(default = "default_min_length")]
+    pub min_length: usize,
+    #[serde(default)]
+    pub require_uppercase: bool,
+    #[serde(default)]
+    pub require_lowercase: bool,
+    #[serde(default)]
+    pub require_digit: bool,
+    /* any ascii punctuation */
+    #[serde(default)]
+    pub require_symbol: bool,
+}
+
+impl Default for ConfigPassword {
+    fn default() -> Self {
+        Self {
+            min_length: default_min_length(),
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+        }
+    }
+}
+
+fn default_min_length() -> usize {
+    8
+}
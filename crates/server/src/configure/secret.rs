@@ -1,15 +1,47 @@
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use std::fs;
 
 use crate::utils;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ConfigJWT {
+    /* single key pair per token type, used while `key_dir` is not set */
     pub private_access_key: PathBuf,
     pub public_access_key: PathBuf,
     pub private_refresh_key: PathBuf,
     pub public_refresh_key: PathBuf,
+    /* rotated keys listed by `keys.toml` in this directory, reloaded while running */
+    #[serde(default)]
+    pub key_dir: Option<PathBuf>,
+}
+
+/* `keys.toml` of `key_dir`, one key set per token type */
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeyManifest {
+    pub access: KeySetManifest,
+    pub refresh: KeySetManifest,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeySetManifest {
+    /* kid of the key new tokens are signed with */
+    pub primary: String,
+    pub keys: Vec<KeyManifestEntry>,
+}
+
+/* paths are relative to `key_dir` */
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeyManifestEntry {
+    pub kid: String,
+    /* only required for the primary key */
+    #[serde(default)]
+    pub private_key: Option<PathBuf>,
+    pub public_key: PathBuf,
+    /* a retired key stops verifying afterwards, keep it past the expiry of its last token */
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 impl ConfigJWT {
@@ -22,9 +54,30 @@ impl ConfigJWT {
     pub fn read_private_refresh_key(&self) -> Result<String, std::io::Error> {
         fs::read_to_string(utils::dir::get_project_root()?.join(&self.private_refresh_key))
     }
-
-    #[allow(dead_code)]
     pub fn read_public_refresh_key(&self) -> Result<String, std::io::Error> {
         fs::read_to_string(utils::dir::get_project_root()?.join(&self.public_refresh_key))
     }
+
+    /* `None` while keys are not rotated */
+    pub fn read_key_manifest(&self) -> anyhow::Result<Option<KeyManifest>> {
+        let Some(key_dir) = self.key_dir_path()? else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(key_dir.join("keys.toml"))?;
+        Ok(Some(toml::from_str(&content)?))
+    }
+
+    pub fn read_key_file(&self, path: &Path) -> anyhow::Result<String> {
+        let key_dir = self
+            .key_dir_path()?
+            .ok_or_else(|| anyhow::anyhow!("jwt.key_dir is not set"))?;
+        Ok(fs::read_to_string(key_dir.join(path))?)
+    }
+
+    fn key_dir_path(&self) -> Result<Option<PathBuf>, std::io::Error> {
+        match &self.key_dir {
+            Some(key_dir) => Ok(Some(utils::dir::get_project_root()?.join(key_dir))),
+            None => Ok(None),
+        }
+    }
 }
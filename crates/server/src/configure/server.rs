@@ -12,6 +12,12 @@ pub struct ConfigHTTP {
     pub tls_cert: String,
     pub tls_key: String,
     pub timeout: u64,
+    /* address of the web client, used to build links sent by email */
+    #[serde(default)]
+    pub web_url: String,
+    /* reverse proxies in front of the server appending to x-forwarded-for, 0 ignores the header */
+    #[serde(default)]
+    pub trusted_proxies: usize,
 }
 
 impl ConfigHTTP {
@@ -0,0 +1,76 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConfigSSO {
+    #[serde(default)]
+    pub oidc: Option<ConfigOIDC>,
+    #[serde(default)]
+    pub ldap: Option<ConfigLDAP>,
+    /* IdP group -> project role, applied on every sso login */
+    #[serde(default)]
+    pub group_mapping: Vec<ConfigGroupMapping>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfigOIDC {
+    /* e.g. https://sso.example.com/realms/dev, discovery is read from /.well-known/openid-configuration */
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+    /* id_token claim holding the group list */
+    #[serde(default = "default_oidc_groups_claim")]
+    pub groups_claim: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfigLDAP {
+    /* e.g. ldap://localhost:389 */
+    pub url: String,
+    /* service account used to look up the user entry */
+    pub bind_dn: String,
+    pub bind_password: String,
+    pub base_dn: String,
+    /* `{username}` is replaced by the escaped login name */
+    #[serde(default = "default_ldap_user_filter")]
+    pub user_filter: String,
+    #[serde(default = "default_ldap_email_attribute")]
+    pub email_attribute: String,
+    /* values are matched against `group_mapping.group` as is, usually group dn */
+    #[serde(default = "default_ldap_group_attribute")]
+    pub group_attribute: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfigGroupMapping {
+    pub group: String,
+    pub project_id: i32,
+    /* name of the user_role */
+    pub role: String,
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "profile".to_string(),
+        "email".to_string(),
+    ]
+}
+
+fn default_oidc_groups_claim() -> String {
+    "groups".to_string()
+}
+
+fn default_ldap_user_filter() -> String {
+    "(uid={username})".to_string()
+}
+
+fn default_ldap_email_attribute() -> String {
+    "mail".to_string()
+}
+
+fn default_ldap_group_attribute() -> String {
+    "memberOf".to_string()
+}
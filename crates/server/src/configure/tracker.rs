@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConfigTracker {
+    #[serde(default)]
+    pub jira: Option<ConfigTrackerItem>,
+    #[serde(default)]
+    pub gitlab: Option<ConfigTrackerItem>,
+    #[serde(default)]
+    pub github: Option<ConfigTrackerItem>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfigTrackerItem {
+    /* e.g. https://jira.example.com, https://gitlab.com, https://api.github.com */
+    pub base_url: String,
+    /* Jira: account email for basic auth; unused by GitLab/GitHub */
+    #[serde(default)]
+    pub username: String,
+    pub token: String,
+    /* Jira: project key; GitLab: project id or url-encoded path; GitHub: owner/repo */
+    pub project: String,
+    /* Jira only: issue type used when creating issues from execute records */
+    #[serde(default)]
+    pub issue_type: Option<String>,
+}
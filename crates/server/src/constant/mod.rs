@@ -1,10 +1,15 @@
 use crate::configure::{env::get_env_source, get_static_dir, template::EmailTemplateEngine};
-use crate::utils::{http::HttpClient, ClientBuilder};
+use crate::utils::{
+    http::HttpClient,
+    jwk::{KeySet, KeyStore, KeyUsage},
+    ClientBuilder,
+};
 use axum::http::{HeaderValue, Method};
 use std::time::Duration;
 
 use jsonwebtoken::{DecodingKey, EncodingKey};
 use once_cell::sync::Lazy;
+use rand::RngCore;
 
 pub mod case;
 
@@ -13,11 +18,39 @@ pub const ENV_PREFIX: &str = "APP";
 // Development mode change 120 to 604800
 pub const EXPIRE_SESSION_CODE_SECS: Duration = Duration::from_secs(604800);
 pub const EXPIRE_REFRESH_TOKEN_SECS: Duration = Duration::from_secs(604800);
+/* safety net only, cached permissions are dropped whenever roles or members change */
+pub const EXPIRE_PERMISSION_SECS: Duration = Duration::from_secs(3600);
+pub const EXPIRE_OIDC_STATE_SECS: Duration = Duration::from_secs(600);
+pub const EXPIRE_INVITATION_SECS: Duration = Duration::from_secs(259200);
+pub const EXPIRE_PASSWORD_RESET_SECS: Duration = Duration::from_secs(1800);
+/* one reset email per address within the window */
+pub const EXPIRE_PASSWORD_RESET_THROTTLE_SECS: Duration = Duration::from_secs(60);
+/* upper bound only, failure counters expire after the configured `lockout` window */
+pub const EXPIRE_LOGIN_FAILURE_SECS: Duration = Duration::from_secs(86400);
+/* time to enter the second factor after the password was accepted */
+pub const EXPIRE_MFA_CHALLENGE_SECS: Duration = Duration::from_secs(300);
 pub const BEARER: &str = "Bearer";
+/* sent as a bearer token too, the prefix tells it apart from a jwt */
+pub const API_TOKEN_PREFIX: &str = "mtk_";
 pub const AUTHORIZATION: &str = "Authorization";
 pub const PROJECT_ID: &str = "ProjectId";
+/* ties an oidc state to the browser that started the authorization */
+pub const OIDC_BINDING_COOKIE: &str = "oidc_binding";
+/* shown by authenticator apps next to the account name */
+pub const MFA_ISSUER: &str = "Meter";
 
-pub const WHITE_LIST: [&str; 3] = ["/auth/login", "/swagger-ui", "/auth/is-login"];
+pub const WHITE_LIST: [&str; 8] = [
+    "/auth/login",
+    "/swagger-ui",
+    "/auth/is-login",
+    "/auth/sso",
+    "/auth/invitation",
+    "/auth/password",
+    /* the refresh token is verified by the handler, the access token may have expired */
+    "/auth/token/refresh",
+    /* public keys for other services verifying access tokens */
+    "/.well-known",
+];
 pub const ALLOW_METHOD: [Method; 6] = [
     Method::GET,
     Method::POST,
@@ -27,15 +60,22 @@ pub const ALLOW_METHOD: [Method; 6] = [
     Method::PUT,
 ];
 pub const ALLOW_ORIGIN: [HeaderValue; 1] = [HeaderValue::from_static("http://localhost:3000")];
-pub const ACCESS_WHITE_LIST: [&str; 5] = [
-    "/auth/login",
-    "/auth/logout",
-    "/swagger-ui",
-    "/user/info",
-    "/auth/is-login",
-];
+/* permission modules, api permissions are declared with these in `api` */
+pub const SYSTEM_USER: &str = "SYSTEM:USER";
+pub const SYSTEM_ROLE: &str = "SYSTEM:ROLE";
+pub const SYSTEM_PARAMETER: &str = "SYSTEM:PARAMETER";
+pub const SYSTEM_PROJECT: &str = "SYSTEM:PROJECT";
+pub const MANAGEMENT_PREFIX: &str = "MANAGEMENT:";
+pub const MANAGEMENT_PROJECT: &str = "MANAGEMENT:PROJECT";
+pub const MANAGEMENT_FUNCTIONAL_CASE: &str = "MANAGEMENT:FUNCTIONAL_CASE";
+pub const MANAGEMENT_REQUIREMENT: &str = "MANAGEMENT:REQUIREMENT";
+pub const MANAGEMENT_ELEMENT: &str = "MANAGEMENT:ELEMENT";
+pub const MANAGEMENT_TEST_PLAN: &str = "MANAGEMENT:TEST_PLAN";
+/* granted to the creator of a project */
+pub const PROJECT_ADMIN_ROLE: &str = "admin";
 pub const EMAIL_ADDR: &str = "chenwentao@datatower.ai";
 pub const REGISTER_EMAIL_SUBJECT: &str = "<DTest-测试平台> 注册邮件通知";
+pub const RESET_PASSWORD_EMAIL_SUBJECT: &str = "<DTest-测试平台> 重置密码";
 
 pub static CONFIG: Lazy<crate::configure::Config> =
     Lazy::new(|| crate::configure::Config::read(get_env_source(ENV_PREFIX)).unwrap());
@@ -44,29 +84,34 @@ pub static HTTP: Lazy<reqwest::Client> =
     Lazy::new(|| HttpClient::build_from_config(&CONFIG).unwrap());
 
 pub static DOCTOR_SCRIPT_PATH: &str = "./static/scripts/doctor";
+/* run on registered machines to fetch the rendered page for selector health checks */
+pub const HEADLESS_BROWSER_CMD: &str = "chromium --headless --disable-gpu --no-sandbox --dump-dom";
+/* run on registered machines for scheduled plan runs, the uploaded spec is appended */
+pub const CYPRESS_RUN_CMD: &str = "npx cypress run --spec";
+/* upper bound of the retry delay of background jobs, in seconds */
+pub const JOB_MAX_BACKOFF: u64 = 3600;
 
-pub static ACCESS_TOKEN_ENCODE_KEY: Lazy<EncodingKey> = Lazy::new(|| {
-    let key = CONFIG.jwt.read_private_access_key().unwrap();
-    EncodingKey::from_rsa_pem(key.as_bytes()).unwrap()
-});
-pub static ACCESS_TOKEN_DECODE_KEY: Lazy<DecodingKey> = Lazy::new(|| {
-    let key = CONFIG.jwt.read_public_access_key().unwrap();
-    DecodingKey::from_rsa_pem(key.as_bytes()).unwrap()
-});
-pub static REFRESH_TOKEN_ENCODE_KEY: Lazy<EncodingKey> = Lazy::new(|| {
-    let key = CONFIG.jwt.read_private_refresh_key().unwrap();
-    EncodingKey::from_rsa_pem(key.as_bytes()).unwrap()
-});
-pub static REFRESH_TOKEN_DECODE_KEY: Lazy<DecodingKey> = Lazy::new(|| {
-    let key = CONFIG.jwt.read_public_refresh_key().unwrap();
-    DecodingKey::from_rsa_pem(key.as_bytes()).unwrap()
+/* replaced by the `reload_signing_key` job while `jwt.key_dir` is set */
+pub static ACCESS_TOKEN_KEYS: Lazy<KeyStore> =
+    Lazy::new(|| KeyStore::new(KeySet::load(&CONFIG.jwt, KeyUsage::Access).unwrap()));
+pub static REFRESH_TOKEN_KEYS: Lazy<KeyStore> =
+    Lazy::new(|| KeyStore::new(KeySet::load(&CONFIG.jwt, KeyUsage::Refresh).unwrap()));
+
+/* tokens of a random secret stop working on restart and on other instances */
+static PAGE_TOKEN_SECRET: Lazy<Vec<u8>> = Lazy::new(|| match &CONFIG.page.token_secret {
+    Some(secret) => secret.as_bytes().to_vec(),
+    None => {
+        let mut secret = vec![0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        secret
+    }
 });
 
 pub static PAGE_ENCODE_KEY: Lazy<EncodingKey> =
-    Lazy::new(|| EncodingKey::from_base64_secret("U29tZVNlY3JldEtleQ==".into()).unwrap());
+    Lazy::new(|| EncodingKey::from_secret(&PAGE_TOKEN_SECRET));
 
 pub static PAGE_DECODE_KEY: Lazy<DecodingKey> =
-    Lazy::new(|| DecodingKey::from_base64_secret("U29tZVNlY3JldEtleQ==".into()).unwrap());
+    Lazy::new(|| DecodingKey::from_secret(&PAGE_TOKEN_SECRET));
 
 pub static TEMPLATE_ENGINE: Lazy<EmailTemplateEngine> = Lazy::new(|| {
     let path = get_static_dir()
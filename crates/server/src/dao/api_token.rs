@@ -0,0 +1,104 @@
+use crate::{
+    entity::api_token::{ApiToken, ApiTokenScope},
+    errors::{AppError, AppResult, Resource, ResourceType},
+    utils,
+};
+use chrono::{DateTime, Utc};
+use db::queries::api_token::*;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct ApiTokenDao<'a, T>
+where
+    T: db::GenericClient,
+{
+    executor: &'a T,
+}
+
+impl<'a, T> ApiTokenDao<'a, T>
+where
+    T: db::GenericClient,
+{
+    pub fn new(executor: &'a T) -> Self {
+        ApiTokenDao { executor }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        &self,
+        name: &str,
+        token_hash: &str,
+        token_prefix: &str,
+        uid: &Uuid,
+        project_id: Option<i32>,
+        permissions: &[i32],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> AppResult<i32> {
+        let id = insert_api_token()
+            .bind(
+                self.executor,
+                &name,
+                &token_hash,
+                &token_prefix,
+                uid,
+                &project_id,
+                &permissions,
+                &expires_at.map(utils::time::to_primitive),
+            )
+            .one()
+            .await?;
+        Ok(id)
+    }
+
+    pub async fn get_by_uid(&self, uid: &Uuid) -> AppResult<Vec<ApiToken>> {
+        let tokens = get_api_tokens_by_user_id()
+            .bind(self.executor, uid)
+            .all()
+            .await?
+            .into_iter()
+            .map(|item| ApiToken {
+                id: item.id,
+                name: item.name,
+                token_prefix: item.token_prefix,
+                project_id: item.project_id,
+                permissions: item.permissions,
+                expires_at: utils::time::to_utc_or_default(item.expires_at),
+                last_used_at: utils::time::to_utc_or_default(item.last_used_at),
+                revoked_at: utils::time::to_utc_or_default(item.revoked_at),
+                created_at: utils::time::to_utc(item.created_at),
+            })
+            .collect::<Vec<_>>();
+        Ok(tokens)
+    }
+
+    /* only the owner can revoke a token */
+    pub async fn revoke(&self, id: &i32, uid: &Uuid) -> AppResult {
+        let revoked = revoke_api_token()
+            .bind(self.executor, uid, id, uid)
+            .opt()
+            .await?;
+        match revoked {
+            Some(_) => Ok(()),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![("id".to_string(), id.to_string())],
+                resource_type: ResourceType::ApiToken,
+            })),
+        }
+    }
+
+    /* looks up a live token and records its usage */
+    pub async fn use_token(&self, token_hash: &str) -> AppResult<Option<ApiTokenScope>> {
+        let scope = use_api_token()
+            .bind(self.executor, &token_hash)
+            .opt()
+            .await?
+            .map(|item| ApiTokenScope {
+                id: item.id,
+                uid: item.user_id,
+                project_id: item.project_id,
+                permissions: item.permissions,
+                expires_at: utils::time::to_utc_or_default(item.expires_at),
+            });
+        Ok(scope)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{entity::audit::AuditAction, errors::AppResult};
+use db::queries::audit::*;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct AuditDao<'a, T>
+where
+    T: db::GenericClient,
+{
+    executor: &'a T,
+}
+
+impl<'a, T> AuditDao<'a, T>
+where
+    T: db::GenericClient,
+{
+    pub fn new(executor: &'a T) -> Self {
+        AuditDao { executor }
+    }
+
+    pub async fn create(
+        &self,
+        action: AuditAction,
+        target: &str,
+        detail: Option<&str>,
+        ip: Option<&str>,
+        operator: Option<Uuid>,
+    ) -> AppResult<i32> {
+        let id = insert_audit_log()
+            .bind(
+                self.executor,
+                &action.to_string(),
+                &target,
+                &detail,
+                &ip,
+                &operator,
+            )
+            .one()
+            .await?;
+        Ok(id)
+    }
+}
@@ -1,17 +1,17 @@
 use std::collections::HashMap;
 
 use crate::{
-    dao::entity::Script,
-    dto::request::Issue,
+    dao::{entity::Script, PageCursor},
     entity::{
         case::{
             CaseExecuteRecord, CaseField, CaseResult, CaseStatus, Field, FieldOption, FieldType,
-            FieldValue, FunctionalCase, Template, TemplateField,
+            FieldValue, FunctionalCase, IssueRelation, IssueSource, Template, TemplateField,
+            TrackerIssue,
         },
         file::FileModule,
     },
     errors::{AppError, AppResult, Resource, ResourceType},
-    utils,
+    utils::{self, claim::PageFilter},
 };
 
 use db::queries::{case::*, field::*, template::*};
@@ -164,6 +164,37 @@ macro_rules! impl_to_template {
 
 impl_to_template!(GetTemplateByProjectId, GetTemplateById);
 
+trait ToIssueRelation {
+    fn to_issue_relation(&self) -> IssueRelation;
+}
+
+macro_rules! impl_to_issue_relation {
+    ($($t:ty),*) => {
+        $(
+            impl ToIssueRelation for $t {
+                fn to_issue_relation(&self) -> IssueRelation {
+                    IssueRelation {
+                        id: self.id,
+                        case_id: self.case_id,
+                        issue_id: self.issue_id.clone(),
+                        source: IssueSource::from_str(&self.source),
+                        uri: self.uri.clone(),
+                        title: self.title.clone(),
+                        status: self.status.clone(),
+                        synced_at: utils::time::to_utc_or_default(self.synced_at),
+                        created_at: utils::time::to_utc(self.created_at),
+                        created_by: self.created_by.clone(),
+                        updated_at: utils::time::to_utc_or_default(self.updated_at),
+                        updated_by: self.updated_by.clone(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_to_issue_relation!(GetIssueRelationsByCaseId, GetIssueRelationById);
+
 impl<'a, T> CaseDao<'a, T>
 where
     T: db::GenericClient,
@@ -303,6 +334,27 @@ where
             .collect::<AppResult<Vec<_>>>()
     }
 
+    pub async fn insert_execute_record(
+        &self,
+        case_id: &i32,
+        result: &CaseResult,
+        plan_id: Option<i32>,
+        created_by: &Uuid,
+    ) -> AppResult<i32> {
+        let record_id = insert_case_execute_record()
+            .bind(
+                self.executor,
+                case_id,
+                &result.to_string(),
+                &None::<String>,
+                &plan_id,
+                created_by,
+            )
+            .one()
+            .await?;
+        Ok(record_id)
+    }
+
     pub async fn get_last_execute_record_by_case_id(
         &self,
         case_id: i32,
@@ -320,6 +372,7 @@ where
                     case_id: r.case_id,
                     result: CaseResult::from_str(&r.result),
                     attach_info: r.attach_info,
+                    issue_relation_id: r.issue_relation_id,
                     created_at,
                     created_by: r.created_by,
                     updated_at,
@@ -405,14 +458,6 @@ where
         Ok(())
     }
 
-    pub async fn get_query_cursor(&self, offset: i64) -> AppResult<i32> {
-        let id = get_query_cursor()
-            .bind(self.executor, &offset)
-            .one()
-            .await?;
-        Ok(id)
-    }
-
     pub async fn soft_delete_field_option(&self, id: i32, deleted_by: Uuid) -> AppResult {
         soft_delete_field_option()
             .bind(self.executor, &deleted_by, &id)
@@ -638,20 +683,142 @@ where
     pub async fn insert_case_issue_relation(
         &self,
         case_id: &i32,
-        issue: &Issue,
+        source: &IssueSource,
+        issue: &TrackerIssue,
         created_by: &Uuid,
-    ) -> AppResult {
-        let _ = insert_case_issue_relation()
+    ) -> AppResult<i32> {
+        let id = insert_case_issue_relation()
             .bind(
                 self.executor,
                 case_id,
                 &issue.issue_id,
-                &issue.source,
+                &source.to_string(),
                 &issue.uri,
+                &Some(issue.title.clone()),
+                &Some(issue.status.clone()),
                 created_by,
             )
+            .one()
+            .await?;
+
+        Ok(id)
+    }
+
+    pub async fn get_issue_relations_by_case_id(
+        &self,
+        case_id: &i32,
+    ) -> AppResult<Vec<IssueRelation>> {
+        let relations = get_issue_relations_by_case_id()
+            .bind(self.executor, case_id)
+            .all()
+            .await?
+            .iter()
+            .map(|r| r.to_issue_relation())
+            .collect();
+        Ok(relations)
+    }
+
+    pub async fn get_issue_relation_by_id(&self, id: &i32) -> AppResult<IssueRelation> {
+        match get_issue_relation_by_id()
+            .bind(self.executor, id)
+            .opt()
+            .await?
+        {
+            Some(r) => Ok(r.to_issue_relation()),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![],
+                resource_type: ResourceType::Issue,
+            })),
+        }
+    }
+
+    pub async fn check_issue_relation_absent(
+        &self,
+        case_id: &i32,
+        source: &IssueSource,
+        issue_id: &str,
+    ) -> AppResult {
+        let relation = get_issue_relation_by_case_and_issue()
+            .bind(self.executor, case_id, &issue_id, &source.to_string())
+            .opt()
+            .await?;
+        match relation {
+            Some(_) => Err(AppError::ResourceExistsError(Resource {
+                details: vec![("issue_id".to_string(), issue_id.to_string())],
+                resource_type: ResourceType::Issue,
+            })),
+            None => Ok(()),
+        }
+    }
+
+    pub async fn sync_issue_relation(
+        &self,
+        id: &i32,
+        title: &Option<String>,
+        status: &Option<String>,
+    ) -> AppResult {
+        sync_issue_relation()
+            .bind(self.executor, title, status, id)
             .await?;
+        Ok(())
+    }
 
+    pub async fn soft_delete_issue_relation(&self, id: &i32, deleted_by: &Uuid) -> AppResult {
+        soft_delete_issue_relation()
+            .bind(self.executor, deleted_by, id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_execute_record_by_id(&self, id: &i32) -> AppResult<CaseExecuteRecord> {
+        match get_execute_record_by_id()
+            .bind(self.executor, id)
+            .opt()
+            .await?
+        {
+            Some(r) => Ok(CaseExecuteRecord {
+                id: r.id,
+                case_id: r.case_id,
+                result: CaseResult::from_str(&r.result),
+                attach_info: r.attach_info.unwrap_or_default(),
+                issue_relation_id: r.issue_relation_id,
+                created_at: utils::time::to_utc(r.created_at),
+                created_by: r.created_by,
+                updated_at: utils::time::to_utc_or_default(r.updated_at),
+                updated_by: r.updated_by,
+            }),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![("record_id".to_string(), id.to_string())],
+                resource_type: ResourceType::Case,
+            })),
+        }
+    }
+
+    /* marks the record as being reported, `false` if it is reported or another report is in flight */
+    pub async fn claim_execute_record_report(&self, id: &i32) -> AppResult<bool> {
+        let claimed = claim_execute_record_report()
+            .bind(self.executor, id)
+            .opt()
+            .await?;
+        Ok(claimed.is_some())
+    }
+
+    pub async fn release_execute_record_report(&self, id: &i32) -> AppResult {
+        release_execute_record_report()
+            .bind(self.executor, id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn bind_execute_record_issue(
+        &self,
+        record_id: &i32,
+        issue_relation_id: &i32,
+        updated_by: &Uuid,
+    ) -> AppResult {
+        bind_execute_record_issue()
+            .bind(self.executor, issue_relation_id, updated_by, record_id)
+            .await?;
         Ok(())
     }
 
@@ -754,3 +921,16 @@ where
         }
     }
 }
+
+impl<T> PageCursor for CaseDao<'_, T>
+where
+    T: db::GenericClient,
+{
+    async fn get_query_cursor(&self, filter: &PageFilter, offset: i64) -> AppResult<Option<i32>> {
+        let id = get_query_cursor()
+            .bind(self.executor, &filter.module_ids, &filter.deleted, &offset)
+            .opt()
+            .await?;
+        Ok(id)
+    }
+}
@@ -3,8 +3,11 @@ use std::collections::HashMap;
 use crate::errors::{AppError, AppResult, Resource, ResourceType};
 
 use crate::dao::entity;
-use crate::dao::entity::ElementInfo;
+use crate::dao::entity::{ElementInfo, ElementReference, OperationOption};
+use crate::dao::PageCursor;
+use crate::entity::element::{HealthCheck, HealthCheckResult, HealthCheckStatus, SelectorCheck};
 use crate::utils;
+use crate::utils::claim::PageFilter;
 use db::queries::element::*;
 use tracing::info;
 use uuid::Uuid;
@@ -19,6 +22,64 @@ where
     pub executor: &'a T,
 }
 
+trait ToElementDetail {
+    fn to_element_detail(self) -> ElementDetail;
+}
+
+macro_rules! impl_to_element_detail {
+    ($($t:ty),*) => {
+        $(
+            impl ToElementDetail for $t {
+                fn to_element_detail(self) -> ElementDetail {
+                    ElementDetail {
+                        id: self.id,
+                        name: self.name,
+                        module_id: self.module_id,
+                        module: self.module_name,
+                        value: self.value,
+                        description: self.description,
+                        element_type: self.element_type,
+                        deleted: self.deleted,
+                        created_at: utils::time::to_utc(self.created_at),
+                        updated_at: utils::time::to_utc_or_default(self.updated_at),
+                        created_by: self.created_by,
+                        updated_by: self.updated_by,
+                        operation_options: vec![],
+                        health_status: self.health_status,
+                        health_checked_at: utils::time::to_utc_or_default(self.health_checked_at),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_to_element_detail!(GetElementList, GetElementById);
+
+macro_rules! impl_to_operation_option {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for OperationOption {
+                fn from(value: $t) -> Self {
+                    OperationOption {
+                        id: value.id,
+                        name: value.name,
+                        internal: value.internal,
+                        exec: value.exec,
+                        template: value.template,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_to_operation_option!(
+    GetOperationOptionById,
+    GetOperationOptions,
+    GetOperationOptionsByElementType
+);
+
 impl<'a, T> ElementDao<'a, T>
 where
     T: db::GenericClient,
@@ -32,6 +93,7 @@ where
             .bind(
                 self.executor,
                 &element.name,
+                &element.module_id,
                 &element.value,
                 &element.element_type,
                 &element.description,
@@ -52,56 +114,65 @@ where
                 name: e.name,
                 element_type: e.element_type,
                 action: e.action,
+                template: e.template,
                 selector: e.value,
+                allowed: e.allowed,
             }),
             None => Err(AppError::NotFoundError(Resource {
                 details: vec![],
-                resource_type: ResourceType::File,
+                resource_type: ResourceType::Element,
             })),
         }
     }
 
-    pub async fn get_query_cursor(&self, offset: i64) -> AppResult<i32> {
-        let id = get_query_cursor()
-            .bind(self.executor, &offset)
-            .one()
-            .await?;
-        Ok(id)
-    }
-
     pub async fn get_element_list(
         &self,
         module_id: &Vec<i32>,
         page_size: &i64,
         last_item_id: &i32,
+        deleted: bool,
     ) -> AppResult<Vec<ElementDetail>> {
         let element_list = get_element_list()
-            .bind(self.executor, module_id, last_item_id, page_size)
+            .bind(self.executor, module_id, &deleted, last_item_id, page_size)
             .all()
             .await?
             .into_iter()
-            .map(|item| {
-                let created_at = utils::time::to_utc(item.created_at);
-                let updated_at = utils::time::to_utc_or_default(item.updated_at);
-                ElementDetail {
-                    id: item.id,
-                    name: item.name,
-                    module: item.module_name,
-                    value: item.value,
-                    description: item.description,
-                    element_type: item.element_type,
-                    created_at,
-                    updated_at,
-                    created_by: item.created_by,
-                    updated_by: item.updated_by,
-                    operation_options: vec![],
-                }
-            })
+            .map(|item| item.to_element_detail())
             .collect::<Vec<_>>();
         info!("query result: {element_list:?}");
         Ok(element_list)
     }
 
+    pub async fn get_element_by_id(&self, id: &i32) -> AppResult<ElementDetail> {
+        match get_element_by_id().bind(self.executor, id).opt().await? {
+            Some(e) => Ok(e.to_element_detail()),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![],
+                resource_type: ResourceType::Element,
+            })),
+        }
+    }
+
+    /* selector value is unique within module, `exclude_id` skips the element itself */
+    pub async fn check_value_unique(
+        &self,
+        module_id: &i32,
+        value: &str,
+        exclude_id: Option<i32>,
+    ) -> AppResult {
+        let exist = get_element_by_module_and_value()
+            .bind(self.executor, module_id, &value)
+            .opt()
+            .await?;
+        match exist {
+            Some(id) if Some(id) != exclude_id => Err(AppError::ResourceExistsError(Resource {
+                details: vec![("value".to_string(), value.to_string())],
+                resource_type: ResourceType::Element,
+            })),
+            _ => Ok(()),
+        }
+    }
+
     pub async fn count(
         &self,
         project_id: &i32,
@@ -129,7 +200,6 @@ where
         }
     }
 
-    #[allow(dead_code)]
     pub async fn update(&self, element: entity::Element, updated_by: Uuid) -> AppResult<()> {
         update()
             .bind(
@@ -144,4 +214,305 @@ where
             .await?;
         Ok(())
     }
+
+    pub async fn update_module(&self, id: &i32, module_id: &i32, updated_by: &Uuid) -> AppResult {
+        update_module()
+            .bind(self.executor, module_id, updated_by, id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn soft_delete(&self, id: &i32, deleted_by: &Uuid) -> AppResult {
+        soft_delete().bind(self.executor, deleted_by, id).await?;
+        Ok(())
+    }
+
+    pub async fn restore(&self, id: &i32, updated_by: &Uuid) -> AppResult {
+        restore().bind(self.executor, updated_by, id).await?;
+        Ok(())
+    }
+
+    /* steps of the element are dropped from the scripts still using it */
+    pub async fn detach_scripts(&self, element_id: &i32) -> AppResult<u64> {
+        let detached = detach_element_scripts()
+            .bind(self.executor, element_id)
+            .await?;
+        Ok(detached)
+    }
+
+    pub async fn get_referenced_scripts(
+        &self,
+        element_id: &i32,
+    ) -> AppResult<Vec<ElementReference>> {
+        let scripts = get_referenced_scripts()
+            .bind(self.executor, element_id)
+            .all()
+            .await?
+            .into_iter()
+            .map(|item| ElementReference {
+                script_id: item.id,
+                case_id: item.case_id,
+                case_name: item.case_name,
+                path: item.path,
+                environment: item.environment,
+                created_at: utils::time::to_utc(item.created_at),
+            })
+            .collect::<Vec<_>>();
+        Ok(scripts)
+    }
+
+    /* element_operation_option rows back script_element_relation, created with the script */
+    pub async fn bind_element_operation(&self, element_id: &i32, option_id: &i32) -> AppResult {
+        insert_element_operation_option()
+            .bind(self.executor, option_id, element_id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn create_operation_option(
+        &self,
+        option: &OperationOption,
+        created_by: &Uuid,
+    ) -> AppResult<i32> {
+        let option_id = insert_operation_option()
+            .bind(
+                self.executor,
+                &option.name,
+                &option.exec,
+                &option.template,
+                created_by,
+            )
+            .one()
+            .await?;
+        Ok(option_id)
+    }
+
+    pub async fn update_operation_option(
+        &self,
+        option: &OperationOption,
+        updated_by: &Uuid,
+    ) -> AppResult {
+        update_operation_option()
+            .bind(
+                self.executor,
+                &option.name,
+                &option.exec,
+                &option.template,
+                updated_by,
+                &option.id,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_operation_option(&self, id: &i32) -> AppResult {
+        delete_operation_option().bind(self.executor, id).await?;
+        Ok(())
+    }
+
+    pub async fn get_operation_option_by_id(&self, id: &i32) -> AppResult<OperationOption> {
+        match get_operation_option_by_id()
+            .bind(self.executor, id)
+            .opt()
+            .await?
+        {
+            Some(o) => Ok(o.into()),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![("id".to_string(), id.to_string())],
+                resource_type: ResourceType::OperationOption,
+            })),
+        }
+    }
+
+    /* exec keyword is unique, `exclude_id` skips the option itself */
+    pub async fn check_exec_unique(&self, exec: &str, exclude_id: Option<i32>) -> AppResult {
+        match get_operation_option_by_exec()
+            .bind(self.executor, &exec)
+            .opt()
+            .await?
+        {
+            Some(id) if Some(id) != exclude_id => Err(AppError::ResourceExistsError(Resource {
+                details: vec![("exec".to_string(), exec.to_string())],
+                resource_type: ResourceType::OperationOption,
+            })),
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn get_operation_options(
+        &self,
+        element_type: Option<&str>,
+    ) -> AppResult<Vec<OperationOption>> {
+        let options = match element_type {
+            Some(t) => get_operation_options_by_element_type()
+                .bind(self.executor, &t)
+                .all()
+                .await?
+                .into_iter()
+                .map(OperationOption::from)
+                .collect::<Vec<_>>(),
+            None => get_operation_options()
+                .bind(self.executor)
+                .all()
+                .await?
+                .into_iter()
+                .map(OperationOption::from)
+                .collect::<Vec<_>>(),
+        };
+        Ok(options)
+    }
+
+    pub async fn count_operation_option_references(&self, option_id: &i32) -> AppResult<i64> {
+        let count = count_operation_option_references()
+            .bind(self.executor, option_id)
+            .one()
+            .await?;
+        Ok(count)
+    }
+
+    pub async fn bind_type_operation_option(
+        &self,
+        element_type: &str,
+        option_id: &i32,
+        created_by: &Uuid,
+    ) -> AppResult {
+        insert_type_operation_option()
+            .bind(self.executor, &element_type, option_id, created_by)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn unbind_type_operation_option(
+        &self,
+        element_type: &str,
+        option_id: &i32,
+    ) -> AppResult {
+        delete_type_operation_option()
+            .bind(self.executor, &element_type, option_id)
+            .await?;
+        Ok(())
+    }
+
+    /* (element_id, selector) of alive elements in module */
+    pub async fn get_selectors_by_module_id(
+        &self,
+        module_id: &i32,
+    ) -> AppResult<Vec<(i32, String)>> {
+        let selectors = get_selectors_by_module_id()
+            .bind(self.executor, module_id)
+            .all()
+            .await?
+            .into_iter()
+            .map(|item| (item.id, item.value))
+            .collect::<Vec<_>>();
+        Ok(selectors)
+    }
+
+    pub async fn create_health_check(
+        &self,
+        module_id: &i32,
+        page_url: &str,
+        machine_id: Option<i32>,
+        created_by: &Uuid,
+    ) -> AppResult<i32> {
+        let check_id = insert_health_check()
+            .bind(self.executor, module_id, &page_url, &machine_id, created_by)
+            .one()
+            .await?;
+        Ok(check_id)
+    }
+
+    pub async fn finish_health_check(
+        &self,
+        id: &i32,
+        status: HealthCheckStatus,
+        error: Option<String>,
+    ) -> AppResult {
+        finish_health_check()
+            .bind(self.executor, &status.to_string(), &error, id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_health_check_by_id(&self, id: &i32) -> AppResult<HealthCheck> {
+        match get_health_check_by_id()
+            .bind(self.executor, id)
+            .opt()
+            .await?
+        {
+            Some(c) => Ok(HealthCheck {
+                id: c.id,
+                module_id: c.module_id,
+                page_url: c.page_url,
+                machine_id: c.machine_id,
+                status: c.status,
+                error: c.error,
+                created_at: utils::time::to_utc(c.created_at),
+                created_by: c.created_by,
+                finished_at: utils::time::to_utc_or_default(c.finished_at),
+            }),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![("id".to_string(), id.to_string())],
+                resource_type: ResourceType::HealthCheck,
+            })),
+        }
+    }
+
+    pub async fn insert_health_check_result(
+        &self,
+        check_id: &i32,
+        check: &SelectorCheck,
+    ) -> AppResult {
+        insert_health_check_result()
+            .bind(
+                self.executor,
+                check_id,
+                &check.element_id,
+                &check.selector,
+                &check.status.to_string(),
+                &check.match_count,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_health_check_results(
+        &self,
+        check_id: &i32,
+    ) -> AppResult<Vec<HealthCheckResult>> {
+        let results = get_health_check_results()
+            .bind(self.executor, check_id)
+            .all()
+            .await?
+            .into_iter()
+            .map(|item| HealthCheckResult {
+                element_id: item.element_id,
+                element_name: item.element_name,
+                selector: item.selector,
+                status: item.status,
+                match_count: item.match_count,
+                checked_at: utils::time::to_utc(item.checked_at),
+            })
+            .collect::<Vec<_>>();
+        Ok(results)
+    }
+
+    /* checks interrupted by a restart stay RUNNING forever otherwise */
+    pub async fn expire_health_checks(&self) -> AppResult<u64> {
+        let expired = expire_health_checks().bind(self.executor).await?;
+        Ok(expired)
+    }
+}
+
+impl<T> PageCursor for ElementDao<'_, T>
+where
+    T: db::GenericClient,
+{
+    async fn get_query_cursor(&self, filter: &PageFilter, offset: i64) -> AppResult<Option<i32>> {
+        let id = get_query_cursor()
+            .bind(self.executor, &filter.module_ids, &filter.deleted, &offset)
+            .opt()
+            .await?;
+        Ok(id)
+    }
 }
@@ -24,7 +24,7 @@ pub struct CaseModuleInfo {
 pub struct Element {
     pub id: i32,
     pub name: String,
-    pub module: String,
+    pub module_id: i32,
     pub value: String,
     pub element_type: String,
     pub description: Option<String>,
@@ -39,6 +39,7 @@ impl Element {
         value: &str,
         element_type: &str,
         description: Option<&str>,
+        module_id: i32,
         created_by: Uuid,
     ) -> Self {
         let description = description.map(|s| s.to_string());
@@ -46,7 +47,7 @@ impl Element {
             id: 0,
             name: name.into(),
             value: value.into(),
-            module: "".into(),
+            module_id,
             element_type: element_type.into(),
             description,
             created_at: Utc::now(),
@@ -60,30 +61,61 @@ impl Element {
 pub struct ElementDetail {
     pub id: i32,
     pub name: String,
+    pub module_id: i32,
     pub module: String,
     pub value: String,
     pub element_type: String,
     pub description: Option<String>,
+    pub deleted: bool,
     pub created_at: DateTime<Utc>,
     pub created_by: String,
     pub updated_at: Option<DateTime<Utc>>,
     pub updated_by: Option<String>,
     pub operation_options: Vec<OperationOption>,
+    /* latest selector health check result, None if never checked */
+    pub health_status: Option<String>,
+    pub health_checked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub struct ElementReference {
+    pub script_id: i32,
+    pub case_id: i32,
+    pub case_name: String,
+    pub path: String,
+    pub environment: String,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct ElementInfo {
     pub name: String,
     pub action: String,
+    pub template: Option<String>,
     pub element_type: String,
     pub selector: Option<String>,
+    pub allowed: bool,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
 pub struct OperationOption {
     pub id: i32,
     pub name: String,
     pub internal: bool,
+    pub exec: String,
+    pub template: Option<String>,
+}
+
+impl OperationOption {
+    pub fn new(name: &str, exec: &str, template: Option<&str>) -> Self {
+        OperationOption {
+            id: 0,
+            name: name.into(),
+            internal: false,
+            exec: exec.into(),
+            template: template.map(|s| s.to_string()),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -104,7 +136,7 @@ pub struct Step {
     pub attach_info: Option<HashMap<String, String>>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Machine {
     pub addr: String,
     pub authentication: String,
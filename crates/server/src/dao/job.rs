@@ -0,0 +1,158 @@
+use crate::{
+    entity::job::{BackgroundJob, ClaimedJob, JobKind, JobStatus},
+    errors::{AppError, AppResult, Resource, ResourceType},
+    utils,
+};
+use chrono::{DateTime, Utc};
+use db::queries::job::*;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct JobDao<'a, T>
+where
+    T: db::GenericClient,
+{
+    executor: &'a T,
+}
+
+impl<'a, T> JobDao<'a, T>
+where
+    T: db::GenericClient,
+{
+    pub fn new(executor: &'a T) -> Self {
+        JobDao { executor }
+    }
+
+    pub async fn create(
+        &self,
+        kind: JobKind,
+        payload: &str,
+        max_attempts: i32,
+        created_by: &Uuid,
+    ) -> AppResult<i32> {
+        let id = insert()
+            .bind(
+                self.executor,
+                &kind.to_string(),
+                &payload,
+                &max_attempts,
+                created_by,
+            )
+            .one()
+            .await?;
+        Ok(id)
+    }
+
+    /* pending jobs locked by another worker are skipped instead of waited for */
+    pub async fn claim(&self, worker: &str) -> AppResult<Option<ClaimedJob>> {
+        let job = claim_job()
+            .bind(self.executor, &worker)
+            .opt()
+            .await?
+            .map(|j| ClaimedJob {
+                id: j.id,
+                kind: JobKind::from_str(&j.kind),
+                payload: j.payload,
+                attempts: j.attempts,
+                max_attempts: j.max_attempts,
+                created_by: j.created_by,
+            });
+        Ok(job)
+    }
+
+    /* returns whether cancellation was requested, also keeps the job from going stale */
+    pub async fn update_progress(&self, id: &i32, progress: i32) -> AppResult<bool> {
+        let cancel_requested = update_progress()
+            .bind(self.executor, &progress.clamp(0, 100), id)
+            .opt()
+            .await?;
+        Ok(cancel_requested.unwrap_or_default())
+    }
+
+    /* refreshes `locked_at` of a job still held by the worker */
+    pub async fn heartbeat(&self, id: &i32, worker: &str) -> AppResult<bool> {
+        let updated = heartbeat_job().bind(self.executor, id, &worker).await?;
+        Ok(updated > 0)
+    }
+
+    /* returns `false` if the job was requeued to another worker meanwhile */
+    pub async fn finish(
+        &self,
+        id: &i32,
+        worker: &str,
+        status: JobStatus,
+        result: Option<String>,
+        error: Option<String>,
+    ) -> AppResult<bool> {
+        let updated = finish_job()
+            .bind(
+                self.executor,
+                &status.to_string(),
+                &result,
+                &error,
+                id,
+                &worker,
+            )
+            .await?;
+        Ok(updated > 0)
+    }
+
+    /* returns `false` if the job was cancelled or requeued meanwhile and must not run again */
+    pub async fn retry(
+        &self,
+        id: &i32,
+        worker: &str,
+        error: &str,
+        run_at: DateTime<Utc>,
+    ) -> AppResult<bool> {
+        let updated = retry_job()
+            .bind(
+                self.executor,
+                &error,
+                &utils::time::to_primitive(run_at),
+                id,
+                &worker,
+            )
+            .await?;
+        Ok(updated > 0)
+    }
+
+    /* returns the status after cancellation, `None` if the job already finished */
+    pub async fn cancel(&self, id: &i32) -> AppResult<Option<String>> {
+        let status = cancel_job().bind(self.executor, id).opt().await?;
+        Ok(status)
+    }
+
+    pub async fn requeue_stale(&self, stale_before: DateTime<Utc>) -> AppResult<u64> {
+        let count = requeue_stale_jobs()
+            .bind(self.executor, &utils::time::to_primitive(stale_before))
+            .await?;
+        Ok(count)
+    }
+
+    pub async fn get_job_by_id(&self, id: &i32) -> AppResult<BackgroundJob> {
+        match get_job_by_id().bind(self.executor, id).opt().await? {
+            Some(j) => Ok(BackgroundJob {
+                id: j.id,
+                kind: j.kind,
+                status: j.status,
+                progress: j.progress,
+                result: j.result.as_deref().map(serde_json::from_str).transpose()?,
+                error: j.error,
+                attempts: j.attempts,
+                max_attempts: j.max_attempts,
+                run_at: utils::time::to_utc(j.run_at),
+                locked_by: j.locked_by,
+                cancel_requested: j.cancel_requested,
+                finished_at: utils::time::to_utc_or_default(j.finished_at),
+                created_at: utils::time::to_utc(j.created_at),
+                created_by: j.created_by,
+                updated_at: utils::time::to_utc_or_default(j.updated_at),
+            }),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![("id".to_string(), id.to_string())],
+                resource_type: ResourceType::Job,
+            })),
+        }
+    }
+}
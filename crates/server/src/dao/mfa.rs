@@ -0,0 +1,107 @@
+use crate::{entity::mfa::UserMfa, errors::AppResult};
+use db::queries::mfa::*;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct MfaDao<'a, T>
+where
+    T: db::GenericClient,
+{
+    executor: &'a T,
+}
+
+impl<'a, T> MfaDao<'a, T>
+where
+    T: db::GenericClient,
+{
+    pub fn new(executor: &'a T) -> Self {
+        MfaDao { executor }
+    }
+
+    pub async fn find(&self, uid: &Uuid) -> AppResult<Option<UserMfa>> {
+        let ret = get_user_mfa()
+            .bind(self.executor, uid)
+            .opt()
+            .await?
+            .map(|item| UserMfa {
+                uid: item.user_id,
+                secret: item.secret,
+                enabled: item.enabled,
+                last_step: item.last_step,
+            });
+        Ok(ret)
+    }
+
+    /* `false` when the user already has an enabled enrollment */
+    pub async fn enroll(
+        &self,
+        uid: &Uuid,
+        secret: &str,
+        recovery_codes: &[String],
+    ) -> AppResult<bool> {
+        let ret = upsert_user_mfa()
+            .bind(self.executor, uid, &secret, &recovery_codes)
+            .opt()
+            .await?;
+        Ok(ret.is_some())
+    }
+
+    pub async fn enable(&self, uid: &Uuid) -> AppResult<bool> {
+        let ret = enable_user_mfa().bind(self.executor, uid).opt().await?;
+        Ok(ret.is_some())
+    }
+
+    /* atomically moves `last_step` forward, `false` when the step was already used */
+    pub async fn use_step(&self, uid: &Uuid, step: i64) -> AppResult<bool> {
+        let ret = use_user_mfa_step()
+            .bind(self.executor, &step, uid)
+            .opt()
+            .await?;
+        Ok(ret.is_some())
+    }
+
+    pub async fn use_recovery_code(&self, uid: &Uuid, code_hash: &str) -> AppResult<bool> {
+        let ret = use_user_mfa_recovery_code()
+            .bind(self.executor, &code_hash, uid)
+            .opt()
+            .await?;
+        Ok(ret.is_some())
+    }
+
+    pub async fn update_recovery_codes(
+        &self,
+        uid: &Uuid,
+        recovery_codes: &[String],
+    ) -> AppResult<bool> {
+        let ret = update_user_mfa_recovery_codes()
+            .bind(self.executor, &recovery_codes, uid)
+            .opt()
+            .await?;
+        Ok(ret.is_some())
+    }
+
+    pub async fn delete(&self, uid: &Uuid) -> AppResult {
+        delete_user_mfa().bind(self.executor, uid).await?;
+        Ok(())
+    }
+
+    pub async fn is_required(&self, uid: &Uuid) -> AppResult<bool> {
+        Ok(is_user_mfa_required()
+            .bind(self.executor, uid)
+            .one()
+            .await?)
+    }
+
+    pub async fn set_role_required(
+        &self,
+        role_id: &i32,
+        required: bool,
+        operator: &Uuid,
+    ) -> AppResult<bool> {
+        let ret = update_role_mfa_required()
+            .bind(self.executor, &required, operator, role_id)
+            .opt()
+            .await?;
+        Ok(ret.is_some())
+    }
+}
@@ -1,8 +1,28 @@
+pub mod api_token;
+pub mod audit;
 pub mod case;
 pub mod element;
 pub mod entity;
 pub mod file;
+pub mod job;
+pub mod mfa;
+pub mod notification;
 pub mod permission;
 pub mod plan;
 pub mod project;
+pub mod requirement;
 pub mod user;
+
+use std::future::Future;
+
+use crate::{errors::AppResult, utils::claim::PageFilter};
+
+/* implemented by the dao of every list paged by id */
+pub trait PageCursor {
+    /* id at `offset` of the list matching `filter`, ordered by id */
+    fn get_query_cursor(
+        &self,
+        filter: &PageFilter,
+        offset: i64,
+    ) -> impl Future<Output = AppResult<Option<i32>>> + Send;
+}
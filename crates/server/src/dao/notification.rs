@@ -0,0 +1,167 @@
+use crate::{
+    entity::notification::{
+        ChannelType, NotificationChannel, NotificationEvent, NotificationPreference,
+    },
+    errors::{AppError, AppResult, Resource, ResourceType},
+    utils,
+};
+use db::queries::notification::*;
+use uuid::Uuid;
+
+macro_rules! impl_to_notification_channel {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for NotificationChannel {
+                fn from(value: $t) -> Self {
+                    NotificationChannel {
+                        id: value.id,
+                        name: value.name,
+                        channel_type: ChannelType::from_str(&value.channel_type),
+                        url: value.url,
+                        secret: value.secret,
+                        created_at: utils::time::to_utc(value.created_at),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_to_notification_channel!(GetNotificationChannelsByUserId, GetNotificationChannel);
+
+#[derive(Debug)]
+pub struct NotificationDao<'a, T>
+where
+    T: db::GenericClient,
+{
+    executor: &'a T,
+}
+
+impl<'a, T> NotificationDao<'a, T>
+where
+    T: db::GenericClient,
+{
+    pub fn new(executor: &'a T) -> Self {
+        NotificationDao { executor }
+    }
+
+    pub async fn create_channel(
+        &self,
+        uid: &Uuid,
+        name: &str,
+        channel_type: ChannelType,
+        url: &str,
+        secret: Option<&str>,
+    ) -> AppResult<i32> {
+        let id = insert_notification_channel()
+            .bind(
+                self.executor,
+                uid,
+                &name,
+                &channel_type.to_string(),
+                &url,
+                &secret,
+            )
+            .one()
+            .await?;
+        Ok(id)
+    }
+
+    pub async fn get_channels(&self, uid: &Uuid) -> AppResult<Vec<NotificationChannel>> {
+        let channels = get_notification_channels_by_user_id()
+            .bind(self.executor, uid)
+            .all()
+            .await?
+            .into_iter()
+            .map(NotificationChannel::from)
+            .collect::<Vec<_>>();
+        Ok(channels)
+    }
+
+    /* channels of other users are never found */
+    pub async fn get_channel(&self, id: &i32, uid: &Uuid) -> AppResult<NotificationChannel> {
+        let channel = get_notification_channel()
+            .bind(self.executor, id, uid)
+            .opt()
+            .await?;
+        match channel {
+            Some(channel) => Ok(channel.into()),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![("id".to_string(), id.to_string())],
+                resource_type: ResourceType::NotificationChannel,
+            })),
+        }
+    }
+
+    /* the channel is also dropped from every preference of the user */
+    pub async fn delete_channel(&self, id: &i32, uid: &Uuid) -> AppResult {
+        let deleted = delete_notification_channel()
+            .bind(self.executor, id, uid)
+            .opt()
+            .await?;
+        if deleted.is_none() {
+            return Err(AppError::NotFoundError(Resource {
+                details: vec![("id".to_string(), id.to_string())],
+                resource_type: ResourceType::NotificationChannel,
+            }));
+        }
+        remove_channel_from_preferences()
+            .bind(self.executor, id, uid)
+            .await?;
+        Ok(())
+    }
+
+    /* one preference per event type, the default one for events never configured */
+    pub async fn get_preferences(&self, uid: &Uuid) -> AppResult<Vec<NotificationPreference>> {
+        let saved = get_notification_preferences_by_user_id()
+            .bind(self.executor, uid)
+            .all()
+            .await?;
+        let preferences = NotificationEvent::ALL
+            .into_iter()
+            .map(|event_type| {
+                saved
+                    .iter()
+                    .find(|item| item.event_type == event_type.to_string())
+                    .map(|item| NotificationPreference {
+                        event_type,
+                        email: item.email,
+                        channel_ids: item.channel_ids.clone(),
+                    })
+                    .unwrap_or_else(|| NotificationPreference::default_for(event_type))
+            })
+            .collect::<Vec<_>>();
+        Ok(preferences)
+    }
+
+    pub async fn get_preference(
+        &self,
+        uid: &Uuid,
+        event_type: NotificationEvent,
+    ) -> AppResult<NotificationPreference> {
+        let preference = self
+            .get_preferences(uid)
+            .await?
+            .into_iter()
+            .find(|item| item.event_type == event_type)
+            .unwrap_or_else(|| NotificationPreference::default_for(event_type));
+        Ok(preference)
+    }
+
+    pub async fn save_preference(
+        &self,
+        uid: &Uuid,
+        preference: &NotificationPreference,
+    ) -> AppResult {
+        upsert_notification_preference()
+            .bind(
+                self.executor,
+                uid,
+                &preference.event_type.to_string(),
+                &preference.email,
+                &preference.channel_ids,
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -55,6 +55,61 @@ where
         Ok(())
     }
 
+    pub async fn delete_role_permission_relation(
+        &self,
+        role_id: i32,
+        permission_ids: Vec<i32>,
+    ) -> AppResult {
+        if permission_ids.is_empty() {
+            return Ok(());
+        }
+        delete_role_permission_relation()
+            .bind(self.executor, &role_id, &permission_ids)
+            .await?;
+        Ok(())
+    }
+
+    /* returns the permission id and whether it was created */
+    pub async fn insert_permission(&self, module: &str, scope: &str) -> AppResult<(i32, bool)> {
+        let permission = insert_permission()
+            .bind(self.executor, &module, &scope)
+            .one()
+            .await?;
+        Ok((permission.id, permission.created))
+    }
+
+    /* `except_name` is an internal role with a limited permission set */
+    pub async fn grant_internal_role_permission(
+        &self,
+        permission_id: i32,
+        except_name: &str,
+    ) -> AppResult {
+        grant_internal_role_permission()
+            .bind(self.executor, &permission_id, &except_name)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn grant_role_permission_by_name(&self, name: &str, permission_id: i32) -> AppResult {
+        grant_role_permission_by_name()
+            .bind(self.executor, &permission_id, &name)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn replace_api_permission_relation(
+        &self,
+        relations: &[(&str, &str, i32)],
+    ) -> AppResult {
+        delete_api_permission_relation().bind(self.executor).await?;
+        for (uri, method, permission_id) in relations.iter() {
+            insert_api_permission_relation()
+                .bind(self.executor, uri, method, permission_id)
+                .await?;
+        }
+        Ok(())
+    }
+
     pub async fn get_permission_by_role_id(&self, role_id: i32) -> AppResult<Vec<Permission>> {
         let permission_list = get_permission_by_role_id()
             .bind(self.executor, &role_id)
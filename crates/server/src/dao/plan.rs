@@ -1,8 +1,61 @@
 use std::collections::HashMap;
 
-use crate::{entity::project::Plan, errors::AppResult, utils};
+use crate::{
+    dao::PageCursor,
+    entity::{
+        plan::{PlanRun, PlanSchedule, PlanScript, RunStatus, RunTrigger},
+        project::Plan,
+    },
+    errors::{AppError, AppResult, Resource, ResourceType},
+    utils::{self, claim::PageFilter},
+};
+use chrono::{DateTime, Utc};
 use db::queries::plan::*;
 use tracing::info;
+use uuid::Uuid;
+
+macro_rules! impl_to_plan_schedule {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for PlanSchedule {
+                fn from(value: $t) -> Self {
+                    PlanSchedule {
+                        id: value.id,
+                        plan_id: value.plan_id,
+                        script_id: value.script_id,
+                        cron: value.cron,
+                        machine_id: value.machine_id,
+                        enabled: value.enabled,
+                        last_fired_at: utils::time::to_utc_or_default(value.last_fired_at),
+                        created_at: utils::time::to_utc(value.created_at),
+                        created_by: value.created_by,
+                        updated_at: utils::time::to_utc_or_default(value.updated_at),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_to_plan_schedule!(GetScheduleByPlanId, GetEnabledSchedules);
+
+macro_rules! impl_to_plan_script {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for PlanScript {
+                fn from(value: $t) -> Self {
+                    PlanScript {
+                        id: value.id,
+                        case_id: value.case_id,
+                        path: value.path,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_to_plan_script!(GetPlanScripts, GetPlanScriptById);
 
 pub struct PlanDao<'a, T>
 where
@@ -36,14 +89,6 @@ where
         Ok(plan_id)
     }
 
-    pub async fn get_query_cursor(&self, offset: i64) -> AppResult<i32> {
-        let id = get_query_cursor()
-            .bind(self.executor, &offset)
-            .one()
-            .await?;
-        Ok(id)
-    }
-
     pub async fn get_plan_list(
         &self,
         module_id: &Vec<i32>,
@@ -108,4 +153,229 @@ where
             .collect::<Vec<_>>();
         Ok(plan_module_count)
     }
+
+    pub async fn get_plan_by_id(&self, id: &i32) -> AppResult<Plan> {
+        match get_plan_by_id().bind(self.executor, id).opt().await? {
+            Some(item) => Ok(Plan {
+                id: item.id,
+                name: item.name,
+                status: item.status,
+                description: item.description,
+                module_id: item.module_id,
+                project_id: item.project_id,
+                created_at: utils::time::to_utc(item.created_at),
+                created_by: item.created_by,
+                updated_at: utils::time::to_utc_or_default(item.updated_at),
+                updated_by: item.updated_by,
+                start_date: utils::time::date_to_utc(item.start_date),
+                end_date: utils::time::date_to_utc(item.end_date),
+            }),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![("id".to_string(), id.to_string())],
+                resource_type: ResourceType::Plan,
+            })),
+        }
+    }
+
+    /* latest script of every alive case in plan */
+    pub async fn get_plan_scripts(&self, plan_id: &i32) -> AppResult<Vec<PlanScript>> {
+        let scripts = get_plan_scripts()
+            .bind(self.executor, plan_id)
+            .all()
+            .await?
+            .into_iter()
+            .map(PlanScript::from)
+            .collect::<Vec<_>>();
+        Ok(scripts)
+    }
+
+    pub async fn get_plan_script_by_id(&self, plan_id: &i32, id: &i32) -> AppResult<PlanScript> {
+        match get_plan_script_by_id()
+            .bind(self.executor, plan_id, id)
+            .opt()
+            .await?
+        {
+            Some(s) => Ok(s.into()),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![("script_id".to_string(), id.to_string())],
+                resource_type: ResourceType::Script,
+            })),
+        }
+    }
+
+    pub async fn upsert_schedule(
+        &self,
+        plan_id: &i32,
+        script_id: Option<i32>,
+        cron: &str,
+        machine_id: &i32,
+        enabled: bool,
+        updated_by: &Uuid,
+    ) -> AppResult<i32> {
+        let schedule_id = upsert_schedule()
+            .bind(
+                self.executor,
+                plan_id,
+                &script_id,
+                &cron,
+                machine_id,
+                &enabled,
+                updated_by,
+            )
+            .one()
+            .await?;
+        Ok(schedule_id)
+    }
+
+    pub async fn soft_delete_schedule(&self, plan_id: &i32, deleted_by: &Uuid) -> AppResult {
+        soft_delete_schedule()
+            .bind(self.executor, deleted_by, plan_id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_schedule_by_plan_id(&self, plan_id: &i32) -> AppResult<PlanSchedule> {
+        match get_schedule_by_plan_id()
+            .bind(self.executor, plan_id)
+            .opt()
+            .await?
+        {
+            Some(s) => Ok(s.into()),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![("plan_id".to_string(), plan_id.to_string())],
+                resource_type: ResourceType::Schedule,
+            })),
+        }
+    }
+
+    /* held until the transaction ends, `claim_schedule` takes the same row lock */
+    pub async fn lock_schedule(&self, plan_id: &i32) -> AppResult {
+        lock_schedule_by_plan_id()
+            .bind(self.executor, plan_id)
+            .opt()
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_enabled_schedules(&self) -> AppResult<Vec<PlanSchedule>> {
+        let schedules = get_enabled_schedules()
+            .bind(self.executor)
+            .all()
+            .await?
+            .into_iter()
+            .map(PlanSchedule::from)
+            .collect::<Vec<_>>();
+        Ok(schedules)
+    }
+
+    /* returns `false` if the slot was already claimed, e.g. by another replica */
+    pub async fn claim_schedule(&self, id: &i32, fired_at: DateTime<Utc>) -> AppResult<bool> {
+        let claimed = claim_schedule()
+            .bind(self.executor, &utils::time::to_primitive(fired_at), id)
+            .opt()
+            .await?;
+        Ok(claimed.is_some())
+    }
+
+    pub async fn create_run(
+        &self,
+        plan_id: &i32,
+        schedule_id: Option<i32>,
+        script_id: Option<i32>,
+        trigger: RunTrigger,
+        created_by: &Uuid,
+    ) -> AppResult<i32> {
+        let run_id = insert_run()
+            .bind(
+                self.executor,
+                plan_id,
+                &schedule_id,
+                &script_id,
+                &trigger.to_string(),
+                created_by,
+            )
+            .one()
+            .await?;
+        Ok(run_id)
+    }
+
+    pub async fn finish_run(
+        &self,
+        id: &i32,
+        status: RunStatus,
+        (total, passed, failed): (i32, i32, i32),
+        error: Option<String>,
+    ) -> AppResult {
+        finish_run()
+            .bind(
+                self.executor,
+                &status.to_string(),
+                &total,
+                &passed,
+                &failed,
+                &error,
+                id,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_run_job(&self, id: &i32, job_id: &i32) -> AppResult {
+        set_run_job().bind(self.executor, job_id, id).await?;
+        Ok(())
+    }
+
+    /* runs whose job ended without finishing them, e.g. the worker was lost */
+    pub async fn expire_lost_runs(&self) -> AppResult<u64> {
+        let expired = expire_lost_runs().bind(self.executor).await?;
+        Ok(expired)
+    }
+
+    pub async fn has_running_run(&self, plan_id: &i32) -> AppResult<bool> {
+        let count = count_running_runs()
+            .bind(self.executor, plan_id)
+            .one()
+            .await?;
+        Ok(count > 0)
+    }
+
+    pub async fn get_runs_by_plan_id(&self, plan_id: &i32, limit: i64) -> AppResult<Vec<PlanRun>> {
+        let runs = get_runs_by_plan_id()
+            .bind(self.executor, plan_id, &limit)
+            .all()
+            .await?
+            .into_iter()
+            .map(|item| PlanRun {
+                id: item.id,
+                plan_id: item.plan_id,
+                schedule_id: item.schedule_id,
+                script_id: item.script_id,
+                job_id: item.job_id,
+                trigger_type: item.trigger_type,
+                status: item.status,
+                total: item.total,
+                passed: item.passed,
+                failed: item.failed,
+                error: item.error,
+                started_at: utils::time::to_utc(item.started_at),
+                finished_at: utils::time::to_utc_or_default(item.finished_at),
+                duration_ms: item.duration_ms,
+                created_by: item.created_by,
+            })
+            .collect::<Vec<_>>();
+        Ok(runs)
+    }
+}
+
+impl<T> PageCursor for PlanDao<'_, T>
+where
+    T: db::GenericClient,
+{
+    async fn get_query_cursor(&self, filter: &PageFilter, offset: i64) -> AppResult<Option<i32>> {
+        let id = get_query_cursor()
+            .bind(self.executor, &filter.module_ids, &offset)
+            .opt()
+            .await?;
+        Ok(id)
+    }
 }
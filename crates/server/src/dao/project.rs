@@ -75,7 +75,6 @@ where
         Ok(ret)
     }
 
-    #[allow(dead_code)]
     pub async fn insert(&self, project: &Project) -> AppResult<i32> {
         let project_id = insert_project()
             .bind(
@@ -90,6 +89,61 @@ where
         Ok(project_id)
     }
 
+    pub async fn update(
+        &self,
+        id: &i32,
+        name: &str,
+        description: &Option<String>,
+        updated_by: &Uuid,
+    ) -> AppResult {
+        update_project()
+            .bind(self.executor, &name, description, updated_by, id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_module_setting(
+        &self,
+        id: &i32,
+        module_setting: &str,
+        updated_by: &Uuid,
+    ) -> AppResult {
+        update_project_module_setting()
+            .bind(self.executor, &module_setting, updated_by, id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_enable(&self, id: &i32, enable: bool, updated_by: &Uuid) -> AppResult {
+        update_project_enable()
+            .bind(self.executor, &enable, updated_by, id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn soft_delete(&self, id: &i32, deleted_by: &Uuid) -> AppResult {
+        soft_delete_project()
+            .bind(self.executor, deleted_by, id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_member_uuids(&self, project_id: &i32) -> AppResult<Vec<Uuid>> {
+        let uuids = get_project_member_uuids()
+            .bind(self.executor, project_id)
+            .all()
+            .await?;
+        Ok(uuids)
+    }
+
+    /* copy the internal template, its fields and options into the project */
+    pub async fn seed_template(&self, project_id: &i32, created_by: &Uuid) -> AppResult {
+        seed_project_template()
+            .bind(self.executor, project_id, created_by)
+            .await?;
+        Ok(())
+    }
+
     pub async fn find_by_id(&self, id: i32) -> AppResult<ProjectInfo> {
         let ret = find_project_by_id().bind(self.executor, &id).opt().await?;
         match ret {
@@ -135,4 +189,38 @@ where
             .collect::<Vec<_>>();
         Ok(members)
     }
+
+    /* project the resource belongs to, soft deleted resources included */
+    pub async fn get_owner(&self, resource_type: ResourceType, id: i32) -> AppResult<i32> {
+        let owner = match resource_type {
+            ResourceType::Module => get_module_owner().bind(self.executor, &id).opt().await?,
+            ResourceType::Case => get_case_owner().bind(self.executor, &id).opt().await?,
+            ResourceType::Element => get_element_owner().bind(self.executor, &id).opt().await?,
+            ResourceType::Template => get_template_owner().bind(self.executor, &id).opt().await?,
+            ResourceType::Field => get_field_owner().bind(self.executor, &id).opt().await?,
+            ResourceType::Issue => {
+                get_issue_relation_owner()
+                    .bind(self.executor, &id)
+                    .opt()
+                    .await?
+            }
+            ResourceType::HealthCheck => {
+                get_health_check_owner()
+                    .bind(self.executor, &id)
+                    .opt()
+                    .await?
+            }
+            _ => {
+                return Err(AppError::BadRequestError(format!(
+                    "{resource_type} is not owned by a project"
+                )))
+            }
+        };
+        owner.ok_or_else(|| {
+            AppError::NotFoundError(Resource {
+                details: vec![("id".to_string(), id.to_string())],
+                resource_type,
+            })
+        })
+    }
 }
@@ -0,0 +1,199 @@
+use crate::{
+    entity::requirement::{Requirement, RequirementCoverage},
+    errors::{AppError, AppResult, Resource, ResourceType},
+    utils,
+};
+use db::queries::requirement::*;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct RequirementDao<'a, T>
+where
+    T: db::GenericClient,
+{
+    executor: &'a T,
+}
+
+trait ToRequirement {
+    fn to_requirement(&self) -> Requirement;
+}
+
+macro_rules! impl_to_requirement {
+    ($($t:ty),*) => {
+        $(
+            impl ToRequirement for $t {
+                fn to_requirement(&self) -> Requirement {
+                    Requirement {
+                        id: self.id,
+                        project_id: self.project_id,
+                        name: self.name.clone(),
+                        description: self.description.clone(),
+                        source: self.source.clone(),
+                        external_id: self.external_id.clone(),
+                        uri: self.uri.clone(),
+                        status: self.status.clone(),
+                        created_at: utils::time::to_utc(self.created_at),
+                        created_by: self.created_by.clone(),
+                        updated_at: utils::time::to_utc_or_default(self.updated_at),
+                        updated_by: self.updated_by.clone(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_to_requirement!(GetRequirementById, GetRequirementList);
+
+impl<'a, T> RequirementDao<'a, T>
+where
+    T: db::GenericClient,
+{
+    pub fn new(executor: &'a T) -> Self {
+        RequirementDao { executor }
+    }
+
+    pub async fn create(&self, requirement: &Requirement, created_by: &Uuid) -> AppResult<i32> {
+        let id = insert()
+            .bind(
+                self.executor,
+                &requirement.project_id,
+                &requirement.name,
+                &requirement.description,
+                &requirement.source,
+                &requirement.external_id,
+                &requirement.uri,
+                &requirement.status,
+                created_by,
+            )
+            .one()
+            .await?;
+        Ok(id)
+    }
+
+    pub async fn update(&self, requirement: &Requirement, updated_by: &Uuid) -> AppResult {
+        update()
+            .bind(
+                self.executor,
+                &requirement.name,
+                &requirement.description,
+                updated_by,
+                &requirement.id,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn soft_delete(&self, id: &i32, deleted_by: &Uuid) -> AppResult {
+        soft_delete().bind(self.executor, deleted_by, id).await?;
+        Ok(())
+    }
+
+    pub async fn get_by_id(&self, id: &i32) -> AppResult<Requirement> {
+        match get_requirement_by_id()
+            .bind(self.executor, id)
+            .opt()
+            .await?
+        {
+            Some(r) => Ok(r.to_requirement()),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![],
+                resource_type: ResourceType::Requirement,
+            })),
+        }
+    }
+
+    pub async fn check_external_absent(
+        &self,
+        project_id: &i32,
+        source: &str,
+        external_id: &str,
+    ) -> AppResult {
+        let requirement = get_requirement_by_external_id()
+            .bind(self.executor, project_id, &source, &external_id)
+            .opt()
+            .await?;
+        match requirement {
+            Some(_) => Err(AppError::ResourceExistsError(Resource {
+                details: vec![("external_id".to_string(), external_id.to_string())],
+                resource_type: ResourceType::Requirement,
+            })),
+            None => Ok(()),
+        }
+    }
+
+    pub async fn get_list(&self, project_id: &i32) -> AppResult<Vec<Requirement>> {
+        let list = get_requirement_list()
+            .bind(self.executor, project_id)
+            .all()
+            .await?
+            .iter()
+            .map(|r| r.to_requirement())
+            .collect();
+        Ok(list)
+    }
+
+    pub async fn insert_case_relation(
+        &self,
+        requirement_id: &i32,
+        case_id: &i32,
+        created_by: &Uuid,
+    ) -> AppResult {
+        insert_case_relation()
+            .bind(self.executor, requirement_id, case_id, created_by)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn soft_delete_case_relation(
+        &self,
+        requirement_id: &i32,
+        case_id: &i32,
+        deleted_by: &Uuid,
+    ) -> AppResult {
+        soft_delete_case_relation()
+            .bind(self.executor, deleted_by, requirement_id, case_id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_case_ids(&self, requirement_id: &i32) -> AppResult<Vec<i32>> {
+        let ids = get_case_ids_by_requirement_id()
+            .bind(self.executor, requirement_id)
+            .all()
+            .await?;
+        Ok(ids)
+    }
+
+    pub async fn get_latest_executed_plan_id(&self, project_id: &i32) -> AppResult<Option<i32>> {
+        let plan_id = get_latest_executed_plan_id()
+            .bind(self.executor, project_id)
+            .opt()
+            .await?;
+        Ok(plan_id)
+    }
+
+    pub async fn get_coverage(
+        &self,
+        project_id: &i32,
+        plan_id: &Option<i32>,
+    ) -> AppResult<Vec<RequirementCoverage>> {
+        let list = get_requirement_coverage()
+            .bind(self.executor, plan_id, project_id)
+            .all()
+            .await?
+            .into_iter()
+            .map(|r| RequirementCoverage {
+                id: r.id,
+                name: r.name,
+                source: r.source,
+                external_id: r.external_id,
+                uri: r.uri,
+                status: r.status,
+                case_count: r.case_count,
+                passed_count: r.passed_count,
+            })
+            .collect();
+        Ok(list)
+    }
+}
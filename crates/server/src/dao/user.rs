@@ -49,13 +49,20 @@ macro_rules! impl_to_user {
 }
 
 // 使用宏为查询的结构体实现ToUser trait
-impl_to_user!(true, GetUserByUsername, GetUserByUuid, GetUserById);
+impl_to_user!(
+    true,
+    GetUserByUsername,
+    GetUserByUuid,
+    GetUserById,
+    GetUserBySsoIdentity
+);
 impl_to_user!(
     false,
     GetUsersByRoleAndProjectId,
     GetUsers,
     GetIdleUsersByProjectId,
-    GetUsersByRoleId
+    GetUsersByRoleId,
+    GetActiveUserByEmail
 );
 
 #[derive(Debug)]
@@ -174,6 +181,15 @@ where
         }
     }
 
+    pub async fn find_by_email(&self, email: &str) -> AppResult<Option<User>> {
+        let user = get_active_user_by_email()
+            .bind(self.executor, &email)
+            .opt()
+            .await?
+            .map(|item| item.to_user());
+        Ok(user)
+    }
+
     pub async fn check_unique_by_username(&self, username: &str) -> AppResult {
         let user = get_user_by_username()
             .bind(self.executor, &username)
@@ -426,6 +442,90 @@ where
         Ok(relation_id)
     }
 
+    pub async fn update_role(
+        &self,
+        role_id: i32,
+        name: &str,
+        description: Option<String>,
+        updated_by: Uuid,
+    ) -> AppResult {
+        update_user_role()
+            .bind(self.executor, &name, &description, &updated_by, &role_id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_role_id_by_name(&self, role_name: &str) -> AppResult<i32> {
+        match get_role_id_by_name()
+            .bind(self.executor, &role_name)
+            .opt()
+            .await?
+        {
+            Some(id) => Ok(id),
+            None => Err(AppError::NotFoundError(Resource {
+                details: vec![("name".to_string(), role_name.to_string())],
+                resource_type: ResourceType::Role,
+            })),
+        }
+    }
+
+    /* role of the user in project, `None` if the user is not a member */
+    pub async fn get_member_role(
+        &self,
+        uid: &Uuid,
+        project_id: &i32,
+    ) -> AppResult<Option<UserRoleOption>> {
+        let role = get_member_role()
+            .bind(self.executor, uid, project_id)
+            .opt()
+            .await?
+            .map(|item| UserRoleOption {
+                id: item.role_id,
+                name: item.role_name,
+            });
+        Ok(role)
+    }
+
+    pub async fn update_user_role_relation(
+        &self,
+        uid: &Uuid,
+        project_id: &i32,
+        role_id: i32,
+        updated_by: Uuid,
+    ) -> AppResult {
+        update_user_role_relation()
+            .bind(self.executor, &role_id, &updated_by, uid, project_id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_user_role_relation(&self, uid: &Uuid, project_id: &i32) -> AppResult {
+        delete_user_role_relation()
+            .bind(self.executor, uid, project_id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_project_ids_by_uuid(&self, uid: &Uuid) -> AppResult<Vec<i32>> {
+        let project_ids = get_project_ids_by_uuid()
+            .bind(self.executor, uid)
+            .all()
+            .await?;
+        Ok(project_ids)
+    }
+
+    /* (user, project) pairs the role is assigned to */
+    pub async fn get_members_by_role_id(&self, role_id: i32) -> AppResult<Vec<(Uuid, i32)>> {
+        let members = get_members_by_role_id()
+            .bind(self.executor, &role_id)
+            .all()
+            .await?
+            .into_iter()
+            .map(|item| (item.user_id, item.project_id))
+            .collect::<Vec<_>>();
+        Ok(members)
+    }
+
     pub async fn all(&self) -> AppResult<Vec<User>> {
         let users = get_users()
             .bind(self.executor)
@@ -437,4 +537,63 @@ where
 
         Ok(users)
     }
+
+    pub async fn is_password_change_required(&self, uid: &Uuid) -> AppResult<bool> {
+        let required = get_password_change_required()
+            .bind(self.executor, uid)
+            .one()
+            .await?;
+        Ok(required)
+    }
+
+    pub async fn set_password_change_required(&self, uid: &Uuid, required: bool) -> AppResult {
+        update_password_change_required()
+            .bind(self.executor, &required, uid)
+            .await?;
+        Ok(())
+    }
+
+    /* user linked to the identity, with whether the user has been deleted since */
+    pub async fn find_by_sso_identity(
+        &self,
+        provider: &str,
+        issuer: &str,
+        subject: &str,
+    ) -> AppResult<Option<(User, bool)>> {
+        let ret = get_user_by_sso_identity()
+            .bind(self.executor, &provider, &issuer, &subject)
+            .opt()
+            .await?;
+        Ok(ret.map(|item| (item.to_user(), item.deleted)))
+    }
+
+    pub async fn insert_sso_identity(
+        &self,
+        uid: &Uuid,
+        provider: &str,
+        issuer: &str,
+        subject: &str,
+    ) -> AppResult {
+        insert_sso_identity()
+            .bind(self.executor, uid, &provider, &issuer, &subject)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_password(
+        &self,
+        uid: &Uuid,
+        hashed_password: &str,
+        password_change_required: bool,
+    ) -> AppResult {
+        update_password()
+            .bind(
+                self.executor,
+                &hashed_password,
+                &password_change_required,
+                uid,
+            )
+            .await?;
+        Ok(())
+    }
 }
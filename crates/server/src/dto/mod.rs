@@ -42,26 +42,49 @@ impl TryFrom<&Email> for Message {
     }
 }
 
-#[allow(dead_code)]
 #[derive(Debug)]
 pub enum EmailTemplate {
-    Register { username: String, password: String },
-    ForgetPassword { username: String, password: String },
+    Register {
+        username: String,
+        link: String,
+    },
+    ForgetPassword {
+        username: String,
+        link: String,
+    },
+    Notification {
+        username: String,
+        title: String,
+        content: String,
+        link: Option<String>,
+    },
 }
 
 impl EmailTemplate {
     pub fn get(&self) -> (tera::Context, &'static str) {
         let mut ctx = tera::Context::new();
         match self {
-            Self::Register { username, password } => {
+            Self::Register { username, link } => {
                 ctx.insert("username", username);
-                ctx.insert("password", password);
+                ctx.insert("link", link);
                 (ctx, "email/register.html")
             }
-            Self::ForgetPassword { username, password } => {
+            Self::ForgetPassword { username, link } => {
                 ctx.insert("username", username);
-                ctx.insert("password", password);
-                (ctx, "register.html")
+                ctx.insert("link", link);
+                (ctx, "email/reset_password.html")
+            }
+            Self::Notification {
+                username,
+                title,
+                content,
+                link,
+            } => {
+                ctx.insert("username", username);
+                ctx.insert("title", title);
+                ctx.insert("content", content);
+                ctx.insert("link", link);
+                (ctx, "email/notification.html")
             }
         }
     }
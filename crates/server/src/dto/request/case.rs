@@ -88,3 +88,47 @@ pub struct DeleteFieldRequest {
 pub struct QueryFieldParam {
     pub field_id: Option<i32>,
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRequirementRequest {
+    #[garde(length(min = 1))]
+    pub name: String,
+    #[garde(skip)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRequirementRequest {
+    #[garde(length(min = 1))]
+    pub source: String,
+    #[garde(length(min = 1))]
+    pub external_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRequirementRequest {
+    #[garde(skip)]
+    pub id: i32,
+    #[garde(length(min = 1))]
+    pub name: String,
+    #[garde(skip)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RequirementCaseRequest {
+    #[garde(skip)]
+    pub requirement_id: i32,
+    #[garde(length(min = 1))]
+    pub case_ids: Vec<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct RequirementInfoParam {
+    pub plan_id: Option<i32>,
+}
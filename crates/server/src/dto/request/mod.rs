@@ -1,4 +1,5 @@
 use crate::dao::entity::Step;
+use crate::entity::project::ProjectModule;
 use chrono::{DateTime, Utc};
 use fake::{
     faker::internet::en::{SafeEmail, Username},
@@ -85,6 +86,7 @@ pub struct ListQueryParam {
     pub deleted: Option<bool>,
     pub page_num: Option<i64>,
     pub page_size: Option<i64>,
+    /* next_page_token of the previous page, the filter above is taken from it when given */
     pub page_token: Option<String>,
 }
 
@@ -105,6 +107,20 @@ pub struct CreateRoleRequest {
     pub permission_list: Vec<i32>,
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRoleRequest {
+    #[garde(skip)]
+    pub id: i32,
+    #[garde(length(min = 3))]
+    pub name: String,
+    #[garde(skip)]
+    pub description: Option<String>,
+    /* the complete permission set after update */
+    #[garde(length(min = 1))]
+    pub permission_list: Vec<i32>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct CreatePlanRequest {
@@ -116,6 +132,22 @@ pub struct CreatePlanRequest {
     pub end_date: Option<DateTime<Utc>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveScheduleRequest {
+    /* six fields with seconds, e.g. `0 0 2 * * *` for 02:00 every day */
+    pub cron: String,
+    pub machine_id: i32,
+    pub script_id: Option<i32>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanRunQueryParam {
+    pub limit: Option<i64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct ElementQueryParam {
@@ -125,12 +157,79 @@ pub struct ElementQueryParam {
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateElementRequest {
+    pub name: String,
+    pub module_id: i32,
+    pub value: String,
+    pub element_type: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateElementRequest {
+    pub id: i32,
     pub name: String,
     pub value: String,
     pub element_type: String,
     pub description: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveElementRequest {
+    pub id: i32,
+    pub module_id: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteElementRequest {
+    pub id: i32,
+    /* delete even if the element is still referenced by scripts */
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOperationOptionRequest {
+    pub name: String,
+    pub exec: String,
+    /* tera snippet, `selector` and `attach_info` of the step are available */
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateOperationOptionRequest {
+    pub id: i32,
+    pub name: String,
+    pub exec: String,
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationOptionQueryParam {
+    pub element_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationOptionBindingRequest {
+    pub element_type: String,
+    pub option_ids: Vec<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheckRequest {
+    pub module_id: i32,
+    pub page_url: String,
+    /* load the page on a registered machine, locally fetched if absent */
+    pub machine_id: Option<i32>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IssueRelationRequest {
@@ -143,7 +242,17 @@ pub struct IssueRelationRequest {
 pub struct Issue {
     pub issue_id: String,
     pub source: String,
-    pub uri: String,
+    /* the tracker link is resolved from the tracker, kept for compatibility */
+    #[serde(default)]
+    pub uri: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportIssueRequest {
+    pub record_id: i32,
+    pub source: String,
+    pub title: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -170,3 +279,50 @@ pub struct AddMemberRequest {
     pub uid: i32,
     pub role: String,
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMemberRequest {
+    pub uid: i32,
+    pub role: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveMemberRequest {
+    pub uid: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateProjectRequest {
+    #[garde(length(min = 1))]
+    pub name: String,
+    #[garde(skip)]
+    pub description: Option<String>,
+    /* every module is enabled when omitted */
+    #[garde(skip)]
+    pub modules: Option<Vec<ProjectModule>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProjectRequest {
+    #[garde(length(min = 1))]
+    pub name: String,
+    #[garde(skip)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateModuleSettingRequest {
+    pub modules: Vec<ProjectModule>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveProjectRequest {
+    pub id: i32,
+    pub archived: bool,
+}
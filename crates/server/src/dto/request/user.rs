@@ -1,7 +1,10 @@
+use chrono::{DateTime, Utc};
 use fake::Dummy;
 use garde::Validate;
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::entity::notification::{ChannelType, NotificationEvent};
 
 #[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
 #[serde(rename_all = "camelCase")]
@@ -25,3 +28,153 @@ pub struct LoginRequest {
     #[garde(length(min = 8))]
     pub password: String,
 }
+
+/* password policy is up to the directory, so only non-empty is checked */
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct LdapLoginRequest {
+    #[garde(length(min = 1, max = 64))]
+    pub username: String,
+    #[garde(length(min = 1))]
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct AcceptInvitationRequest {
+    #[garde(length(min = 30))]
+    pub token: String,
+    /* strength is checked against `ConfigPassword` */
+    #[garde(length(min = 1, max = 64))]
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct ForgotPasswordRequest {
+    #[garde(email)]
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct ResetPasswordRequest {
+    #[garde(length(min = 30))]
+    pub token: String,
+    #[garde(length(min = 1, max = 64))]
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordRequest {
+    #[garde(length(min = 1))]
+    pub old_password: String,
+    #[garde(length(min = 1, max = 64))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct InvitationRequest {
+    /* user ids */
+    #[garde(length(min = 1))]
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct ForceLogoutRequest {
+    /* user ids */
+    #[garde(length(min = 1))]
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct UnlockUserRequest {
+    /* user ids */
+    #[garde(length(min = 1))]
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, IntoParams)]
+pub struct OidcCallbackParam {
+    pub code: String,
+    pub state: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiTokenRequest {
+    #[garde(length(min = 1, max = 64))]
+    pub name: String,
+    /* omitted for a personal token usable in every project of the owner */
+    #[garde(skip)]
+    pub project_id: Option<i32>,
+    #[garde(length(min = 1))]
+    pub permission_list: Vec<i32>,
+    /* never expires when omitted */
+    #[garde(skip)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct MfaLoginRequest {
+    /* returned by `/auth/login` together with `MfaRequired` */
+    #[garde(length(min = 30))]
+    pub challenge: String,
+    /* a code of the authenticator app or an unused recovery code */
+    #[garde(length(min = 6, max = 32))]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct MfaEnrollChallengeRequest {
+    /* returned by `/auth/login` together with `MfaEnrollmentRequired` */
+    #[garde(length(min = 30))]
+    pub challenge: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct MfaCodeRequest {
+    #[garde(length(min = 6, max = 32))]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRoleMfaRequest {
+    #[garde(skip)]
+    pub role_id: i32,
+    /* members of the role can not log in without a second factor */
+    #[garde(skip)]
+    pub required: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct ResetMfaRequest {
+    /* user ids */
+    #[garde(length(min = 1))]
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateNotificationChannelRequest {
+    #[garde(length(min = 1, max = 64))]
+    pub name: String,
+    #[garde(skip)]
+    pub channel_type: ChannelType,
+    /* incoming webhook of the chat group, or any endpoint accepting the json payload, http(s) to public addresses only */
+    #[garde(url)]
+    pub url: String,
+    /* signs the messages, required by DingTalk and Feishu robots with signature check */
+    #[garde(length(min = 1))]
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNotificationPreferenceRequest {
+    #[garde(skip)]
+    pub event_type: NotificationEvent,
+    #[garde(skip)]
+    pub email: bool,
+    /* channels of the user, empty to notify by email only */
+    #[garde(skip)]
+    pub channel_ids: Vec<i32>,
+}
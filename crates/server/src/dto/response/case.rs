@@ -39,6 +39,7 @@ pub struct GetTemplateResponse {
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ListFunctionalCaseResponse {
     pub total: i32,
+    /* empty at the end of the list */
     pub next_page_token: String,
     pub list: Vec<FunctionalCaseResponse>,
 }
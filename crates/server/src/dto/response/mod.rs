@@ -5,8 +5,10 @@ use utoipa::ToSchema;
 use crate::{
     dao::entity::ElementDetail,
     entity::{
+        element::{HealthCheck, HealthCheckResult},
         file::ModuleType,
         project::{Plan, Project},
+        requirement::{Requirement, RequirementCoverage},
         user::User,
     },
     errors::AppResponseError,
@@ -29,6 +31,13 @@ impl MessageResponse {
     }
 }
 
+/* permission ids granted to and revoked from the role */
+#[derive(Debug, Deserialize, Serialize, ToSchema, Default, PartialEq, Eq)]
+pub struct UpdateRoleResponse {
+    pub added: Vec<i32>,
+    pub removed: Vec<i32>,
+}
+
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct ProjectInfoResponse {
     pub id: i32,
@@ -85,18 +94,39 @@ pub struct ListUserResponse {
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ListElementResponse {
+    /* empty at the end of the list */
     pub next_page_token: String,
     pub list: Vec<ElementDetail>,
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HealthCheckResponse {
+    pub check: HealthCheck,
+    pub results: Vec<HealthCheckResult>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ListPlanResponse {
+    /* empty at the end of the list */
     pub next_page_token: String,
     pub list: Vec<Plan>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
-pub struct RequirementInfoResponse {}
+pub struct RequirementInfoResponse {
+    /* plan used for passed count, latest executed plan of project by default */
+    pub plan_id: Option<i32>,
+    pub total: i64,
+    pub covered: i64,
+    pub list: Vec<RequirementCoverage>,
+    pub uncovered: Vec<RequirementCoverage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RequirementResponse {
+    pub requirement: Requirement,
+    pub case_ids: Vec<i32>,
+}
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ElementResponse {
@@ -1,20 +1,36 @@
 use crate::{
     constant::BEARER,
-    entity::user::{UserRole, UserRolePermission, UserRoleRelation},
+    entity::{
+        session::SessionInfo,
+        user::{UserRole, UserRolePermission, UserRoleRelation},
+    },
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use uuid::Uuid;
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct RegisterResponse {
     pub id: i32,
 }
 
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct OidcAuthorizeResponse {
+    /* authorization endpoint of the provider, the client redirects the browser there */
+    pub url: String,
+}
+
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub enum LoginResponse {
     Token(TokenResponse),
     Code { message: String, expire_in: u64 },
+    /* the password was not chosen by the user, `ticket` is accepted by `/auth/invitation/accept` */
+    PasswordChangeRequired { ticket: String },
+    /* the password was accepted, `challenge` and a code are sent to `/auth/login/mfa` */
+    MfaRequired { challenge: String },
+    /* a role of the user requires a second factor, set it up with `/auth/login/mfa/enroll` */
+    MfaEnrollmentRequired { challenge: String },
 }
 
 impl From<TokenResponse> for LoginResponse {
@@ -53,3 +69,35 @@ pub struct GetUserInfoResponse {
     pub user_role_relations: Vec<UserRoleRelation>,
     pub user_roles: Vec<UserRole>,
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListSessionResponse {
+    /* session of the request, revoking it logs the caller out */
+    pub current: Uuid,
+    pub list: Vec<SessionInfo>,
+}
+
+/* recovery codes are only returned here, they are stored hashed */
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaEnrollResponse {
+    /* base32, for entering the key manually */
+    pub secret: String,
+    /* rendered as a qr code by the client */
+    pub otpauth_uri: String,
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/* `token` is only returned here, it can not be read again */
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiTokenResponse {
+    pub id: i32,
+    pub token: String,
+}
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/* the plain token is never stored, only returned once on creation */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiToken {
+    pub id: i32,
+    pub name: String,
+    pub token_prefix: String,
+    /* `None` for a personal token usable in every project of the owner */
+    pub project_id: Option<i32>,
+    pub permissions: Vec<i32>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/* what a request authenticated by an api token may do, attached by the auth middleware */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiTokenScope {
+    pub id: i32,
+    pub uid: Uuid,
+    pub project_id: Option<i32>,
+    pub permissions: Vec<i32>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuditAction {
+    LoginLockout,
+    LoginUnlock,
+    MfaReset,
+    RoleMfaRequired,
+}
+
+impl ToString for AuditAction {
+    fn to_string(&self) -> String {
+        match self {
+            AuditAction::LoginLockout => "LOGIN_LOCKOUT".to_string(),
+            AuditAction::LoginUnlock => "LOGIN_UNLOCK".to_string(),
+            AuditAction::MfaReset => "MFA_RESET".to_string(),
+            AuditAction::RoleMfaRequired => "ROLE_MFA_REQUIRED".to_string(),
+        }
+    }
+}
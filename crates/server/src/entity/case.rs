@@ -53,6 +53,7 @@ pub struct CaseExecuteRecord {
     pub case_id: i32,
     pub result: CaseResult,
     pub attach_info: String,
+    pub issue_relation_id: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub created_by: String,
     pub updated_at: Option<DateTime<Utc>>,
@@ -83,6 +84,79 @@ impl CaseResult {
     }
 }
 
+impl ToString for CaseResult {
+    fn to_string(&self) -> String {
+        let result_str = match self {
+            Self::UnExecuted => "UN_EXECUTED",
+            Self::Passed => "PASSED",
+            Self::Blocked => "BLOCKED",
+            Self::Skipped => "SKIPPED",
+            Self::Failed => "FAILED",
+            Self::Unknown => "UNKNOWN",
+        };
+        result_str.to_string()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+pub struct IssueRelation {
+    pub id: i32,
+    pub case_id: i32,
+    pub issue_id: String,
+    pub source: IssueSource,
+    pub uri: String,
+    pub title: Option<String>,
+    pub status: Option<String>,
+    pub synced_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub updated_by: Option<String>,
+}
+
+/* 缺陷平台中的缺陷信息 */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TrackerIssue {
+    pub issue_id: String,
+    pub title: String,
+    pub status: String,
+    pub uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum IssueSource {
+    Jira,
+    #[serde(rename = "GITLAB")]
+    GitLab,
+    #[serde(rename = "GITHUB")]
+    GitHub,
+    Unknown,
+}
+
+impl ToString for IssueSource {
+    fn to_string(&self) -> String {
+        let source_str = match self {
+            Self::Jira => "JIRA",
+            Self::GitLab => "GITLAB",
+            Self::GitHub => "GITHUB",
+            Self::Unknown => "UNKNOWN",
+        };
+        format!("{}", source_str)
+    }
+}
+
+impl IssueSource {
+    pub fn from_str(source: &str) -> Self {
+        match source.to_ascii_uppercase().as_str() {
+            "JIRA" => IssueSource::Jira,
+            "GITLAB" => IssueSource::GitLab,
+            "GITHUB" => IssueSource::GitHub,
+            _ => IssueSource::Unknown,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum CaseStatus {
@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SelectorStatus {
+    /* exactly one node matched */
+    Found,
+    Missing,
+    /* more than one node matched, steps may act on the wrong one */
+    Ambiguous,
+    /* selector could not be parsed */
+    Invalid,
+}
+
+impl SelectorStatus {
+    pub fn from_match_count(count: usize) -> Self {
+        match count {
+            0 => SelectorStatus::Missing,
+            1 => SelectorStatus::Found,
+            _ => SelectorStatus::Ambiguous,
+        }
+    }
+}
+
+impl ToString for SelectorStatus {
+    fn to_string(&self) -> String {
+        format!("{:?}", self).to_ascii_uppercase()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HealthCheckStatus {
+    Running,
+    Finished,
+    Failed,
+}
+
+impl ToString for HealthCheckStatus {
+    fn to_string(&self) -> String {
+        format!("{:?}", self).to_ascii_uppercase()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub struct HealthCheck {
+    pub id: i32,
+    pub module_id: i32,
+    pub page_url: String,
+    pub machine_id: Option<i32>,
+    pub status: String,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub struct SelectorCheck {
+    pub element_id: i32,
+    pub selector: String,
+    pub status: SelectorStatus,
+    pub match_count: i32,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub struct HealthCheckResult {
+    pub element_id: i32,
+    pub element_name: String,
+    pub selector: String,
+    pub status: String,
+    pub match_count: i32,
+    pub checked_at: DateTime<Utc>,
+}
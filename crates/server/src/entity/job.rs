@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BackgroundJob {
+    pub id: i32,
+    pub kind: String,
+    pub status: String,
+    /* 0-100, reported by the running worker */
+    pub progress: i32,
+    /* output of the job once succeeded, e.g. the generated script */
+    pub result: Option<serde_json::Value>,
+    /* reason of the latest failed attempt */
+    pub error: Option<String>,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: DateTime<Utc>,
+    pub locked_by: Option<String>,
+    pub cancel_requested: bool,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/* a job claimed by a worker */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimedJob {
+    pub id: i32,
+    pub kind: JobKind,
+    pub payload: String,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub created_by: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobKind {
+    GenerateScript,
+    Diagnose,
+    SendInvitation,
+    SendNotification,
+    HealthCheck,
+    PlanRun,
+    Unknown,
+}
+
+impl JobKind {
+    pub fn from_str(kind: &str) -> Self {
+        match kind {
+            "GENERATE_SCRIPT" => JobKind::GenerateScript,
+            "DIAGNOSE" => JobKind::Diagnose,
+            "SEND_INVITATION" => JobKind::SendInvitation,
+            "SEND_NOTIFICATION" => JobKind::SendNotification,
+            "HEALTH_CHECK" => JobKind::HealthCheck,
+            "PLAN_RUN" => JobKind::PlanRun,
+            _ => JobKind::Unknown,
+        }
+    }
+}
+
+impl ToString for JobKind {
+    fn to_string(&self) -> String {
+        match self {
+            JobKind::GenerateScript => "GENERATE_SCRIPT".to_string(),
+            JobKind::Diagnose => "DIAGNOSE".to_string(),
+            JobKind::SendInvitation => "SEND_INVITATION".to_string(),
+            JobKind::SendNotification => "SEND_NOTIFICATION".to_string(),
+            JobKind::HealthCheck => "HEALTH_CHECK".to_string(),
+            JobKind::PlanRun => "PLAN_RUN".to_string(),
+            JobKind::Unknown => "UNKNOWN".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl ToString for JobStatus {
+    fn to_string(&self) -> String {
+        format!("{:?}", self).to_ascii_uppercase()
+    }
+}
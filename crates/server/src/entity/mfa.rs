@@ -0,0 +1,12 @@
+use uuid::Uuid;
+
+/* totp enrollment of a user, `enabled` once the first code was verified */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserMfa {
+    pub uid: Uuid,
+    /* hex encoded */
+    pub secret: String,
+    pub enabled: bool,
+    /* last accepted time step, a code is never accepted twice */
+    pub last_step: i64,
+}
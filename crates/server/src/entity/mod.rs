@@ -1,9 +1,18 @@
 use crate::errors::ResourceType;
 
+pub mod api_token;
+pub mod audit;
 pub mod case;
+pub mod element;
 pub mod file;
+pub mod job;
+pub mod mfa;
+pub mod notification;
 pub mod permission;
+pub mod plan;
 pub mod project;
+pub mod requirement;
+pub mod session;
 pub mod user;
 
 pub trait AppEntity {
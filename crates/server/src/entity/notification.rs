@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NotificationEvent {
+    CaseReviewAssigned,
+    PlanRunFinished,
+    ExecutionFailed,
+    CommentMention,
+}
+
+impl NotificationEvent {
+    pub const ALL: [NotificationEvent; 4] = [
+        NotificationEvent::CaseReviewAssigned,
+        NotificationEvent::PlanRunFinished,
+        NotificationEvent::ExecutionFailed,
+        NotificationEvent::CommentMention,
+    ];
+}
+
+impl ToString for NotificationEvent {
+    fn to_string(&self) -> String {
+        match self {
+            Self::CaseReviewAssigned => "CASE_REVIEW_ASSIGNED".to_string(),
+            Self::PlanRunFinished => "PLAN_RUN_FINISHED".to_string(),
+            Self::ExecutionFailed => "EXECUTION_FAILED".to_string(),
+            Self::CommentMention => "COMMENT_MENTION".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChannelType {
+    Webhook,
+    Slack,
+    #[serde(rename = "DINGTALK")]
+    DingTalk,
+    Feishu,
+    Unknown,
+}
+
+impl ToString for ChannelType {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Webhook => "WEBHOOK".to_string(),
+            Self::Slack => "SLACK".to_string(),
+            Self::DingTalk => "DINGTALK".to_string(),
+            Self::Feishu => "FEISHU".to_string(),
+            Self::Unknown => "UNKNOWN".to_string(),
+        }
+    }
+}
+
+impl ChannelType {
+    pub fn from_str(channel_type: &str) -> Self {
+        match channel_type.to_ascii_uppercase().as_str() {
+            "WEBHOOK" => ChannelType::Webhook,
+            "SLACK" => ChannelType::Slack,
+            "DINGTALK" => ChannelType::DingTalk,
+            "FEISHU" => ChannelType::Feishu,
+            _ => ChannelType::Unknown,
+        }
+    }
+}
+
+/* a webhook of the user, email needs no channel and goes to the address of the account */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationChannel {
+    pub id: i32,
+    pub name: String,
+    pub channel_type: ChannelType,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/* where the user is notified of one event type */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreference {
+    pub event_type: NotificationEvent,
+    pub email: bool,
+    pub channel_ids: Vec<i32>,
+}
+
+impl NotificationPreference {
+    /* events the user never configured are sent by email only */
+    pub fn default_for(event_type: NotificationEvent) -> Self {
+        Self {
+            event_type,
+            email: true,
+            channel_ids: vec![],
+        }
+    }
+}
+
+/* the message sent on every channel */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Notification {
+    pub event_type: NotificationEvent,
+    pub title: String,
+    pub content: String,
+    pub link: Option<String>,
+}
+
+impl Notification {
+    /* plain text for chat channels */
+    pub fn text(&self) -> String {
+        match &self.link {
+            Some(link) => format!("{}\n{}\n{link}", self.title, self.content),
+            None => format!("{}\n{}", self.title, self.content),
+        }
+    }
+}
@@ -7,3 +7,53 @@ pub struct Permission {
     pub module: String,
     pub scope: String,
 }
+
+/* what the access check needs to know about a user in a project */
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct UserPermission {
+    pub enable: bool,
+    pub permissions: Vec<i32>,
+    /* archived projects are read only */
+    #[serde(default)]
+    pub archived: bool,
+    /* modules enabled in the project, `None` when the project never configured them */
+    #[serde(default)]
+    pub modules: Option<Vec<String>>,
+}
+
+/* who may call a route, declared next to the routes in `api` */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Access {
+    /* no project context needed, the auth middleware still applies */
+    Open,
+    /* any enabled member of the project */
+    Member,
+    Require {
+        module: &'static str,
+        scope: &'static str,
+    },
+}
+
+impl Access {
+    pub const fn read(module: &'static str) -> Self {
+        Self::Require {
+            module,
+            scope: "READ",
+        }
+    }
+
+    pub const fn write(module: &'static str) -> Self {
+        Self::Require {
+            module,
+            scope: "WRITE",
+        }
+    }
+}
+
+/* `path` is the axum route template, as reported by `MatchedPath` */
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ApiPermission {
+    pub method: &'static str,
+    pub path: String,
+    pub access: Access,
+}
@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub struct PlanSchedule {
+    pub id: i32,
+    pub plan_id: i32,
+    /* only this script runs when set, otherwise the latest script of every case in plan */
+    pub script_id: Option<i32>,
+    pub cron: String,
+    pub machine_id: i32,
+    pub enabled: bool,
+    pub last_fired_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub struct PlanRun {
+    pub id: i32,
+    pub plan_id: i32,
+    pub schedule_id: Option<i32>,
+    pub script_id: Option<i32>,
+    /* background job executing the run */
+    pub job_id: Option<i32>,
+    pub trigger_type: String,
+    pub status: String,
+    pub total: i32,
+    pub passed: i32,
+    pub failed: i32,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<i64>,
+    pub created_by: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanScript {
+    pub id: i32,
+    pub case_id: i32,
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RunTrigger {
+    Schedule,
+    Manual,
+}
+
+impl ToString for RunTrigger {
+    fn to_string(&self) -> String {
+        format!("{:?}", self).to_ascii_uppercase()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RunStatus {
+    Running,
+    Passed,
+    Failed,
+    /* the run could not complete, e.g. machine unreachable */
+    Error,
+}
+
+impl ToString for RunStatus {
+    fn to_string(&self) -> String {
+        format!("{:?}", self).to_ascii_uppercase()
+    }
+}
@@ -1,8 +1,13 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::constant::{
+    MANAGEMENT_ELEMENT, MANAGEMENT_FUNCTIONAL_CASE, MANAGEMENT_REQUIREMENT, MANAGEMENT_TEST_PLAN,
+};
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
 pub struct Plan {
     pub id: i32,
@@ -61,7 +66,6 @@ pub struct Project {
 }
 
 impl Project {
-    #[allow(dead_code)]
     pub fn new(
         name: String,
         created_by: Uuid,
@@ -96,3 +100,39 @@ pub struct ProjectInfo {
     pub description: Option<String>,
     pub module_setting: Option<String>,
 }
+
+/* modules a project can switch on and off, stored as a json array in `module_setting` */
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    Clone,
+    Copy,
+    Display,
+    EnumIter,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum ProjectModule {
+    BugManagement,
+    CaseManagement,
+    ApiTest,
+    TestPlan,
+}
+
+impl ProjectModule {
+    /* the project module a permission module belongs to, `None` when it can not be switched off */
+    pub fn of(permission_module: &str) -> Option<Self> {
+        match permission_module {
+            MANAGEMENT_FUNCTIONAL_CASE | MANAGEMENT_REQUIREMENT => Some(Self::CaseManagement),
+            MANAGEMENT_ELEMENT => Some(Self::ApiTest),
+            MANAGEMENT_TEST_PLAN => Some(Self::TestPlan),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::case::IssueSource;
+
+/* 本地创建需求的来源标识, 其余来源与缺陷平台一致 */
+pub const LOCAL_SOURCE: &str = "LOCAL";
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub struct Requirement {
+    pub id: i32,
+    pub project_id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub source: String,
+    pub external_id: Option<String>,
+    pub uri: Option<String>,
+    pub status: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub updated_by: Option<String>,
+}
+
+impl Requirement {
+    pub fn new(project_id: i32, name: &str, description: Option<String>) -> Self {
+        Requirement {
+            id: 0,
+            project_id,
+            name: name.to_string(),
+            description,
+            source: LOCAL_SOURCE.to_string(),
+            external_id: None,
+            uri: None,
+            status: None,
+            created_at: Utc::now(),
+            created_by: "".to_string(),
+            updated_at: None,
+            updated_by: None,
+        }
+    }
+
+    pub fn imported(
+        project_id: i32,
+        source: IssueSource,
+        external_id: String,
+        name: String,
+        uri: String,
+        status: String,
+    ) -> Self {
+        Requirement {
+            source: source.to_string(),
+            external_id: Some(external_id),
+            uri: Some(uri),
+            status: Some(status),
+            ..Requirement::new(project_id, &name, None)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub struct RequirementCoverage {
+    pub id: i32,
+    pub name: String,
+    pub source: String,
+    pub external_id: Option<String>,
+    pub uri: Option<String>,
+    pub status: Option<String>,
+    pub case_count: i64,
+    pub passed_count: i64,
+}
@@ -3,6 +3,7 @@ pub enum FieldException {
     NotAllowed,
     Mismatch,
     UnknownType,
+    OptionMismatch,
 }
 
 impl ToString for FieldException {
@@ -12,6 +13,7 @@ impl ToString for FieldException {
             Self::NotAllowed => "field not allowed",
             Self::Mismatch => "mismatch value with type",
             Self::UnknownType => "unknown field type",
+            Self::OptionMismatch => "option does not belong to the field",
         };
         format!("Field Exception: {msg}")
     }
@@ -29,3 +31,278 @@ impl ToString for UserException {
         format!("User Exception: {msg}")
     }
 }
+
+pub enum IssueException {
+    UnknownSource,
+    TrackerNotConfigured,
+    RecordNotFailed,
+    AlreadyReported,
+    InvalidIssueId,
+}
+
+impl ToString for IssueException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::UnknownSource => "unknown issue source",
+            Self::TrackerNotConfigured => "issue tracker not configured",
+            Self::RecordNotFailed => "only failed execute record can be reported",
+            Self::AlreadyReported => "execute record is already reported or being reported",
+            Self::InvalidIssueId => "invalid issue id for tracker",
+        };
+        format!("Issue Exception: {msg}")
+    }
+}
+
+pub enum ElementException {
+    ModuleTypeMismatch,
+    Referenced,
+    OperationNotAllowed,
+    InternalOperation,
+    OperationReferenced,
+}
+
+impl ToString for ElementException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::ModuleTypeMismatch => "module is not an element module",
+            Self::Referenced => "element is referenced by scripts",
+            Self::OperationNotAllowed => "operation not allowed for element type",
+            Self::InternalOperation => "internal operation can not be modified",
+            Self::OperationReferenced => "operation is referenced by scripts",
+        };
+        format!("Element Exception: {msg}")
+    }
+}
+
+pub enum PlanException {
+    InvalidCron,
+    RunInProgress,
+    NoScript,
+}
+
+impl ToString for PlanException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::InvalidCron => "invalid cron expression",
+            Self::RunInProgress => "plan run already in progress",
+            Self::NoScript => "no script to run in plan",
+        };
+        format!("Plan Exception: {msg}")
+    }
+}
+
+pub enum JobException {
+    Cancelled,
+    NotCancellable,
+    UnknownKind,
+}
+
+impl ToString for JobException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::Cancelled => "job cancelled",
+            Self::NotCancellable => "job already finished",
+            Self::UnknownKind => "unknown job kind",
+        };
+        format!("Job Exception: {msg}")
+    }
+}
+
+pub enum RoleException {
+    InternalRole,
+    UnknownPermission,
+    AlreadyMember,
+    NotMember,
+    LastAdmin,
+    NotProjectRole,
+}
+
+impl ToString for RoleException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::InternalRole => "internal role can not be modified",
+            Self::UnknownPermission => "unknown permission",
+            Self::AlreadyMember => "user is already a member of project",
+            Self::NotMember => "user is not a member of project",
+            Self::LastAdmin => "project must keep at least one admin",
+            Self::NotProjectRole => "role can not be assigned to project members",
+        };
+        format!("Role Exception: {msg}")
+    }
+}
+
+pub enum ProjectException {
+    NameExists,
+}
+
+impl ToString for ProjectException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::NameExists => "project name already exists",
+        };
+        format!("Project Exception: {msg}")
+    }
+}
+
+pub enum ApiTokenException {
+    ExpiredAlready,
+    PermissionNotGranted,
+}
+
+impl ToString for ApiTokenException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::ExpiredAlready => "expiry must be in the future",
+            Self::PermissionNotGranted => "token can not be granted permissions the owner lacks",
+        };
+        format!("Api Token Exception: {msg}")
+    }
+}
+
+pub enum SsoException {
+    NotConfigured,
+    InvalidCredential,
+    InvalidState,
+    MissingEmail,
+    AccountNotLinked,
+}
+
+impl ToString for SsoException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::NotConfigured => "sso provider not configured",
+            Self::InvalidCredential => "invalid sso credential",
+            Self::InvalidState => "unknown or expired sso state",
+            Self::MissingEmail => "identity provider returned no email",
+            Self::AccountNotLinked => {
+                "a local account with this name is not linked to the identity"
+            }
+        };
+        format!("Sso Exception: {msg}")
+    }
+}
+
+pub enum InvitationException {
+    InvalidToken,
+    AlreadyAccepted,
+}
+
+impl ToString for InvitationException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::InvalidToken => "invitation is invalid or expired",
+            Self::AlreadyAccepted => "invitation already accepted",
+        };
+        format!("Invitation Exception: {msg}")
+    }
+}
+
+pub enum PasswordException {
+    TooShort,
+    MissingUppercase,
+    MissingLowercase,
+    MissingDigit,
+    MissingSymbol,
+    WrongPassword,
+    InvalidResetToken,
+    ResetThrottled,
+}
+
+impl ToString for PasswordException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::TooShort => "password is too short",
+            Self::MissingUppercase => "password must contain an uppercase letter",
+            Self::MissingLowercase => "password must contain a lowercase letter",
+            Self::MissingDigit => "password must contain a digit",
+            Self::MissingSymbol => "password must contain a symbol",
+            Self::WrongPassword => "current password is wrong",
+            Self::InvalidResetToken => "reset token is invalid or expired",
+            Self::ResetThrottled => "reset email was sent recently, try again later",
+        };
+        format!("Password Exception: {msg}")
+    }
+}
+
+pub enum SessionException {
+    Invalid,
+    RefreshTokenReused,
+    RefreshTokenOutdated,
+}
+
+impl ToString for SessionException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::Invalid => "session is invalid or revoked",
+            Self::RefreshTokenReused => "refresh token was already used, the session is revoked",
+            Self::RefreshTokenOutdated => "refresh token is outdated, please log in again",
+        };
+        format!("Session Exception: {msg}")
+    }
+}
+
+pub enum LoginException {
+    Locked,
+    Throttled,
+}
+
+impl ToString for LoginException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::Locked => "too many failed logins, locked temporarily",
+            Self::Throttled => "too many failed logins, slow down",
+        };
+        format!("Login Exception: {msg}")
+    }
+}
+
+pub enum MfaException {
+    InvalidChallenge,
+    InvalidCode,
+    NotEnrolled,
+    AlreadyEnabled,
+    Required,
+}
+
+impl ToString for MfaException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::InvalidChallenge => "login challenge is invalid or expired",
+            Self::InvalidCode => "verification code is invalid",
+            Self::NotEnrolled => "two-factor authentication is not set up",
+            Self::AlreadyEnabled => "two-factor authentication is already enabled",
+            Self::Required => "two-factor authentication is required by your role",
+        };
+        format!("Mfa Exception: {msg}")
+    }
+}
+
+pub enum PageException {
+    InvalidToken,
+}
+
+impl ToString for PageException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::InvalidToken => "page token is invalid or expired, start from the first page",
+        };
+        format!("Page Exception: {msg}")
+    }
+}
+
+pub enum NotificationException {
+    UnknownChannelType,
+    ChannelNotFound,
+    Rejected(String),
+}
+
+impl ToString for NotificationException {
+    fn to_string(&self) -> String {
+        let msg = match self {
+            Self::UnknownChannelType => "unknown notification channel type".to_string(),
+            Self::ChannelNotFound => "notification channel not found".to_string(),
+            Self::Rejected(reason) => format!("message rejected by the channel: {reason}"),
+        };
+        format!("Notification Exception: {msg}")
+    }
+}
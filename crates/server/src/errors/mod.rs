@@ -9,6 +9,7 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use ldap3::LdapError;
 use lettre::transport::smtp::Error as SmtpError;
 use serde::{Deserialize, Serialize};
 use ssh2::Error as SshError;
@@ -58,6 +59,28 @@ pub enum ResourceType {
     Case,
     #[strum(serialize = "MODULE")]
     Module,
+    #[strum(serialize = "ISSUE")]
+    Issue,
+    #[strum(serialize = "REQUIREMENT")]
+    Requirement,
+    #[strum(serialize = "ELEMENT")]
+    Element,
+    #[strum(serialize = "OPERATION_OPTION")]
+    OperationOption,
+    #[strum(serialize = "HEALTH_CHECK")]
+    HealthCheck,
+    #[strum(serialize = "PLAN")]
+    Plan,
+    #[strum(serialize = "SCRIPT")]
+    Script,
+    #[strum(serialize = "SCHEDULE")]
+    Schedule,
+    #[strum(serialize = "JOB")]
+    Job,
+    #[strum(serialize = "API_TOKEN")]
+    ApiToken,
+    #[strum(serialize = "NOTIFICATION_CHANNEL")]
+    NotificationChannel,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -76,6 +99,10 @@ pub enum AppError {
     MultipartError(#[from] axum::extract::multipart::MultipartError),
     #[error("forbidden: {0}")]
     ForbiddenError(String),
+    #[error("too many requests: {0}")]
+    TooManyRequestsError(String),
+    #[error("{0}")]
+    JobCancelledError(String),
     #[error(transparent)]
     ConfigError(#[from] config::ConfigError),
     #[error("{0}")]
@@ -130,6 +157,8 @@ pub enum AppError {
     SmtpError(#[from] SmtpError),
     #[error(transparent)]
     LettreError(#[from] lettre::error::Error),
+    #[error(transparent)]
+    LdapError(#[from] LdapError),
 }
 
 pub fn invalid_input_error(field: &'static str, message: &'static str) -> AppError {
@@ -287,6 +316,18 @@ impl AppError {
                 vec![("details".to_string(), err)],
                 StatusCode::BAD_REQUEST,
             ),
+            TooManyRequestsError(err) => (
+                "TOO_MANY_REQUESTS_ERROR".to_string(),
+                None,
+                vec![("details".to_string(), err)],
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            JobCancelledError(_err) => (
+                "JOB_CANCELLED_ERROR".to_string(),
+                None,
+                vec![],
+                StatusCode::CONFLICT,
+            ),
             InvalidInputError(err) => (
                 "INVALID_INPUT_ERROR".to_string(),
                 None,
@@ -373,6 +414,12 @@ impl AppError {
                 vec![],
                 StatusCode::INTERNAL_SERVER_ERROR,
             ),
+            LdapError(_err) => (
+                "LDAP_ERROR".to_string(),
+                None,
+                vec![],
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
         };
 
         (
@@ -1,5 +1,8 @@
 use futures::FutureExt;
-use server::{configure, constant::CONFIG, errors::AppResult, server::AppServer, utils::task};
+use server::{
+    configure, constant::CONFIG, errors::AppResult, server::AppServer,
+    service::schedule::AsyncScheduler, utils::task,
+};
 use tracing::info;
 
 #[tokio::main]
@@ -12,11 +15,18 @@ async fn main() -> AppResult<()> {
     let config = CONFIG.clone();
     info!("Initializing server with configuration: {:?}", config);
 
-    /* Run server with graceful shutdown */
+    /* Run server, scheduler and job workers with graceful shutdown */
     let server = AppServer::new(config).await?;
+    let scheduler = server.scheduler();
+    let workers = server.workers();
 
     info!("********************* Starting the server *********************");
-    task::join_all(vec![(true, server.run().boxed())]).await?;
+    task::join_all(vec![
+        (true, server.run().boxed()),
+        (true, async { Ok(scheduler.run().await?) }.boxed()),
+        (true, workers.run().boxed()),
+    ])
+    .await?;
 
     Ok(())
 }
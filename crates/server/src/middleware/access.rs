@@ -1,5 +1,6 @@
 use axum::{
     body::Body,
+    extract::MatchedPath,
     http::{Request, StatusCode},
     response::Response,
 };
@@ -8,7 +9,13 @@ use crate::utils;
 use tower::{Layer, Service};
 use uuid::Uuid;
 
-use crate::{constant, errors::AppResponseError, service::permission, state::AppState};
+use crate::{
+    api,
+    entity::{api_token::ApiTokenScope, permission::Access},
+    errors::AppResponseError,
+    service::permission,
+    state::AppState,
+};
 
 #[derive(Clone)]
 pub struct AccessLayer;
@@ -43,7 +50,11 @@ where
     }
 
     fn call(&mut self, req: Request<Body>) -> Self::Future {
-        let uri = req.uri().clone();
+        /* permissions are declared against route templates, not literal paths */
+        let path = req
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|path| path.as_str().to_string());
         let method = req.method().clone();
         let headers = req.headers().clone();
         let state = req
@@ -55,13 +66,19 @@ where
             Some(i) => i.clone(),
             None => Uuid::nil(),
         };
+        let token_scope = req.extensions().get::<ApiTokenScope>().cloned();
         let future = self.inner.call(req);
         Box::pin(async move {
-            /* Bypass api which is in WHITE_LIST */
-            if constant::ACCESS_WHITE_LIST
-                .iter()
-                .any(|route| uri.path().starts_with(route))
-            {
+            /* Bypass unmatched requests and routes declared as open */
+            let Some(path) = path else {
+                return future.await;
+            };
+            if api::access(method.as_str(), &path) == Some(Access::Open) {
+                /* api tokens only reach routes guarded by a permission they carry */
+                if token_scope.is_some() {
+                    let resp = build_error_response(StatusCode::FORBIDDEN, "Access denied");
+                    return Ok(resp);
+                }
                 return future.await;
             }
             /* access check main logic */
@@ -74,15 +91,31 @@ where
                 }
             };
 
-            match permission::check_user_permission(
+            let checked = match permission::check_user_permission(
                 &state,
                 &uid,
                 &project_id,
-                uri.path(),
+                &path,
                 method.as_str(),
             )
             .await
             {
+                Ok(true) => match token_scope {
+                    Some(scope) => {
+                        permission::check_api_token_scope(
+                            &state,
+                            &scope,
+                            &project_id,
+                            &path,
+                            method.as_str(),
+                        )
+                        .await
+                    }
+                    None => Ok(true),
+                },
+                other => other,
+            };
+            match checked {
                 Ok(true) => {
                     return future.await;
                 }
@@ -72,19 +72,33 @@ where
             }
             if auth_str.starts_with(constant::BEARER) {
                 let token = auth_str[6..].trim();
-                match UserClaims::decode(token, &constant::ACCESS_TOKEN_DECODE_KEY) {
-                    Ok(user_claims) => {
-                        if service::session::check(&state.redis, &user_claims.claims)
-                            .await
-                            .is_ok()
-                        {
-                            let uid = user_claims.claims.uid;
-                            req.extensions_mut().insert(uid);
+                if token.starts_with(constant::API_TOKEN_PREFIX) {
+                    match service::api_token::authenticate(&state, token).await {
+                        Ok(Some(scope)) => {
+                            req.extensions_mut().insert(scope.uid);
+                            req.extensions_mut()
+                                .insert(UserClaims::for_api_token(&scope));
+                            req.extensions_mut().insert(scope);
                             return inner.call(req).await;
                         }
+                        Ok(None) => err_msg = "Invalid api token".to_string(),
+                        Err(err) => err_msg = err.to_string(),
                     }
-                    Err(err) => {
-                        err_msg = err.to_string();
+                } else {
+                    match UserClaims::decode(token, &constant::ACCESS_TOKEN_KEYS) {
+                        Ok(user_claims) => {
+                            if service::session::check(&state.redis, &user_claims.claims)
+                                .await
+                                .is_ok()
+                            {
+                                let uid = user_claims.claims.uid;
+                                req.extensions_mut().insert(uid);
+                                return inner.call(req).await;
+                            }
+                        }
+                        Err(err) => {
+                            err_msg = err.to_string();
+                        }
                     }
                 }
             }
@@ -4,6 +4,7 @@ use axum::http::{
     header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
     StatusCode,
 };
+use std::net::SocketAddr;
 use std::time::Duration;
 use tokio::signal;
 use tower::ServiceBuilder;
@@ -14,6 +15,13 @@ use crate::{
     configure::Config,
     errors::AppResult,
     middleware::{access::AccessLayer, auth::AuthLayer},
+    service::{
+        element, job,
+        job::JobWorkerPool,
+        permission, plan,
+        schedule::{Job, JobScheduler},
+        token,
+    },
     state::AppState,
 };
 
@@ -30,9 +38,71 @@ impl AppServer {
         config.http.http_port = addr.port();
 
         let state = AppState::new(config).await?;
+        permission::sync_api_permissions(&state, &api::PERMISSIONS).await?;
         Ok(Self { state, tcp })
     }
 
+    /* jobs run with `[schedule.<name>]` settings, falling back to the default cron */
+    pub fn scheduler(&self) -> JobScheduler {
+        let mut scheduler = JobScheduler::new().with_shutdown(shutdown_signal());
+
+        let state = self.state.clone();
+        let config = job_config(&self.state.config, "expire_health_check", "0 */10 * * * *");
+        scheduler.add_job(
+            Job::with_config_async(&config, move || {
+                let state = state.clone();
+                async move { element::expire_health_checks(&state).await }
+            })
+            .name("expire_health_check"),
+        );
+
+        let state = self.state.clone();
+        let config = job_config(&self.state.config, "plan_schedule", "0 * * * * *");
+        scheduler.add_job(
+            Job::with_config_async(&config, move || {
+                let state = state.clone();
+                async move { plan::fire_due_schedules(&state).await }
+            })
+            .name("plan_schedule"),
+        );
+
+        let state = self.state.clone();
+        let config = job_config(&self.state.config, "expire_plan_run", "30 * * * * *");
+        scheduler.add_job(
+            Job::with_config_async(&config, move || {
+                let state = state.clone();
+                async move { plan::expire_lost_runs(&state).await }
+            })
+            .name("expire_plan_run"),
+        );
+
+        let state = self.state.clone();
+        let config = job_config(&self.state.config, "reload_signing_key", "0 * * * * *");
+        scheduler.add_job(
+            Job::with_config_async(&config, move || {
+                let state = state.clone();
+                async move { token::reload_keys(&state).await }
+            })
+            .name("reload_signing_key"),
+        );
+
+        let state = self.state.clone();
+        let config = job_config(&self.state.config, "requeue_stale_job", "0 * * * * *");
+        scheduler.add_job(
+            Job::with_config_async(&config, move || {
+                let state = state.clone();
+                async move { job::requeue_stale_jobs(&state).await }
+            })
+            .name("requeue_stale_job"),
+        );
+
+        scheduler
+    }
+
+    pub fn workers(&self) -> JobWorkerPool {
+        JobWorkerPool::new(self.state.clone()).with_shutdown(shutdown_signal())
+    }
+
     pub async fn run(self) -> AppResult<()> {
         /* Middleware */
         let authorization = ServiceBuilder::new().layer(AuthLayer);
@@ -53,14 +123,25 @@ impl AppServer {
             .layer(cors)
             .fallback(|| async { (StatusCode::NOT_FOUND, "Not Found") });
 
-        axum::serve(self.tcp, app.into_make_service())
-            .with_graceful_shutdown(shutdown_signal())
-            .await?;
+        axum::serve(
+            self.tcp,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
         Ok(())
     }
 }
 
-async fn shutdown_signal() {
+fn job_config(config: &Config, name: &str, default_cron: &str) -> toml::Table {
+    let mut table = config.schedule.get(name).cloned().unwrap_or_default();
+    table
+        .entry("cron")
+        .or_insert_with(|| toml::Value::String(default_cron.to_string()));
+    table
+}
+
+pub async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
             .await
@@ -0,0 +1,103 @@
+use chrono::Utc;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    constant::API_TOKEN_PREFIX,
+    dao::{api_token::ApiTokenDao, permission::PermissionDao, user::UserDao},
+    dto::{request::user::CreateApiTokenRequest, response::user::CreateApiTokenResponse},
+    entity::api_token::{ApiToken, ApiTokenScope},
+    errors::{
+        message::{ApiTokenException, RoleException},
+        AppError, AppResult,
+    },
+    state::AppState,
+};
+
+/* a plain digest is enough, the token itself carries 256 random bits */
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/* 256 random bits in hex, also used for invitation and password reset links */
+pub fn random_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn generate_token() -> String {
+    format!("{API_TOKEN_PREFIX}{}", random_secret())
+}
+
+pub async fn create(
+    state: &AppState,
+    uid: Uuid,
+    request: CreateApiTokenRequest,
+) -> AppResult<CreateApiTokenResponse> {
+    info!("service layer create api token with request: {request:?}, created_by: {uid}");
+    if request.expires_at.is_some_and(|t| t <= Utc::now()) {
+        return Err(AppError::BadRequestError(
+            ApiTokenException::ExpiredAlready.to_string(),
+        ));
+    }
+    let client = state.pool.get().await?;
+    let perm_dao = PermissionDao::new(&client);
+    /* a project token can never do more than its owner in the project */
+    let granted = match request.project_id {
+        Some(project_id) => {
+            let role = UserDao::new(&client)
+                .get_member_role(&uid, &project_id)
+                .await?
+                .ok_or_else(|| AppError::BadRequestError(RoleException::NotMember.to_string()))?;
+            perm_dao.get_permission_by_role_id(role.id).await?
+        }
+        None => perm_dao.get_basic_permission().await?,
+    }
+    .into_iter()
+    .map(|p| p.id)
+    .collect::<Vec<_>>();
+    if request
+        .permission_list
+        .iter()
+        .any(|id| !granted.contains(id))
+    {
+        return Err(AppError::BadRequestError(
+            ApiTokenException::PermissionNotGranted.to_string(),
+        ));
+    }
+    let token = generate_token();
+    let id = ApiTokenDao::new(&client)
+        .create(
+            &request.name,
+            &hash_token(&token),
+            &token[..API_TOKEN_PREFIX.len() + 8],
+            &uid,
+            request.project_id,
+            &request.permission_list,
+            request.expires_at,
+        )
+        .await?;
+    Ok(CreateApiTokenResponse { id, token })
+}
+
+pub async fn list(state: &AppState, uid: Uuid) -> AppResult<Vec<ApiToken>> {
+    let client = state.pool.get().await?;
+    ApiTokenDao::new(&client).get_by_uid(&uid).await
+}
+
+pub async fn revoke(state: &AppState, uid: Uuid, id: i32) -> AppResult {
+    info!("service layer revoke api token {id} by {uid}");
+    let client = state.pool.get().await?;
+    ApiTokenDao::new(&client).revoke(&id, &uid).await
+}
+
+/* `None` when the token is unknown, revoked or expired */
+pub async fn authenticate(state: &AppState, token: &str) -> AppResult<Option<ApiTokenScope>> {
+    let client = state.pool.get().await?;
+    ApiTokenDao::new(&client)
+        .use_token(&hash_token(token))
+        .await
+}
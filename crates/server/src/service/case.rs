@@ -1,5 +1,6 @@
 use std::{collections::HashMap, path::Path};
 
+use futures::future::join_all;
 use tokio::try_join;
 use tracing::info;
 use uuid::Uuid;
@@ -14,21 +15,27 @@ use crate::{
                 QueryFieldParam, UpdateFieldRequest, UpdateFunctionalCaseRequest,
             },
             CaseQueryParam, CreateScriptRequest, DeleteEntityRequest, DiagnoseRequest,
-            IssueRelationRequest, ListQueryParam,
+            IssueRelationRequest, ListQueryParam, ReportIssueRequest,
         },
         response::{
             case::{FunctionalCaseResponse, GetTemplateResponse, ListFunctionalCaseResponse},
-            CreateEntityResponse, CreateScriptResponse, DiagnoseResponse, RequirementInfoResponse,
+            CreateEntityResponse, CreateScriptResponse, DiagnoseResponse,
         },
     },
-    entity::case::{CaseResult, Field, FieldInfo, FieldType, FieldValue, FunctionalCase},
+    entity::case::{
+        CaseResult, Field, FieldInfo, FieldType, FieldValue, FunctionalCase, IssueRelation,
+        IssueSource,
+    },
     errors::{message::*, AppError, AppResult, Resource, ResourceType},
     service::{
         engine::{self, StepInfo},
-        token::{generate_page_token, parse_page_token},
+        issue::{IssueTracker, NewIssue, Tracker},
+        job::JobHandle,
+        page,
+        project::{check_owner, check_project},
     },
     state::AppState,
-    utils::{claim::PageClaims, parse_ids},
+    utils::claim::PageKind,
 };
 
 pub async fn template(state: &AppState, project_id: i32) -> AppResult<GetTemplateResponse> {
@@ -104,11 +111,7 @@ pub async fn update_field(
     let case_dao = CaseDao::new(&transaction);
     let change_type = FieldType::from_str(&request.field_type);
     let mut field = case_dao.get_field_by_id(request.id).await?;
-    if project_id != field.project_id {
-        return Err(AppError::ForbiddenError(
-            UserException::Forbidden.to_string(),
-        ));
-    };
+    check_project(field.project_id, project_id)?;
     field.name = request.name;
     field.field_type = request.field_type;
     field.remark = request.remark;
@@ -123,6 +126,12 @@ pub async fn update_field(
             if let Some(options) = request.options {
                 for option in options.into_iter() {
                     match case_dao.get_field_option_by_id(option.id).await {
+                        /* an option of another field, possibly of another project */
+                        Ok(o) if o.field_id != field.id => {
+                            return Err(AppError::BadRequestError(
+                                FieldException::OptionMismatch.to_string(),
+                            ))
+                        }
                         Ok(mut o) => {
                             o.value = option.value;
                             o.position = option.position;
@@ -162,11 +171,7 @@ pub async fn delete_field(
     let transaction = client.transaction().await?;
     let case_dao = CaseDao::new(&transaction);
     let field = case_dao.get_field_by_id(request.id).await?;
-    if field.project_id != project_id {
-        return Err(AppError::ForbiddenError(
-            UserException::Forbidden.to_string(),
-        ));
-    }
+    check_project(field.project_id, project_id)?;
     case_dao.soft_delete_field(request.id, uid).await?;
     if let FieldType::Select = FieldType::from_str(&field.field_type) {
         for option in field.options {
@@ -182,10 +187,11 @@ pub async fn get_field_list(
     project_id: i32,
     params: QueryFieldParam,
 ) -> AppResult<Vec<Field>> {
-    let mut client = state.pool.get().await?;
-    let case_dao = CaseDao::new(&mut client);
+    let client = state.pool.get().await?;
+    let case_dao = CaseDao::new(&client);
     /* Fields with options */
     if let Some(id) = params.field_id {
+        check_owner(&client, ResourceType::Field, id, project_id).await?;
         return Ok(vec![case_dao.get_field_by_id(id).await?]);
     }
     case_dao.get_fields(project_id).await
@@ -207,9 +213,25 @@ where
     Ok((template_required_field_ids, allowed_field_ids))
 }
 
+/* a selected value is an option id, it has to be an option of the field itself */
+async fn check_field_option<T: db::GenericClient>(
+    case_dao: &CaseDao<'_, T>,
+    field_id: i32,
+    option_id: i32,
+) -> AppResult {
+    let option = case_dao.get_field_option_by_id(option_id).await?;
+    if option.field_id != field_id {
+        return Err(AppError::BadRequestError(
+            FieldException::OptionMismatch.to_string(),
+        ));
+    }
+    Ok(())
+}
+
 pub async fn create_functional_case(
     state: &AppState,
     uid: Uuid,
+    project_id: i32,
     request: CreateFunctionalCaseRequest,
 ) -> AppResult<i32> {
     let mut client = state.pool.get().await?;
@@ -217,9 +239,17 @@ pub async fn create_functional_case(
     let case_dao = CaseDao::new(&transaction);
     let file_dao = FileDao::new(&transaction);
     let module = file_dao.get_module_by_id(request.module_id).await?;
+    check_owner(&transaction, ResourceType::Module, module.id, project_id).await?;
     /* insert into functional_cases */
     let case = FunctionalCase::new(&request.name, module, request.template_id, request.tags);
     /* check template exist or not, otherwise return not found err */
+    check_owner(
+        &transaction,
+        ResourceType::Template,
+        case.template_id,
+        project_id,
+    )
+    .await?;
     let template = case_dao.get_template_by_id(case.template_id).await?;
 
     let (template_required_field_ids, allowed_field_ids) = field_classify(&template.fields)?;
@@ -239,6 +269,7 @@ pub async fn create_functional_case(
     for item in request.fields.into_iter() {
         /* get field by field_id */
         let field = case_dao.get_field_by_id(item.id).await?;
+        check_project(field.project_id, project_id)?;
         /* TODO: Check whether field is unique or not while field is unique_required is true */
         if template
             .fields
@@ -258,6 +289,7 @@ pub async fn create_functional_case(
                     .await?;
             }
             (FieldType::Select, FieldValue::Select(option)) => {
+                check_field_option(&case_dao, field.id, option).await?;
                 let value = option.to_string();
                 case_dao
                     .insert_case_field_relation(case_id, field.id, &value, uid)
@@ -286,7 +318,9 @@ pub async fn update_functional_case(
     let case_dao = CaseDao::new(&transaction);
     let file_dao = FileDao::new(&transaction);
     let module = file_dao.get_module_by_id(request.module_id).await?;
+    check_owner(&transaction, ResourceType::Module, module.id, project_id).await?;
     let mut case = case_dao.get_functional_case_by_id(request.id).await?;
+    check_owner(&transaction, ResourceType::Case, case.id, project_id).await?;
     /* Setter */
     case.name = request.name;
     case.module = module;
@@ -322,6 +356,7 @@ pub async fn update_functional_case(
                             .await?;
                     }
                     (FieldType::Select, FieldValue::Select(option)) => {
+                        check_field_option(&case_dao, item.id, option).await?;
                         let value = option.to_string();
                         case_dao
                             .update_case_field_relation(field.id, &value, updated_by)
@@ -352,6 +387,7 @@ pub async fn delete_functional_case(
     let transaction = client.transaction().await?;
     let case_dao = CaseDao::new(&transaction);
     let case = case_dao.get_functional_case_by_id(request.id).await?;
+    check_owner(&transaction, ResourceType::Case, case.id, project_id).await?;
     case_dao
         .soft_delete_functional_case(case.id, deleted_by)
         .await?;
@@ -363,13 +399,19 @@ pub async fn delete_functional_case(
     Ok(())
 }
 
-pub async fn delete_by_module_id(state: &AppState, uid: Uuid, module_id: i32) -> AppResult {
+pub async fn delete_by_module_id(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    module_id: i32,
+) -> AppResult {
     info!("case service layer delete case module with {module_id}");
     let mut client = state.pool.get().await?;
     let transaction = client.transaction().await?;
     let file_dao = FileDao::new(&transaction);
     let case_dao = CaseDao::new(&transaction);
     let module = file_dao.get_module_by_id(module_id).await?;
+    check_owner(&transaction, ResourceType::Module, module.id, project_id).await?;
     file_dao.soft_delete_by_id(uid, module.id).await?;
     case_dao
         .soft_delete_functional_case_by_module_id(module.id, uid)
@@ -380,12 +422,14 @@ pub async fn delete_by_module_id(state: &AppState, uid: Uuid, module_id: i32) ->
 
 pub async fn get_functional_case(
     state: &AppState,
+    project_id: i32,
     case_id: i32,
 ) -> AppResult<FunctionalCaseResponse> {
     info!("service layer get functional case with case_id {case_id:?}");
     let client = state.pool.get().await?;
     let case_dao = CaseDao::new(&client);
     let case = case_dao.get_functional_case_by_id(case_id).await?;
+    check_owner(&client, ResourceType::Case, case.id, project_id).await?;
     let fields = case_dao.get_fields_by_case_id(case.id).await?;
     let last_execute_result = case_dao
         .get_last_execute_record_by_case_id(case.id)
@@ -412,23 +456,169 @@ pub async fn get_functional_case(
 pub async fn create_issue_relation(
     state: &AppState,
     uid: Uuid,
+    project_id: i32,
     request: IssueRelationRequest,
 ) -> AppResult {
     info!("service layer create issue relation with request: {request:?}");
+    /* validate issues on tracker before writing any relation */
+    let mut issues = Vec::new();
+    for item in request.issues.iter() {
+        let source = IssueSource::from_str(&item.source);
+        let tracker = Tracker::new(source, &state.config.tracker, &state.http)?;
+        let issue = tracker.get_issue(&item.issue_id).await?;
+        issues.push((source, issue));
+    }
     let mut client = state.pool.get().await?;
     let transaction = client.transaction().await?;
     let case_dao = CaseDao::new(&transaction);
-    for item in request.issues.iter() {
+    let case = case_dao.get_functional_case_by_id(request.case_id).await?;
+    check_owner(&transaction, ResourceType::Case, case.id, project_id).await?;
+    for (source, issue) in issues.iter() {
+        case_dao
+            .check_issue_relation_absent(&case.id, source, &issue.issue_id)
+            .await?;
         case_dao
-            .insert_case_issue_relation(&request.case_id, item, &uid)
+            .insert_case_issue_relation(&case.id, source, issue, &uid)
             .await?;
     }
     transaction.commit().await?;
     Ok(())
 }
 
-pub async fn info(_state: &AppState, _project_id: &i32) -> AppResult<RequirementInfoResponse> {
-    Ok(RequirementInfoResponse {})
+pub async fn get_issue_relation_list(
+    state: &AppState,
+    project_id: i32,
+    case_id: i32,
+) -> AppResult<Vec<IssueRelation>> {
+    info!("service layer get issue relation list with case_id: {case_id}");
+    let mut relations = {
+        let client = state.pool.get().await?;
+        let case_dao = CaseDao::new(&client);
+        let case = case_dao.get_functional_case_by_id(case_id).await?;
+        check_owner(&client, ResourceType::Case, case.id, project_id).await?;
+        case_dao.get_issue_relations_by_case_id(&case.id).await?
+    };
+    /* refresh title/status concurrently outside the transaction, keep the stored value on failure */
+    let issues = join_all(relations.iter().map(|relation| async move {
+        let tracker = Tracker::new(relation.source, &state.config.tracker, &state.http)?;
+        tracker.get_issue(&relation.issue_id).await
+    }))
+    .await;
+    let mut changed = Vec::new();
+    for (relation, issue) in relations.iter_mut().zip(issues) {
+        match issue {
+            Ok(issue) => {
+                let title = Some(issue.title);
+                let status = Some(issue.status);
+                if relation.title != title || relation.status != status {
+                    relation.title = title;
+                    relation.status = status;
+                    changed.push(relation.id);
+                }
+            }
+            Err(e) => info!(
+                "failed to sync issue {} from {:?}: {e}",
+                relation.issue_id, relation.source
+            ),
+        }
+    }
+    if !changed.is_empty() {
+        let mut client = state.pool.get().await?;
+        let transaction = client.transaction().await?;
+        let case_dao = CaseDao::new(&transaction);
+        for relation in relations.iter().filter(|r| changed.contains(&r.id)) {
+            case_dao
+                .sync_issue_relation(&relation.id, &relation.title, &relation.status)
+                .await?;
+        }
+        transaction.commit().await?;
+    }
+    Ok(relations)
+}
+
+pub async fn delete_issue_relation(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: DeleteEntityRequest,
+) -> AppResult {
+    info!("service layer delete issue relation with request: {request:?}, deleted_by: {uid}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let case_dao = CaseDao::new(&transaction);
+    let relation = case_dao.get_issue_relation_by_id(&request.id).await?;
+    check_owner(&transaction, ResourceType::Issue, relation.id, project_id).await?;
+    case_dao
+        .soft_delete_issue_relation(&relation.id, &uid)
+        .await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn report_issue(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: ReportIssueRequest,
+) -> AppResult<CreateEntityResponse> {
+    info!("service layer report issue with request: {request:?}");
+    let source = IssueSource::from_str(&request.source);
+    let tracker = Tracker::new(source, &state.config.tracker, &state.http)?;
+    let mut client = state.pool.get().await?;
+    /* claimed in a short transaction, the tracker is called without holding a connection or lock */
+    let (case, record) = {
+        let transaction = client.transaction().await?;
+        let case_dao = CaseDao::new(&transaction);
+        let record = case_dao
+            .get_execute_record_by_id(&request.record_id)
+            .await?;
+        let case = case_dao.get_functional_case_by_id(record.case_id).await?;
+        check_owner(&transaction, ResourceType::Case, case.id, project_id).await?;
+        if record.result != CaseResult::Failed {
+            return Err(AppError::BadRequestError(
+                IssueException::RecordNotFailed.to_string(),
+            ));
+        }
+        if !case_dao.claim_execute_record_report(&record.id).await? {
+            return Err(AppError::BadRequestError(
+                IssueException::AlreadyReported.to_string(),
+            ));
+        }
+        transaction.commit().await?;
+        (case, record)
+    };
+    drop(client);
+    let new_issue = NewIssue {
+        title: request
+            .title
+            .unwrap_or_else(|| format!("[{}] 用例执行失败", case.name)),
+        description: format!(
+            "用例: {} (ID: {})\n执行记录: {}\n执行人: {}\n执行时间: {}\n\n{}",
+            case.name, case.id, record.id, record.created_by, record.created_at, record.attach_info
+        ),
+    };
+    let issue = tracker.create_issue(&new_issue).await;
+    let mut client = state.pool.get().await?;
+    let issue = match issue {
+        Ok(issue) => issue,
+        Err(e) => {
+            /* nothing was created, the record can be reported again right away */
+            CaseDao::new(&client)
+                .release_execute_record_report(&record.id)
+                .await?;
+            return Err(e);
+        }
+    };
+    let transaction = client.transaction().await?;
+    let case_dao = CaseDao::new(&transaction);
+    let relation_id = case_dao
+        .insert_case_issue_relation(&case.id, &source, &issue, &uid)
+        .await?;
+    case_dao
+        .bind_execute_record_issue(&record.id, &relation_id, &uid)
+        .await?;
+    transaction.commit().await?;
+    Ok(CreateEntityResponse { id: relation_id })
 }
 
 pub async fn get_functional_case_list(
@@ -440,51 +630,31 @@ pub async fn get_functional_case_list(
     let mut client = state.pool.get().await?;
     let transaction = client.transaction().await?;
     let case_dao = CaseDao::new(&transaction);
-    let page_claims = match param.page_token {
-        Some(page_token) => parse_page_token(page_token)?,
-        None => {
-            let page_size = param.page_size.unwrap_or(10).clamp(1, 100);
-            let page_num = param.page_num.unwrap_or(1).max(1);
-            let offset = (page_num - 1) * page_size;
-            let last_item_id = if offset > 0 {
-                case_dao.get_query_cursor(offset).await?
-            } else {
-                0
-            };
-            let module_ids = match param.module_ids {
-                Some(ids) => parse_ids(&ids)?,
-                None => {
-                    let file_dao = FileDao::new(&transaction);
-                    file_dao
-                        .get_all_module_id(project_id, "CASE".into())
-                        .await?
-                }
-            };
-            info!("===================>>> module ids: {:?}", module_ids);
-            PageClaims::new(page_size, page_num, last_item_id, module_ids)
-        }
-    };
+    let page = page::resolve(
+        state,
+        &transaction,
+        &case_dao,
+        PageKind::Case,
+        *project_id,
+        &param,
+    )
+    .await?;
     let total = case_dao
-        .count_case_by_module_ids(&page_claims.module_ids)
+        .count_case_by_module_ids(&page.filter.module_ids)
         .await?;
-    let deleted = param.deleted.unwrap_or(false);
     let functional_case_list = case_dao
         .get_functional_case_list(
-            &page_claims.module_ids,
-            page_claims.last_item_id,
-            page_claims.page_size,
-            deleted,
+            &page.filter.module_ids,
+            page.after,
+            page.page_size,
+            page.filter.deleted,
         )
         .await?;
-    let next_cursor = match functional_case_list.last() {
-        Some(l) => l.id,
-        None => 0,
-    };
-    let next_page_token = generate_page_token(
-        page_claims.page_size,
-        page_claims.page_num + 1,
-        next_cursor,
-        page_claims.module_ids,
+    let next_page_token = page::next_page_token(
+        state,
+        page,
+        functional_case_list.last().map(|case| case.id),
+        functional_case_list.len(),
     )?;
     let mut list: Vec<FunctionalCaseResponse> = Vec::new();
     for case in functional_case_list.into_iter() {
@@ -540,22 +710,54 @@ where
     info!("get step list with params: {req:?}");
     let mut info_list = Vec::new();
     for item in req.iter() {
-        if let Ok(info) = dao.get_element(item.element_id, item.option_id).await {
-            info_list.push(StepInfo {
-                position: item.position,
-                action: info.action,
-                selector: info.selector,
-                attach_info: item.attach_info.clone(),
-            })
+        let info = dao.get_element(item.element_id, item.option_id).await?;
+        if !info.allowed {
+            return Err(AppError::BadRequestError(
+                ElementException::OperationNotAllowed.to_string(),
+            ));
         }
+        info_list.push(StepInfo {
+            position: item.position,
+            action: info.action,
+            template: info.template,
+            selector: info.selector,
+            attach_info: item.attach_info.clone(),
+        })
     }
     Ok(info_list)
 }
 
+/* checked before enqueueing, the job itself runs without project context */
+pub async fn check_script_request(
+    state: &AppState,
+    project_id: i32,
+    request: &CreateScriptRequest,
+) -> AppResult {
+    let client = state.pool.get().await?;
+    check_owner(&client, ResourceType::Case, request.case_id, project_id).await?;
+    let steps = request
+        .pre_processors
+        .iter()
+        .chain(request.steps.iter())
+        .chain(request.after_processors.iter());
+    for step in steps {
+        check_owner(&client, ResourceType::Element, step.element_id, project_id).await?;
+    }
+    /* steps with operations not allowed for their element type are refused before the job runs */
+    let element_dao = ElementDao::new(&client);
+    try_join!(
+        get_step_list(&element_dao, &request.pre_processors),
+        get_step_list(&element_dao, &request.steps),
+        get_step_list(&element_dao, &request.after_processors)
+    )?;
+    Ok(())
+}
+
 pub async fn gen_script(
     state: &AppState,
     uid: Uuid,
     request: CreateScriptRequest,
+    job: &JobHandle,
 ) -> AppResult<CreateScriptResponse> {
     info!("service layer generate script with request: {request:?}");
     /* construct DriveData with request parameters */
@@ -567,6 +769,7 @@ pub async fn gen_script(
         get_step_list(&element_dao, &request.steps),
         get_step_list(&element_dao, &request.after_processors)
     )?;
+    job.progress(30).await?;
 
     /* generate script with engine service */
     let data = engine::DriveData {
@@ -578,6 +781,7 @@ pub async fn gen_script(
         after_processors,
     };
     let mut script = engine::generator(data).await?;
+    job.progress(60).await?;
 
     /* insert script record into database */
     let case_dao = CaseDao::new(&transaction);
@@ -589,6 +793,16 @@ pub async fn gen_script(
     let script_id: i32 = case_dao.insert_script(script).await?;
 
     /* binding relationship for element used in script */
+    let steps = request
+        .pre_processors
+        .iter()
+        .chain(request.steps.iter())
+        .chain(request.after_processors.iter());
+    for step in steps {
+        element_dao
+            .bind_element_operation(&step.element_id, &step.option_id)
+            .await?;
+    }
     try_join!(
         case_dao.insert_script_element_relation(
             &script_id,
@@ -602,6 +816,8 @@ pub async fn gen_script(
             &request.after_processors,
         )
     )?;
+    /* last chance to cancel, nothing is persisted before commit */
+    job.progress(90).await?;
     transaction.commit().await?;
     Ok(CreateScriptResponse {
         id: script_id,
@@ -612,6 +828,7 @@ pub async fn gen_script(
 pub async fn env_diagnose(
     state: &AppState,
     request: DiagnoseRequest,
+    job: &JobHandle,
 ) -> AppResult<DiagnoseResponse> {
     /* script path */
     let script_path_str = format!("{}/{}", DOCTOR_SCRIPT_PATH, &request.script_name);
@@ -620,6 +837,7 @@ pub async fn env_diagnose(
     let client = state.pool.get().await?;
     let case_dao = CaseDao::new(&client);
     let machine = case_dao.get_machine(&request.machine_id).await?;
+    job.progress(10).await?;
 
     let resp = engine::doctor_script(machine, Path::new(&script_path_str)).await?;
     Ok(DiagnoseResponse { msg: resp })
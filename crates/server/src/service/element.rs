@@ -1,35 +1,214 @@
 use std::collections::HashMap;
 
-use tracing::info;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
 use uuid::Uuid;
 
 use crate::{
-    dao::{element::ElementDao, entity::Element, file::FileDao},
+    dao::{
+        case::CaseDao,
+        element::ElementDao,
+        entity::{Element, ElementDetail, ElementReference, OperationOption},
+        file::FileDao,
+        job::JobDao,
+    },
     dto::{
-        request::{CreateElementRequest, ElementQueryParam, ListQueryParam},
-        response::ListElementResponse,
+        request::{
+            CreateElementRequest, CreateOperationOptionRequest, DeleteElementRequest,
+            DeleteEntityRequest, ElementQueryParam, HealthCheckRequest, ListQueryParam,
+            MoveElementRequest, OperationOptionBindingRequest, OperationOptionQueryParam,
+            UpdateElementRequest, UpdateOperationOptionRequest,
+        },
+        response::{HealthCheckResponse, ListElementResponse},
     },
-    errors::AppResult,
-    service::token::{generate_page_token, parse_page_token},
+    entity::{element::HealthCheckStatus, file::ModuleType, job::JobKind},
+    errors::{message::ElementException, AppError, AppResult, ResourceType},
+    service::{engine, page, project::check_owner},
     state::AppState,
-    utils::{claim::PageClaims, parse_ids},
+    utils::{claim::PageKind, http::check_public_url},
 };
 
-pub async fn create(state: &AppState, uid: Uuid, request: CreateElementRequest) -> AppResult {
+/* elements can only be placed in modules of type ELEMENT of the same project */
+async fn check_element_module<T: db::GenericClient>(
+    executor: &T,
+    module_id: i32,
+    project_id: i32,
+) -> AppResult {
+    let file_dao = FileDao::new(executor);
+    let module = file_dao.get_module_by_id(module_id).await?;
+    check_owner(executor, ResourceType::Module, module.id, project_id).await?;
+    if module.module_type != ModuleType::Element {
+        return Err(AppError::BadRequestError(
+            ElementException::ModuleTypeMismatch.to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn create(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: CreateElementRequest,
+) -> AppResult<i32> {
+    info!("service layer create element with request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    check_element_module(&transaction, request.module_id, project_id).await?;
+    let element_dao = ElementDao::new(&transaction);
+    element_dao
+        .check_value_unique(&request.module_id, &request.value, None)
+        .await?;
+    let element = Element::new(
+        &request.name,
+        &request.value,
+        &request.element_type,
+        request.description.as_deref(),
+        request.module_id,
+        uid,
+    );
+    let element_id = element_dao.create(element).await?;
+    transaction.commit().await?;
+    Ok(element_id)
+}
+
+pub async fn info(state: &AppState, project_id: i32, element_id: i32) -> AppResult<ElementDetail> {
+    info!("service layer query element information with id: {element_id}");
     let client = state.pool.get().await?;
-    // let transaction = client.transaction().await?;
     let element_dao = ElementDao::new(&client);
-    let element = Element::new(
+    let mut element = element_dao.get_element_by_id(&element_id).await?;
+    check_owner(&client, ResourceType::Element, element.id, project_id).await?;
+    element.operation_options = element_dao
+        .get_operation_options(Some(&element.element_type))
+        .await?;
+    Ok(element)
+}
+
+pub async fn update(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: UpdateElementRequest,
+) -> AppResult {
+    info!("service layer update element with request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let element_dao = ElementDao::new(&transaction);
+    let detail = element_dao.get_element_by_id(&request.id).await?;
+    check_owner(&transaction, ResourceType::Element, detail.id, project_id).await?;
+    element_dao
+        .check_value_unique(&detail.module_id, &request.value, Some(detail.id))
+        .await?;
+    let mut element = Element::new(
         &request.name,
         &request.value,
         &request.element_type,
         request.description.as_deref(),
+        detail.module_id,
         uid,
     );
-    let _element_id = element_dao.create(element).await?;
+    element.id = detail.id;
+    element_dao.update(element, uid).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn move_to(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: MoveElementRequest,
+) -> AppResult {
+    info!("service layer move element with request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    check_element_module(&transaction, request.module_id, project_id).await?;
+    let element_dao = ElementDao::new(&transaction);
+    let detail = element_dao.get_element_by_id(&request.id).await?;
+    check_owner(&transaction, ResourceType::Element, detail.id, project_id).await?;
+    if detail.module_id != request.module_id {
+        element_dao
+            .check_value_unique(&request.module_id, &detail.value, Some(detail.id))
+            .await?;
+        element_dao
+            .update_module(&detail.id, &request.module_id, &uid)
+            .await?;
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn delete(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: DeleteElementRequest,
+) -> AppResult {
+    info!("service layer delete element with request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let element_dao = ElementDao::new(&transaction);
+    let detail = element_dao.get_element_by_id(&request.id).await?;
+    check_owner(&transaction, ResourceType::Element, detail.id, project_id).await?;
+    if !request.force
+        && !element_dao
+            .get_referenced_scripts(&detail.id)
+            .await?
+            .is_empty()
+    {
+        return Err(AppError::BadRequestError(
+            ElementException::Referenced.to_string(),
+        ));
+    }
+    /* scripts would otherwise keep pointing at the deleted element, restoring it does not re-attach them */
+    let detached = element_dao.detach_scripts(&detail.id).await?;
+    if detached > 0 {
+        warn!(
+            "element {} deleted, {detached} script steps detached",
+            detail.id
+        );
+    }
+    element_dao.soft_delete(&detail.id, &uid).await?;
+    transaction.commit().await?;
     Ok(())
 }
 
+pub async fn restore(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: DeleteEntityRequest,
+) -> AppResult {
+    info!("service layer restore element with request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let element_dao = ElementDao::new(&transaction);
+    let detail = element_dao.get_element_by_id(&request.id).await?;
+    check_owner(&transaction, ResourceType::Element, detail.id, project_id).await?;
+    if detail.deleted {
+        /* another alive element may have taken the selector value meanwhile */
+        element_dao
+            .check_value_unique(&detail.module_id, &detail.value, Some(detail.id))
+            .await?;
+        element_dao.restore(&detail.id, &uid).await?;
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn references(
+    state: &AppState,
+    project_id: i32,
+    element_id: i32,
+) -> AppResult<Vec<ElementReference>> {
+    info!("service layer query scripts referencing element: {element_id}");
+    let client = state.pool.get().await?;
+    let element_dao = ElementDao::new(&client);
+    let detail = element_dao.get_element_by_id(&element_id).await?;
+    check_owner(&client, ResourceType::Element, detail.id, project_id).await?;
+    element_dao.get_referenced_scripts(&detail.id).await
+}
+
 /* Element exec main logic */
 #[allow(dead_code)]
 pub async fn exec(state: &AppState, script_id: i32) -> AppResult {
@@ -51,45 +230,39 @@ pub async fn get_element_list(
     let mut client = state.pool.get().await?;
     let transaction = client.transaction().await?;
     let element_dao = ElementDao::new(&transaction);
-    let page_claims = match param.page_token {
-        Some(page_token) => parse_page_token(page_token)?,
-        None => {
-            let page_size = param.page_size.unwrap_or(10).clamp(1, 100);
-            let page_num = param.page_num.unwrap_or(1).max(1);
-            let offset = (page_num - 1) * page_size;
-            let last_item_id = if offset > 0 {
-                element_dao.get_query_cursor(offset).await?
-            } else {
-                0
-            };
-            let module_ids = match param.module_ids {
-                Some(ids) => parse_ids(&ids)?,
-                None => {
-                    let file_dao = FileDao::new(&transaction);
-                    file_dao
-                        .get_all_module_id(project_id, "ELEMENT".into())
-                        .await?
-                }
-            };
-            PageClaims::new(page_size, page_num, last_item_id, module_ids)
-        }
-    };
-    let list = element_dao
+    let page = page::resolve(
+        state,
+        &transaction,
+        &element_dao,
+        PageKind::Element,
+        *project_id,
+        &param,
+    )
+    .await?;
+    let mut list = element_dao
         .get_element_list(
-            &page_claims.module_ids,
-            &page_claims.page_size,
-            &page_claims.last_item_id,
+            &page.filter.module_ids,
+            &page.page_size,
+            &page.after,
+            page.filter.deleted,
         )
         .await?;
-    let next_cursor = match list.last() {
-        Some(l) => l.id,
-        None => 0,
-    };
-    let next_page_token = generate_page_token(
-        page_claims.page_size,
-        page_claims.page_num + 1,
-        next_cursor,
-        page_claims.module_ids,
+    /* allowed operations are bound per element type, query each type once */
+    let mut type_options: HashMap<String, Vec<OperationOption>> = HashMap::new();
+    for element in list.iter_mut() {
+        if !type_options.contains_key(&element.element_type) {
+            let options = element_dao
+                .get_operation_options(Some(&element.element_type))
+                .await?;
+            type_options.insert(element.element_type.clone(), options);
+        }
+        element.operation_options = type_options[&element.element_type].clone();
+    }
+    let next_page_token = page::next_page_token(
+        state,
+        page,
+        list.last().map(|element| element.id),
+        list.len(),
     )?;
     transaction.commit().await?;
     Ok(ListElementResponse {
@@ -114,3 +287,248 @@ pub async fn count(
     let hmap = element_dao.count(project_id, &is_deleted).await?;
     Ok(hmap)
 }
+
+pub async fn create_operation_option(
+    state: &AppState,
+    uid: Uuid,
+    request: CreateOperationOptionRequest,
+) -> AppResult<i32> {
+    info!("service layer create operation option with request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let element_dao = ElementDao::new(&transaction);
+    element_dao.check_exec_unique(&request.exec, None).await?;
+    let option = OperationOption::new(&request.name, &request.exec, request.template.as_deref());
+    let option_id = element_dao.create_operation_option(&option, &uid).await?;
+    transaction.commit().await?;
+    Ok(option_id)
+}
+
+pub async fn get_operation_options(
+    state: &AppState,
+    param: OperationOptionQueryParam,
+) -> AppResult<Vec<OperationOption>> {
+    info!("service layer query operation options with param: {param:?}");
+    let client = state.pool.get().await?;
+    let element_dao = ElementDao::new(&client);
+    element_dao
+        .get_operation_options(param.element_type.as_deref())
+        .await
+}
+
+pub async fn update_operation_option(
+    state: &AppState,
+    uid: Uuid,
+    request: UpdateOperationOptionRequest,
+) -> AppResult {
+    info!("service layer update operation option with request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let element_dao = ElementDao::new(&transaction);
+    let mut option = element_dao.get_operation_option_by_id(&request.id).await?;
+    if option.internal {
+        return Err(AppError::BadRequestError(
+            ElementException::InternalOperation.to_string(),
+        ));
+    }
+    element_dao
+        .check_exec_unique(&request.exec, Some(option.id))
+        .await?;
+    option.name = request.name;
+    option.exec = request.exec;
+    option.template = request.template;
+    element_dao.update_operation_option(&option, &uid).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn delete_operation_option(state: &AppState, request: DeleteEntityRequest) -> AppResult {
+    info!("service layer delete operation option with request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let element_dao = ElementDao::new(&transaction);
+    let option = element_dao.get_operation_option_by_id(&request.id).await?;
+    if option.internal {
+        return Err(AppError::BadRequestError(
+            ElementException::InternalOperation.to_string(),
+        ));
+    }
+    if element_dao
+        .count_operation_option_references(&option.id)
+        .await?
+        > 0
+    {
+        return Err(AppError::BadRequestError(
+            ElementException::OperationReferenced.to_string(),
+        ));
+    }
+    element_dao.delete_operation_option(&option.id).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn bind_operation_options(
+    state: &AppState,
+    uid: Uuid,
+    request: OperationOptionBindingRequest,
+) -> AppResult {
+    info!("service layer bind operation options with request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let element_dao = ElementDao::new(&transaction);
+    for option_id in request.option_ids.iter() {
+        let option = element_dao.get_operation_option_by_id(option_id).await?;
+        element_dao
+            .bind_type_operation_option(&request.element_type, &option.id, &uid)
+            .await?;
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn unbind_operation_options(
+    state: &AppState,
+    request: OperationOptionBindingRequest,
+) -> AppResult {
+    info!("service layer unbind operation options with request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let element_dao = ElementDao::new(&transaction);
+    for option_id in request.option_ids.iter() {
+        element_dao
+            .unbind_type_operation_option(&request.element_type, option_id)
+            .await?;
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn health_check(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: HealthCheckRequest,
+) -> AppResult<i32> {
+    info!("service layer create element health check with request: {request:?}");
+    /* the page is fetched by the server itself without a machine */
+    if request.machine_id.is_none() {
+        check_public_url(&request.page_url).await?;
+    }
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    check_element_module(&transaction, request.module_id, project_id).await?;
+    if let Some(machine_id) = request.machine_id {
+        CaseDao::new(&transaction).get_machine(&machine_id).await?;
+    }
+    let element_dao = ElementDao::new(&transaction);
+    let check_id = element_dao
+        .create_health_check(
+            &request.module_id,
+            &request.page_url,
+            request.machine_id,
+            &uid,
+        )
+        .await?;
+    let payload = serde_json::to_string(&HealthCheckJob { check_id, request })?;
+    /* a failed check is reported on its record, the user starts a new one instead of a retry */
+    JobDao::new(&transaction)
+        .create(JobKind::HealthCheck, &payload, 1, &uid)
+        .await?;
+    transaction.commit().await?;
+    Ok(check_id)
+}
+
+/* payload of `JobKind::HealthCheck`, queued in the transaction creating the check */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HealthCheckJob {
+    pub check_id: i32,
+    pub request: HealthCheckRequest,
+}
+
+/* loading the page may take a while, the outcome is reported through the check record */
+pub async fn run_health_check(state: &AppState, job: &HealthCheckJob) -> AppResult {
+    let outcome = load_page(state, &job.request).await;
+    let (status, error) = match &outcome {
+        Ok(html) => {
+            save_health_check(state, job.check_id, job.request.module_id, html).await?;
+            (HealthCheckStatus::Finished, None)
+        }
+        Err(e) => {
+            warn!("element health check {} failed: {e:?}", job.check_id);
+            (HealthCheckStatus::Failed, Some(e.to_string()))
+        }
+    };
+    let client = state.pool.get().await?;
+    ElementDao::new(&client)
+        .finish_health_check(&job.check_id, status, error)
+        .await?;
+    outcome.map(|_| ())
+}
+
+async fn load_page(state: &AppState, request: &HealthCheckRequest) -> AppResult<String> {
+    let html = match request.machine_id {
+        Some(machine_id) => {
+            let machine = {
+                let client = state.pool.get().await?;
+                CaseDao::new(&client).get_machine(&machine_id).await?
+            };
+            engine::dump_dom(machine, &request.page_url).await?
+        }
+        /* local stand-in: static html without running page scripts */
+        None => {
+            let url = check_public_url(&request.page_url).await?;
+            state
+                .public_http
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await?
+        }
+    };
+    Ok(html)
+}
+
+/* checks the selectors of the module against the loaded page */
+pub async fn save_health_check(
+    state: &AppState,
+    check_id: i32,
+    module_id: i32,
+    html: &str,
+) -> AppResult {
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let element_dao = ElementDao::new(&transaction);
+    let selectors = element_dao.get_selectors_by_module_id(&module_id).await?;
+    for check in engine::check_selectors(html, &selectors).iter() {
+        element_dao
+            .insert_health_check_result(&check_id, check)
+            .await?;
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn get_health_check(
+    state: &AppState,
+    project_id: i32,
+    check_id: i32,
+) -> AppResult<HealthCheckResponse> {
+    info!("service layer query element health check with id: {check_id}");
+    let client = state.pool.get().await?;
+    let element_dao = ElementDao::new(&client);
+    let check = element_dao.get_health_check_by_id(&check_id).await?;
+    check_owner(&client, ResourceType::HealthCheck, check.id, project_id).await?;
+    let results = element_dao.get_health_check_results(&check.id).await?;
+    Ok(HealthCheckResponse { check, results })
+}
+
+pub async fn expire_health_checks(state: &AppState) -> AppResult {
+    let client = state.pool.get().await?;
+    let expired = ElementDao::new(&client).expire_health_checks().await?;
+    if expired > 0 {
+        warn!("expired {expired} element health checks");
+    }
+    Ok(())
+}
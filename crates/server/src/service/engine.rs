@@ -1,6 +1,9 @@
+use crate::constant::{CYPRESS_RUN_CMD, HEADLESS_BROWSER_CMD};
 use crate::dao::entity::Machine;
+use crate::entity::element::{SelectorCheck, SelectorStatus};
 use crate::{configure::Config, dao::entity::Script, errors::AppResult};
 use chrono::Utc;
+use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
 use ssh2::Session;
 use std::path::Path;
@@ -39,10 +42,24 @@ pub enum Framework {
 pub struct StepInfo {
     pub position: i32,
     pub action: String,
+    /* custom operation snippet, rendered against the step before the case template */
+    pub template: Option<String>,
     pub selector: Option<String>,
     pub attach_info: Option<HashMap<String, String>>,
 }
 
+pub fn render_step_templates(steps: &mut [StepInfo]) -> AppResult {
+    for step in steps.iter_mut() {
+        if let Some(template) = &step.template {
+            let mut ctx = Context::new();
+            ctx.insert("selector", &step.selector);
+            ctx.insert("attach_info", &step.attach_info);
+            step.template = Some(Tera::one_off(template, &ctx, false)?);
+        }
+    }
+    Ok(())
+}
+
 fn remove_empty_lines(value: &Value, _: &HashMap<String, Value>) -> TeraResult<Value> {
     let s = value.as_str().unwrap_or("");
     let cleaned = s
@@ -53,7 +70,7 @@ fn remove_empty_lines(value: &Value, _: &HashMap<String, Value>) -> TeraResult<V
     Ok(Value::String(cleaned))
 }
 
-pub async fn generator(script: DriveData) -> AppResult<Script> {
+pub async fn generator(mut script: DriveData) -> AppResult<Script> {
     let config = Config::parse("./config.toml").expect("Failed to parse configuration file");
 
     // initialize Tera template engine.
@@ -62,6 +79,11 @@ pub async fn generator(script: DriveData) -> AppResult<Script> {
     // register customized filter.
     tera.register_filter("remove_empty_lines", remove_empty_lines);
 
+    // render custom operation snippets.
+    render_step_templates(&mut script.pre_processors)?;
+    render_step_templates(&mut script.steps)?;
+    render_step_templates(&mut script.after_processors)?;
+
     // create template context.
     let mut ctx = Context::new();
 
@@ -154,3 +176,96 @@ pub async fn doctor_script(machine: Machine, script: &Path) -> AppResult<String>
     channel.wait_close()?;
     Ok(ret.to_string())
 }
+
+/* quote for a POSIX shell, `'` is closed, escaped and reopened */
+fn shell_quote(raw: &str) -> String {
+    format!("'{}'", raw.replace('\'', r"'\''"))
+}
+
+fn dump_dom_blocking(machine: Machine, page_url: String) -> AppResult<String> {
+    let tcp = TcpStream::connect(&machine.addr)?;
+    let mut session = Session::new()?;
+    session.set_tcp_stream(tcp);
+    session.handshake()?;
+
+    let v: Value = serde_json::from_str(&machine.authentication)?;
+    let user = v["user"].as_str().unwrap_or_default();
+    let password = v["password"].as_str().unwrap_or_default();
+    session.userauth_password(user, password)?;
+
+    let mut channel = session.channel_session()?;
+    channel.exec(&format!(
+        "{HEADLESS_BROWSER_CMD} {}",
+        shell_quote(&page_url)
+    ))?;
+    let mut output = Vec::new();
+    channel.read_to_end(&mut output)?;
+    channel.wait_close()?;
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+/* render page with the headless browser on the machine and return the DOM */
+pub async fn dump_dom(machine: Machine, page_url: &str) -> AppResult<String> {
+    let page_url = page_url.to_string();
+    let join_handle = tokio::task::spawn_blocking(move || dump_dom_blocking(machine, page_url));
+    join_handle.await?
+}
+
+fn run_script_blocking(machine: Machine, script: String) -> AppResult<bool> {
+    let file_name = Path::new(&script)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| script.clone());
+    let tcp = TcpStream::connect(&machine.addr)?;
+    let mut session = Session::new()?;
+    session.set_tcp_stream(tcp);
+    session.handshake()?;
+
+    let v: Value = serde_json::from_str(&machine.authentication)?;
+    let user = v["user"].as_str().unwrap_or_default();
+    let password = v["password"].as_str().unwrap_or_default();
+    session.userauth_password(user, password)?;
+
+    let sftp = session.sftp()?;
+    let mut script_file = File::open(&script)?;
+    let mut remote_file = sftp.create(Path::new(&file_name))?;
+    std::io::copy(&mut script_file, &mut remote_file)?;
+    drop(remote_file);
+
+    let mut channel = session.channel_session()?;
+    channel.exec(&format!("{CYPRESS_RUN_CMD} {}", shell_quote(&file_name)))?;
+    let mut output = Vec::new();
+    channel.read_to_end(&mut output)?;
+    channel.wait_close()?;
+    Ok(channel.exit_status()? == 0)
+}
+
+/* upload the script to the machine and run it, `true` when every spec passed */
+pub async fn run_script(machine: Machine, script: &str) -> AppResult<bool> {
+    let script = script.to_string();
+    let join_handle = tokio::task::spawn_blocking(move || run_script_blocking(machine, script));
+    join_handle.await?
+}
+
+/* count matches of each (element_id, selector) in the page */
+pub fn check_selectors(html: &str, selectors: &[(i32, String)]) -> Vec<SelectorCheck> {
+    let document = Html::parse_document(html);
+    selectors
+        .iter()
+        .map(|(element_id, selector)| {
+            let (status, match_count) = match Selector::parse(selector) {
+                Ok(s) => {
+                    let count = document.select(&s).count();
+                    (SelectorStatus::from_match_count(count), count as i32)
+                }
+                Err(_) => (SelectorStatus::Invalid, 0),
+            };
+            SelectorCheck {
+                element_id: *element_id,
+                selector: selector.clone(),
+                status,
+                match_count,
+            }
+        })
+        .collect()
+}
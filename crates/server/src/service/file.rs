@@ -5,7 +5,8 @@ use crate::{
         response::{CreateEntityResponse, FileModuleResponse},
     },
     entity::file::{FileModule, ModuleType},
-    errors::{AppError, AppResult},
+    errors::{AppError, AppResult, ResourceType},
+    service::project::check_owner,
     state::AppState,
 };
 use std::collections::HashMap;
@@ -28,6 +29,7 @@ pub async fn create_file_module(
             .get_module_by_id(parent_id)
             .await
             .map_err(|e| AppError::BadRequestError(e.to_string()))?;
+        check_owner(&transaction, ResourceType::Module, parent_id, project_id).await?;
         file_dao
             .get_descendant_by_id(parent_id)
             .await
@@ -65,6 +67,7 @@ pub async fn create_file_module(
 pub async fn update_file_module(
     state: &AppState,
     uid: Uuid,
+    project_id: i32,
     _module_type: ModuleType,
     request: UpdateModuleRequest,
 ) -> AppResult {
@@ -72,8 +75,10 @@ pub async fn update_file_module(
     let client = state.pool.get().await?;
     let file_dao = FileDao::new(&client);
     let mut module = file_dao.get_module_by_id(request.id).await?;
+    check_owner(&client, ResourceType::Module, module.id, project_id).await?;
     module.name = request.name;
-    if request.parent_id.is_some() {
+    if let Some(parent_id) = request.parent_id {
+        check_owner(&client, ResourceType::Module, parent_id, project_id).await?;
         module.parent_id = request.parent_id;
     }
     file_dao.update_file_module(module, uid).await?;
@@ -94,6 +99,7 @@ pub async fn get_file_module(
     project_dao.find_by_id(project_id.clone()).await?;
     let file_modules: Vec<FileModule> = if let Some(module_id) = params.module_id {
         let module = file_dao.get_module_by_id(module_id).await?;
+        check_owner(&transaction, ResourceType::Module, module.id, *project_id).await?;
         vec![module]
     } else {
         let deleted = params.deleted.unwrap_or(false);
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    constant::REGISTER_EMAIL_SUBJECT,
+    dao::user::UserDao,
+    dto::{
+        request::user::{AcceptInvitationRequest, InvitationRequest},
+        response::user::LoginResponse,
+        EmailTemplate,
+    },
+    entity::user::User,
+    errors::{message::InvitationException, AppError, AppResult},
+    service::{
+        api_token::{hash_token, random_secret},
+        mfa,
+        redis::{self, InvitationKey, UserInvitationKey},
+    },
+    state::AppState,
+    utils::{self, header::ClientInfo, smtp},
+};
+
+/* issues a single use token for the user, the pending one stops working */
+pub async fn create(state: &AppState, uid: Uuid) -> AppResult<String> {
+    revoke_pending(state, uid).await?;
+    let token = random_secret();
+    let token_hash = hash_token(&token);
+    redis::set(
+        &state.redis,
+        (
+            &InvitationKey {
+                token_hash: token_hash.clone(),
+            },
+            &uid,
+        ),
+    )
+    .await?;
+    redis::set(
+        &state.redis,
+        (&UserInvitationKey { uuid: uid }, &token_hash),
+    )
+    .await?;
+    Ok(token)
+}
+
+async fn revoke_pending(state: &AppState, uid: Uuid) -> AppResult {
+    let key = UserInvitationKey { uuid: uid };
+    if let Some(token_hash) = redis::get(&state.redis, &key).await? {
+        redis::del(&state.redis, &InvitationKey { token_hash }).await?;
+        redis::del(&state.redis, &key).await?;
+    }
+    Ok(())
+}
+
+fn link(state: &AppState, token: &str) -> String {
+    format!(
+        "{}/invitation?token={token}",
+        state.config.http.web_url.trim_end_matches('/')
+    )
+}
+
+pub async fn send(state: &AppState, user: &User) -> AppResult {
+    info!("Send invitation to user: {}", user.username);
+    let token = create(state, user.uuid).await?;
+    let template = EmailTemplate::Register {
+        username: user.username.clone(),
+        link: link(state, &token),
+    };
+    smtp::send(&state.email, &template, REGISTER_EMAIL_SUBJECT, &user.email).await
+}
+
+/* payload of `JobKind::SendInvitation`, queued in the transaction creating the user */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvitationJob {
+    pub uuid: Uuid,
+}
+
+/* no-op once the invitation was accepted, so a retried job never sends a stale link */
+pub async fn send_pending(state: &AppState, uid: Uuid) -> AppResult {
+    let user = {
+        let client = state.pool.get().await?;
+        let user_dao = UserDao::new(&client);
+        if !user_dao.is_password_change_required(&uid).await? {
+            return Ok(());
+        }
+        user_dao.find_by_uid(&uid).await?
+    };
+    send(state, &user).await
+}
+
+async fn pending_users(state: &AppState, ids: &[i32]) -> AppResult<Vec<User>> {
+    let client = state.pool.get().await?;
+    let user_dao = UserDao::new(&client);
+    let mut users = vec![];
+    for id in ids.iter() {
+        let user = user_dao.find_by_id(id).await?;
+        if !user_dao.is_password_change_required(&user.uuid).await? {
+            return Err(AppError::BadRequestError(
+                InvitationException::AlreadyAccepted.to_string(),
+            ));
+        }
+        users.push(user);
+    }
+    Ok(users)
+}
+
+pub async fn resend(state: &AppState, request: InvitationRequest) -> AppResult {
+    info!("service layer resend invitation with request: {request:?}");
+    for user in pending_users(state, &request.ids).await?.iter() {
+        send(state, user).await?;
+    }
+    Ok(())
+}
+
+pub async fn revoke(state: &AppState, request: InvitationRequest) -> AppResult {
+    info!("service layer revoke invitation with request: {request:?}");
+    for user in pending_users(state, &request.ids).await?.iter() {
+        revoke_pending(state, user.uuid).await?;
+    }
+    Ok(())
+}
+
+/* the invitee chooses a password and is logged in right away */
+pub async fn accept(
+    state: &AppState,
+    request: AcceptInvitationRequest,
+    client_info: &ClientInfo,
+) -> AppResult<LoginResponse> {
+    let key = InvitationKey {
+        token_hash: hash_token(&request.token),
+    };
+    utils::password::check_strength(&state.config.password, &request.password)?;
+    let invalid = || AppError::BadRequestError(InvitationException::InvalidToken.to_string());
+    let uid = redis::get(&state.redis, &key).await?.ok_or_else(invalid)?;
+    /* whoever deletes the key first owns the token */
+    if !redis::del(&state.redis, &key).await? {
+        return Err(invalid());
+    }
+    redis::del(&state.redis, &UserInvitationKey { uuid: uid }).await?;
+    let hashed_password = utils::password::hash(request.password).await?;
+    let client = state.pool.get().await?;
+    let user_dao = UserDao::new(&client);
+    let user = user_dao.find_by_uid(&uid).await?;
+    if !user.enable {
+        return Err(AppError::ForbiddenError("user is disabled".to_string()));
+    }
+    user_dao
+        .update_password(&uid, &hashed_password, false)
+        .await?;
+    info!("User accepted invitation: {}", user.username);
+    mfa::complete_login(state, &user, client_info).await
+}
@@ -0,0 +1,369 @@
+use std::future::Future;
+
+use reqwest::{header, Response, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use tracing::info;
+
+use crate::{
+    configure::tracker::{ConfigTracker, ConfigTrackerItem},
+    entity::case::{IssueSource, TrackerIssue},
+    errors::{message::IssueException, AppError, AppResult, Resource, ResourceType},
+    utils::http::HttpClient,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewIssue {
+    pub title: String,
+    pub description: String,
+}
+
+pub trait IssueTracker {
+    fn source(&self) -> IssueSource;
+
+    /* returns NotFoundError(Issue) when the issue does not exist on the tracker */
+    fn get_issue(&self, issue_id: &str) -> impl Future<Output = AppResult<TrackerIssue>> + Send;
+
+    fn create_issue(
+        &self,
+        issue: &NewIssue,
+    ) -> impl Future<Output = AppResult<TrackerIssue>> + Send;
+}
+
+async fn parse_response<T: DeserializeOwned>(resp: Response, issue_id: &str) -> AppResult<T> {
+    if resp.status() == StatusCode::NOT_FOUND {
+        return Err(AppError::NotFoundError(Resource {
+            details: vec![("issue_id".to_string(), issue_id.to_string())],
+            resource_type: ResourceType::Issue,
+        }));
+    }
+    Ok(resp.error_for_status()?.json::<T>().await?)
+}
+
+/* issue_id ends up in the url path, only accept what the tracker can actually issue */
+fn check_issue_id(issue_id: &str, allowed: impl Fn(char) -> bool) -> AppResult {
+    if issue_id.is_empty() || !issue_id.chars().all(allowed) {
+        return Err(AppError::BadRequestError(
+            IssueException::InvalidIssueId.to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/* jira accepts both the issue key (PROJ-123) and the numeric id */
+fn check_jira_issue_id(issue_id: &str) -> AppResult {
+    check_issue_id(issue_id, |c| {
+        c.is_ascii_alphanumeric() || c == '-' || c == '_'
+    })
+}
+
+/* gitlab iid and github number are plain integers */
+fn check_numeric_issue_id(issue_id: &str) -> AppResult {
+    check_issue_id(issue_id, |c| c.is_ascii_digit())
+}
+
+fn trim_base_url(base_url: &str) -> String {
+    base_url.trim_end_matches('/').to_string()
+}
+
+#[derive(Debug, Clone)]
+pub struct JiraTracker {
+    http: HttpClient,
+    config: ConfigTrackerItem,
+}
+
+#[derive(Debug, Deserialize)]
+struct JiraIssue {
+    key: String,
+    fields: JiraIssueFields,
+}
+
+#[derive(Debug, Deserialize)]
+struct JiraIssueFields {
+    summary: String,
+    status: JiraIssueStatus,
+}
+
+#[derive(Debug, Deserialize)]
+struct JiraIssueStatus {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JiraCreatedIssue {
+    key: String,
+}
+
+impl JiraTracker {
+    pub fn new(http: HttpClient, config: ConfigTrackerItem) -> Self {
+        Self { http, config }
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("{}/rest/api/2/{path}", trim_base_url(&self.config.base_url))
+    }
+}
+
+impl IssueTracker for JiraTracker {
+    fn source(&self) -> IssueSource {
+        IssueSource::Jira
+    }
+
+    async fn get_issue(&self, issue_id: &str) -> AppResult<TrackerIssue> {
+        info!("jira tracker get issue: {issue_id}");
+        check_jira_issue_id(issue_id)?;
+        let resp = self
+            .http
+            .get(self.api_url(&format!("issue/{issue_id}?fields=summary,status")))
+            .basic_auth(&self.config.username, Some(&self.config.token))
+            .send()
+            .await?;
+        let issue: JiraIssue = parse_response(resp, issue_id).await?;
+        Ok(TrackerIssue {
+            uri: format!(
+                "{}/browse/{}",
+                trim_base_url(&self.config.base_url),
+                issue.key
+            ),
+            issue_id: issue.key,
+            title: issue.fields.summary,
+            status: issue.fields.status.name,
+        })
+    }
+
+    async fn create_issue(&self, issue: &NewIssue) -> AppResult<TrackerIssue> {
+        info!("jira tracker create issue: {issue:?}");
+        let body = json!({
+            "fields": {
+                "project": { "key": self.config.project },
+                "summary": issue.title,
+                "description": issue.description,
+                "issuetype": { "name": self.config.issue_type.as_deref().unwrap_or("Bug") },
+            }
+        });
+        let resp = self
+            .http
+            .post(self.api_url("issue"))
+            .basic_auth(&self.config.username, Some(&self.config.token))
+            .json(&body)
+            .send()
+            .await?;
+        let created: JiraCreatedIssue = parse_response(resp, &self.config.project).await?;
+        /* jira only returns the key on creation, fetch again for the workflow status */
+        self.get_issue(&created.key).await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GitLabTracker {
+    http: HttpClient,
+    config: ConfigTrackerItem,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabIssue {
+    iid: i64,
+    title: String,
+    state: String,
+    web_url: String,
+}
+
+impl From<GitLabIssue> for TrackerIssue {
+    fn from(issue: GitLabIssue) -> Self {
+        TrackerIssue {
+            issue_id: issue.iid.to_string(),
+            title: issue.title,
+            status: issue.state,
+            uri: issue.web_url,
+        }
+    }
+}
+
+impl GitLabTracker {
+    pub fn new(http: HttpClient, config: ConfigTrackerItem) -> Self {
+        Self { http, config }
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!(
+            "{}/api/v4/projects/{}/{path}",
+            trim_base_url(&self.config.base_url),
+            self.config.project
+        )
+    }
+}
+
+impl IssueTracker for GitLabTracker {
+    fn source(&self) -> IssueSource {
+        IssueSource::GitLab
+    }
+
+    async fn get_issue(&self, issue_id: &str) -> AppResult<TrackerIssue> {
+        info!("gitlab tracker get issue: {issue_id}");
+        check_numeric_issue_id(issue_id)?;
+        let resp = self
+            .http
+            .get(self.api_url(&format!("issues/{issue_id}")))
+            .header("PRIVATE-TOKEN", &self.config.token)
+            .send()
+            .await?;
+        let issue: GitLabIssue = parse_response(resp, issue_id).await?;
+        Ok(issue.into())
+    }
+
+    async fn create_issue(&self, issue: &NewIssue) -> AppResult<TrackerIssue> {
+        info!("gitlab tracker create issue: {issue:?}");
+        let body = json!({
+            "title": issue.title,
+            "description": issue.description,
+        });
+        let resp = self
+            .http
+            .post(self.api_url("issues"))
+            .header("PRIVATE-TOKEN", &self.config.token)
+            .json(&body)
+            .send()
+            .await?;
+        let issue: GitLabIssue = parse_response(resp, &self.config.project).await?;
+        Ok(issue.into())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GitHubTracker {
+    http: HttpClient,
+    config: ConfigTrackerItem,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubIssue {
+    number: i64,
+    title: String,
+    state: String,
+    html_url: String,
+}
+
+impl From<GitHubIssue> for TrackerIssue {
+    fn from(issue: GitHubIssue) -> Self {
+        TrackerIssue {
+            issue_id: issue.number.to_string(),
+            title: issue.title,
+            status: issue.state,
+            uri: issue.html_url,
+        }
+    }
+}
+
+impl GitHubTracker {
+    pub fn new(http: HttpClient, config: ConfigTrackerItem) -> Self {
+        Self { http, config }
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!(
+            "{}/repos/{}/{path}",
+            trim_base_url(&self.config.base_url),
+            self.config.project
+        )
+    }
+
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        /* github rejects requests without user-agent */
+        builder
+            .bearer_auth(&self.config.token)
+            .header(header::ACCEPT, "application/vnd.github+json")
+            .header(header::USER_AGENT, "meter")
+    }
+}
+
+impl IssueTracker for GitHubTracker {
+    fn source(&self) -> IssueSource {
+        IssueSource::GitHub
+    }
+
+    async fn get_issue(&self, issue_id: &str) -> AppResult<TrackerIssue> {
+        info!("github tracker get issue: {issue_id}");
+        check_numeric_issue_id(issue_id)?;
+        let resp = self
+            .request(self.http.get(self.api_url(&format!("issues/{issue_id}"))))
+            .send()
+            .await?;
+        let issue: GitHubIssue = parse_response(resp, issue_id).await?;
+        Ok(issue.into())
+    }
+
+    async fn create_issue(&self, issue: &NewIssue) -> AppResult<TrackerIssue> {
+        info!("github tracker create issue: {issue:?}");
+        let body = json!({
+            "title": issue.title,
+            "body": issue.description,
+        });
+        let resp = self
+            .request(self.http.post(self.api_url("issues")))
+            .json(&body)
+            .send()
+            .await?;
+        let issue: GitHubIssue = parse_response(resp, &self.config.project).await?;
+        Ok(issue.into())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Tracker {
+    Jira(JiraTracker),
+    GitLab(GitLabTracker),
+    GitHub(GitHubTracker),
+}
+
+impl Tracker {
+    pub fn new(source: IssueSource, config: &ConfigTracker, http: &HttpClient) -> AppResult<Self> {
+        let not_configured =
+            || AppError::BadRequestError(IssueException::TrackerNotConfigured.to_string());
+        let tracker = match source {
+            IssueSource::Jira => Tracker::Jira(JiraTracker::new(
+                http.clone(),
+                config.jira.clone().ok_or_else(not_configured)?,
+            )),
+            IssueSource::GitLab => Tracker::GitLab(GitLabTracker::new(
+                http.clone(),
+                config.gitlab.clone().ok_or_else(not_configured)?,
+            )),
+            IssueSource::GitHub => Tracker::GitHub(GitHubTracker::new(
+                http.clone(),
+                config.github.clone().ok_or_else(not_configured)?,
+            )),
+            IssueSource::Unknown => {
+                return Err(AppError::BadRequestError(
+                    IssueException::UnknownSource.to_string(),
+                ))
+            }
+        };
+        Ok(tracker)
+    }
+}
+
+impl IssueTracker for Tracker {
+    fn source(&self) -> IssueSource {
+        match self {
+            Self::Jira(t) => t.source(),
+            Self::GitLab(t) => t.source(),
+            Self::GitHub(t) => t.source(),
+        }
+    }
+
+    async fn get_issue(&self, issue_id: &str) -> AppResult<TrackerIssue> {
+        match self {
+            Self::Jira(t) => t.get_issue(issue_id).await,
+            Self::GitLab(t) => t.get_issue(issue_id).await,
+            Self::GitHub(t) => t.get_issue(issue_id).await,
+        }
+    }
+
+    async fn create_issue(&self, issue: &NewIssue) -> AppResult<TrackerIssue> {
+        match self {
+            Self::Jira(t) => t.create_issue(issue).await,
+            Self::GitLab(t) => t.create_issue(issue).await,
+            Self::GitHub(t) => t.create_issue(issue).await,
+        }
+    }
+}
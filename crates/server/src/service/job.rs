@@ -0,0 +1,301 @@
+use std::{
+    future::{self, Future},
+    time::Duration,
+};
+
+use chrono::Utc;
+use futures::{future::BoxFuture, FutureExt};
+use serde::Serialize;
+use tokio::sync::watch;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    constant::JOB_MAX_BACKOFF,
+    dao::job::JobDao,
+    dto::request::{CreateScriptRequest, DiagnoseRequest},
+    entity::job::{BackgroundJob, ClaimedJob, JobKind, JobStatus},
+    errors::{
+        message::{JobException, UserException},
+        AppError, AppResult,
+    },
+    service::{
+        case,
+        element::{self, HealthCheckJob},
+        invitation::{self, InvitationJob},
+        notification::{self, NotificationJob},
+        plan::{self, PlanRunJob},
+    },
+    state::AppState,
+};
+
+/* passed to the running job for progress reporting and cancellation */
+#[derive(Clone)]
+pub struct JobHandle {
+    state: AppState,
+    id: i32,
+}
+
+impl JobHandle {
+    /* fails with `AppError::JobCancelledError` once cancellation was requested */
+    pub async fn progress(&self, progress: i32) -> AppResult {
+        let client = self.state.pool.get().await?;
+        if JobDao::new(&client)
+            .update_progress(&self.id, progress)
+            .await?
+        {
+            return Err(AppError::JobCancelledError(
+                JobException::Cancelled.to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/* delay before the next attempt, `attempts` counts the runs so far */
+pub fn backoff(base: u64, attempts: i32) -> Duration {
+    let exp = (attempts - 1).clamp(0, 16) as u32;
+    Duration::from_secs(base.saturating_mul(1 << exp).min(JOB_MAX_BACKOFF))
+}
+
+pub async fn enqueue<P: Serialize>(
+    state: &AppState,
+    uid: Uuid,
+    kind: JobKind,
+    payload: &P,
+) -> AppResult<i32> {
+    info!("service layer enqueue job with kind: {kind:?}, created_by: {uid}");
+    let payload = serde_json::to_string(payload)?;
+    let client = state.pool.get().await?;
+    JobDao::new(&client)
+        .create(kind, &payload, state.config.job.max_attempts, &uid)
+        .await
+}
+
+pub async fn get_job(state: &AppState, uid: Uuid, job_id: i32) -> AppResult<BackgroundJob> {
+    info!("service layer get job with job_id: {job_id}");
+    let client = state.pool.get().await?;
+    let job = JobDao::new(&client).get_job_by_id(&job_id).await?;
+    if job.created_by != uid {
+        return Err(AppError::ForbiddenError(
+            UserException::Forbidden.to_string(),
+        ));
+    }
+    Ok(job)
+}
+
+/* pending jobs are cancelled right away, running ones at their next progress report */
+pub async fn cancel(state: &AppState, uid: Uuid, job_id: i32) -> AppResult {
+    info!("service layer cancel job with job_id: {job_id}");
+    let client = state.pool.get().await?;
+    let job_dao = JobDao::new(&client);
+    let job = job_dao.get_job_by_id(&job_id).await?;
+    if job.created_by != uid {
+        return Err(AppError::ForbiddenError(
+            UserException::Forbidden.to_string(),
+        ));
+    }
+    match job_dao.cancel(&job_id).await? {
+        Some(_) => Ok(()),
+        None => Err(AppError::BadRequestError(
+            JobException::NotCancellable.to_string(),
+        )),
+    }
+}
+
+/* hand jobs of crashed workers to the others, those asked to cancel are not run again */
+pub async fn requeue_stale_jobs(state: &AppState) -> AppResult {
+    let stale_after = chrono::Duration::seconds(state.config.job.stale_after as i64);
+    let client = state.pool.get().await?;
+    let requeued = JobDao::new(&client)
+        .requeue_stale(Utc::now() - stale_after)
+        .await?;
+    if requeued > 0 {
+        warn!("requeued {requeued} stale jobs");
+    }
+    Ok(())
+}
+
+async fn execute(state: &AppState, handle: &JobHandle, job: &ClaimedJob) -> AppResult<String> {
+    let result = match job.kind {
+        JobKind::GenerateScript => {
+            let request: CreateScriptRequest = serde_json::from_str(&job.payload)?;
+            let resp = case::gen_script(state, job.created_by, request, handle).await?;
+            serde_json::to_string(&resp)?
+        }
+        JobKind::Diagnose => {
+            let request: DiagnoseRequest = serde_json::from_str(&job.payload)?;
+            let resp = case::env_diagnose(state, request, handle).await?;
+            serde_json::to_string(&resp)?
+        }
+        JobKind::SendInvitation => {
+            let payload: InvitationJob = serde_json::from_str(&job.payload)?;
+            invitation::send_pending(state, payload.uuid).await?;
+            serde_json::to_string(&payload)?
+        }
+        JobKind::SendNotification => {
+            let payload: NotificationJob = serde_json::from_str(&job.payload)?;
+            notification::deliver(state, &payload).await?;
+            serde_json::to_string(&payload.target)?
+        }
+        JobKind::HealthCheck => {
+            let payload: HealthCheckJob = serde_json::from_str(&job.payload)?;
+            element::run_health_check(state, &payload).await?;
+            payload.check_id.to_string()
+        }
+        JobKind::PlanRun => {
+            let payload: PlanRunJob = serde_json::from_str(&job.payload)?;
+            plan::execute_run(state, &payload).await;
+            payload.run_id.to_string()
+        }
+        JobKind::Unknown => {
+            return Err(AppError::BadRequestError(
+                JobException::UnknownKind.to_string(),
+            ))
+        }
+    };
+    Ok(result)
+}
+
+/* keeps `locked_at` fresh while the job runs, so a long job is not requeued as stale */
+async fn heartbeat(state: &AppState, id: i32, worker: &str) {
+    let interval = Duration::from_secs((state.config.job.stale_after / 3).max(1));
+    loop {
+        tokio::time::sleep(interval).await;
+        let beat = async {
+            let client = state.pool.get().await?;
+            JobDao::new(&client).heartbeat(&id, worker).await
+        };
+        if let Err(e) = beat.await {
+            warn!("job worker {worker} failed to renew the lock of job {id}: {e:?}");
+        }
+    }
+}
+
+/* returns `false` if there was no due job */
+async fn work_once(state: &AppState, worker: &str) -> AppResult<bool> {
+    let job = {
+        let client = state.pool.get().await?;
+        JobDao::new(&client).claim(worker).await?
+    };
+    let Some(job) = job else {
+        return Ok(false);
+    };
+    info!(
+        "job worker {worker} claimed job {} of kind {:?}, attempt {}",
+        job.id, job.kind, job.attempts
+    );
+    let handle = JobHandle {
+        state: state.clone(),
+        id: job.id,
+    };
+    let outcome = tokio::select! {
+        outcome = execute(state, &handle, &job) => outcome,
+        _ = heartbeat(state, job.id, worker) => unreachable!("the heartbeat never stops"),
+    };
+
+    let client = state.pool.get().await?;
+    let job_dao = JobDao::new(&client);
+    let finished = match outcome {
+        Ok(result) => {
+            job_dao
+                .finish(&job.id, worker, JobStatus::Succeeded, Some(result), None)
+                .await?
+        }
+        Err(AppError::JobCancelledError(_)) => {
+            job_dao
+                .finish(&job.id, worker, JobStatus::Cancelled, None, None)
+                .await?
+        }
+        Err(e) => {
+            warn!("job {} failed on attempt {}: {e:?}", job.id, job.attempts);
+            let error = e.to_string();
+            /* client errors fail the same way on every attempt */
+            let retryable = e.response().0.is_server_error();
+            if retryable && job.attempts < job.max_attempts {
+                let delay = backoff(state.config.job.backoff, job.attempts);
+                let run_at = Utc::now() + chrono::Duration::from_std(delay).unwrap_or_default();
+                job_dao.retry(&job.id, worker, &error, run_at).await?
+                    || job_dao
+                        .finish(&job.id, worker, JobStatus::Cancelled, None, Some(error))
+                        .await?
+            } else {
+                job_dao
+                    .finish(&job.id, worker, JobStatus::Failed, None, Some(error))
+                    .await?
+            }
+        }
+    };
+    if !finished {
+        warn!(
+            "job {} was requeued while worker {worker} ran it, its outcome is dropped",
+            job.id
+        );
+    }
+    Ok(true)
+}
+
+async fn worker_loop(state: AppState, worker: String, mut shutdown: watch::Receiver<bool>) {
+    let idle = Duration::from_secs(state.config.job.poll_interval.max(1));
+    while !*shutdown.borrow() {
+        let claimed = match work_once(&state, &worker).await {
+            Ok(claimed) => claimed,
+            Err(e) => {
+                warn!("job worker {worker} failed: {e:?}");
+                false
+            }
+        };
+        if !claimed {
+            tokio::select! {
+                _ = tokio::time::sleep(idle) => {}
+                _ = shutdown.changed() => {}
+            }
+        }
+    }
+    info!("job worker {worker} stopped");
+}
+
+pub struct JobWorkerPool {
+    state: AppState,
+    shutdown: Option<BoxFuture<'static, ()>>,
+}
+
+impl JobWorkerPool {
+    pub fn new(state: AppState) -> Self {
+        Self {
+            state,
+            shutdown: None,
+        }
+    }
+
+    /* running jobs are finished before the pool stops */
+    pub fn with_shutdown(mut self, signal: impl Future<Output = ()> + Send + 'static) -> Self {
+        self.shutdown = Some(signal.boxed());
+        self
+    }
+
+    pub async fn run(self) -> AppResult {
+        let (sender, receiver) = watch::channel(false);
+        /* worker names only need to be unique across replicas */
+        let prefix = Uuid::new_v4().simple().to_string();
+        let workers = (0..self.state.config.job.workers)
+            .map(|i| {
+                tokio::spawn(worker_loop(
+                    self.state.clone(),
+                    format!("{prefix}-{i}"),
+                    receiver.clone(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        info!("started {} job workers", workers.len());
+        match self.shutdown {
+            Some(shutdown) => shutdown.await,
+            None => future::pending::<()>().await,
+        }
+        let _ = sender.send(true);
+        for worker in workers {
+            worker.await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    dao::{audit::AuditDao, user::UserDao},
+    dto::request::user::UnlockUserRequest,
+    entity::audit::AuditAction,
+    errors::{message::LoginException, AppError, AppResult},
+    service::redis::{self, LoginFailureKey},
+    state::AppState,
+    utils::header::ClientInfo,
+};
+
+fn user_key(username: &str) -> LoginFailureKey {
+    LoginFailureKey {
+        subject: format!("user:{}", username.to_lowercase()),
+    }
+}
+
+fn ip_key(ip: &str) -> LoginFailureKey {
+    LoginFailureKey {
+        subject: format!("ip:{ip}"),
+    }
+}
+
+fn too_many(exception: LoginException, retry_after: u64) -> AppError {
+    AppError::TooManyRequestsError(format!(
+        "{}, retry after {retry_after}s",
+        exception.to_string()
+    ))
+}
+
+/* rejects the attempt before the password is verified */
+pub async fn check(state: &AppState, username: &str, client_info: &ClientInfo) -> AppResult {
+    let config = &state.config.lockout;
+    if let Some(ip) = client_info.ip.as_deref() {
+        let key = ip_key(ip);
+        if redis::get(&state.redis, &key).await?.unwrap_or(0) >= config.ip_max_failures {
+            let ttl = redis::get_ttl(&state.redis, &key).await?.max(0) as u64;
+            return Err(too_many(LoginException::Locked, ttl));
+        }
+    }
+    let key = user_key(username);
+    let Some(failures) = redis::get(&state.redis, &key).await? else {
+        return Ok(());
+    };
+    let ttl = redis::get_ttl(&state.redis, &key).await?.max(0) as u64;
+    if failures >= config.max_failures {
+        return Err(too_many(LoginException::Locked, ttl));
+    }
+    /* the counter expiry is renewed on every failure, so it tells when the last one was */
+    let elapsed = config.window_secs.saturating_sub(ttl);
+    let backoff = config.backoff(failures);
+    if elapsed < backoff {
+        return Err(too_many(LoginException::Throttled, backoff - elapsed));
+    }
+    Ok(())
+}
+
+async fn count_failure(
+    state: &AppState,
+    key: &LoginFailureKey,
+    max_failures: i64,
+    client_info: &ClientInfo,
+) -> AppResult {
+    let config = &state.config.lockout;
+    let failures = redis::incr(&state.redis, key, config.window()).await?;
+    if failures < max_failures {
+        return Ok(());
+    }
+    redis::expire(&state.redis, key, config.lockout()).await?;
+    if failures == max_failures {
+        warn!("Lock out {} after {failures} failed logins", key.subject);
+        let client = state.pool.get().await?;
+        AuditDao::new(&client)
+            .create(
+                AuditAction::LoginLockout,
+                &key.subject,
+                Some(&format!("{failures} failed logins")),
+                client_info.ip.as_deref(),
+                None,
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/* unknown usernames are counted as well, so locking does not tell which ones exist */
+pub async fn record_failure(
+    state: &AppState,
+    username: &str,
+    client_info: &ClientInfo,
+) -> AppResult {
+    let config = &state.config.lockout;
+    if let Some(ip) = client_info.ip.as_deref() {
+        count_failure(state, &ip_key(ip), config.ip_max_failures, client_info).await?;
+    }
+    count_failure(state, &user_key(username), config.max_failures, client_info).await
+}
+
+pub async fn reset(state: &AppState, username: &str) -> AppResult {
+    redis::del(&state.redis, &user_key(username)).await?;
+    Ok(())
+}
+
+pub async fn unlock(
+    state: &AppState,
+    operator: Uuid,
+    request: UnlockUserRequest,
+    client_info: &ClientInfo,
+) -> AppResult {
+    info!("service layer unlock login with request: {request:?}");
+    let client = state.pool.get().await?;
+    let user_dao = UserDao::new(&client);
+    let audit_dao = AuditDao::new(&client);
+    for id in request.ids.iter() {
+        let user = user_dao.find_by_id(id).await?;
+        let key = user_key(&user.username);
+        if redis::del(&state.redis, &key).await? {
+            audit_dao
+                .create(
+                    AuditAction::LoginUnlock,
+                    &key.subject,
+                    None,
+                    client_info.ip.as_deref(),
+                    Some(operator),
+                )
+                .await?;
+        }
+    }
+    Ok(())
+}
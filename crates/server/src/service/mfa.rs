@@ -0,0 +1,379 @@
+use chrono::Utc;
+use rand::{Rng, RngCore};
+use totp_rs::{Algorithm, TOTP};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    constant::MFA_ISSUER,
+    dao::{audit::AuditDao, mfa::MfaDao, user::UserDao},
+    dto::{
+        request::user::{
+            MfaCodeRequest, MfaEnrollChallengeRequest, MfaLoginRequest, ResetMfaRequest,
+            UpdateRoleMfaRequest,
+        },
+        response::user::{LoginResponse, MfaEnrollResponse, RecoveryCodesResponse},
+    },
+    entity::{audit::AuditAction, mfa::UserMfa, user::User},
+    errors::{message::MfaException, AppError, AppResult, Resource, ResourceType},
+    service::{
+        api_token::{hash_token, random_secret},
+        lockout,
+        redis::{self, MfaChallengeKey},
+        session, token,
+    },
+    state::AppState,
+    utils::header::ClientInfo,
+};
+
+const STEP_SECS: u64 = 30;
+const RECOVERY_CODE_COUNT: usize = 10;
+
+fn totp(secret: &str, username: &str) -> AppResult<TOTP> {
+    let bytes = hex::decode(secret)
+        .map_err(|e| AppError::UnknownError(anyhow::anyhow!("invalid totp secret: {e}")))?;
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        0,
+        STEP_SECS,
+        bytes,
+        Some(MFA_ISSUER.to_string()),
+        username.to_string(),
+    )
+    .map_err(|e| AppError::UnknownError(anyhow::anyhow!("invalid totp secret: {e:?}")))
+}
+
+/* 160 bits in hex, the length recommended for sha1 totp */
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 20];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/* `xxxxx-xxxxx`, lowercase letters and digits without the look-alikes */
+fn generate_recovery_codes() -> Vec<String> {
+    const CHARSET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+    let mut rng = rand::thread_rng();
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let code: String = (0..10)
+                .map(|_| CHARSET[rng.gen_range(0..CHARSET.len())] as char)
+                .collect();
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+/* recovery codes are compared without case, dashes or spaces */
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn is_totp_code(code: &str) -> bool {
+    code.len() == 6 && code.chars().all(|c| c.is_ascii_digit())
+}
+
+/* one step of clock drift is accepted each way */
+fn matching_step(totp: &TOTP, code: &str, now: u64) -> Option<u64> {
+    let step = now / STEP_SECS;
+    [step.saturating_sub(1), step, step + 1]
+        .into_iter()
+        .find(|s| totp.generate(s * STEP_SECS) == code)
+}
+
+/* a code is used up once accepted, recovery codes only count after enrollment */
+async fn verify_code<T: db::GenericClient>(
+    dao: &MfaDao<'_, T>,
+    mfa: &UserMfa,
+    username: &str,
+    code: &str,
+) -> AppResult<bool> {
+    let code = code.trim();
+    if is_totp_code(code) {
+        let now = Utc::now().timestamp() as u64;
+        return match matching_step(&totp(&mfa.secret, username)?, code, now) {
+            Some(step) if step as i64 > mfa.last_step => dao.use_step(&mfa.uid, step as i64).await,
+            _ => Ok(false),
+        };
+    }
+    if !mfa.enabled {
+        return Ok(false);
+    }
+    dao.use_recovery_code(&mfa.uid, &hash_token(&normalize_recovery_code(code)))
+        .await
+}
+
+/* failed codes count towards the login lockout of the user */
+async fn verify<T: db::GenericClient>(
+    state: &AppState,
+    dao: &MfaDao<'_, T>,
+    mfa: &UserMfa,
+    user: &User,
+    code: &str,
+    client_info: &ClientInfo,
+) -> AppResult {
+    lockout::check(state, &user.username, client_info).await?;
+    if !verify_code(dao, mfa, &user.username, code).await? {
+        lockout::record_failure(state, &user.username, client_info).await?;
+        return Err(AppError::BadRequestError(
+            MfaException::InvalidCode.to_string(),
+        ));
+    }
+    lockout::reset(state, &user.username).await
+}
+
+async fn enroll_user<T: db::GenericClient>(
+    dao: &MfaDao<'_, T>,
+    user: &User,
+) -> AppResult<MfaEnrollResponse> {
+    let secret = generate_secret();
+    let recovery_codes = generate_recovery_codes();
+    let hashes = recovery_codes
+        .iter()
+        .map(|code| hash_token(&normalize_recovery_code(code)))
+        .collect::<Vec<_>>();
+    if !dao.enroll(&user.uuid, &secret, &hashes).await? {
+        return Err(AppError::BadRequestError(
+            MfaException::AlreadyEnabled.to_string(),
+        ));
+    }
+    info!("User started mfa enrollment: {}", user.username);
+    let totp = totp(&secret, &user.username)?;
+    Ok(MfaEnrollResponse {
+        secret: totp.get_secret_base32(),
+        otpauth_uri: totp.get_url(),
+        recovery_codes,
+    })
+}
+
+async fn create_challenge(state: &AppState, uid: Uuid) -> AppResult<String> {
+    let challenge = random_secret();
+    let key = MfaChallengeKey {
+        token_hash: hash_token(&challenge),
+    };
+    redis::set(&state.redis, (&key, &uid)).await?;
+    Ok(challenge)
+}
+
+async fn challenge_user(state: &AppState, challenge: &str) -> AppResult<(MfaChallengeKey, Uuid)> {
+    let key = MfaChallengeKey {
+        token_hash: hash_token(challenge),
+    };
+    let uid = redis::get(&state.redis, &key)
+        .await?
+        .ok_or_else(|| AppError::BadRequestError(MfaException::InvalidChallenge.to_string()))?;
+    Ok((key, uid))
+}
+
+/* last step of every login, the second factor is asked for before a session is created */
+pub async fn complete_login(
+    state: &AppState,
+    user: &User,
+    client_info: &ClientInfo,
+) -> AppResult<LoginResponse> {
+    let (enabled, required) = {
+        let client = state.pool.get().await?;
+        let dao = MfaDao::new(&client);
+        let enabled = dao.find(&user.uuid).await?.is_some_and(|mfa| mfa.enabled);
+        (enabled, !enabled && dao.is_required(&user.uuid).await?)
+    };
+    if enabled {
+        let challenge = create_challenge(state, user.uuid).await?;
+        return Ok(LoginResponse::MfaRequired { challenge });
+    }
+    if required {
+        let challenge = create_challenge(state, user.uuid).await?;
+        return Ok(LoginResponse::MfaEnrollmentRequired { challenge });
+    }
+    let session_id = session::start(&state.redis, user.uuid, client_info).await?;
+    let resp = token::generate_tokens(&state.redis, user.uuid, session_id).await?;
+    Ok(LoginResponse::Token(resp))
+}
+
+/* second step of the login, also confirms an enrollment started with the challenge */
+pub async fn login(
+    state: &AppState,
+    request: MfaLoginRequest,
+    client_info: &ClientInfo,
+) -> AppResult<LoginResponse> {
+    let (key, uid) = challenge_user(state, &request.challenge).await?;
+    let client = state.pool.get().await?;
+    let user = UserDao::new(&client).find_by_uid(&uid).await?;
+    if !user.enable {
+        return Err(AppError::ForbiddenError("user is disabled".to_string()));
+    }
+    let dao = MfaDao::new(&client);
+    let mfa = dao
+        .find(&uid)
+        .await?
+        .ok_or_else(|| AppError::BadRequestError(MfaException::NotEnrolled.to_string()))?;
+    verify(state, &dao, &mfa, &user, &request.code, client_info).await?;
+    /* whoever deletes the key first owns the challenge */
+    if !redis::del(&state.redis, &key).await? {
+        return Err(AppError::BadRequestError(
+            MfaException::InvalidChallenge.to_string(),
+        ));
+    }
+    if !mfa.enabled {
+        dao.enable(&uid).await?;
+        info!("User enabled mfa: {}", user.username);
+    }
+    let session_id = session::start(&state.redis, uid, client_info).await?;
+    let resp = token::generate_tokens(&state.redis, uid, session_id).await?;
+    Ok(LoginResponse::Token(resp))
+}
+
+/* enrollment of a user whose role requires mfa, before the first login */
+pub async fn enroll_by_challenge(
+    state: &AppState,
+    request: MfaEnrollChallengeRequest,
+) -> AppResult<MfaEnrollResponse> {
+    let (_, uid) = challenge_user(state, &request.challenge).await?;
+    let client = state.pool.get().await?;
+    let user = UserDao::new(&client).find_by_uid(&uid).await?;
+    enroll_user(&MfaDao::new(&client), &user).await
+}
+
+/* replaces an enrollment that was not confirmed yet */
+pub async fn enroll(state: &AppState, uid: Uuid) -> AppResult<MfaEnrollResponse> {
+    let client = state.pool.get().await?;
+    let user = UserDao::new(&client).find_by_uid(&uid).await?;
+    enroll_user(&MfaDao::new(&client), &user).await
+}
+
+pub async fn confirm(
+    state: &AppState,
+    uid: Uuid,
+    request: MfaCodeRequest,
+    client_info: &ClientInfo,
+) -> AppResult {
+    let client = state.pool.get().await?;
+    let user = UserDao::new(&client).find_by_uid(&uid).await?;
+    let dao = MfaDao::new(&client);
+    let mfa = match dao.find(&uid).await? {
+        Some(mfa) if mfa.enabled => {
+            return Err(AppError::BadRequestError(
+                MfaException::AlreadyEnabled.to_string(),
+            ))
+        }
+        Some(mfa) => mfa,
+        None => {
+            return Err(AppError::BadRequestError(
+                MfaException::NotEnrolled.to_string(),
+            ))
+        }
+    };
+    verify(state, &dao, &mfa, &user, &request.code, client_info).await?;
+    dao.enable(&uid).await?;
+    info!("User enabled mfa: {}", user.username);
+    Ok(())
+}
+
+async fn find_enabled<T: db::GenericClient>(dao: &MfaDao<'_, T>, uid: &Uuid) -> AppResult<UserMfa> {
+    dao.find(uid)
+        .await?
+        .filter(|mfa| mfa.enabled)
+        .ok_or_else(|| AppError::BadRequestError(MfaException::NotEnrolled.to_string()))
+}
+
+pub async fn disable(
+    state: &AppState,
+    uid: Uuid,
+    request: MfaCodeRequest,
+    client_info: &ClientInfo,
+) -> AppResult {
+    let client = state.pool.get().await?;
+    let user = UserDao::new(&client).find_by_uid(&uid).await?;
+    let dao = MfaDao::new(&client);
+    if dao.is_required(&uid).await? {
+        return Err(AppError::ForbiddenError(MfaException::Required.to_string()));
+    }
+    let mfa = find_enabled(&dao, &uid).await?;
+    verify(state, &dao, &mfa, &user, &request.code, client_info).await?;
+    dao.delete(&uid).await?;
+    info!("User disabled mfa: {}", user.username);
+    Ok(())
+}
+
+/* the previous recovery codes stop working */
+pub async fn regenerate_recovery_codes(
+    state: &AppState,
+    uid: Uuid,
+    request: MfaCodeRequest,
+    client_info: &ClientInfo,
+) -> AppResult<RecoveryCodesResponse> {
+    let client = state.pool.get().await?;
+    let user = UserDao::new(&client).find_by_uid(&uid).await?;
+    let dao = MfaDao::new(&client);
+    let mfa = find_enabled(&dao, &uid).await?;
+    verify(state, &dao, &mfa, &user, &request.code, client_info).await?;
+    let recovery_codes = generate_recovery_codes();
+    let hashes = recovery_codes
+        .iter()
+        .map(|code| hash_token(&normalize_recovery_code(code)))
+        .collect::<Vec<_>>();
+    dao.update_recovery_codes(&uid, &hashes).await?;
+    Ok(RecoveryCodesResponse { recovery_codes })
+}
+
+/* applies from the next login of the members, active sessions stay valid */
+pub async fn set_role_required(
+    state: &AppState,
+    operator: Uuid,
+    request: UpdateRoleMfaRequest,
+    client_info: &ClientInfo,
+) -> AppResult {
+    info!("service layer set role mfa policy with request: {request:?}");
+    let client = state.pool.get().await?;
+    if !MfaDao::new(&client)
+        .set_role_required(&request.role_id, request.required, &operator)
+        .await?
+    {
+        return Err(AppError::NotFoundError(Resource {
+            details: vec![("role_id".to_string(), request.role_id.to_string())],
+            resource_type: ResourceType::Role,
+        }));
+    }
+    AuditDao::new(&client)
+        .create(
+            AuditAction::RoleMfaRequired,
+            &format!("role:{}", request.role_id),
+            Some(&format!("required: {}", request.required)),
+            client_info.ip.as_deref(),
+            Some(operator),
+        )
+        .await?;
+    Ok(())
+}
+
+/* for users who lost their authenticator and recovery codes */
+pub async fn reset(
+    state: &AppState,
+    operator: Uuid,
+    request: ResetMfaRequest,
+    client_info: &ClientInfo,
+) -> AppResult {
+    info!("service layer reset mfa with request: {request:?}");
+    let client = state.pool.get().await?;
+    let user_dao = UserDao::new(&client);
+    let mfa_dao = MfaDao::new(&client);
+    let audit_dao = AuditDao::new(&client);
+    for id in request.ids.iter() {
+        let user = user_dao.find_by_id(id).await?;
+        mfa_dao.delete(&user.uuid).await?;
+        audit_dao
+            .create(
+                AuditAction::MfaReset,
+                &format!("user:{}", user.username),
+                None,
+                client_info.ip.as_deref(),
+                Some(operator),
+            )
+            .await?;
+    }
+    Ok(())
+}
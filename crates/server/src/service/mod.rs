@@ -1,12 +1,22 @@
+pub mod api_token;
 pub mod case;
 pub mod element;
 pub mod engine;
 pub mod file;
+pub mod invitation;
+pub mod issue;
+pub mod job;
+pub mod lockout;
+pub mod mfa;
+pub mod notification;
+pub mod page;
 pub mod permission;
 pub mod plan;
 pub mod project;
+pub mod requirement;
 mod redis;
 pub mod schedule;
 pub mod session;
+pub mod sso;
 pub mod token;
 pub mod user;
@@ -0,0 +1,496 @@
+use std::{future::Future, sync::Arc};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{header, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    dao::{job::JobDao, notification::NotificationDao, user::UserDao},
+    dto::{
+        request::user::{CreateNotificationChannelRequest, UpdateNotificationPreferenceRequest},
+        response::CreateEntityResponse,
+        EmailTemplate,
+    },
+    entity::{
+        job::JobKind,
+        notification::{ChannelType, Notification, NotificationChannel, NotificationPreference},
+    },
+    errors::{message::NotificationException, AppError, AppResult},
+    state::AppState,
+    utils::{
+        http::{check_public_url, HttpClient},
+        smtp::{self, EmailClient},
+    },
+};
+
+pub const SIGNATURE_HEADER: &str = "X-Notification-Signature";
+pub const EVENT_HEADER: &str = "X-Notification-Event";
+
+pub trait Notifier {
+    fn send(&self, notification: &Notification) -> impl Future<Output = AppResult> + Send;
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts keys of any size");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/* the chat apis answer 200 with an error code in the body */
+fn rejected(reason: String) -> AppError {
+    AppError::UnknownError(anyhow::anyhow!(
+        NotificationException::Rejected(reason).to_string()
+    ))
+}
+
+#[derive(Debug, Clone)]
+pub struct EmailNotifier {
+    client: Arc<EmailClient>,
+    username: String,
+    receiver: String,
+}
+
+impl EmailNotifier {
+    pub fn new(client: Arc<EmailClient>, username: &str, receiver: &str) -> Self {
+        Self {
+            client,
+            username: username.to_string(),
+            receiver: receiver.to_string(),
+        }
+    }
+}
+
+impl Notifier for EmailNotifier {
+    async fn send(&self, notification: &Notification) -> AppResult {
+        let template = EmailTemplate::Notification {
+            username: self.username.clone(),
+            title: notification.title.clone(),
+            content: notification.content.clone(),
+            link: notification.link.clone(),
+        };
+        smtp::send(&self.client, &template, &notification.title, &self.receiver).await
+    }
+}
+
+/* posts the notification as json, signed with hmac-sha256 of the body when a secret is set */
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    http: HttpClient,
+    url: String,
+    secret: Option<String>,
+}
+
+impl WebhookNotifier {
+    pub fn new(http: HttpClient, url: &str, secret: Option<&str>) -> Self {
+        Self {
+            http,
+            url: url.to_string(),
+            secret: secret.map(str::to_string),
+        }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    async fn send(&self, notification: &Notification) -> AppResult {
+        let body = serde_json::to_string(&json!({
+            "event_type": notification.event_type,
+            "title": notification.title,
+            "content": notification.content,
+            "link": notification.link,
+            "sent_at": Utc::now().timestamp(),
+        }))?;
+        let mut request = self
+            .http
+            .post(&self.url)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, notification.event_type.to_string());
+        if let Some(secret) = &self.secret {
+            let signature = hex::encode(hmac_sha256(secret.as_bytes(), body.as_bytes()));
+            request = request.header(SIGNATURE_HEADER, format!("sha256={signature}"));
+        }
+        request.body(body).send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SlackNotifier {
+    http: HttpClient,
+    url: String,
+}
+
+impl SlackNotifier {
+    pub fn new(http: HttpClient, url: &str) -> Self {
+        Self {
+            http,
+            url: url.to_string(),
+        }
+    }
+}
+
+impl Notifier for SlackNotifier {
+    async fn send(&self, notification: &Notification) -> AppResult {
+        self.http
+            .post(&self.url)
+            .json(&json!({ "text": notification.text() }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DingTalkResponse {
+    errcode: i64,
+    #[serde(default)]
+    errmsg: String,
+}
+
+/* robot of a DingTalk group, the signature goes into the query string */
+#[derive(Debug, Clone)]
+pub struct DingTalkNotifier {
+    http: HttpClient,
+    url: String,
+    secret: Option<String>,
+}
+
+impl DingTalkNotifier {
+    pub fn new(http: HttpClient, url: &str, secret: Option<&str>) -> Self {
+        Self {
+            http,
+            url: url.to_string(),
+            secret: secret.map(str::to_string),
+        }
+    }
+
+    pub fn signed_url(&self, timestamp: i64) -> AppResult<Url> {
+        let mut url = Url::parse(&self.url)
+            .map_err(|e| AppError::BadRequestError(format!("invalid webhook url: {e}")))?;
+        if let Some(secret) = &self.secret {
+            let string_to_sign = format!("{timestamp}\n{secret}");
+            let sign = STANDARD.encode(hmac_sha256(secret.as_bytes(), string_to_sign.as_bytes()));
+            url.query_pairs_mut()
+                .append_pair("timestamp", &timestamp.to_string())
+                .append_pair("sign", &sign);
+        }
+        Ok(url)
+    }
+}
+
+impl Notifier for DingTalkNotifier {
+    async fn send(&self, notification: &Notification) -> AppResult {
+        let url = self.signed_url(Utc::now().timestamp_millis())?;
+        let resp = self
+            .http
+            .post(url)
+            .json(&json!({
+                "msgtype": "text",
+                "text": { "content": notification.text() },
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DingTalkResponse>()
+            .await?;
+        if resp.errcode != 0 {
+            return Err(rejected(format!("{} {}", resp.errcode, resp.errmsg)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FeishuResponse {
+    #[serde(default)]
+    code: i64,
+    #[serde(default)]
+    msg: String,
+}
+
+/* custom bot of a Feishu group, the signature goes into the body */
+#[derive(Debug, Clone)]
+pub struct FeishuNotifier {
+    http: HttpClient,
+    url: String,
+    secret: Option<String>,
+}
+
+impl FeishuNotifier {
+    pub fn new(http: HttpClient, url: &str, secret: Option<&str>) -> Self {
+        Self {
+            http,
+            url: url.to_string(),
+            secret: secret.map(str::to_string),
+        }
+    }
+
+    pub fn body(&self, notification: &Notification, timestamp: i64) -> serde_json::Value {
+        let mut body = json!({
+            "msg_type": "text",
+            "content": { "text": notification.text() },
+        });
+        if let Some(secret) = &self.secret {
+            /* the key is the string to sign, the message is empty */
+            let string_to_sign = format!("{timestamp}\n{secret}");
+            body["timestamp"] = json!(timestamp.to_string());
+            body["sign"] = json!(STANDARD.encode(hmac_sha256(string_to_sign.as_bytes(), b"")));
+        }
+        body
+    }
+}
+
+impl Notifier for FeishuNotifier {
+    async fn send(&self, notification: &Notification) -> AppResult {
+        let resp = self
+            .http
+            .post(&self.url)
+            .json(&self.body(notification, Utc::now().timestamp()))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<FeishuResponse>()
+            .await?;
+        if resp.code != 0 {
+            return Err(rejected(format!("{} {}", resp.code, resp.msg)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Channel {
+    Email(EmailNotifier),
+    Webhook(WebhookNotifier),
+    Slack(SlackNotifier),
+    DingTalk(DingTalkNotifier),
+    Feishu(FeishuNotifier),
+}
+
+impl Channel {
+    pub fn new(channel: &NotificationChannel, http: &HttpClient) -> AppResult<Self> {
+        let secret = channel.secret.as_deref();
+        let channel = match channel.channel_type {
+            ChannelType::Webhook => {
+                Channel::Webhook(WebhookNotifier::new(http.clone(), &channel.url, secret))
+            }
+            ChannelType::Slack => Channel::Slack(SlackNotifier::new(http.clone(), &channel.url)),
+            ChannelType::DingTalk => {
+                Channel::DingTalk(DingTalkNotifier::new(http.clone(), &channel.url, secret))
+            }
+            ChannelType::Feishu => {
+                Channel::Feishu(FeishuNotifier::new(http.clone(), &channel.url, secret))
+            }
+            ChannelType::Unknown => {
+                return Err(AppError::BadRequestError(
+                    NotificationException::UnknownChannelType.to_string(),
+                ))
+            }
+        };
+        Ok(channel)
+    }
+}
+
+impl Notifier for Channel {
+    async fn send(&self, notification: &Notification) -> AppResult {
+        match self {
+            Self::Email(n) => n.send(notification).await,
+            Self::Webhook(n) => n.send(notification).await,
+            Self::Slack(n) => n.send(notification).await,
+            Self::DingTalk(n) => n.send(notification).await,
+            Self::Feishu(n) => n.send(notification).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NotificationTarget {
+    Email,
+    Channel(i32),
+}
+
+/* payload of `JobKind::SendNotification`, one job per recipient and channel so a retry never repeats the others */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationJob {
+    pub uuid: Uuid,
+    pub target: NotificationTarget,
+    pub notification: Notification,
+}
+
+pub fn targets(preference: &NotificationPreference) -> Vec<NotificationTarget> {
+    let mut targets = vec![];
+    if preference.email {
+        targets.push(NotificationTarget::Email);
+    }
+    targets.extend(
+        preference
+            .channel_ids
+            .iter()
+            .map(|id| NotificationTarget::Channel(*id)),
+    );
+    targets
+}
+
+/* queues the notification on the channels each recipient chose for the event */
+pub async fn notify(
+    state: &AppState,
+    recipients: &[Uuid],
+    notification: &Notification,
+) -> AppResult {
+    info!(
+        "service layer notify {:?} to {} recipients",
+        notification.event_type,
+        recipients.len()
+    );
+    let client = state.pool.get().await?;
+    let notification_dao = NotificationDao::new(&client);
+    let job_dao = JobDao::new(&client);
+    let mut recipients = recipients.to_vec();
+    recipients.sort();
+    recipients.dedup();
+    for uid in recipients.iter() {
+        let preference = notification_dao
+            .get_preference(uid, notification.event_type)
+            .await?;
+        for target in targets(&preference) {
+            let payload = serde_json::to_string(&NotificationJob {
+                uuid: *uid,
+                target,
+                notification: notification.clone(),
+            })?;
+            job_dao
+                .create(
+                    JobKind::SendNotification,
+                    &payload,
+                    state.config.job.max_attempts,
+                    uid,
+                )
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/* run by the job worker, a channel deleted in the meantime is skipped */
+pub async fn deliver(state: &AppState, job: &NotificationJob) -> AppResult {
+    let channel = {
+        let client = state.pool.get().await?;
+        match job.target {
+            NotificationTarget::Email => {
+                let user = UserDao::new(&client).find_by_uid(&job.uuid).await?;
+                Channel::Email(EmailNotifier::new(
+                    state.email.clone(),
+                    &user.username,
+                    &user.email,
+                ))
+            }
+            NotificationTarget::Channel(id) => {
+                match NotificationDao::new(&client)
+                    .get_channel(&id, &job.uuid)
+                    .await
+                {
+                    Ok(channel) => {
+                        /* the host may resolve elsewhere since the channel was created */
+                        check_public_url(&channel.url).await?;
+                        Channel::new(&channel, &state.public_http)?
+                    }
+                    Err(AppError::NotFoundError(_)) => {
+                        warn!("skip notification to deleted channel {id}");
+                        return Ok(());
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    };
+    channel.send(&job.notification).await
+}
+
+pub async fn list_preferences(
+    state: &AppState,
+    uid: Uuid,
+) -> AppResult<Vec<NotificationPreference>> {
+    let client = state.pool.get().await?;
+    NotificationDao::new(&client).get_preferences(&uid).await
+}
+
+pub async fn update_preference(
+    state: &AppState,
+    uid: Uuid,
+    request: UpdateNotificationPreferenceRequest,
+) -> AppResult {
+    info!("service layer update notification preference with request: {request:?}");
+    let client = state.pool.get().await?;
+    let notification_dao = NotificationDao::new(&client);
+    let mut channel_ids = request.channel_ids;
+    channel_ids.sort();
+    channel_ids.dedup();
+    for id in channel_ids.iter() {
+        if let Err(AppError::NotFoundError(_)) = notification_dao.get_channel(id, &uid).await {
+            return Err(AppError::BadRequestError(
+                NotificationException::ChannelNotFound.to_string(),
+            ));
+        }
+    }
+    notification_dao
+        .save_preference(
+            &uid,
+            &NotificationPreference {
+                event_type: request.event_type,
+                email: request.email,
+                channel_ids,
+            },
+        )
+        .await
+}
+
+pub async fn list_channels(state: &AppState, uid: Uuid) -> AppResult<Vec<NotificationChannel>> {
+    let client = state.pool.get().await?;
+    NotificationDao::new(&client).get_channels(&uid).await
+}
+
+pub async fn create_channel(
+    state: &AppState,
+    uid: Uuid,
+    request: CreateNotificationChannelRequest,
+) -> AppResult<CreateEntityResponse> {
+    info!(
+        "service layer create notification channel: {} of type {:?}",
+        request.name, request.channel_type
+    );
+    if request.channel_type == ChannelType::Unknown {
+        return Err(AppError::BadRequestError(
+            NotificationException::UnknownChannelType.to_string(),
+        ));
+    }
+    check_public_url(&request.url).await?;
+    let client = state.pool.get().await?;
+    let id = NotificationDao::new(&client)
+        .create_channel(
+            &uid,
+            &request.name,
+            request.channel_type,
+            &request.url,
+            request.secret.as_deref(),
+        )
+        .await?;
+    Ok(CreateEntityResponse { id })
+}
+
+pub async fn delete_channel(state: &AppState, uid: Uuid, id: i32) -> AppResult {
+    info!("service layer delete notification channel: {id}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    NotificationDao::new(&transaction)
+        .delete_channel(&id, &uid)
+        .await?;
+    transaction.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use crate::{
+    constant::{PAGE_DECODE_KEY, PAGE_ENCODE_KEY},
+    dao::{file::FileDao, PageCursor},
+    dto::request::ListQueryParam,
+    errors::{message::PageException, AppError, AppResult, ResourceType},
+    service::project::check_owner,
+    state::AppState,
+    utils::{
+        claim::{PageClaims, PageFilter, PageKind},
+        parse_ids,
+    },
+};
+
+const DEFAULT_PAGE_SIZE: i64 = 10;
+const MAX_PAGE_SIZE: i64 = 100;
+
+impl PageKind {
+    fn module_type(&self) -> &'static str {
+        match self {
+            PageKind::Case => "CASE",
+            PageKind::Plan => "PLAN",
+            PageKind::Element => "ELEMENT",
+        }
+    }
+}
+
+fn invalid_token() -> AppError {
+    AppError::BadRequestError(PageException::InvalidToken.to_string())
+}
+
+/* page of the request, with a token the filter params of the request are ignored */
+pub async fn resolve<T, C>(
+    state: &AppState,
+    executor: &T,
+    cursor: &C,
+    kind: PageKind,
+    project_id: i32,
+    param: &ListQueryParam,
+) -> AppResult<PageClaims>
+where
+    T: db::GenericClient,
+    C: PageCursor,
+{
+    if let Some(page_token) = &param.page_token {
+        let page = PageClaims::decode(page_token, &PAGE_DECODE_KEY)
+            .map_err(|_| invalid_token())?
+            .claims;
+        /* module ids were checked against the project the token was issued for */
+        if page.filter.kind != kind || page.filter.project_id != project_id {
+            return Err(invalid_token());
+        }
+        return Ok(page);
+    }
+    let page_size = param
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let page_num = param.page_num.unwrap_or(1).max(1);
+    let module_ids = match &param.module_ids {
+        Some(ids) => {
+            let ids = parse_ids(ids)?;
+            for id in ids.iter() {
+                check_owner(executor, ResourceType::Module, *id, project_id).await?;
+            }
+            ids
+        }
+        None => {
+            FileDao::new(executor)
+                .get_all_module_id(&project_id, kind.module_type())
+                .await?
+        }
+    };
+    let filter = PageFilter {
+        kind,
+        project_id,
+        module_ids,
+        deleted: param.deleted.unwrap_or(false),
+    };
+    /* the page starts after the last item of the previous one, past the end it is empty */
+    let offset = (page_num - 1) * page_size;
+    let after = if offset > 0 {
+        cursor
+            .get_query_cursor(&filter, offset - 1)
+            .await?
+            .unwrap_or(i32::MAX)
+    } else {
+        0
+    };
+    Ok(PageClaims::new(
+        state.config.page.token_expire(),
+        filter,
+        page_size,
+        page_num,
+        after,
+    ))
+}
+
+/* empty once a page comes back short, there is nothing after it */
+pub fn next_page_token(
+    state: &AppState,
+    page: PageClaims,
+    last_id: Option<i32>,
+    count: usize,
+) -> AppResult<String> {
+    let Some(last_id) = last_id.filter(|_| count as i64 >= page.page_size) else {
+        return Ok(String::new());
+    };
+    let next = PageClaims::new(
+        state.config.page.token_expire(),
+        page.filter,
+        page.page_size,
+        page.page_num + 1,
+        last_id,
+    );
+    Ok(next.encode(&PAGE_ENCODE_KEY)?)
+}
@@ -1,11 +1,34 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use tracing::info;
+
 use crate::{
-    dao::{permission::PermissionDao, user::UserDao},
-    entity::{permission::Permission, user::UserRolePermission},
+    api,
+    constant::{MANAGEMENT_PREFIX, PROJECT_ADMIN_ROLE},
+    dao::{permission::PermissionDao, project::ProjectDao, user::UserDao},
+    dto::response::UpdateRoleResponse,
+    entity::{
+        api_token::ApiTokenScope,
+        permission::{Access, ApiPermission, Permission, UserPermission},
+        project::ProjectModule,
+        user::UserRolePermission,
+    },
     errors::AppResult,
+    service::redis::{self, ApiPermissionKey, UserPermissionKey},
     state::AppState,
 };
 use uuid::Uuid;
 
+/* permissions to grant and revoke to turn `current` into `desired` */
+pub fn diff_permissions(current: &[i32], desired: &[i32]) -> UpdateRoleResponse {
+    let current = current.iter().copied().collect::<BTreeSet<_>>();
+    let desired = desired.iter().copied().collect::<BTreeSet<_>>();
+    UpdateRoleResponse {
+        added: desired.difference(&current).copied().collect(),
+        removed: current.difference(&desired).copied().collect(),
+    }
+}
+
 #[allow(dead_code)]
 pub async fn get_role_permission(state: &AppState, role_id: i32) -> AppResult<Vec<Permission>> {
     let client = state.pool.get().await?;
@@ -21,31 +44,235 @@ pub async fn get_role_permission_list(state: &AppState) -> AppResult<Vec<UserRol
     Ok(perm_dao.get_permission_group_by_role().await?)
 }
 
-pub async fn check_user_permission(
+async fn load_user_permission(
     state: &AppState,
     uid: &Uuid,
     project_id: &i32,
-    uri: &str,
-    method: &str,
-) -> AppResult<bool> {
+) -> AppResult<UserPermission> {
     let client = state.pool.get().await?;
     let user_dao = UserDao::new(&client);
     /* 判断当前用户是否处于可用状态 */
     let user = user_dao.find_by_uid(uid).await?;
     if !user.enable {
-        return Ok(false);
+        return Ok(UserPermission {
+            enable: false,
+            permissions: vec![],
+            archived: false,
+            modules: None,
+        });
     }
+    let project = ProjectDao::new(&client).find_by_id(*project_id).await?;
+    let modules = project
+        .module_setting
+        .map(|s| serde_json::from_str::<Vec<String>>(&s))
+        .transpose()?;
     let perm_dao = PermissionDao::new(&client);
     /* 获取当前用戶的角色及对应的权限 */
     let role = user_dao
         .get_role_by_uuid_and_project_id(uid, project_id)
         .await?;
-    let role_permission_list = perm_dao.get_permission_by_role_id(role.id).await?;
+    let permissions = perm_dao
+        .get_permission_by_role_id(role.id)
+        .await?
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+    Ok(UserPermission {
+        enable: true,
+        permissions,
+        archived: !project.enable,
+        modules,
+    })
+}
+
+async fn load_api_permission(state: &AppState, uri: &str, method: &str) -> AppResult<Vec<i32>> {
+    let client = state.pool.get().await?;
+    let perm_dao = PermissionDao::new(&client);
+    let permissions = perm_dao
+        .get_permission_by_api(uri, method)
+        .await?
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+    Ok(permissions)
+}
+
+/* served from redis with a single round trip, database is only hit on cache miss */
+pub async fn check_user_permission(
+    state: &AppState,
+    uid: &Uuid,
+    project_id: &i32,
+    uri: &str,
+    method: &str,
+) -> AppResult<bool> {
+    let user_key = UserPermissionKey {
+        uuid: *uid,
+        project_id: *project_id,
+    };
+    let api_key = ApiPermissionKey {
+        method: method.to_string(),
+        uri: uri.to_string(),
+    };
+    let (user_permission, api_permission) =
+        redis::get_pair(&state.redis, &user_key, &api_key).await?;
+    let user_permission = match user_permission {
+        Some(p) => p,
+        None => {
+            let p = load_user_permission(state, uid, project_id).await?;
+            redis::set(&state.redis, (&user_key, &p)).await?;
+            p
+        }
+    };
+    if !user_permission.enable {
+        return Ok(false);
+    }
+    /* disabled modules are rejected, archived projects are read only */
+    if let Some(Access::Require { module, scope }) = api::access(method, uri) {
+        if let (Some(project_module), Some(enabled)) =
+            (ProjectModule::of(module), &user_permission.modules)
+        {
+            if !enabled.contains(&project_module.to_string()) {
+                return Ok(false);
+            }
+        }
+        if user_permission.archived && scope == "WRITE" && module.starts_with(MANAGEMENT_PREFIX) {
+            return Ok(false);
+        }
+    }
     /* 查询请求API所需的权限列表 */
-    let api_permission_list = perm_dao.get_permission_by_api(uri, method).await?;
-    let enable = api_permission_list
+    let api_permission = match api_permission {
+        Some(p) => p,
+        None => {
+            let p = load_api_permission(state, uri, method).await?;
+            redis::set(&state.redis, (&api_key, &p)).await?;
+            p
+        }
+    };
+    let enable = api_permission
         .iter()
-        .all(|item| role_permission_list.contains(item));
+        .all(|item| user_permission.permissions.contains(item));
 
     Ok(enable)
 }
+
+/* on top of the owner's permissions, a token is limited to its project and permissions */
+pub async fn check_api_token_scope(
+    state: &AppState,
+    scope: &ApiTokenScope,
+    project_id: &i32,
+    uri: &str,
+    method: &str,
+) -> AppResult<bool> {
+    if scope.project_id.is_some_and(|id| id != *project_id) {
+        return Ok(false);
+    }
+    let api_key = ApiPermissionKey {
+        method: method.to_string(),
+        uri: uri.to_string(),
+    };
+    let api_permission = match redis::get(&state.redis, &api_key).await? {
+        Some(p) => p,
+        None => {
+            let p = load_api_permission(state, uri, method).await?;
+            redis::set(&state.redis, (&api_key, &p)).await?;
+            p
+        }
+    };
+    /* routes open to every member are out of reach */
+    Ok(!api_permission.is_empty()
+        && api_permission
+            .iter()
+            .all(|item| scope.permissions.contains(item)))
+}
+
+/* call after commit, otherwise a concurrent check may cache the old permissions again */
+pub async fn invalidate_member(state: &AppState, uid: &Uuid, project_id: &i32) -> AppResult {
+    let key = UserPermissionKey {
+        uuid: *uid,
+        project_id: *project_id,
+    };
+    redis::del(&state.redis, &key).await?;
+    Ok(())
+}
+
+pub async fn invalidate_user(state: &AppState, uid: &Uuid) -> AppResult {
+    let project_ids = {
+        let client = state.pool.get().await?;
+        UserDao::new(&client).get_project_ids_by_uuid(uid).await?
+    };
+    for project_id in project_ids.iter() {
+        invalidate_member(state, uid, project_id).await?;
+    }
+    Ok(())
+}
+
+pub async fn invalidate_project(state: &AppState, project_id: &i32) -> AppResult {
+    let members = {
+        let client = state.pool.get().await?;
+        ProjectDao::new(&client)
+            .get_member_uuids(project_id)
+            .await?
+    };
+    for uid in members.iter() {
+        invalidate_member(state, uid, project_id).await?;
+    }
+    Ok(())
+}
+
+pub async fn invalidate_role(state: &AppState, role_id: i32) -> AppResult {
+    let members = {
+        let client = state.pool.get().await?;
+        UserDao::new(&client)
+            .get_members_by_role_id(role_id)
+            .await?
+    };
+    for (uid, project_id) in members.iter() {
+        invalidate_member(state, uid, project_id).await?;
+    }
+    Ok(())
+}
+
+/* new permissions are granted to the internal roles so they keep full access, the internal project admin only gets the management ones */
+pub async fn sync_api_permissions(state: &AppState, permissions: &[ApiPermission]) -> AppResult {
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let perm_dao = PermissionDao::new(&transaction);
+    let mut ids = BTreeMap::new();
+    let mut relations = vec![];
+    for api in permissions.iter() {
+        let Access::Require { module, scope } = api.access else {
+            continue;
+        };
+        let id = match ids.get(&(module, scope)) {
+            Some(&id) => id,
+            None => {
+                let (id, created) = perm_dao.insert_permission(module, scope).await?;
+                if created {
+                    info!("created permission {module} {scope}");
+                    perm_dao
+                        .grant_internal_role_permission(id, PROJECT_ADMIN_ROLE)
+                        .await?;
+                    if module.starts_with(MANAGEMENT_PREFIX) {
+                        perm_dao
+                            .grant_role_permission_by_name(PROJECT_ADMIN_ROLE, id)
+                            .await?;
+                    }
+                }
+                ids.insert((module, scope), id);
+                id
+            }
+        };
+        relations.push((api.path.as_str(), api.method, id));
+    }
+    perm_dao.replace_api_permission_relation(&relations).await?;
+    transaction.commit().await?;
+    for api in permissions.iter() {
+        let key = ApiPermissionKey {
+            method: api.method.to_string(),
+            uri: api.path.clone(),
+        };
+        redis::del(&state.redis, &key).await?;
+    }
+    info!("synced {} api permissions", relations.len());
+    Ok(())
+}
@@ -1,25 +1,51 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
-use tracing::info;
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use tokio_postgres::error::SqlState;
+use tracing::{info, warn};
 use uuid::Uuid;
 
 use crate::{
-    dao::{file::FileDao, plan::PlanDao},
+    dao::{case::CaseDao, job::JobDao, plan::PlanDao},
     dto::{
-        request::{CreatePlanRequest, ListQueryParam, PlanQueryParam},
+        request::{
+            CreatePlanRequest, ListQueryParam, PlanQueryParam, PlanRunQueryParam,
+            SaveScheduleRequest,
+        },
         response::ListPlanResponse,
     },
-    entity::project::Plan,
-    errors::AppResult,
-    service::token::{generate_page_token, parse_page_token},
+    entity::{
+        case::CaseResult,
+        job::JobKind,
+        notification::{Notification, NotificationEvent},
+        plan::{PlanRun, PlanSchedule, PlanScript, RunStatus, RunTrigger},
+        project::Plan,
+    },
+    errors::{message::PlanException, AppError, AppResult, ResourceType},
+    service::{
+        engine, notification, page,
+        project::{check_owner, check_project},
+    },
     state::AppState,
-    utils::{claim::PageClaims, parse_ids},
+    utils::claim::PageKind,
 };
 
-pub async fn create(state: &AppState, uid: Uuid, request: CreatePlanRequest) -> AppResult {
+fn parse_cron(cron: &str) -> AppResult<Schedule> {
+    Schedule::from_str(cron)
+        .map_err(|_| AppError::BadRequestError(PlanException::InvalidCron.to_string()))
+}
+
+pub async fn create(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: CreatePlanRequest,
+) -> AppResult {
     info!("service layer create plan with request_body: {request:?} created_by: {uid}");
-    let mut client = state.pool.get().await?;
-    let plan_dao = PlanDao::new(&mut client);
+    let client = state.pool.get().await?;
+    let plan_dao = PlanDao::new(&client);
     let plan = Plan::new(
         &request.name,
         request.project_id,
@@ -29,6 +55,8 @@ pub async fn create(state: &AppState, uid: Uuid, request: CreatePlanRequest) ->
         request.start_date,
         request.end_date,
     );
+    check_project(plan.project_id, project_id)?;
+    check_owner(&client, ResourceType::Module, plan.module_id, project_id).await?;
     let _plan_id = plan_dao.create(plan).await?;
     Ok(())
 }
@@ -58,49 +86,380 @@ pub async fn get_plan_list(
     let mut client = state.pool.get().await?;
     let transaction = client.transaction().await?;
     let plan_dao = PlanDao::new(&transaction);
-    let page_claims = match param.page_token {
-        Some(page_token) => parse_page_token(page_token)?,
-        None => {
-            let page_size = param.page_size.unwrap_or(10).clamp(1, 100);
-            let page_num = param.page_num.unwrap_or(1).max(1);
-            let offset = (page_num - 1) * page_size;
-            let last_item_id = if offset > 0 {
-                plan_dao.get_query_cursor(offset).await?
-            } else {
-                0
-            };
-            let module_ids = match param.module_ids {
-                Some(ids) => parse_ids(&ids)?,
-                None => {
-                    let file_dao = FileDao::new(&transaction);
-                    file_dao
-                        .get_all_module_id(project_id, "PLAN".into())
-                        .await?
-                }
-            };
-            PageClaims::new(page_size, page_num, last_item_id, module_ids)
-        }
-    };
+    let page = page::resolve(
+        state,
+        &transaction,
+        &plan_dao,
+        PageKind::Plan,
+        *project_id,
+        &param,
+    )
+    .await?;
     let list = plan_dao
-        .get_plan_list(
-            &page_claims.module_ids,
-            &page_claims.page_size,
-            &page_claims.last_item_id,
-        )
+        .get_plan_list(&page.filter.module_ids, &page.page_size, &page.after)
         .await?;
-    let next_cursor = match list.last() {
-        Some(l) => l.id,
-        None => 0,
-    };
-    let next_page_token = generate_page_token(
-        page_claims.page_size,
-        page_claims.page_num + 1,
-        next_cursor,
-        page_claims.module_ids,
-    )?;
+    let next_page_token =
+        page::next_page_token(state, page, list.last().map(|plan| plan.id), list.len())?;
     transaction.commit().await?;
     Ok(ListPlanResponse {
         next_page_token,
         list,
     })
 }
+
+pub async fn get_schedule(
+    state: &AppState,
+    project_id: i32,
+    plan_id: i32,
+) -> AppResult<PlanSchedule> {
+    info!("service layer get schedule with plan_id: {plan_id}");
+    let client = state.pool.get().await?;
+    let plan_dao = PlanDao::new(&client);
+    let plan = plan_dao.get_plan_by_id(&plan_id).await?;
+    check_project(plan.project_id, project_id)?;
+    plan_dao.get_schedule_by_plan_id(&plan_id).await
+}
+
+pub async fn save_schedule(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    plan_id: i32,
+    request: SaveScheduleRequest,
+) -> AppResult<i32> {
+    info!("service layer save schedule with plan_id: {plan_id}, request: {request:?}");
+    parse_cron(&request.cron)?;
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let plan_dao = PlanDao::new(&transaction);
+    let plan = plan_dao.get_plan_by_id(&plan_id).await?;
+    check_project(plan.project_id, project_id)?;
+    CaseDao::new(&transaction)
+        .get_machine(&request.machine_id)
+        .await?;
+    if let Some(script_id) = request.script_id {
+        plan_dao.get_plan_script_by_id(&plan_id, &script_id).await?;
+    }
+    let schedule_id = plan_dao
+        .upsert_schedule(
+            &plan_id,
+            request.script_id,
+            &request.cron,
+            &request.machine_id,
+            request.enabled,
+            &uid,
+        )
+        .await?;
+    transaction.commit().await?;
+    Ok(schedule_id)
+}
+
+pub async fn delete_schedule(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    plan_id: i32,
+) -> AppResult {
+    info!("service layer delete schedule with plan_id: {plan_id}");
+    let client = state.pool.get().await?;
+    let plan_dao = PlanDao::new(&client);
+    let plan = plan_dao.get_plan_by_id(&plan_id).await?;
+    check_project(plan.project_id, project_id)?;
+    plan_dao.get_schedule_by_plan_id(&plan_id).await?;
+    plan_dao.soft_delete_schedule(&plan_id, &uid).await
+}
+
+/* run the schedule of plan right away, regardless of cron and enabled */
+pub async fn run_now(state: &AppState, uid: Uuid, project_id: i32, plan_id: i32) -> AppResult<i32> {
+    info!("service layer run plan schedule now with plan_id: {plan_id}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let plan_dao = PlanDao::new(&transaction);
+    let plan = plan_dao.get_plan_by_id(&plan_id).await?;
+    check_project(plan.project_id, project_id)?;
+    let schedule = plan_dao.get_schedule_by_plan_id(&plan_id).await?;
+    /* a concurrent run now or scheduled fire waits here and then sees the run started by this one */
+    plan_dao.lock_schedule(&plan_id).await?;
+    if plan_dao.has_running_run(&plan_id).await? {
+        return Err(AppError::BadRequestError(
+            PlanException::RunInProgress.to_string(),
+        ));
+    }
+    let run_id = start_run(&transaction, schedule, RunTrigger::Manual, uid).await?;
+    transaction.commit().await?;
+    Ok(run_id)
+}
+
+/* payload of `JobKind::PlanRun`, queued in the transaction creating the run */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanRunJob {
+    pub run_id: i32,
+    pub uid: Uuid,
+    pub schedule: PlanSchedule,
+}
+
+/* the partial unique index on running runs rejects a run the schedule lock did not serialize */
+fn run_in_progress(e: AppError) -> AppError {
+    match &e {
+        AppError::DatabaseError(err) if err.code() == Some(&SqlState::UNIQUE_VIOLATION) => {
+            AppError::BadRequestError(PlanException::RunInProgress.to_string())
+        }
+        _ => e,
+    }
+}
+
+/* scripts have side effects, a lost run is not repeated but expired by `expire_lost_runs` */
+async fn start_run<T: db::GenericClient>(
+    executor: &T,
+    schedule: PlanSchedule,
+    trigger: RunTrigger,
+    uid: Uuid,
+) -> AppResult<i32> {
+    let plan_dao = PlanDao::new(executor);
+    let run_id = plan_dao
+        .create_run(
+            &schedule.plan_id,
+            Some(schedule.id),
+            schedule.script_id,
+            trigger,
+            &uid,
+        )
+        .await
+        .map_err(run_in_progress)?;
+    let payload = serde_json::to_string(&PlanRunJob {
+        run_id,
+        uid,
+        schedule,
+    })?;
+    let job_id = JobDao::new(executor)
+        .create(JobKind::PlanRun, &payload, 1, &uid)
+        .await?;
+    plan_dao.set_run_job(&run_id, &job_id).await?;
+    Ok(run_id)
+}
+
+pub async fn get_runs(
+    state: &AppState,
+    project_id: i32,
+    plan_id: i32,
+    param: PlanRunQueryParam,
+) -> AppResult<Vec<PlanRun>> {
+    info!("service layer get plan runs with plan_id: {plan_id}, param: {param:?}");
+    let client = state.pool.get().await?;
+    let plan_dao = PlanDao::new(&client);
+    let plan = plan_dao.get_plan_by_id(&plan_id).await?;
+    check_project(plan.project_id, project_id)?;
+    let limit = param.limit.unwrap_or(20).clamp(1, 100);
+    plan_dao.get_runs_by_plan_id(&plan_id, limit).await
+}
+
+/* latest cron slot in (from, now], `None` if the schedule is not due */
+pub fn due_slot(
+    cron: &str,
+    from: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> AppResult<Option<DateTime<Utc>>> {
+    let schedule = parse_cron(cron)?;
+    Ok(schedule.after(&from).take_while(|t| *t <= now).last())
+}
+
+/* ticked by the scheduler on every replica, a slot is claimed by exactly one of them */
+pub async fn fire_due_schedules(state: &AppState) -> AppResult {
+    let now = Utc::now();
+    let mut client = state.pool.get().await?;
+    let schedules = PlanDao::new(&client).get_enabled_schedules().await?;
+    for schedule in schedules {
+        /* slots missed while every replica was down collapse into a single run */
+        let from = schedule
+            .last_fired_at
+            .or(schedule.updated_at)
+            .unwrap_or(schedule.created_at);
+        let slot = match due_slot(&schedule.cron, from, now) {
+            Ok(Some(slot)) => slot,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!(
+                    "skip plan schedule {} with invalid cron: {e:?}",
+                    schedule.id
+                );
+                continue;
+            }
+        };
+        /* the slot is claimed together with queueing its run, the claim locks the schedule row like `run_now` */
+        let transaction = client.transaction().await?;
+        let plan_dao = PlanDao::new(&transaction);
+        if !plan_dao.claim_schedule(&schedule.id, slot).await? {
+            continue;
+        }
+        if plan_dao.has_running_run(&schedule.plan_id).await? {
+            warn!(
+                "skip plan schedule {} at {slot}, previous run still in progress",
+                schedule.id
+            );
+            transaction.commit().await?;
+            continue;
+        }
+        let schedule_id = schedule.id;
+        let uid = schedule.created_by;
+        let run_id = start_run(&transaction, schedule, RunTrigger::Schedule, uid).await?;
+        transaction.commit().await?;
+        info!("plan schedule {schedule_id} fired run {run_id} for slot {slot}");
+    }
+    Ok(())
+}
+
+/* runs left RUNNING by a lost job would block the plan forever */
+pub async fn expire_lost_runs(state: &AppState) -> AppResult {
+    let client = state.pool.get().await?;
+    let expired = PlanDao::new(&client).expire_lost_runs().await?;
+    if expired > 0 {
+        warn!("expired {expired} lost plan runs");
+    }
+    Ok(())
+}
+
+pub async fn execute_run(state: &AppState, job: &PlanRunJob) {
+    let (run_id, uid, schedule) = (job.run_id, job.uid, &job.schedule);
+    let outcome = run_scripts(state, schedule, uid).await;
+    let (status, counts, error, failed) = match outcome {
+        Ok((total, failed)) => {
+            let failures = failed.len() as i32;
+            let status = match failures {
+                0 => RunStatus::Passed,
+                _ => RunStatus::Failed,
+            };
+            (status, (total, total - failures, failures), None, failed)
+        }
+        Err(e) => {
+            warn!("plan run {run_id} failed: {e:?}");
+            (RunStatus::Error, (0, 0, 0), Some(e.to_string()), vec![])
+        }
+    };
+    let finished = async {
+        let client = state.pool.get().await?;
+        PlanDao::new(&client)
+            .finish_run(&run_id, status, counts, error.clone())
+            .await
+    };
+    if let Err(e) = finished.await {
+        warn!("failed to finish plan run {run_id}: {e:?}");
+    }
+    let notified = notify_run_finished(state, run_id, schedule.plan_id, status, counts, error, uid);
+    if let Err(e) = notified.await {
+        warn!("failed to notify plan run {run_id}: {e:?}");
+    }
+    if !failed.is_empty() {
+        let notified = notify_run_failed(state, run_id, schedule.plan_id, &failed, uid);
+        if let Err(e) = notified.await {
+            warn!("failed to notify failed scripts of plan run {run_id}: {e:?}");
+        }
+    }
+}
+
+/* sent to the owner of the plan and whoever triggered the run */
+async fn notify_run_finished(
+    state: &AppState,
+    run_id: i32,
+    plan_id: i32,
+    status: RunStatus,
+    (total, passed, failed): (i32, i32, i32),
+    error: Option<String>,
+    uid: Uuid,
+) -> AppResult {
+    let plan = {
+        let client = state.pool.get().await?;
+        PlanDao::new(&client).get_plan_by_id(&plan_id).await?
+    };
+    let content = match error {
+        Some(error) => format!("Run {run_id} stopped with an error: {error}"),
+        None => format!("Run {run_id}: {passed} of {total} scripts passed, {failed} failed"),
+    };
+    let notification = Notification {
+        event_type: NotificationEvent::PlanRunFinished,
+        title: format!("Plan {} finished: {}", plan.name, status.to_string()),
+        content,
+        link: None,
+    };
+    notification::notify(state, &[plan.created_by, uid], &notification).await
+}
+
+/* returns the number of scripts and the failed ones, every script result is recorded against the plan */
+async fn run_scripts(
+    state: &AppState,
+    schedule: &PlanSchedule,
+    uid: Uuid,
+) -> AppResult<(i32, Vec<PlanScript>)> {
+    let (scripts, machine) = {
+        let client = state.pool.get().await?;
+        let plan_dao = PlanDao::new(&client);
+        let scripts = match schedule.script_id {
+            Some(script_id) => vec![
+                plan_dao
+                    .get_plan_script_by_id(&schedule.plan_id, &script_id)
+                    .await?,
+            ],
+            None => plan_dao.get_plan_scripts(&schedule.plan_id).await?,
+        };
+        let machine = CaseDao::new(&client)
+            .get_machine(&schedule.machine_id)
+            .await?;
+        (scripts, machine)
+    };
+    if scripts.is_empty() {
+        return Err(AppError::BadRequestError(
+            PlanException::NoScript.to_string(),
+        ));
+    }
+    let total = scripts.len() as i32;
+    let mut failed = vec![];
+    for script in scripts.into_iter() {
+        /* keep no connection while the script is running */
+        let result = match engine::run_script(machine.clone(), &script.path).await? {
+            true => CaseResult::Passed,
+            false => CaseResult::Failed,
+        };
+        let client = state.pool.get().await?;
+        CaseDao::new(&client)
+            .insert_execute_record(&script.case_id, &result, Some(schedule.plan_id), &uid)
+            .await?;
+        if result == CaseResult::Failed {
+            failed.push(script);
+        }
+    }
+    Ok((total, failed))
+}
+
+/* one notification per run listing every failed script, sent to whoever triggered the run */
+async fn notify_run_failed(
+    state: &AppState,
+    run_id: i32,
+    plan_id: i32,
+    failed: &[PlanScript],
+    uid: Uuid,
+) -> AppResult {
+    let mut lines = vec![];
+    {
+        let client = state.pool.get().await?;
+        let case_dao = CaseDao::new(&client);
+        for script in failed.iter() {
+            let case_name = match case_dao.get_functional_case_by_id(script.case_id).await {
+                Ok(case) => case.name,
+                Err(e) => {
+                    warn!("failed to get failed case {}: {e:?}", script.case_id);
+                    format!("#{}", script.case_id)
+                }
+            };
+            lines.push(format!("{case_name}: {}", script.path));
+        }
+    }
+    let notification = Notification {
+        event_type: NotificationEvent::ExecutionFailed,
+        title: format!(
+            "{} scripts failed in run {run_id} of plan {plan_id}",
+            failed.len()
+        ),
+        content: lines.join("\n"),
+        link: None,
+    };
+    notification::notify(state, &[uid], &notification).await
+}
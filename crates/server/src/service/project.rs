@@ -1,11 +1,51 @@
+use crate::constant::PROJECT_ADMIN_ROLE;
+use crate::dao::file::FileDao;
 use crate::dao::project::*;
 use crate::dao::user::UserDao;
+use crate::dto::request::{
+    AddMemberRequest, ArchiveProjectRequest, CreateProjectRequest, DeleteEntityRequest,
+    RemoveMemberRequest, UpdateMemberRequest, UpdateModuleSettingRequest, UpdateProjectRequest,
+};
 use crate::dto::response::{MessageResponse, ProjectInfoResponse};
-use crate::errors::AppResult;
+use crate::entity::file::{FileModule, ModuleType};
+use crate::entity::project::{Project, ProjectModule};
+use crate::errors::{
+    message::{ProjectException, RoleException, UserException},
+    AppError, AppResult, ResourceType,
+};
+use crate::service::permission;
 use crate::state::AppState;
 use crate::{dao::entity::ProjectMember, entity::project::ProjectInfo};
+use strum::IntoEnumIterator;
+use tokio_postgres::error::SqlState;
+use tracing::info;
 use uuid::Uuid;
 
+const ROOT_MODULE_NAME: &str = "默认模块";
+
+/* resources are loaded by id, make sure they belong to the project authorised by the access middleware */
+pub fn check_project(owner: i32, project_id: i32) -> AppResult {
+    if owner != project_id {
+        return Err(AppError::ForbiddenError(
+            UserException::Forbidden.to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/* same as `check_project` for resources loaded without their project */
+pub async fn check_owner<T: db::GenericClient>(
+    executor: &T,
+    resource_type: ResourceType,
+    id: i32,
+    project_id: i32,
+) -> AppResult {
+    let owner = ProjectDao::new(executor)
+        .get_owner(resource_type, id)
+        .await?;
+    check_project(owner, project_id)
+}
+
 /* 获取项目信息 */
 pub async fn info(state: &AppState, project_id: i32) -> AppResult<ProjectInfoResponse> {
     let client = state.pool.get().await?;
@@ -13,7 +53,7 @@ pub async fn info(state: &AppState, project_id: i32) -> AppResult<ProjectInfoRes
     let project = project_dao.find_by_id(project_id).await?;
     let user_dao = UserDao::new(&client);
     let admin_list = user_dao
-        .find_by_role_and_project_id("admin", project_id)
+        .find_by_role_and_project_id(PROJECT_ADMIN_ROLE, project_id)
         .await?;
     let module_list = match project.module_setting {
         Some(s) => serde_json::from_str(s.as_str()),
@@ -41,6 +81,133 @@ pub async fn list(state: &AppState, uid: Uuid) -> AppResult<Vec<ProjectInfo>> {
     Ok(projects)
 }
 
+/* the partial unique index on the name catches the create or rename that raced the check above */
+fn name_exists(e: AppError) -> AppError {
+    match &e {
+        AppError::DatabaseError(err) if err.code() == Some(&SqlState::UNIQUE_VIOLATION) => {
+            AppError::BadRequestError(ProjectException::NameExists.to_string())
+        }
+        _ => e,
+    }
+}
+
+/* seeds the internal template, a root module per module type and makes the creator admin */
+pub async fn create(state: &AppState, uid: Uuid, request: CreateProjectRequest) -> AppResult<i32> {
+    info!("service layer create project with request: {request:?}, created_by: {uid}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let project_dao = ProjectDao::new(&transaction);
+    if project_dao.find_by_name(request.name.clone()).await.is_ok() {
+        return Err(AppError::BadRequestError(
+            ProjectException::NameExists.to_string(),
+        ));
+    }
+    let modules = request
+        .modules
+        .unwrap_or_else(|| ProjectModule::iter().collect());
+    let project = Project::new(
+        request.name,
+        uid,
+        request.description,
+        Some(serde_json::to_string(&modules)?),
+    );
+    let project_id = project_dao.insert(&project).await.map_err(name_exists)?;
+    project_dao.seed_template(&project_id, &uid).await?;
+    let file_dao = FileDao::new(&transaction);
+    for module_type in [
+        ModuleType::Case,
+        ModuleType::Bug,
+        ModuleType::Plan,
+        ModuleType::Element,
+    ] {
+        let module = FileModule {
+            id: 0,
+            name: ROOT_MODULE_NAME.to_string(),
+            module_type,
+            position: 0,
+            parent_id: None,
+        };
+        file_dao
+            .insert_file_module(&uid, project_id, &module)
+            .await?;
+    }
+    let user_dao = UserDao::new(&transaction);
+    let role_id = user_dao.get_role_id_by_name(PROJECT_ADMIN_ROLE).await?;
+    user_dao
+        .insert_user_role_relation(uid, role_id, project_id, uid)
+        .await?;
+    transaction.commit().await?;
+    Ok(project_id)
+}
+
+pub async fn update(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: UpdateProjectRequest,
+) -> AppResult {
+    info!("service layer update project with project_id: {project_id}, request: {request:?}");
+    let client = state.pool.get().await?;
+    let project_dao = ProjectDao::new(&client);
+    let project = project_dao.find_by_id(project_id).await?;
+    if project.name != request.name && project_dao.find_by_name(request.name.clone()).await.is_ok()
+    {
+        return Err(AppError::BadRequestError(
+            ProjectException::NameExists.to_string(),
+        ));
+    }
+    project_dao
+        .update(&project.id, &request.name, &request.description, &uid)
+        .await
+        .map_err(name_exists)
+}
+
+pub async fn update_module_setting(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: UpdateModuleSettingRequest,
+) -> AppResult {
+    info!(
+        "service layer update module setting with project_id: {project_id}, request: {request:?}"
+    );
+    let module_setting = serde_json::to_string(&request.modules)?;
+    {
+        let client = state.pool.get().await?;
+        let project_dao = ProjectDao::new(&client);
+        let project = project_dao.find_by_id(project_id).await?;
+        project_dao
+            .update_module_setting(&project.id, &module_setting, &uid)
+            .await?;
+    }
+    permission::invalidate_project(state, &project_id).await
+}
+
+/* archived projects stay readable, every write of the management modules is rejected */
+pub async fn archive(state: &AppState, uid: Uuid, request: ArchiveProjectRequest) -> AppResult {
+    info!("service layer archive project with request: {request:?}");
+    {
+        let client = state.pool.get().await?;
+        let project_dao = ProjectDao::new(&client);
+        let project = project_dao.find_by_id(request.id).await?;
+        project_dao
+            .update_enable(&project.id, !request.archived, &uid)
+            .await?;
+    }
+    permission::invalidate_project(state, &request.id).await
+}
+
+pub async fn delete(state: &AppState, uid: Uuid, request: DeleteEntityRequest) -> AppResult {
+    info!("service layer delete project with request: {request:?}, deleted_by: {uid}");
+    {
+        let client = state.pool.get().await?;
+        let project_dao = ProjectDao::new(&client);
+        let project = project_dao.find_by_id(request.id).await?;
+        project_dao.soft_delete(&project.id, &uid).await?;
+    }
+    permission::invalidate_project(state, &request.id).await
+}
+
 pub async fn permission(
     state: &AppState,
     project_id: i32,
@@ -61,6 +228,129 @@ pub async fn members(state: &AppState, project_id: &i32) -> AppResult<Vec<Projec
     Ok(members)
 }
 
+pub async fn add_member(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: AddMemberRequest,
+) -> AppResult {
+    info!("service layer add member with project_id: {project_id}, request: {request:?}");
+    let client = state.pool.get().await?;
+    let project_dao = ProjectDao::new(&client);
+    project_dao.find_by_id(project_id).await?;
+    let user_dao = UserDao::new(&client);
+    let member = user_dao.find_by_id(&request.uid).await?;
+    if user_dao
+        .get_member_role(&member.uuid, &project_id)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::BadRequestError(
+            RoleException::AlreadyMember.to_string(),
+        ));
+    }
+    let role_id = get_member_role_id(&user_dao, &request.role).await?;
+    user_dao
+        .insert_user_role_relation(member.uuid, role_id, project_id, uid)
+        .await?;
+    permission::invalidate_member(state, &member.uuid, &project_id).await?;
+    Ok(())
+}
+
+/* members only get project roles, internal ones carry the system permissions except the project admin */
+async fn get_member_role_id<T: db::GenericClient>(
+    user_dao: &UserDao<'_, T>,
+    role_name: &str,
+) -> AppResult<i32> {
+    let role_id = user_dao.get_role_id_by_name(role_name).await?;
+    let role = user_dao.get_role_by_id(role_id).await?;
+    if role.role_type != "PROJECT" || (role.internal && role.name != PROJECT_ADMIN_ROLE) {
+        return Err(AppError::BadRequestError(
+            RoleException::NotProjectRole.to_string(),
+        ));
+    }
+    Ok(role.id)
+}
+
+/* the last admin of a project can neither be demoted nor removed */
+async fn check_last_admin<T: db::GenericClient>(
+    user_dao: &UserDao<'_, T>,
+    project_id: i32,
+    current_role: &str,
+) -> AppResult {
+    if current_role == PROJECT_ADMIN_ROLE
+        && user_dao
+            .find_by_role_and_project_id(PROJECT_ADMIN_ROLE, project_id)
+            .await?
+            .len()
+            <= 1
+    {
+        return Err(AppError::BadRequestError(
+            RoleException::LastAdmin.to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn update_member(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: UpdateMemberRequest,
+) -> AppResult {
+    info!("service layer update member with project_id: {project_id}, request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let user_dao = UserDao::new(&transaction);
+    let member = user_dao.find_by_id(&request.uid).await?;
+    let current = match user_dao.get_member_role(&member.uuid, &project_id).await? {
+        Some(role) => role,
+        None => {
+            return Err(AppError::BadRequestError(
+                RoleException::NotMember.to_string(),
+            ))
+        }
+    };
+    if current.name == request.role {
+        return Ok(());
+    }
+    check_last_admin(&user_dao, project_id, &current.name).await?;
+    let role_id = get_member_role_id(&user_dao, &request.role).await?;
+    user_dao
+        .update_user_role_relation(&member.uuid, &project_id, role_id, uid)
+        .await?;
+    transaction.commit().await?;
+    permission::invalidate_member(state, &member.uuid, &project_id).await?;
+    Ok(())
+}
+
+pub async fn remove_member(
+    state: &AppState,
+    project_id: i32,
+    request: RemoveMemberRequest,
+) -> AppResult {
+    info!("service layer remove member with project_id: {project_id}, request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let user_dao = UserDao::new(&transaction);
+    let member = user_dao.find_by_id(&request.uid).await?;
+    let current = match user_dao.get_member_role(&member.uuid, &project_id).await? {
+        Some(role) => role,
+        None => {
+            return Err(AppError::BadRequestError(
+                RoleException::NotMember.to_string(),
+            ))
+        }
+    };
+    check_last_admin(&user_dao, project_id, &current.name).await?;
+    user_dao
+        .delete_user_role_relation(&member.uuid, &project_id)
+        .await?;
+    transaction.commit().await?;
+    permission::invalidate_member(state, &member.uuid, &project_id).await?;
+    Ok(())
+}
+
 #[allow(dead_code)]
 pub async fn get_idle_users(_state: &AppState, _project_id: &i32) -> AppResult<Vec<()>> {
     Ok(vec![])
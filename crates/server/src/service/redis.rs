@@ -3,7 +3,12 @@ use db::redis::{RedisClient, RedisClientExt};
 use std::fmt::{Debug, Display, Formatter};
 use std::time::Duration;
 
-use crate::constant::EXPIRE_SESSION_CODE_SECS;
+use crate::constant::{
+    EXPIRE_INVITATION_SECS, EXPIRE_LOGIN_FAILURE_SECS, EXPIRE_MFA_CHALLENGE_SECS,
+    EXPIRE_OIDC_STATE_SECS, EXPIRE_PASSWORD_RESET_SECS, EXPIRE_PASSWORD_RESET_THROTTLE_SECS,
+    EXPIRE_PERMISSION_SECS, EXPIRE_REFRESH_TOKEN_SECS, EXPIRE_SESSION_CODE_SECS,
+};
+use crate::entity::{permission::UserPermission, session::SessionInfo};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use tracing::info;
@@ -35,7 +40,199 @@ impl Display for SessionKey {
     }
 }
 
-#[allow(dead_code)]
+#[derive(Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct SessionInfoKey {
+    pub uuid: Uuid,
+    pub sid: Uuid,
+}
+
+impl RedisKey for SessionInfoKey {
+    type Value = SessionInfo;
+    const EXPIRE_TIME: Duration = EXPIRE_SESSION_CODE_SECS;
+}
+
+impl Display for SessionInfoKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SESSION_INFO_KEY:{}:{}", self.uuid, self.sid)
+    }
+}
+
+/* outstanding refresh token of a session, removed when the token is exchanged */
+#[derive(Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct RefreshTokenKey {
+    pub jti: Uuid,
+}
+
+impl RedisKey for RefreshTokenKey {
+    /* session id */
+    type Value = Uuid;
+    const EXPIRE_TIME: Duration = EXPIRE_REFRESH_TOKEN_SECS;
+}
+
+impl Display for RefreshTokenKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "REFRESH_TOKEN_KEY:{}", self.jti)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct UserPermissionKey {
+    pub uuid: Uuid,
+    pub project_id: i32,
+}
+
+impl RedisKey for UserPermissionKey {
+    type Value = UserPermission;
+    const EXPIRE_TIME: Duration = EXPIRE_PERMISSION_SECS;
+}
+
+impl Display for UserPermissionKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "USER_PERMISSION_KEY:{}:{}", self.uuid, self.project_id)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct ApiPermissionKey {
+    pub method: String,
+    pub uri: String,
+}
+
+impl RedisKey for ApiPermissionKey {
+    /* permission ids required by the api */
+    type Value = Vec<i32>;
+    const EXPIRE_TIME: Duration = EXPIRE_PERMISSION_SECS;
+}
+
+impl Display for ApiPermissionKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "API_PERMISSION_KEY:{}:{}", self.method, self.uri)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct OidcStateKey {
+    pub state: String,
+}
+
+/* nonce sent along with the authorization request, and the digest of the browser binding cookie */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OidcState {
+    pub nonce: String,
+    pub binding_hash: String,
+}
+
+impl RedisKey for OidcStateKey {
+    type Value = OidcState;
+    const EXPIRE_TIME: Duration = EXPIRE_OIDC_STATE_SECS;
+}
+
+impl Display for OidcStateKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "OIDC_STATE_KEY:{}", self.state)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct InvitationKey {
+    /* only the digest is stored, the token itself lives in the link */
+    pub token_hash: String,
+}
+
+impl RedisKey for InvitationKey {
+    type Value = Uuid;
+    const EXPIRE_TIME: Duration = EXPIRE_INVITATION_SECS;
+}
+
+impl Display for InvitationKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "INVITATION_KEY:{}", self.token_hash)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct UserInvitationKey {
+    pub uuid: Uuid,
+}
+
+impl RedisKey for UserInvitationKey {
+    /* digest of the pending invitation, so a resend can drop the old link */
+    type Value = String;
+    const EXPIRE_TIME: Duration = EXPIRE_INVITATION_SECS;
+}
+
+impl Display for UserInvitationKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "USER_INVITATION_KEY:{}", self.uuid)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct PasswordResetKey {
+    pub token_hash: String,
+}
+
+impl RedisKey for PasswordResetKey {
+    type Value = Uuid;
+    const EXPIRE_TIME: Duration = EXPIRE_PASSWORD_RESET_SECS;
+}
+
+impl Display for PasswordResetKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PASSWORD_RESET_KEY:{}", self.token_hash)
+    }
+}
+
+/* password accepted, waiting for the second factor of the user */
+#[derive(Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct MfaChallengeKey {
+    pub token_hash: String,
+}
+
+impl RedisKey for MfaChallengeKey {
+    type Value = Uuid;
+    const EXPIRE_TIME: Duration = EXPIRE_MFA_CHALLENGE_SECS;
+}
+
+impl Display for MfaChallengeKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MFA_CHALLENGE_KEY:{}", self.token_hash)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct PasswordResetThrottleKey {
+    pub email: String,
+}
+
+impl RedisKey for PasswordResetThrottleKey {
+    type Value = bool;
+    const EXPIRE_TIME: Duration = EXPIRE_PASSWORD_RESET_THROTTLE_SECS;
+}
+
+impl Display for PasswordResetThrottleKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PASSWORD_RESET_THROTTLE_KEY:{}", self.email)
+    }
+}
+
+/* failed logins of `subject`, e.g. `user:alice` or `ip:10.0.0.1` */
+#[derive(Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct LoginFailureKey {
+    pub subject: String,
+}
+
+impl RedisKey for LoginFailureKey {
+    type Value = i64;
+    const EXPIRE_TIME: Duration = EXPIRE_LOGIN_FAILURE_SECS;
+}
+
+impl Display for LoginFailureKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LOGIN_FAILURE_KEY:{}", self.subject)
+    }
+}
+
 pub async fn set<K>(client: &RedisClient, (key, value): (&K, &K::Value)) -> AppResult
 where
     K: RedisKey,
@@ -81,7 +278,6 @@ where
     Ok(client.lrange(&key.to_string(), start, stop).await?)
 }
 
-#[allow(dead_code)]
 pub async fn get<K>(client: &RedisClient, key: &K) -> AppResult<Option<K::Value>>
 where
     K: RedisKey,
@@ -94,19 +290,58 @@ where
         .transpose()?)
 }
 
+/* fetch two keys within a single round trip */
+pub async fn get_pair<K1, K2>(
+    client: &RedisClient,
+    first: &K1,
+    second: &K2,
+) -> AppResult<(Option<K1::Value>, Option<K2::Value>)>
+where
+    K1: RedisKey,
+    K2: RedisKey,
+{
+    info!("MGET value from redis keys: {first:?}, {second:?}");
+    let mut values = client
+        .mget(&[&first.to_string(), &second.to_string()])
+        .await?
+        .into_iter();
+    let first = values
+        .next()
+        .flatten()
+        .map(|v| serde_json::from_str::<K1::Value>(&v))
+        .transpose()?;
+    let second = values
+        .next()
+        .flatten()
+        .map(|v| serde_json::from_str::<K2::Value>(&v))
+        .transpose()?;
+    Ok((first, second))
+}
+
 pub async fn del(client: &RedisClient, key: &impl RedisKey) -> AppResult<bool> {
     info!("Delete redis key: {key:?}");
     Ok(client.del(&key.to_string()).await?)
 }
 
-#[allow(dead_code)]
 pub async fn get_ttl(client: &RedisClient, key: &impl RedisKey) -> AppResult<i64> {
     info!("Get redis key: {key:?} ttl");
     Ok(client.ttl(&key.to_string()).await?)
 }
 
-#[allow(dead_code)]
 pub async fn check_exist(client: &RedisClient, key: &impl RedisKey) -> AppResult<bool> {
     info!("Check redis key: {key:?} exist");
     Ok(client.exist(&key.to_string()).await?)
 }
+
+pub async fn incr<K>(client: &RedisClient, key: &K, expire: Duration) -> AppResult<i64>
+where
+    K: RedisKey<Value = i64>,
+{
+    info!("INCR redis key: {key:?}");
+    Ok(client.incr(&key.to_string(), expire).await?)
+}
+
+pub async fn expire(client: &RedisClient, key: &impl RedisKey, expire: Duration) -> AppResult {
+    info!("Expire redis key: {key:?} in {expire:?}");
+    Ok(client.expire(&key.to_string(), expire).await?)
+}
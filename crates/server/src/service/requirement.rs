@@ -0,0 +1,202 @@
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    dao::{case::CaseDao, plan::PlanDao, requirement::RequirementDao},
+    dto::{
+        request::{
+            case::{
+                CreateRequirementRequest, ImportRequirementRequest, RequirementCaseRequest,
+                RequirementInfoParam, UpdateRequirementRequest,
+            },
+            DeleteEntityRequest,
+        },
+        response::{CreateEntityResponse, RequirementInfoResponse, RequirementResponse},
+    },
+    entity::{
+        case::IssueSource,
+        requirement::{Requirement, RequirementCoverage},
+    },
+    errors::{AppResult, ResourceType},
+    service::{
+        issue::{IssueTracker, Tracker},
+        project::{check_owner, check_project},
+    },
+    state::AppState,
+};
+
+pub async fn create(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: CreateRequirementRequest,
+) -> AppResult<CreateEntityResponse> {
+    info!("requirement service layer create with {request:?}, project_id: {project_id}");
+    let client = state.pool.get().await?;
+    let requirement_dao = RequirementDao::new(&client);
+    let requirement = Requirement::new(project_id, &request.name, request.description);
+    let id = requirement_dao.create(&requirement, &uid).await?;
+    Ok(CreateEntityResponse { id })
+}
+
+pub async fn import(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: ImportRequirementRequest,
+) -> AppResult<CreateEntityResponse> {
+    info!("requirement service layer import with {request:?}, project_id: {project_id}");
+    let source = IssueSource::from_str(&request.source);
+    let tracker = Tracker::new(source, &state.config.tracker, &state.http)?;
+    let issue = tracker.get_issue(&request.external_id).await?;
+    let client = state.pool.get().await?;
+    let requirement_dao = RequirementDao::new(&client);
+    requirement_dao
+        .check_external_absent(&project_id, &source.to_string(), &issue.issue_id)
+        .await?;
+    let requirement = Requirement::imported(
+        project_id,
+        source,
+        issue.issue_id,
+        issue.title,
+        issue.uri,
+        issue.status,
+    );
+    let id = requirement_dao.create(&requirement, &uid).await?;
+    Ok(CreateEntityResponse { id })
+}
+
+pub async fn update(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: UpdateRequirementRequest,
+) -> AppResult {
+    info!("requirement service layer update with {request:?}, project_id: {project_id}");
+    let client = state.pool.get().await?;
+    let requirement_dao = RequirementDao::new(&client);
+    let mut requirement = requirement_dao.get_by_id(&request.id).await?;
+    check_project(requirement.project_id, project_id)?;
+    requirement.name = request.name;
+    requirement.description = request.description;
+    requirement_dao.update(&requirement, &uid).await
+}
+
+pub async fn delete(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: DeleteEntityRequest,
+) -> AppResult {
+    info!("requirement service layer delete with {request:?}, project_id: {project_id}");
+    let client = state.pool.get().await?;
+    let requirement_dao = RequirementDao::new(&client);
+    let requirement = requirement_dao.get_by_id(&request.id).await?;
+    check_project(requirement.project_id, project_id)?;
+    /* relations with cases are removed along with requirement */
+    requirement_dao.soft_delete(&requirement.id, &uid).await
+}
+
+pub async fn get(
+    state: &AppState,
+    project_id: i32,
+    requirement_id: i32,
+) -> AppResult<RequirementResponse> {
+    info!("requirement service layer get with id: {requirement_id}, project_id: {project_id}");
+    let client = state.pool.get().await?;
+    let requirement_dao = RequirementDao::new(&client);
+    let requirement = requirement_dao.get_by_id(&requirement_id).await?;
+    check_project(requirement.project_id, project_id)?;
+    let case_ids = requirement_dao.get_case_ids(&requirement.id).await?;
+    Ok(RequirementResponse {
+        requirement,
+        case_ids,
+    })
+}
+
+pub async fn list(state: &AppState, project_id: i32) -> AppResult<Vec<Requirement>> {
+    info!("requirement service layer list with project_id: {project_id}");
+    let client = state.pool.get().await?;
+    let requirement_dao = RequirementDao::new(&client);
+    requirement_dao.get_list(&project_id).await
+}
+
+pub async fn link_cases(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: RequirementCaseRequest,
+) -> AppResult {
+    info!("requirement service layer link cases with {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let requirement_dao = RequirementDao::new(&transaction);
+    let case_dao = CaseDao::new(&transaction);
+    let requirement = requirement_dao.get_by_id(&request.requirement_id).await?;
+    check_project(requirement.project_id, project_id)?;
+    for case_id in request.case_ids.iter() {
+        let case = case_dao.get_functional_case_by_id(*case_id).await?;
+        check_owner(&transaction, ResourceType::Case, case.id, project_id).await?;
+        requirement_dao
+            .insert_case_relation(&requirement.id, &case.id, &uid)
+            .await?;
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn unlink_cases(
+    state: &AppState,
+    uid: Uuid,
+    project_id: i32,
+    request: RequirementCaseRequest,
+) -> AppResult {
+    info!("requirement service layer unlink cases with {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let requirement_dao = RequirementDao::new(&transaction);
+    let requirement = requirement_dao.get_by_id(&request.requirement_id).await?;
+    check_project(requirement.project_id, project_id)?;
+    for case_id in request.case_ids.iter() {
+        requirement_dao
+            .soft_delete_case_relation(&requirement.id, case_id, &uid)
+            .await?;
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn info(
+    state: &AppState,
+    project_id: &i32,
+    param: RequirementInfoParam,
+) -> AppResult<RequirementInfoResponse> {
+    info!(
+        "requirement service layer coverage info with project_id: {project_id}, param: {param:?}"
+    );
+    let client = state.pool.get().await?;
+    let requirement_dao = RequirementDao::new(&client);
+    let plan_id = match param.plan_id {
+        Some(id) => {
+            let plan = PlanDao::new(&client).get_plan_by_id(&id).await?;
+            check_project(plan.project_id, *project_id)?;
+            Some(id)
+        }
+        None => {
+            requirement_dao
+                .get_latest_executed_plan_id(project_id)
+                .await?
+        }
+    };
+    let list = requirement_dao.get_coverage(project_id, &plan_id).await?;
+    let uncovered: Vec<RequirementCoverage> =
+        list.iter().filter(|r| r.case_count == 0).cloned().collect();
+    let total = list.len() as i64;
+    Ok(RequirementInfoResponse {
+        plan_id,
+        total,
+        covered: total - uncovered.len() as i64,
+        list,
+        uncovered,
+    })
+}
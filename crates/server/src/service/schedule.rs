@@ -1,12 +1,18 @@
 use core::panic;
-use std::{future::Future, io, str::FromStr, time::Duration};
+use std::{future::Future, io, str::FromStr, sync::Arc, time::Duration};
 
 use chrono::{DateTime, Utc};
 use cron::Schedule;
+use futures::future::{self, BoxFuture, FutureExt};
+use rand::Rng;
 use std::{any::Any, time::Instant};
+use tokio::task::JoinSet;
 use toml::Table;
+use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::errors::AppResult;
+
 // use super::redis::set;
 
 #[derive(Debug)]
@@ -25,6 +31,8 @@ pub struct JobContext {
     immediate: bool,
     // Remaining ticks.
     remaining_ticks: Option<usize>,
+    // Upper bound of the random delay added to each tick.
+    jitter: Option<Duration>,
     // Last time when running the job.
     last_tick: Option<DateTime<Utc>>,
 
@@ -34,6 +42,12 @@ pub struct JobContext {
     job_data: Option<Box<dyn Any + Send>>,
 }
 
+impl Default for JobContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl JobContext {
     pub fn new() -> Self {
         Self {
@@ -44,6 +58,7 @@ impl JobContext {
             disabled: false,
             immediate: false,
             remaining_ticks: None,
+            jitter: None,
             last_tick: None,
             // execution_error: None,
             job_data: None,
@@ -53,26 +68,37 @@ impl JobContext {
     // Start the job.
     pub fn start(&mut self) {
         self.start_time = Instant::now();
+        self.last_tick = Some(Utc::now());
     }
 
-    // Finish the job.
-    pub fn finish(&mut self) {
+    // Count the tick once the job has been started.
+    pub fn consume_tick(&mut self) {
         if let Some(ticks) = self.remaining_ticks {
             self.remaining_ticks = Some(ticks.saturating_sub(1));
         }
+    }
+
+    // Finish a job that ran on the scheduler loop.
+    pub fn finish(&mut self) {
+        self.consume_tick();
 
         let job_id = self.job_id.to_string();
         let job_name = self.job_name;
         let execution_time = self.start_time.elapsed();
-        tracing::warn!(
+        tracing::info!(
             job_id,
             job_name,
             remaining_ticks = self.remaining_ticks,
             last_tick = self.last_tick.map(|dt| dt.to_string()),
-            execution_time_millis = execution_time.as_millis()
+            execution_time_millis = execution_time.as_millis(),
+            "scheduled job finished"
         );
     }
 
+    pub fn set_name(&mut self, name: &'static str) {
+        self.job_name = Some(name);
+    }
+
     pub fn set_source(&mut self, source: impl Into<String>) {
         self.source = source.into();
     }
@@ -80,15 +106,64 @@ impl JobContext {
     pub fn set_disabled_status(&mut self, disabled: bool) {
         self.disabled = disabled;
     }
+
+    pub fn set_immediate(&mut self, immediate: bool) {
+        self.immediate = immediate;
+    }
+
+    pub fn set_remaining_ticks(&mut self, ticks: usize) {
+        self.remaining_ticks = Some(ticks);
+    }
+
+    pub fn set_jitter(&mut self, jitter: Duration) {
+        self.jitter = Some(jitter);
+    }
+
+    pub fn set_job_data(&mut self, data: impl Any + Send) {
+        self.job_data = Some(Box::new(data));
+    }
+
+    pub fn job_data<T: Any>(&self) -> Option<&T> {
+        self.job_data.as_ref().and_then(|d| d.downcast_ref::<T>())
+    }
+
+    pub fn job_name(&self) -> Option<&'static str> {
+        self.job_name
+    }
+
+    pub fn remaining_ticks(&self) -> Option<usize> {
+        self.remaining_ticks
+    }
+
+    pub fn last_tick(&self) -> Option<DateTime<Utc>> {
+        self.last_tick
+    }
+
+    // Returns `true` if the job is enabled and still has ticks left.
+    pub fn is_ready(&self) -> bool {
+        !self.disabled && self.remaining_ticks != Some(0)
+    }
 }
 
 pub type CronJob = fn(ctx: &mut JobContext);
 
+pub type AsyncCronJob = Arc<dyn Fn() -> BoxFuture<'static, AppResult> + Send + Sync>;
+
+#[derive(Clone)]
+pub enum JobExec {
+    // Runs on the scheduler loop.
+    Sync(CronJob),
+    // Spawned as a tokio task.
+    Async(AsyncCronJob),
+}
+
 pub struct Job {
     context: JobContext,
     /// Cron expression parser
     schedule: Schedule,
-    exec: CronJob,
+    exec: JobExec,
+    /// Next time the job is supposed to run, jitter included
+    next_tick: Option<DateTime<Utc>>,
 }
 
 impl Job {
@@ -98,36 +173,124 @@ impl Job {
     ///
     /// Panics if the cron expression is invalid
     pub fn new(cron_expr: &str, exec: CronJob) -> Self {
-        let schedule = Schedule::from_str(cron_expr)
-            .unwrap_or_else(|err| panic!("invalid cron expression: `{cron_expr}`: {err}"));
-        let mut context = JobContext::new();
-        context.set_source(cron_expr);
-        Self {
-            context,
-            schedule,
-            exec,
-        }
+        Self::build(cron_expr, JobExec::Sync(exec))
     }
+
+    /// Creates a new instance running an async closure
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cron expression is invalid
+    pub fn new_async<F, Fut>(cron_expr: &str, exec: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = AppResult> + Send + 'static,
+    {
+        Self::build(cron_expr, JobExec::Async(Arc::new(move || exec().boxed())))
+    }
+
     /// Creates a new instance with configuration
     ///
     /// # Panics
     ///
     /// Panics if the configuration is invalid
     pub fn with_config(config: &Table, exec: CronJob) -> Self {
-        let cron_expr = config.get_str("cron").unwrap_or_default();
+        Self::build_with_config(config, JobExec::Sync(exec))
+    }
+
+    /// Creates a new instance running an async closure with configuration
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is invalid
+    pub fn with_config_async<F, Fut>(config: &Table, exec: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = AppResult> + Send + 'static,
+    {
+        Self::build_with_config(config, JobExec::Async(Arc::new(move || exec().boxed())))
+    }
+
+    fn build(cron_expr: &str, exec: JobExec) -> Self {
         let schedule = Schedule::from_str(cron_expr)
             .unwrap_or_else(|err| panic!("invalid cron expression: `{cron_expr}`: {err}"));
         let mut context = JobContext::new();
-
-        if let Some(disabled) = config.get_bool("disabled") {
-            context.set_disabled_status(disabled);
-        }
-        // Create a new Job instance
+        context.set_source(cron_expr);
         Self {
             context,
             schedule,
             exec,
+            next_tick: None,
+        }
+    }
+
+    /* supported keys: cron, disabled, immediate, ticks, jitter (seconds) */
+    fn build_with_config(config: &Table, exec: JobExec) -> Self {
+        let cron_expr = config
+            .get("cron")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let mut job = Self::build(cron_expr, exec);
+
+        if let Some(disabled) = config.get("disabled").and_then(|v| v.as_bool()) {
+            job.context.set_disabled_status(disabled);
         }
+        if let Some(immediate) = config.get("immediate").and_then(|v| v.as_bool()) {
+            job.context.set_immediate(immediate);
+        }
+        if let Some(ticks) = config.get("ticks").and_then(|v| v.as_integer()) {
+            let ticks = usize::try_from(ticks)
+                .unwrap_or_else(|err| panic!("invalid job ticks: `{ticks}`: {err}"));
+            job.context.set_remaining_ticks(ticks);
+        }
+        if let Some(jitter) = config.get("jitter").and_then(|v| v.as_integer()) {
+            let jitter = u64::try_from(jitter)
+                .unwrap_or_else(|err| panic!("invalid job jitter: `{jitter}`: {err}"));
+            job.context.set_jitter(Duration::from_secs(jitter));
+        }
+        job
+    }
+
+    pub fn name(mut self, name: &'static str) -> Self {
+        self.context.set_name(name);
+        self
+    }
+
+    pub fn context_mut(&mut self) -> &mut JobContext {
+        &mut self.context
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.context.is_ready()
+    }
+
+    // Computes the next tick, the first one fires right away if the job is immediate.
+    fn schedule_next(&mut self) {
+        let now = Utc::now();
+        if self.context.immediate && self.context.last_tick.is_none() {
+            self.next_tick = Some(now);
+            return;
+        }
+        let jitter = match self.context.jitter {
+            Some(jitter) if !jitter.is_zero() => {
+                let millis = rand::thread_rng().gen_range(0..=jitter.as_millis() as u64);
+                chrono::Duration::milliseconds(millis as i64)
+            }
+            _ => chrono::Duration::zero(),
+        };
+        self.next_tick = self.schedule.after(&now).next().map(|t| t + jitter);
+    }
+
+    fn time_till_next_tick(&self) -> Option<Duration> {
+        if !self.is_ready() {
+            return None;
+        }
+        self.next_tick
+            .map(|t| (t - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+    }
+
+    fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.is_ready() && self.next_tick.is_some_and(|t| t <= now)
     }
 }
 
@@ -158,3 +321,122 @@ pub trait AsyncScheduler {
     // Runs the scheduler and returns an `std::io::Error` if failed.
     fn run(self) -> impl Future<Output = io::Result<()>> + Send;
 }
+
+/// Tokio based scheduler, async jobs are spawned so a slow job never delays the others
+pub struct JobScheduler {
+    jobs: Vec<Job>,
+    running: JoinSet<()>,
+    shutdown: Option<BoxFuture<'static, ()>>,
+}
+
+impl Default for JobScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobScheduler {
+    pub fn new() -> Self {
+        Self {
+            jobs: Vec::new(),
+            running: JoinSet::new(),
+            shutdown: None,
+        }
+    }
+
+    pub fn add_job(&mut self, job: Job) {
+        self.jobs.push(job);
+    }
+
+    /// Stops ticking once the signal resolves, running jobs are awaited before `run` returns
+    pub fn with_shutdown(mut self, signal: impl Future<Output = ()> + Send + 'static) -> Self {
+        self.shutdown = Some(signal.boxed());
+        self
+    }
+}
+
+impl AsyncScheduler for JobScheduler {
+    fn is_ready(&self) -> bool {
+        self.jobs.iter().any(|job| job.is_ready())
+    }
+
+    // Sync jobs run on the scheduler loop and block the following ticks.
+    fn is_blocking(&self) -> bool {
+        self.jobs
+            .iter()
+            .any(|job| matches!(job.exec, JobExec::Sync(_)))
+    }
+
+    fn time_till_next_job(&self) -> Option<Duration> {
+        self.jobs
+            .iter()
+            .filter_map(|job| job.time_till_next_tick())
+            .min()
+    }
+
+    async fn tick(&mut self) {
+        let now = Utc::now();
+        let running = &mut self.running;
+        for job in self.jobs.iter_mut().filter(|job| job.is_due(now)) {
+            job.context.start();
+            match &job.exec {
+                JobExec::Sync(exec) => {
+                    exec(&mut job.context);
+                    job.context.finish();
+                }
+                /* completion is logged by the spawned task, it has barely started here */
+                JobExec::Async(exec) => {
+                    let job_name = job.context.job_name.unwrap_or_default();
+                    let task = exec();
+                    running.spawn(async move {
+                        let start_time = Instant::now();
+                        match task.await {
+                            Ok(_) => info!(
+                                job_name,
+                                execution_time_millis = start_time.elapsed().as_millis(),
+                                "scheduled job finished"
+                            ),
+                            Err(e) => warn!(job_name, "scheduled job failed: {e:?}"),
+                        }
+                    });
+                    job.context.consume_tick();
+                }
+            }
+            job.schedule_next();
+        }
+        /* reap finished jobs */
+        while running.try_join_next().is_some() {}
+    }
+
+    async fn run(mut self) -> io::Result<()> {
+        let mut shutdown = self
+            .shutdown
+            .take()
+            .unwrap_or_else(|| future::pending().boxed());
+        for job in self.jobs.iter_mut() {
+            job.schedule_next();
+        }
+        info!("scheduler started with {} jobs", self.jobs.len());
+        loop {
+            match self.time_till_next_job() {
+                Some(wait) => {
+                    tokio::select! {
+                        _ = &mut shutdown => break,
+                        _ = tokio::time::sleep(wait) => self.tick().await,
+                    }
+                }
+                /* no job left to run */
+                None => {
+                    shutdown.await;
+                    break;
+                }
+            }
+        }
+        info!(
+            "scheduler shutting down, waiting for {} running jobs",
+            self.running.len()
+        );
+        while self.running.join_next().await.is_some() {}
+        Ok(())
+    }
+}
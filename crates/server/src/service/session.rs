@@ -1,58 +1,141 @@
 use crate::{
+    entity::session::SessionInfo,
     errors::{AppError, AppResult},
-    service::redis::{self, SessionKey},
-    utils::claim::UserClaims,
+    service::redis::{self, SessionInfoKey, SessionKey},
+    utils::{claim::UserClaims, header::ClientInfo},
 };
 use chrono::Utc;
 use db::redis::RedisClient;
 use log::info;
 use uuid::Uuid;
 
+/* `last_seen` is written at most once per interval to keep the auth path cheap */
+const TOUCH_INTERVAL_SECS: i64 = 60;
+
 pub async fn check(redis: &RedisClient, claims: &UserClaims) -> AppResult<Uuid> {
     let session_key = SessionKey { uuid: claims.uid };
-    let session_ids = redis::lrange(redis, &session_key, 0, -1)
-        .await?
-        .ok_or_else(|| {
-            AppError::NotFoundError(crate::errors::Resource {
-                details: vec![("session_key".to_string(), claims.sid.to_string())],
-                resource_type: crate::errors::ResourceType::Session,
-            })
-        })?
-        .iter()
-        .map(|item| Uuid::parse_str(item.trim_matches('"')).expect("Failed to parse session_id..."))
-        .collect::<Vec<Uuid>>();
+    let session_ids = list_ids(redis, claims.uid).await?;
+    /* only this token is rejected, the other sessions of the user stay valid */
     if !session_ids.contains(&claims.sid) {
-        info!("Session id invalid so delete it: {session_ids:?}.");
-        redis::del(redis, &session_key).await?;
+        info!(
+            "Session id {} is not active for user {}.",
+            claims.sid, claims.uid
+        );
         return Err(AppError::InvalidSessionError(
             "Invalid session id".to_string(),
         ));
     }
-    // tokio::time::sleep(Duration::from_secs(3)).await;
     if (claims.exp) < Utc::now().timestamp() {
-        info!("access_token expired so delete it: {session_ids:?}.");
+        info!("access_token expired so delete it: {}.", claims.sid);
         redis::lrem(redis, (&session_key, &claims.sid), 0).await?;
         return Err(AppError::UnauthorizedError(
             "access_token is expired".to_string(),
         ));
     }
+    touch(redis, claims.uid, claims.sid).await?;
     Ok(claims.uid)
 }
 
+async fn list_ids(redis: &RedisClient, uid: Uuid) -> AppResult<Vec<Uuid>> {
+    redis::lrange(redis, &SessionKey { uuid: uid }, 0, -1)
+        .await?
+        .unwrap_or_default()
+        .iter()
+        .map(|item| Ok(Uuid::parse_str(item.trim_matches('"'))?))
+        .collect()
+}
+
+/* sessions created before metadata was recorded get it on their next request */
+pub async fn touch(redis: &RedisClient, uid: Uuid, sid: Uuid) -> AppResult {
+    let key = SessionInfoKey { uuid: uid, sid };
+    let now = Utc::now();
+    let info = match redis::get(redis, &key).await? {
+        Some(info) if (now - info.last_seen).num_seconds() < TOUCH_INTERVAL_SECS => {
+            return Ok(());
+        }
+        Some(info) => SessionInfo {
+            last_seen: now,
+            ..info
+        },
+        None => SessionInfo {
+            sid,
+            created_at: now,
+            last_seen: now,
+            ip: None,
+            user_agent: None,
+        },
+    };
+    redis::set(redis, (&key, &info)).await
+}
+
+/* active sessions of the user, most recent login first */
+pub async fn list(redis: &RedisClient, uid: Uuid) -> AppResult<Vec<SessionInfo>> {
+    let mut ret = vec![];
+    for sid in list_ids(redis, uid).await? {
+        if let Some(info) = redis::get(redis, &SessionInfoKey { uuid: uid, sid }).await? {
+            ret.push(info);
+        }
+    }
+    Ok(ret)
+}
+
+/* drops sessions whose metadata expired, their tokens can not be used any more */
+pub async fn prune(redis: &RedisClient, uid: Uuid) -> AppResult {
+    for sid in list_ids(redis, uid).await? {
+        if !redis::check_exist(redis, &SessionInfoKey { uuid: uid, sid }).await? {
+            info!("Prune stale session {sid} of user {uid}.");
+            redis::lrem(redis, (&SessionKey { uuid: uid }, &sid), 0).await?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn exists(redis: &RedisClient, uid: Uuid, sid: Uuid) -> AppResult<bool> {
+    Ok(list_ids(redis, uid).await?.contains(&sid))
+}
+
 pub async fn delete(redis: &RedisClient, uid: Uuid, sid: Uuid) -> AppResult {
     let session_key = SessionKey { uuid: uid };
     redis::lrem(redis, (&session_key, &sid), 0).await?;
+    redis::del(redis, &SessionInfoKey { uuid: uid, sid }).await?;
     Ok(())
 }
 
 pub async fn destroy(redis: &RedisClient, uid: Uuid) -> AppResult {
+    for sid in list_ids(redis, uid).await? {
+        redis::del(redis, &SessionInfoKey { uuid: uid, sid }).await?;
+    }
     let session_key = SessionKey { uuid: uid };
     redis::del(redis, &session_key).await?;
     Ok(())
 }
 
+/* ends every session of the user except `keep` */
+pub async fn destroy_others(redis: &RedisClient, uid: Uuid, keep: Uuid) -> AppResult {
+    for sid in list_ids(redis, uid).await? {
+        if sid != keep {
+            delete(redis, uid, sid).await?;
+        }
+    }
+    Ok(())
+}
+
 pub async fn set(redis: &RedisClient, uuid: Uuid) -> AppResult<Uuid> {
+    start(redis, uuid, &ClientInfo::default()).await
+}
+
+/* new session for a login from `client` */
+pub async fn start(redis: &RedisClient, uuid: Uuid, client_info: &ClientInfo) -> AppResult<Uuid> {
     let (key, value) = generate(uuid);
+    let now = Utc::now();
+    let info = SessionInfo {
+        sid: value,
+        created_at: now,
+        last_seen: now,
+        ip: client_info.ip.clone(),
+        user_agent: client_info.user_agent.clone(),
+    };
+    redis::set(redis, (&SessionInfoKey { uuid, sid: value }, &info)).await?;
     redis::lpush(redis, (&key, &value)).await?;
     Ok(value)
 }
@@ -0,0 +1,472 @@
+use std::collections::HashMap;
+
+use jsonwebtoken::{jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use ldap3::{ldap_escape, LdapConnAsync, Scope, SearchEntry};
+use rand::RngCore;
+use reqwest::Url;
+use serde::Deserialize;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    configure::sso::{ConfigGroupMapping, ConfigLDAP, ConfigOIDC},
+    constant::{EXPIRE_OIDC_STATE_SECS, OIDC_BINDING_COOKIE},
+    dao::user::UserDao,
+    dto::{
+        request::user::{LdapLoginRequest, OidcCallbackParam},
+        response::user::{LoginResponse, OidcAuthorizeResponse},
+    },
+    entity::user::User,
+    errors::{message::SsoException, AppError, AppResult},
+    service::{
+        api_token::{hash_token, random_secret},
+        lockout, mfa, permission,
+        redis::{self, OidcState, OidcStateKey},
+    },
+    state::AppState,
+    utils::{self, header::ClientInfo},
+};
+
+pub const PROVIDER_OIDC: &str = "OIDC";
+pub const PROVIDER_LDAP: &str = "LDAP";
+
+/* user as asserted by the identity provider */
+#[derive(Debug, Clone)]
+pub struct SsoIdentity {
+    pub provider: &'static str,
+    /* oidc issuer or ldap url */
+    pub issuer: String,
+    /* oidc `sub` or ldap dn, the only stable key of the identity */
+    pub subject: String,
+    /* name of the account created on the first login */
+    pub username: String,
+    pub email: Option<String>,
+    pub groups: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OidcDiscovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OidcTokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OidcClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    preferred_username: Option<String>,
+    #[serde(flatten)]
+    extra: HashMap<String, serde_json::Value>,
+}
+
+fn invalid_credential() -> AppError {
+    AppError::UnauthorizedError(SsoException::InvalidCredential.to_string())
+}
+
+fn random_hex() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/* role per project granted by the groups, the first matching mapping of a project wins */
+pub fn map_groups(mappings: &[ConfigGroupMapping], groups: &[String]) -> Vec<(i32, String)> {
+    let mut ret: Vec<(i32, String)> = vec![];
+    for mapping in mappings.iter() {
+        if groups.contains(&mapping.group) && !ret.iter().any(|(id, _)| *id == mapping.project_id) {
+            ret.push((mapping.project_id, mapping.role.clone()));
+        }
+    }
+    ret
+}
+
+fn oidc_config(state: &AppState) -> AppResult<&ConfigOIDC> {
+    state
+        .config
+        .sso
+        .oidc
+        .as_ref()
+        .ok_or_else(|| AppError::BadRequestError(SsoException::NotConfigured.to_string()))
+}
+
+async fn oidc_discovery(state: &AppState, config: &ConfigOIDC) -> AppResult<OidcDiscovery> {
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        config.issuer.trim_end_matches('/')
+    );
+    let discovery: OidcDiscovery = state
+        .http
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    if discovery.issuer.trim_end_matches('/') != config.issuer.trim_end_matches('/') {
+        return Err(AppError::BadRequestError(format!(
+            "issuer mismatch: {}",
+            discovery.issuer
+        )));
+    }
+    Ok(discovery)
+}
+
+/* the returned binding is set as a cookie, the callback only accepts the state from the same browser */
+pub async fn oidc_authorize(state: &AppState) -> AppResult<(OidcAuthorizeResponse, String)> {
+    let config = oidc_config(state)?;
+    let discovery = oidc_discovery(state, config).await?;
+    let csrf_state = random_hex();
+    let nonce = random_hex();
+    let binding = random_secret();
+    redis::set(
+        &state.redis,
+        (
+            &OidcStateKey {
+                state: csrf_state.clone(),
+            },
+            &OidcState {
+                nonce: nonce.clone(),
+                binding_hash: hash_token(&binding),
+            },
+        ),
+    )
+    .await?;
+    let url = Url::parse_with_params(
+        &discovery.authorization_endpoint,
+        &[
+            ("response_type", "code"),
+            ("client_id", config.client_id.as_str()),
+            ("redirect_uri", config.redirect_uri.as_str()),
+            ("scope", config.scopes.join(" ").as_str()),
+            ("state", csrf_state.as_str()),
+            ("nonce", nonce.as_str()),
+        ],
+    )
+    .map_err(|e| AppError::BadRequestError(e.to_string()))?;
+    Ok((
+        OidcAuthorizeResponse {
+            url: url.to_string(),
+        },
+        binding,
+    ))
+}
+
+/* the callback is reached by a redirect from the provider, so the cookie has to be sent cross site */
+pub fn binding_cookie(state: &AppState, binding: &str) -> String {
+    let secure = state
+        .config
+        .sso
+        .oidc
+        .as_ref()
+        .is_some_and(|oidc| oidc.redirect_uri.starts_with("https://"));
+    format!(
+        "{OIDC_BINDING_COOKIE}={binding}; Path=/auth/sso/oidc; Max-Age={}; HttpOnly; SameSite=Lax{}",
+        EXPIRE_OIDC_STATE_SECS.as_secs(),
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+async fn verify_id_token(
+    state: &AppState,
+    config: &ConfigOIDC,
+    discovery: &OidcDiscovery,
+    id_token: &str,
+    nonce: &str,
+) -> AppResult<OidcClaims> {
+    let header = jsonwebtoken::decode_header(id_token).map_err(|_| invalid_credential())?;
+    /* a symmetric alg would turn the public jwk into a shared secret */
+    if matches!(
+        header.alg,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+    ) {
+        return Err(invalid_credential());
+    }
+    let jwks: JwkSet = state
+        .http
+        .get(&discovery.jwks_uri)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let jwk = match header.kid.as_deref() {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+    .ok_or_else(invalid_credential)?;
+    let key = DecodingKey::from_jwk(jwk)?;
+    let mut validation = Validation::new(header.alg);
+    validation.set_issuer(&[&discovery.issuer]);
+    validation.set_audience(&[&config.client_id]);
+    let claims = jsonwebtoken::decode::<OidcClaims>(id_token, &key, &validation)
+        .map_err(|e| {
+            warn!("Failed to verify id token: {e:?}");
+            invalid_credential()
+        })?
+        .claims;
+    if claims.nonce.as_deref() != Some(nonce) {
+        return Err(invalid_credential());
+    }
+    Ok(claims)
+}
+
+pub async fn oidc_callback(
+    state: &AppState,
+    param: OidcCallbackParam,
+    binding: Option<&str>,
+    client_info: &ClientInfo,
+) -> AppResult<LoginResponse> {
+    let config = oidc_config(state)?;
+    let key = OidcStateKey {
+        state: param.state.clone(),
+    };
+    let invalid_state = || AppError::BadRequestError(SsoException::InvalidState.to_string());
+    let saved = redis::get(&state.redis, &key)
+        .await?
+        .ok_or_else(invalid_state)?;
+    /* state is single use, only the request that deleted it goes on */
+    if !redis::del(&state.redis, &key).await? {
+        return Err(invalid_state());
+    }
+    if binding.map(hash_token).as_deref() != Some(saved.binding_hash.as_str()) {
+        warn!("Oidc state used by another browser");
+        return Err(invalid_state());
+    }
+    let discovery = oidc_discovery(state, config).await?;
+    let resp = state
+        .http
+        .post(&discovery.token_endpoint)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", param.code.as_str()),
+            ("redirect_uri", config.redirect_uri.as_str()),
+            ("client_id", config.client_id.as_str()),
+            ("client_secret", config.client_secret.as_str()),
+        ])
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        warn!("Failed to exchange code, status: {}", resp.status());
+        return Err(invalid_credential());
+    }
+    let tokens: OidcTokenResponse = resp.json().await?;
+    let claims = verify_id_token(state, config, &discovery, &tokens.id_token, &saved.nonce).await?;
+    info!("oidc login with subject: {}", claims.sub);
+    let groups = claims
+        .extra
+        .get(&config.groups_claim)
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let identity = SsoIdentity {
+        provider: PROVIDER_OIDC,
+        issuer: discovery.issuer,
+        username: claims.preferred_username.unwrap_or(claims.sub.clone()),
+        subject: claims.sub,
+        email: claims.email,
+        groups,
+    };
+    login(state, identity, client_info).await
+}
+
+fn ldap_config(state: &AppState) -> AppResult<&ConfigLDAP> {
+    state
+        .config
+        .sso
+        .ldap
+        .as_ref()
+        .ok_or_else(|| AppError::BadRequestError(SsoException::NotConfigured.to_string()))
+}
+
+fn ldap_attr(entry: &SearchEntry, name: &str) -> Vec<String> {
+    entry
+        .attrs
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, values)| values.clone())
+        .unwrap_or_default()
+}
+
+/* search the user with the service account, then bind as the user to check the password */
+async fn ldap_authenticate(
+    config: &ConfigLDAP,
+    request: &LdapLoginRequest,
+) -> AppResult<SsoIdentity> {
+    let (conn, mut ldap) = LdapConnAsync::new(&config.url).await?;
+    ldap3::drive!(conn);
+    ldap.simple_bind(&config.bind_dn, &config.bind_password)
+        .await?
+        .success()?;
+    let filter = config
+        .user_filter
+        .replace("{username}", &ldap_escape(request.username.as_str()));
+    let (entries, _) = ldap
+        .search(
+            &config.base_dn,
+            Scope::Subtree,
+            &filter,
+            vec![
+                config.email_attribute.as_str(),
+                config.group_attribute.as_str(),
+            ],
+        )
+        .await?
+        .success()?;
+    if entries.len() != 1 {
+        info!(
+            "ldap search returned {} entries for {}",
+            entries.len(),
+            request.username
+        );
+        return Err(invalid_credential());
+    }
+    let entry = SearchEntry::construct(entries.into_iter().next().unwrap());
+    let bind = ldap.simple_bind(&entry.dn, &request.password).await?;
+    let _ = ldap.unbind().await;
+    bind.success().map_err(|_| invalid_credential())?;
+    Ok(SsoIdentity {
+        provider: PROVIDER_LDAP,
+        issuer: config.url.clone(),
+        subject: entry.dn.clone(),
+        username: request.username.clone(),
+        email: ldap_attr(&entry, &config.email_attribute)
+            .into_iter()
+            .next(),
+        groups: ldap_attr(&entry, &config.group_attribute),
+    })
+}
+
+pub async fn ldap_login(
+    state: &AppState,
+    request: LdapLoginRequest,
+    client_info: &ClientInfo,
+) -> AppResult<LoginResponse> {
+    let config = ldap_config(state)?;
+    /* throttled like password logins, failures of both count against the same username */
+    lockout::check(state, &request.username, client_info).await?;
+    let identity = match ldap_authenticate(config, &request).await {
+        Ok(identity) => identity,
+        Err(e @ AppError::UnauthorizedError(_)) => {
+            lockout::record_failure(state, &request.username, client_info).await?;
+            return Err(e);
+        }
+        Err(e) => return Err(e),
+    };
+    lockout::reset(state, &request.username).await?;
+    login(state, identity, client_info).await
+}
+
+/* users are found by the identity linked on their first login, never by the editable name */
+async fn provision(state: &AppState, identity: &SsoIdentity) -> AppResult<User> {
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let user_dao = UserDao::new(&transaction);
+    match user_dao
+        .find_by_sso_identity(identity.provider, &identity.issuer, &identity.subject)
+        .await?
+    {
+        Some((_, true)) => return Err(AppError::ForbiddenError("user is deleted".to_string())),
+        Some((user, false)) => return Ok(user),
+        None => {}
+    }
+    let username = identity.username.to_lowercase();
+    match user_dao.find_by_username(username.clone()).await {
+        Ok(_) => {
+            warn!(
+                "Refuse sso login of {} onto local user {username}",
+                identity.subject
+            );
+            return Err(AppError::ForbiddenError(
+                SsoException::AccountNotLinked.to_string(),
+            ));
+        }
+        Err(AppError::NotFoundError(_)) => {}
+        Err(e) => return Err(e),
+    }
+    let email = identity
+        .email
+        .as_deref()
+        .ok_or_else(|| AppError::BadRequestError(SsoException::MissingEmail.to_string()))?;
+    user_dao.check_unique_by_email(email).await?;
+    info!("Provision sso user: {username}, email: {email}");
+    /* password login stays unusable until the user resets it */
+    let password = utils::password::generate()?;
+    let hashed_password = utils::password::hash(password).await?;
+    let mut new_user = User::new(&username, &hashed_password, email, Uuid::nil(), true);
+    new_user.created_by = new_user.uuid;
+    new_user.id = user_dao.insert(&new_user).await?;
+    /* a concurrent first login of the same identity fails on the unique key */
+    user_dao
+        .insert_sso_identity(
+            &new_user.uuid,
+            identity.provider,
+            &identity.issuer,
+            &identity.subject,
+        )
+        .await?;
+    transaction.commit().await?;
+    Ok(new_user)
+}
+
+/* grants and updates the mapped roles, memberships without a mapping are left as they are */
+async fn apply_group_mapping(state: &AppState, uid: Uuid, groups: &[String]) -> AppResult {
+    let roles = map_groups(&state.config.sso.group_mapping, groups);
+    if roles.is_empty() {
+        return Ok(());
+    }
+    let mut changed = vec![];
+    {
+        let mut client = state.pool.get().await?;
+        let transaction = client.transaction().await?;
+        let user_dao = UserDao::new(&transaction);
+        for (project_id, role_name) in roles.iter() {
+            let role_id = user_dao.get_role_id_by_name(role_name).await?;
+            match user_dao.get_member_role(&uid, project_id).await? {
+                Some(role) if role.id == role_id => continue,
+                Some(_) => {
+                    user_dao
+                        .update_user_role_relation(&uid, project_id, role_id, uid)
+                        .await?
+                }
+                None => {
+                    user_dao
+                        .insert_user_role_relation(uid, role_id, *project_id, uid)
+                        .await?;
+                }
+            }
+            changed.push(*project_id);
+        }
+        transaction.commit().await?;
+    }
+    for project_id in changed.iter() {
+        permission::invalidate_member(state, &uid, project_id).await?;
+    }
+    Ok(())
+}
+
+async fn login(
+    state: &AppState,
+    identity: SsoIdentity,
+    client_info: &ClientInfo,
+) -> AppResult<LoginResponse> {
+    let user = provision(state, &identity).await?;
+    if !user.enable {
+        return Err(AppError::ForbiddenError("user is disabled".to_string()));
+    }
+    apply_group_mapping(state, user.uuid, &identity.groups).await?;
+    mfa::complete_login(state, &user, client_info).await
+}
@@ -1,40 +1,69 @@
 use crate::{
     constant::{
-        ACCESS_TOKEN_ENCODE_KEY, EXPIRE_REFRESH_TOKEN_SECS, EXPIRE_SESSION_CODE_SECS,
-        PAGE_DECODE_KEY, PAGE_ENCODE_KEY, REFRESH_TOKEN_DECODE_KEY, REFRESH_TOKEN_ENCODE_KEY,
+        ACCESS_TOKEN_KEYS, EXPIRE_REFRESH_TOKEN_SECS, EXPIRE_SESSION_CODE_SECS, REFRESH_TOKEN_KEYS,
     },
     dao::user,
     dto::{request::RefreshTokenRequest, response::user::TokenResponse},
-    errors::AppResult,
-    service,
+    errors::{message::SessionException, AppError, AppResult},
+    service::{
+        redis::{self, RefreshTokenKey},
+        session,
+    },
     state::AppState,
-    utils::claim::{PageClaims, UserClaims},
+    utils::{
+        claim::UserClaims,
+        jwk::{KeySet, KeyUsage},
+    },
 };
 
-use tracing::info;
+use db::redis::RedisClient;
+use jsonwebtoken::jwk::JwkSet;
+use tracing::{info, warn};
 use uuid::Uuid;
 
+/* every refresh token is single use, presenting a used one revokes its session */
 pub async fn refresh(state: &AppState, request: RefreshTokenRequest) -> AppResult<TokenResponse> {
-    let user_claims = UserClaims::decode(&request.refresh_token, &REFRESH_TOKEN_DECODE_KEY)?.claims;
+    let user_claims = UserClaims::decode(&request.refresh_token, &REFRESH_TOKEN_KEYS)?.claims;
     info!("Refresh token: {user_claims:?}.");
     let client = state.pool.get().await?;
     let user_dao = user::UserDao::new(&client);
     let user = user_dao.find_by_uid(&user_claims.uid).await?;
-    let session_id = service::session::set(&state.redis, user.uuid).await?;
-    info!("Set new session for user: {}", user.uuid);
-    let resp = generate_tokens(user.uuid, session_id)?;
-    Ok(TokenResponse::new(
-        resp.access_token,
-        request.refresh_token,
-        EXPIRE_SESSION_CODE_SECS.as_secs(),
-    ))
+    let (uid, sid) = (user.uuid, user_claims.sid);
+    if !session::exists(&state.redis, uid, sid).await? {
+        return Err(AppError::InvalidSessionError(
+            SessionException::Invalid.to_string(),
+        ));
+    }
+    /* tokens issued before rotation carry no jti and can't be used only once, log in again */
+    let Some(jti) = user_claims.jti else {
+        return Err(AppError::UnauthorizedError(
+            SessionException::RefreshTokenOutdated.to_string(),
+        ));
+    };
+    if !redis::del(&state.redis, &RefreshTokenKey { jti }).await? {
+        warn!("Refresh token {jti} reused, revoke session {sid} of user {uid}");
+        session::delete(&state.redis, uid, sid).await?;
+        return Err(AppError::UnauthorizedError(
+            SessionException::RefreshTokenReused.to_string(),
+        ));
+    }
+    session::touch(&state.redis, uid, sid).await?;
+    session::prune(&state.redis, uid).await?;
+    generate_tokens(&state.redis, uid, sid).await
 }
 
-pub fn generate_tokens(uuid: Uuid, session_id: Uuid) -> AppResult<TokenResponse> {
-    let access_token = UserClaims::new(EXPIRE_SESSION_CODE_SECS, uuid, session_id)
-        .encode(&ACCESS_TOKEN_ENCODE_KEY)?;
+pub async fn generate_tokens(
+    redis: &RedisClient,
+    uuid: Uuid,
+    session_id: Uuid,
+) -> AppResult<TokenResponse> {
+    let jti = Uuid::new_v4();
+    let access_token =
+        UserClaims::new(EXPIRE_SESSION_CODE_SECS, uuid, session_id).encode(&ACCESS_TOKEN_KEYS)?;
     let refresh_token = UserClaims::new(EXPIRE_REFRESH_TOKEN_SECS, uuid, session_id)
-        .encode(&REFRESH_TOKEN_ENCODE_KEY)?;
+        .with_jti(jti)
+        .encode(&REFRESH_TOKEN_KEYS)?;
+    redis::set(redis, (&RefreshTokenKey { jti }, &session_id)).await?;
     Ok(TokenResponse::new(
         access_token,
         refresh_token,
@@ -42,18 +71,31 @@ pub fn generate_tokens(uuid: Uuid, session_id: Uuid) -> AppResult<TokenResponse>
     ))
 }
 
-pub fn generate_page_token(
-    page_size: i64,
-    page_num: i64,
-    last_item_id: i32,
-    module_ids: Vec<i32>,
-) -> AppResult<String> {
-    let page_token =
-        PageClaims::new(page_size, page_num, last_item_id, module_ids).encode(&PAGE_ENCODE_KEY)?;
-    Ok(page_token)
+/* picks up keys added to or retired from `jwt.key_dir`, a broken manifest keeps the current keys */
+pub async fn reload_keys(state: &AppState) -> AppResult {
+    if state.config.jwt.key_dir.is_none() {
+        return Ok(());
+    }
+    let access = KeySet::load(&state.config.jwt, KeyUsage::Access)?;
+    let refresh = KeySet::load(&state.config.jwt, KeyUsage::Refresh)?;
+    for (store, keys) in [
+        (&*ACCESS_TOKEN_KEYS, access),
+        (&*REFRESH_TOKEN_KEYS, refresh),
+    ] {
+        let current = store.current();
+        if current.primary() != keys.primary() || current.kids() != keys.kids() {
+            info!(
+                "Reload signing keys {:?}, primary: {}",
+                keys.kids(),
+                keys.primary()
+            );
+        }
+        store.replace(keys);
+    }
+    Ok(())
 }
 
-pub fn parse_page_token(page_token: String) -> AppResult<PageClaims> {
-    let page_claims = PageClaims::decode(&page_token, &PAGE_DECODE_KEY)?.claims;
-    Ok(page_claims)
+/* only access tokens are meant to be verified by other services */
+pub fn jwks() -> JwkSet {
+    ACCESS_TOKEN_KEYS.jwks()
 }
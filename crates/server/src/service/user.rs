@@ -1,66 +1,148 @@
-use chrono::Utc;
+use std::collections::HashSet;
+
 use tracing::{error, info};
 use uuid::Uuid;
 
 use crate::{
-    constant::REGISTER_EMAIL_SUBJECT,
-    dao::{permission::PermissionDao, user::UserDao},
+    constant::RESET_PASSWORD_EMAIL_SUBJECT,
+    dao::{job::JobDao, permission::PermissionDao, user::UserDao},
     dto::{
         request::{
-            user::{LoginRequest, UpdateUserStatusRequest},
+            user::{
+                ChangePasswordRequest, ForceLogoutRequest, ForgotPasswordRequest, LoginRequest,
+                ResetPasswordRequest, UpdateUserStatusRequest,
+            },
             *,
         },
         response::{
-            user::{GetUserInfoResponse, LoginResponse},
-            CreateEntityResponse, MessageResponse,
+            user::{GetUserInfoResponse, ListSessionResponse, LoginResponse},
+            CreateEntityResponse, MessageResponse, UpdateRoleResponse,
         },
         EmailTemplate,
     },
-    entity::user::{User, UserRole, UserRoleOption, UserRolePermission},
-    errors::{AppError, AppResult, Resource, ResourceType},
+    entity::{
+        job::JobKind,
+        user::{User, UserRole, UserRoleOption, UserRolePermission},
+    },
+    errors::{
+        message::{PasswordException, RoleException},
+        AppError, AppResult, Resource, ResourceType,
+    },
     service::{
-        redis::{self, SessionKey},
-        session, token,
+        api_token::{hash_token, random_secret},
+        invitation::{self, InvitationJob},
+        lockout, mfa,
+        permission::{self, diff_permissions},
+        redis::{self, PasswordResetKey, PasswordResetThrottleKey},
+        session,
     },
     state::AppState,
-    utils::{self, claim::UserClaims, smtp},
+    utils::{self, claim::UserClaims, header::ClientInfo, smtp},
 };
-/* 用户注册 */
+/* 用户注册, 全部校验通过后在同一事务中创建, 邀请邮件提交后由后台任务发送 */
 pub async fn batch_register(state: &AppState, uid: Uuid, request: RegisterRequest) -> AppResult {
     info!("Register a new user request: {request:?}.");
-    /* TODO: 新增逻辑批量创建用户 */
-    for item in request.user_info_list {
-        register(state, item.username, item.email, uid).await?;
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let user_dao = UserDao::new(&transaction);
+    /* 逐行校验, 汇总所有错误后一并返回 */
+    let mut report = garde::Report::new();
+    let mut usernames = HashSet::new();
+    let mut emails = HashSet::new();
+    for (idx, item) in request.user_info_list.iter().enumerate() {
+        let username = item.username.to_lowercase();
+        if !usernames.insert(username.clone()) {
+            report.append(
+                garde::Path::new(idx).join("username"),
+                garde::Error::new("duplicated in the batch"),
+            );
+        }
+        if !emails.insert(item.email.to_lowercase()) {
+            report.append(
+                garde::Path::new(idx).join("email"),
+                garde::Error::new("duplicated in the batch"),
+            );
+        }
+        if let Some(field) =
+            check_unique_username_or_email(&user_dao, &username, &item.email).await?
+        {
+            report.append(
+                garde::Path::new(idx).join(field),
+                garde::Error::new("already exists"),
+            );
+        }
+    }
+    if report.iter().next().is_some() {
+        return Err(AppError::InvalidInputError(report));
+    }
+    let job_dao = JobDao::new(&transaction);
+    for item in request.user_info_list.iter() {
+        /* 生成随机密码, 用户通过邀请链接设置自己的密码 */
+        let password = utils::password::generate()?;
+        let hashed_password = utils::password::hash(password).await?;
+        let new_user = User::new(&item.username, &hashed_password, &item.email, uid, true);
+        user_dao.insert(&new_user).await?;
+        user_dao
+            .set_password_change_required(&new_user.uuid, true)
+            .await?;
+        let payload = serde_json::to_string(&InvitationJob {
+            uuid: new_user.uuid,
+        })?;
+        job_dao
+            .create(
+                JobKind::SendInvitation,
+                &payload,
+                state.config.job.max_attempts,
+                &uid,
+            )
+            .await?;
     }
+    transaction.commit().await?;
     Ok(())
 }
 
-/* 单个用户注册 */
-pub async fn register(
-    state: &AppState,
-    username: String,
-    email: String,
-    created_by: Uuid,
-) -> AppResult {
-    info!("Register new user with username: {username}, email: {email}");
-    check_unique_username_or_email(state, &username, &email).await?;
-    /* 生成随机密码 */
-    let password = utils::password::generate()?;
-    let hashed_password = utils::password::hash(password.clone()).await?;
-    let new_user = User::new(&username, &hashed_password, &email, created_by, true);
-    let mut client = state.pool.get().await?;
-    let transaction = client.transaction().await?;
-    let user_dao = UserDao::new(&transaction);
-    match user_dao.insert(&new_user).await {
-        Ok(_) => {
-            /* 增加邮件发送逻辑 */
-            let template = EmailTemplate::Register { username, password };
-            smtp::send(&state.email, &template, REGISTER_EMAIL_SUBJECT, &email).await?;
-            transaction.commit().await?;
-            Ok(())
+/* 解析用户 csv, 每行为 username,email, 首行表头可选 */
+pub fn parse_user_csv(content: &str) -> AppResult<Vec<UserInfo>> {
+    let mut report = garde::Report::new();
+    let mut ret = vec![];
+    /* 空行由 csv reader 跳过, 行号取自记录位置 */
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+    for (idx, record) in reader.records().enumerate() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map_or(idx as u64 + 1, |p| p.line());
+                report.append(
+                    garde::Path::new("file").join(line as usize),
+                    garde::Error::new(format!("invalid csv: {e}")),
+                );
+                continue;
+            }
+        };
+        let line = record.position().map_or(idx as u64 + 1, |p| p.line()) as usize;
+        match record.iter().collect::<Vec<_>>().as_slice() {
+            [username, email]
+                if idx == 0
+                    && username.eq_ignore_ascii_case("username")
+                    && email.eq_ignore_ascii_case("email") => {}
+            [username, email] => ret.push(UserInfo {
+                username: username.to_string(),
+                email: email.to_string(),
+            }),
+            _ => report.append(
+                garde::Path::new("file").join(line),
+                garde::Error::new("expected username,email"),
+            ),
         }
-        Err(e) => Err(e),
     }
+    if report.iter().next().is_some() {
+        return Err(AppError::InvalidInputError(report));
+    }
+    Ok(ret)
 }
 
 /* 用户删除 */
@@ -68,6 +150,7 @@ pub async fn batch_delete(state: &AppState, operator: Uuid, uids: Vec<i32>) -> A
     let mut client = state.pool.get().await?;
     let transaction = client.transaction().await?;
     let user_dao = UserDao::new(&transaction);
+    let mut uuids = vec![];
     /* 用户信息删除 */
     for &id in uids.iter() {
         info!("delete user with id: {id}");
@@ -82,8 +165,12 @@ pub async fn batch_delete(state: &AppState, operator: Uuid, uids: Vec<i32>) -> A
         session::destroy(&state.redis, user.uuid).await?;
         /* 数据库软删除 */
         user_dao.soft_deleted_user(operator, &id).await?;
+        uuids.push(user.uuid);
     }
     transaction.commit().await?;
+    for uuid in uuids.iter() {
+        permission::invalidate_user(state, uuid).await?;
+    }
     Ok(())
 }
 
@@ -92,6 +179,7 @@ pub async fn update_status(state: &AppState, request: UpdateUserStatusRequest) -
     let mut client = state.pool.get().await?;
     let transaction = client.transaction().await?;
     let user_dao = UserDao::new(&transaction);
+    let mut uuids = vec![];
     for id in request.select_ids.iter() {
         let user = user_dao.find_by_id(&id).await?;
         if user.enable == request.enable {
@@ -100,37 +188,156 @@ pub async fn update_status(state: &AppState, request: UpdateUserStatusRequest) -
                 resource_type: ResourceType::User,
             }));
         };
+        uuids.push(user.uuid);
     }
     user_dao
         .batch_update_user_status(request.enable, request.select_ids)
         .await?;
     transaction.commit().await?;
+    for uuid in uuids.iter() {
+        permission::invalidate_user(state, uuid).await?;
+    }
     Ok(())
 }
 
 /* 用户登录 */
-pub async fn login(state: &AppState, request: LoginRequest) -> AppResult<LoginResponse> {
+pub async fn login(
+    state: &AppState,
+    request: LoginRequest,
+    client_info: &ClientInfo,
+) -> AppResult<LoginResponse> {
+    let username = request.username.to_lowercase();
+    /* 连续失败的用户名或IP需等待或被临时锁定 */
+    lockout::check(state, &username, client_info).await?;
     let client = state.pool.get().await?;
     let user_dao = UserDao::new(&client);
-    let username = request.username.to_lowercase();
-    match user_dao.find_by_username(username).await {
-        Ok(user) => {
-            /* 校验用户密码 */
-            utils::password::verify(request.password.clone(), user.hashed_password.clone()).await?;
-            /* 用户是否处于启用状态 */
-            if !user.enable {
-                Err(AppError::ForbiddenError("user is disabled".to_string()))
-            } else {
-                /* 生成token */
-                let session_id = session::set(&state.redis, user.uuid).await?;
-                let resp = token::generate_tokens(user.uuid, session_id)?;
-                Ok(LoginResponse::Token(resp))
-            }
+    let user = match user_dao.find_by_username(username.clone()).await {
+        Ok(user) => Some(user),
+        Err(AppError::NotFoundError(_)) => None,
+        Err(e) => return Err(e),
+    };
+    /* 校验用户密码 */
+    let verified = match &user {
+        Some(user) => {
+            utils::password::verify(request.password.clone(), user.hashed_password.clone())
+                .await
+                .is_ok()
         }
-        Err(_) => Err(AppError::BadRequestError(
+        None => {
+            utils::password::verify_dummy(request.password.clone()).await;
+            false
+        }
+    };
+    let Some(user) = user.filter(|_| verified) else {
+        lockout::record_failure(state, &username, client_info).await?;
+        return Err(AppError::BadRequestError(
             "Error username/password".to_string(),
-        )),
+        ));
+    };
+    lockout::reset(state, &username).await?;
+    /* 用户是否处于启用状态 */
+    if !user.enable {
+        Err(AppError::ForbiddenError("user is disabled".to_string()))
+    } else if user_dao.is_password_change_required(&user.uuid).await? {
+        /* 密码并非用户自己设置, 需通过邀请凭证重新设置 */
+        let ticket = invitation::create(state, user.uuid).await?;
+        Ok(LoginResponse::PasswordChangeRequired { ticket })
+    } else {
+        /* 开启两步验证的用户需再提交验证码, 否则生成token */
+        mfa::complete_login(state, &user, client_info).await
+    }
+}
+
+/* 忘记密码, 邮箱不存在时同样返回成功, 避免泄露账号信息 */
+pub async fn forgot_password(state: &AppState, request: ForgotPasswordRequest) -> AppResult {
+    let throttle_key = PasswordResetThrottleKey {
+        email: request.email.to_lowercase(),
+    };
+    if redis::check_exist(&state.redis, &throttle_key).await? {
+        return Err(AppError::TooManyRequestsError(
+            PasswordException::ResetThrottled.to_string(),
+        ));
+    }
+    redis::set(&state.redis, (&throttle_key, &true)).await?;
+    let user = {
+        let client = state.pool.get().await?;
+        UserDao::new(&client).find_by_email(&request.email).await?
+    };
+    let Some(user) = user.filter(|user| user.enable) else {
+        info!("No enabled user to reset password for");
+        return Ok(());
+    };
+    let token = random_secret();
+    redis::set(
+        &state.redis,
+        (
+            &PasswordResetKey {
+                token_hash: hash_token(&token),
+            },
+            &user.uuid,
+        ),
+    )
+    .await?;
+    let template = EmailTemplate::ForgetPassword {
+        username: user.username,
+        link: format!(
+            "{}/password/reset?token={token}",
+            state.config.http.web_url.trim_end_matches('/')
+        ),
+    };
+    smtp::send(
+        &state.email,
+        &template,
+        RESET_PASSWORD_EMAIL_SUBJECT,
+        &user.email,
+    )
+    .await
+}
+
+/* 通过邮件中的凭证重置密码, 所有会话失效 */
+pub async fn reset_password(state: &AppState, request: ResetPasswordRequest) -> AppResult {
+    utils::password::check_strength(&state.config.password, &request.password)?;
+    let key = PasswordResetKey {
+        token_hash: hash_token(&request.token),
+    };
+    let invalid = || AppError::BadRequestError(PasswordException::InvalidResetToken.to_string());
+    let uid = redis::get(&state.redis, &key).await?.ok_or_else(invalid)?;
+    if !redis::del(&state.redis, &key).await? {
+        return Err(invalid());
+    }
+    let hashed_password = utils::password::hash(request.password).await?;
+    {
+        let client = state.pool.get().await?;
+        UserDao::new(&client)
+            .update_password(&uid, &hashed_password, false)
+            .await?;
     }
+    info!("User reset password: {uid}");
+    session::destroy(&state.redis, uid).await?;
+    Ok(())
+}
+
+/* 修改密码, 仅保留当前会话 */
+pub async fn change_password(
+    state: &AppState,
+    uid: Uuid,
+    sid: Uuid,
+    request: ChangePasswordRequest,
+) -> AppResult {
+    utils::password::check_strength(&state.config.password, &request.new_password)?;
+    let client = state.pool.get().await?;
+    let user_dao = UserDao::new(&client);
+    let user = user_dao.find_by_uid(&uid).await?;
+    utils::password::verify(request.old_password, user.hashed_password)
+        .await
+        .map_err(|_| AppError::BadRequestError(PasswordException::WrongPassword.to_string()))?;
+    let hashed_password = utils::password::hash(request.new_password).await?;
+    user_dao
+        .update_password(&uid, &hashed_password, false)
+        .await?;
+    info!("User changed password: {uid}");
+    session::destroy_others(&state.redis, uid, sid).await?;
+    Ok(())
 }
 
 /* 用户登出 */
@@ -142,38 +349,45 @@ pub async fn logout(state: &AppState, uid: Uuid, sid: Uuid) -> AppResult<Message
     })
 }
 
-/* 用户是否已经登录 */
-pub async fn is_login(state: &AppState, claims: UserClaims) -> AppResult<LoginResponse> {
+/* 用户是否已经登录, 不再签发新的token, 续期通过 refresh token 完成 */
+pub async fn is_login(state: &AppState, claims: UserClaims) -> AppResult<MessageResponse> {
     info!("user service layer check whether user is login or not");
-    let session_key = SessionKey { uuid: claims.uid };
-    let session_ids = redis::lrange(&state.redis, &session_key, 0, -1)
-        .await?
-        .ok_or_else(|| {
-            AppError::NotFoundError(Resource {
-                details: vec![("session_key".to_string(), claims.sid.to_string())],
-                resource_type: ResourceType::Session,
-            })
-        })?
-        .iter()
-        .map(|item| Ok(Uuid::parse_str(item.trim_matches('"'))?))
-        .collect::<AppResult<Vec<Uuid>>>()?;
-    if !session_ids.contains(&claims.sid) {
-        info!("Session id invalid so delete it: {session_ids:?}.");
-        redis::del(&state.redis, &session_key).await?;
-        return Err(AppError::InvalidSessionError(
-            "Invalid session id".to_string(),
-        ));
+    session::check(&state.redis, &claims).await?;
+    Ok(MessageResponse::new("User is login"))
+}
+
+/* 当前用户的活跃会话 */
+pub async fn list_sessions(
+    state: &AppState,
+    uid: Uuid,
+    sid: Uuid,
+) -> AppResult<ListSessionResponse> {
+    let list = session::list(&state.redis, uid).await?;
+    Ok(ListSessionResponse { current: sid, list })
+}
+
+/* 注销当前用户的某个会话 */
+pub async fn revoke_session(state: &AppState, uid: Uuid, sid: Uuid) -> AppResult {
+    if !session::exists(&state.redis, uid, sid).await? {
+        return Err(AppError::NotFoundError(Resource {
+            details: vec![("sid".to_string(), sid.to_string())],
+            resource_type: ResourceType::Session,
+        }));
     }
-    if (claims.exp) < Utc::now().timestamp() {
-        info!("access_token expired so delete it: {session_ids:?}.");
-        redis::lrem(&state.redis, (&session_key, &claims.sid), 0).await?;
-        return Err(AppError::UnauthorizedError(
-            "access_token is expired".to_string(),
-        ));
+    info!("User {uid} revoked session {sid}");
+    session::delete(&state.redis, uid, sid).await
+}
+
+/* 管理员强制用户下线 */
+pub async fn force_logout(state: &AppState, request: ForceLogoutRequest) -> AppResult {
+    info!("service layer force logout with request: {request:?}");
+    let client = state.pool.get().await?;
+    let user_dao = UserDao::new(&client);
+    for id in request.ids.iter() {
+        let user = user_dao.find_by_id(id).await?;
+        session::destroy(&state.redis, user.uuid).await?;
     }
-    let session_id = session::set(&state.redis, claims.uid).await?;
-    let resp = token::generate_tokens(claims.uid, session_id)?;
-    Ok(LoginResponse::Token(resp))
+    Ok(())
 }
 
 pub async fn info(state: &AppState, uid: Uuid) -> AppResult<GetUserInfoResponse> {
@@ -253,6 +467,60 @@ pub async fn create_role(
     Ok(CreateEntityResponse { id: role_id })
 }
 
+pub async fn update_role(
+    state: &AppState,
+    request: UpdateRoleRequest,
+    uid: Uuid,
+) -> AppResult<UpdateRoleResponse> {
+    info!("service layer update role with request: {request:?}");
+    let mut client = state.pool.get().await?;
+    let transaction = client.transaction().await?;
+    let user_dao = UserDao::new(&transaction);
+    let perm_dao = PermissionDao::new(&transaction);
+    let role = user_dao.get_role_by_id(request.id).await?;
+    if role.internal {
+        return Err(AppError::ForbiddenError(
+            RoleException::InternalRole.to_string(),
+        ));
+    }
+    if role.name != request.name {
+        user_dao.check_role_unique_by_name(&request.name).await?;
+    }
+    let known = perm_dao
+        .get_basic_permission()
+        .await?
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+    if request.permission_list.iter().any(|id| !known.contains(id)) {
+        return Err(AppError::BadRequestError(
+            RoleException::UnknownPermission.to_string(),
+        ));
+    }
+    user_dao
+        .update_role(request.id, &request.name, request.description, uid)
+        .await?;
+
+    let current = perm_dao
+        .get_permission_by_role_id(request.id)
+        .await?
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+    let diff = diff_permissions(&current, &request.permission_list);
+    perm_dao
+        .insert_role_permission_relation(request.id, diff.added.clone())
+        .await?;
+    perm_dao
+        .delete_role_permission_relation(request.id, diff.removed.clone())
+        .await?;
+    transaction.commit().await?;
+    if !diff.added.is_empty() || !diff.removed.is_empty() {
+        permission::invalidate_role(state, request.id).await?;
+    }
+    Ok(diff)
+}
+
 pub async fn get_role(state: &AppState, role_id: i32) -> AppResult<UserRole> {
     let client = state.pool.get().await?;
     let user_dao = UserDao::new(&client);
@@ -300,13 +568,18 @@ pub async fn role_list(state: &AppState, project_id: i32) -> AppResult<Vec<UserR
     Ok(role_list)
 }
 
-pub async fn check_unique_username_or_email(
-    state: &AppState,
+/* 返回已被占用的字段, 均未占用时为 `None` */
+pub async fn check_unique_username_or_email<T: db::GenericClient>(
+    user_dao: &UserDao<'_, T>,
     username: &str,
     email: &str,
-) -> AppResult {
-    let client = state.pool.get().await?;
-    let user_dao = UserDao::new(&client);
-    user_dao.check_unique_by_username(username).await?;
-    user_dao.check_unique_by_email(email).await
+) -> AppResult<Option<&'static str>> {
+    match user_dao.check_unique_by_username(username).await {
+        Err(AppError::ResourceExistsError(_)) => return Ok(Some("username")),
+        other => other?,
+    }
+    match user_dao.check_unique_by_email(email).await {
+        Err(AppError::ResourceExistsError(_)) => Ok(Some("email")),
+        other => other.map(|_| None),
+    }
 }
@@ -1,7 +1,11 @@
 use crate::{
     configure::Config,
     errors::AppResult,
-    utils::{http::HttpClient, smtp::{EmailClient, email_client_builder}, ClientBuilder},
+    utils::{
+        http::{build_public_client, HttpClient},
+        smtp::{EmailClient, email_client_builder},
+        ClientBuilder,
+    },
 };
 use db::{redis::RedisClient, create_pool, redis_client_builder};
 use std::sync::Arc;
@@ -13,6 +17,8 @@ pub struct AppState {
     pub redis: Arc<RedisClient>,
     pub email: Arc<EmailClient>,
     pub http: HttpClient,
+    /* for urls given by users, e.g. webhooks, never reaches internal addresses */
+    pub public_http: HttpClient,
 }
 
 impl AppState {
@@ -21,12 +27,14 @@ impl AppState {
         let redis = Arc::new(redis_client_builder(&config.storage.redis_url));
         let email = Arc::new(email_client_builder(&config.smtp));
         let http = HttpClient::build_from_config(&config)?;
+        let public_http = build_public_client(&config)?;
         Ok(Self {
             config: Arc::new(config),
             pool,
             redis,
             email,
             http,
+            public_http,
         })
     }
 }
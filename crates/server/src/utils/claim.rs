@@ -14,19 +14,16 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::constant::ACCESS_TOKEN_DECODE_KEY;
+use crate::constant::ACCESS_TOKEN_KEYS;
+use crate::entity::api_token::ApiTokenScope;
 use crate::errors::{AppError, AppResult};
+use crate::utils::jwk::KeyStore;
 
 pub static DECODE_HEADER: Lazy<Validation> = Lazy::new(|| Validation::new(Algorithm::RS256));
 pub static ENCODE_HEADER: Lazy<Header> = Lazy::new(|| Header::new(Algorithm::RS256));
 
 pub static PAGE_ENCODE_HEADER: Lazy<Header> = Lazy::new(|| Header::new(Algorithm::HS256));
-pub static PAGE_DECODE_HEADER: Lazy<Validation> = Lazy::new(|| {
-    let mut validation = Validation::new(Algorithm::HS256);
-    validation.validate_exp = false;
-    validation.required_spec_claims.clear();
-    validation
-});
+pub static PAGE_DECODE_HEADER: Lazy<Validation> = Lazy::new(|| Validation::new(Algorithm::HS256));
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, ToSchema)]
 pub struct UserClaims {
@@ -34,6 +31,9 @@ pub struct UserClaims {
     pub exp: i64,
     pub uid: Uuid,
     pub sid: Uuid,
+    /* only set on refresh tokens, each one can be exchanged once */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<Uuid>,
 }
 
 impl UserClaims {
@@ -44,18 +44,39 @@ impl UserClaims {
             exp: now + (duration.as_secs() as i64),
             uid: uuid,
             sid: session_id,
+            jti: None,
         }
     }
 
+    pub fn with_jti(self, jti: Uuid) -> Self {
+        Self {
+            jti: Some(jti),
+            ..self
+        }
+    }
+
+    /* api tokens have no session, `sid` stays nil */
+    pub fn for_api_token(scope: &ApiTokenScope) -> Self {
+        Self {
+            iat: Utc::now().timestamp(),
+            exp: scope.expires_at.map_or(i64::MAX, |t| t.timestamp()),
+            uid: scope.uid,
+            sid: Uuid::nil(),
+            jti: None,
+        }
+    }
+
+    /* verified by any valid key of the set, see `KeyStore::decode` */
     pub fn decode(
         token: &str,
-        key: &DecodingKey,
+        keys: &KeyStore,
     ) -> Result<TokenData<Self>, jsonwebtoken::errors::Error> {
-        jsonwebtoken::decode::<UserClaims>(token, key, &DECODE_HEADER)
+        keys.decode::<UserClaims>(token, &DECODE_HEADER)
     }
 
-    pub fn encode(&self, key: &EncodingKey) -> Result<String, jsonwebtoken::errors::Error> {
-        jsonwebtoken::encode(&ENCODE_HEADER, self, key)
+    /* signed by the primary key, its kid goes into the header */
+    pub fn encode(&self, keys: &KeyStore) -> Result<String, jsonwebtoken::errors::Error> {
+        keys.encode(&ENCODE_HEADER, self)
     }
 }
 
@@ -66,10 +87,14 @@ where
     type Rejection = AppError;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        /* set by the auth middleware for requests authenticated by an api token */
+        if let Some(user_claims) = parts.extensions.get::<UserClaims>() {
+            return Ok(user_claims.clone());
+        }
         let TypedHeader(Authorization(bearer)) = parts
             .extract::<TypedHeader<Authorization<Bearer>>>()
             .await?;
-        let user_claims = UserClaims::decode(bearer.token(), &ACCESS_TOKEN_DECODE_KEY)?.claims;
+        let user_claims = UserClaims::decode(bearer.token(), &ACCESS_TOKEN_KEYS)?.claims;
         Ok(user_claims)
     }
 }
@@ -96,21 +121,47 @@ impl UserClaimsRequest for axum::extract::Request {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PageKind {
+    Case,
+    Plan,
+    Element,
+}
+
+/* filter of the list a token was issued for, later pages are queried with the same one */
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, ToSchema)]
+pub struct PageFilter {
+    pub kind: PageKind,
+    pub project_id: i32,
+    pub module_ids: Vec<i32>,
+    pub deleted: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, ToSchema)]
 pub struct PageClaims {
+    pub exp: i64,
+    pub filter: PageFilter,
     pub page_size: i64,
     pub page_num: i64,
-    pub last_item_id: i32,
-    pub module_ids: Vec<i32>,
+    /* id of the last item already returned, lists are ordered by id */
+    pub after: i32,
 }
 
 impl PageClaims {
-    pub fn new(page_size: i64, page_num: i64, last_item_id: i32, module_ids: Vec<i32>) -> Self {
+    pub fn new(
+        duration: Duration,
+        filter: PageFilter,
+        page_size: i64,
+        page_num: i64,
+        after: i32,
+    ) -> Self {
         Self {
+            exp: Utc::now().timestamp() + (duration.as_secs() as i64),
+            filter,
             page_size,
             page_num,
-            last_item_id,
-            module_ids,
+            after,
         }
     }
 
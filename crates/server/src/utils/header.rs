@@ -1,9 +1,19 @@
 use crate::{
     constant::PROJECT_ID,
     errors::{AppError, AppResult},
+    state::AppState,
 };
 
-use axum::http::HeaderMap;
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{
+        header::{COOKIE, USER_AGENT},
+        request::Parts,
+        HeaderMap,
+    },
+};
 
 pub fn validate_project_id(headers: &HeaderMap, project_id: i32) -> AppResult {
     let id = extract_project_id(headers)?;
@@ -29,3 +39,78 @@ pub fn extract_project_id(headers: &HeaderMap) -> AppResult<i32> {
 
     Ok(id)
 }
+
+/* value of the cookie `name` sent with the request */
+pub fn extract_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+/* where a request comes from, recorded on the session created by a login */
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    /* address of the tcp peer, or the client named by the proxy headers behind a trusted proxy */
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/* each trusted proxy appends the address it was reached from, walk back from the right past them */
+pub fn forwarded_client_ip(headers: &HeaderMap, trusted_proxies: usize) -> Option<String> {
+    if trusted_proxies == 0 {
+        return None;
+    }
+    let hops = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect::<Vec<_>>();
+    /* entries left of the trusted ones are set by the client and not taken */
+    hops.len()
+        .checked_sub(trusted_proxies)
+        .map(|index| hops[index])
+        .filter(|ip| !ip.is_empty())
+        .map(str::to_string)
+}
+
+impl ClientInfo {
+    /* proxy headers are only taken behind trusted proxies, otherwise any client could pick its own ip */
+    fn from_parts(parts: &Parts, trusted_proxies: usize) -> Self {
+        let peer_ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string());
+        Self {
+            ip: forwarded_client_ip(&parts.headers, trusted_proxies).or(peer_ip),
+            user_agent: parts
+                .headers
+                .get(USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string),
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let trusted_proxies = parts
+            .extensions
+            .get::<AppState>()
+            .map_or(0, |state| state.config.http.trusted_proxies);
+        Ok(Self::from_parts(parts, trusted_proxies))
+    }
+}
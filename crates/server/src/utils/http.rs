@@ -1,7 +1,18 @@
-use std::time::Duration;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
 
-use crate::{errors::AppResult, configure::Config};
-use reqwest::Response;
+use crate::{
+    errors::{AppError, AppResult},
+    configure::Config,
+};
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect::Policy,
+    Response, Url,
+};
 use serde::Serialize;
 
 use super::ClientBuilder;
@@ -39,6 +50,91 @@ impl ClientBuilder for HttpClient {
     }
 }
 
+/* addresses a request to a url given by a user may reach */
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                /* carrier-grade nat, 100.64.0.0/10 */
+                || (a == 100 && (b & 0xc0) == 64))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    /* unique local, fc00::/7 */
+                    || (first & 0xfe00) == 0xfc00
+                    /* link local, fe80::/10 */
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+/* http(s) url whose host only resolves to public addresses, checked before the server calls a url given by a user */
+pub async fn check_public_url(url: &str) -> AppResult<Url> {
+    let invalid = |reason: &str| AppError::BadRequestError(format!("url not allowed: {reason}"));
+    let parsed = Url::parse(url).map_err(|_| invalid("malformed"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid("only http and https are allowed"));
+    }
+    let host = parsed.host_str().ok_or_else(|| invalid("missing host"))?;
+    let port = parsed
+        .port_or_known_default()
+        .ok_or_else(|| invalid("missing port"))?;
+    /* ipv6 literals keep their brackets in the host */
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|_| invalid("host not resolved"))?
+        .collect::<Vec<SocketAddr>>();
+    if addrs.is_empty() || !addrs.iter().all(|addr| is_public_ip(addr.ip())) {
+        return Err(invalid("host resolves to a non public address"));
+    }
+    Ok(parsed)
+}
+
+/* drops non public addresses at connect time, so a checked host can't be rebound to an internal one */
+#[derive(Debug, Clone, Copy, Default)]
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect::<Vec<_>>();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/* client for urls given by users, literal ip hosts are left to `check_public_url` */
+pub fn build_public_client(config: &Config) -> AppResult<HttpClient> {
+    Ok(reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.http.timeout))
+        .dns_resolver(Arc::new(PublicResolver))
+        .redirect(Policy::none())
+        .no_proxy()
+        .build()?)
+}
+
 impl HttpClientExt for HttpClient {
     async fn post_request<T: Serialize + ?Sized + Send + Sync>(
         &self,
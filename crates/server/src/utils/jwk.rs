@@ -0,0 +1,231 @@
+use std::sync::{Arc, RwLock};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{
+    errors::{Error, ErrorKind},
+    jwk::{
+        AlgorithmParameters, CommonParameters, Jwk, JwkSet, KeyAlgorithm, PublicKeyUse,
+        RSAKeyParameters, RSAKeyType,
+    },
+    DecodingKey, EncodingKey, Header, TokenData, Validation,
+};
+use rsa::{
+    pkcs1::DecodeRsaPublicKey, pkcs8::DecodePublicKey, traits::PublicKeyParts, RsaPublicKey,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::configure::secret::{ConfigJWT, KeySetManifest};
+use crate::errors::{AppError, AppResult};
+
+/* kid of the single key pair configured without `key_dir` */
+const DEFAULT_KID: &str = "default";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyUsage {
+    Access,
+    Refresh,
+}
+
+#[derive(Clone)]
+pub struct SigningKey {
+    pub kid: String,
+    encoding: Option<EncodingKey>,
+    decoding: DecodingKey,
+    jwk: Jwk,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+fn key_error(kid: &str, e: impl std::fmt::Display) -> AppError {
+    AppError::ConfigError(config::ConfigError::Message(format!(
+        "invalid jwt key {kid}: {e}"
+    )))
+}
+
+impl SigningKey {
+    /* rsa public key in spki or pkcs1 pem, only the primary key needs the private half */
+    pub fn from_pem(
+        kid: &str,
+        private_pem: Option<&str>,
+        public_pem: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> AppResult<Self> {
+        let public_key = RsaPublicKey::from_public_key_pem(public_pem)
+            .or_else(|_| RsaPublicKey::from_pkcs1_pem(public_pem))
+            .map_err(|e| key_error(kid, e))?;
+        let jwk = Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(KeyAlgorithm::RS256),
+                key_id: Some(kid.to_string()),
+                ..Default::default()
+            },
+            algorithm: AlgorithmParameters::RSA(RSAKeyParameters {
+                key_type: RSAKeyType::RSA,
+                n: URL_SAFE_NO_PAD.encode(public_key.n().to_bytes_be()),
+                e: URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be()),
+            }),
+        };
+        let encoding = private_pem
+            .map(|pem| EncodingKey::from_rsa_pem(pem.as_bytes()))
+            .transpose()
+            .map_err(|e| key_error(kid, e))?;
+        Ok(Self {
+            kid: kid.to_string(),
+            encoding,
+            decoding: DecodingKey::from_jwk(&jwk).map_err(|e| key_error(kid, e))?,
+            jwk,
+            expires_at,
+        })
+    }
+
+    fn is_valid(&self, now: DateTime<Utc>) -> bool {
+        !self.expires_at.is_some_and(|t| t <= now)
+    }
+}
+
+/* keys of one token type, tokens are signed by `primary` and verified by any valid key */
+#[derive(Clone)]
+pub struct KeySet {
+    primary: String,
+    keys: Vec<SigningKey>,
+}
+
+impl KeySet {
+    pub fn new(primary: &str, keys: Vec<SigningKey>) -> AppResult<Self> {
+        match keys.iter().find(|key| key.kid == primary) {
+            Some(key) if key.encoding.is_some() => {}
+            Some(_) => return Err(key_error(primary, "primary key has no private key")),
+            None => return Err(key_error(primary, "primary key is not listed")),
+        }
+        Ok(Self {
+            primary: primary.to_string(),
+            keys,
+        })
+    }
+
+    pub fn load(config: &ConfigJWT, usage: KeyUsage) -> AppResult<Self> {
+        let manifest = config
+            .read_key_manifest()
+            .map_err(|e| key_error("manifest", e))?;
+        let Some(manifest) = manifest else {
+            let (private_pem, public_pem) = match usage {
+                KeyUsage::Access => (
+                    config.read_private_access_key()?,
+                    config.read_public_access_key()?,
+                ),
+                KeyUsage::Refresh => (
+                    config.read_private_refresh_key()?,
+                    config.read_public_refresh_key()?,
+                ),
+            };
+            let key = SigningKey::from_pem(DEFAULT_KID, Some(&private_pem), &public_pem, None)?;
+            return Self::new(DEFAULT_KID, vec![key]);
+        };
+        let set = match usage {
+            KeyUsage::Access => manifest.access,
+            KeyUsage::Refresh => manifest.refresh,
+        };
+        Self::from_manifest(config, &set)
+    }
+
+    fn from_manifest(config: &ConfigJWT, manifest: &KeySetManifest) -> AppResult<Self> {
+        let mut keys = vec![];
+        for entry in manifest.keys.iter() {
+            let read = |path| {
+                config
+                    .read_key_file(path)
+                    .map_err(|e| key_error(&entry.kid, e))
+            };
+            let private_pem = entry.private_key.as_deref().map(read).transpose()?;
+            let public_pem = read(entry.public_key.as_path())?;
+            keys.push(SigningKey::from_pem(
+                &entry.kid,
+                private_pem.as_deref(),
+                &public_pem,
+                entry.expires_at,
+            )?);
+        }
+        Self::new(&manifest.primary, keys)
+    }
+
+    pub fn primary(&self) -> &str {
+        &self.primary
+    }
+
+    pub fn kids(&self) -> Vec<&str> {
+        self.keys.iter().map(|key| key.kid.as_str()).collect()
+    }
+}
+
+/* key set shared by every request, swapped as a whole when the key directory is reloaded */
+pub struct KeyStore {
+    inner: RwLock<Arc<KeySet>>,
+}
+
+impl KeyStore {
+    pub fn new(keys: KeySet) -> Self {
+        Self {
+            inner: RwLock::new(Arc::new(keys)),
+        }
+    }
+
+    pub fn current(&self) -> Arc<KeySet> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn replace(&self, keys: KeySet) {
+        *self.inner.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(keys);
+    }
+
+    pub fn encode<T: Serialize>(&self, header: &Header, claims: &T) -> Result<String, Error> {
+        let keys = self.current();
+        let primary = keys
+            .keys
+            .iter()
+            .find(|key| key.kid == keys.primary)
+            .and_then(|key| key.encoding.as_ref())
+            .ok_or_else(|| Error::from(ErrorKind::InvalidRsaKey("no primary key".to_string())))?;
+        let header = Header {
+            kid: Some(keys.primary.clone()),
+            ..header.clone()
+        };
+        jsonwebtoken::encode(&header, claims, primary)
+    }
+
+    /* tokens issued before keys had ids are tried against every valid key */
+    pub fn decode<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        validation: &Validation,
+    ) -> Result<TokenData<T>, Error> {
+        let kid = jsonwebtoken::decode_header(token)?.kid;
+        let keys = self.current();
+        let now = Utc::now();
+        let mut ret = Err(Error::from(ErrorKind::InvalidSignature));
+        for key in keys.keys.iter().filter(|key| key.is_valid(now)) {
+            if kid.as_ref().is_some_and(|kid| *kid != key.kid) {
+                continue;
+            }
+            ret = jsonwebtoken::decode::<T>(token, &key.decoding, validation);
+            if ret.is_ok() {
+                break;
+            }
+        }
+        ret
+    }
+
+    /* public keys that still verify tokens, for other services */
+    pub fn jwks(&self) -> JwkSet {
+        let now = Utc::now();
+        JwkSet {
+            keys: self
+                .current()
+                .keys
+                .iter()
+                .filter(|key| key.is_valid(now))
+                .map(|key| key.jwk.clone())
+                .collect(),
+        }
+    }
+}
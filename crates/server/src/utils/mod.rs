@@ -8,6 +8,7 @@ pub mod dir;
 pub mod hash;
 pub mod header;
 pub mod http;
+pub mod jwk;
 pub mod password;
 pub mod smtp;
 pub mod task;
@@ -1,7 +1,17 @@
 use super::hash;
-use crate::errors::{AppError, AppResult};
+use crate::{
+    configure::password::ConfigPassword,
+    errors::{message::PasswordException, AppError, AppResult},
+};
+use once_cell::sync::Lazy;
 use rand::Rng;
 use tracing::debug;
+use uuid::Uuid;
+
+/* hashed with the same parameters as real passwords, verified against when the user does not exist */
+static DUMMY_HASH: Lazy<String> = Lazy::new(|| {
+    hash::argon_hash(Uuid::new_v4().to_string()).expect("Failed to hash dummy password")
+});
 
 pub async fn hash(password: String) -> AppResult<String> {
     let join_handle = tokio::task::spawn_blocking(move || hash::argon_hash(password));
@@ -21,6 +31,11 @@ pub async fn verify(password: String, hash: String) -> AppResult {
     }
 }
 
+/* takes as long as `verify`, so a missing user can not be told apart by the response time */
+pub async fn verify_dummy(password: String) {
+    let _ = tokio::task::spawn_blocking(move || hash::argon_verify(password, &*DUMMY_HASH)).await;
+}
+
 pub fn generate() -> AppResult<String> {
     const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
                                 abcdefghijklmnopqrstuvwxyz\
@@ -36,3 +51,32 @@ pub fn generate() -> AppResult<String> {
 
     Ok(password)
 }
+
+pub fn check_strength(config: &ConfigPassword, password: &str) -> AppResult {
+    let rules = [
+        (
+            password.chars().count() >= config.min_length,
+            PasswordException::TooShort,
+        ),
+        (
+            !config.require_uppercase || password.chars().any(|c| c.is_uppercase()),
+            PasswordException::MissingUppercase,
+        ),
+        (
+            !config.require_lowercase || password.chars().any(|c| c.is_lowercase()),
+            PasswordException::MissingLowercase,
+        ),
+        (
+            !config.require_digit || password.chars().any(|c| c.is_ascii_digit()),
+            PasswordException::MissingDigit,
+        ),
+        (
+            !config.require_symbol || password.chars().any(|c| c.is_ascii_punctuation()),
+            PasswordException::MissingSymbol,
+        ),
+    ];
+    match rules.into_iter().find(|(passed, _)| !passed) {
+        Some((_, e)) => Err(AppError::BadRequestError(e.to_string())),
+        None => Ok(()),
+    }
+}
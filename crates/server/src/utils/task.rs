@@ -26,8 +26,10 @@ pub async fn join_all(tasks: Vec<Task>) -> AppResult {
             }
         });
     }
+    /* channel closes once every fail-fast task finished without error */
+    drop(sender);
     match receiver.recv().await {
         Some(err) => Err(err),
-        None => unreachable!("This channel never closed..."),
+        None => Ok(()),
     }
 }
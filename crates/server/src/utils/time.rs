@@ -21,6 +21,14 @@ pub fn to_utc_or_default(opt_time: Option<PrimitiveDateTime>) -> Option<DateTime
     opt_time.map(to_utc)
 }
 
+pub fn to_primitive(datetime: DateTime<Utc>) -> PrimitiveDateTime {
+    let odt = OffsetDateTime::from_unix_timestamp_nanos(
+        datetime.timestamp_nanos_opt().unwrap_or_default() as i128,
+    )
+    .unwrap_or(OffsetDateTime::UNIX_EPOCH);
+    PrimitiveDateTime::new(odt.date(), odt.time())
+}
+
 pub fn to_date(datetime: DateTime<Utc>) -> Date {
     let timestamp = datetime.timestamp();
     let odt = OffsetDateTime::from_unix_timestamp(timestamp).unwrap();
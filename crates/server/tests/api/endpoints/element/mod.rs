@@ -0,0 +1,3 @@
+pub mod test_element;
+pub mod test_health_check;
+pub mod test_operation_option;
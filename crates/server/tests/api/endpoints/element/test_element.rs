@@ -0,0 +1,317 @@
+use crate::{context::seeder::SeedDbTestContext, helper::user::Role};
+use fake::{Fake, Faker};
+use server::{
+    dao::{case::CaseDao, element::ElementDao, entity::Step, file::FileDao},
+    dto::request::{
+        case::{CreateFunctionalCaseRequest, FieldValue, SelectedField},
+        CreateElementRequest, DeleteElementRequest, DeleteEntityRequest, MoveElementRequest,
+    },
+    entity::file::{FileModule, ModuleType},
+    errors::AppError,
+    service::{case, element},
+};
+use test_context::test_context;
+
+async fn create_module(ctx: &SeedDbTestContext, module_type: ModuleType) -> i32 {
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let client = ctx.app.state.pool.get().await.unwrap();
+    FileDao::new(&client)
+        .insert_file_module(
+            &admin.uuid,
+            ctx.project.id,
+            &FileModule {
+                id: 0,
+                name: Faker.fake::<String>(),
+                module_type,
+                position: 1,
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap()
+}
+
+fn create_request(module_id: i32, value: &str) -> CreateElementRequest {
+    CreateElementRequest {
+        name: Faker.fake::<String>(),
+        module_id,
+        value: value.to_string(),
+        element_type: "button".to_string(),
+        description: None,
+    }
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_element_only_lives_in_element_modules(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let element_module = create_module(ctx, ModuleType::Element).await;
+    let case_module = create_module(ctx, ModuleType::Case).await;
+
+    let err = element::create(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        create_request(case_module, "#submit"),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+
+    let id = element::create(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        create_request(element_module, "#submit"),
+    )
+    .await
+    .unwrap();
+    let err = element::move_to(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        MoveElementRequest {
+            id,
+            module_id: case_module,
+        },
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+    let detail = element::info(state, ctx.project.id, id).await.unwrap();
+    assert_eq!(detail.module_id, element_module);
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_element_value_is_unique_within_module(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let module_id = create_module(ctx, ModuleType::Element).await;
+    let other_module_id = create_module(ctx, ModuleType::Element).await;
+
+    element::create(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        create_request(module_id, "#submit"),
+    )
+    .await
+    .unwrap();
+    let err = element::create(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        create_request(module_id, "#submit"),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::ResourceExistsError(_)), "{err:?}");
+
+    /* the same value in another module is a different element, moving it over collides */
+    let id = element::create(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        create_request(other_module_id, "#submit"),
+    )
+    .await
+    .unwrap();
+    let err = element::move_to(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        MoveElementRequest { id, module_id },
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::ResourceExistsError(_)), "{err:?}");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_restore_element_when_value_is_taken(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let module_id = create_module(ctx, ModuleType::Element).await;
+    let id = element::create(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        create_request(module_id, "#submit"),
+    )
+    .await
+    .unwrap();
+    element::delete(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        DeleteElementRequest { id, force: false },
+    )
+    .await
+    .unwrap();
+    let taken_id = element::create(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        create_request(module_id, "#submit"),
+    )
+    .await
+    .unwrap();
+
+    let err = element::restore(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        DeleteEntityRequest { id },
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::ResourceExistsError(_)), "{err:?}");
+    assert!(
+        element::info(state, ctx.project.id, id)
+            .await
+            .unwrap()
+            .deleted
+    );
+
+    element::delete(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        DeleteElementRequest {
+            id: taken_id,
+            force: false,
+        },
+    )
+    .await
+    .unwrap();
+    element::restore(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        DeleteEntityRequest { id },
+    )
+    .await
+    .unwrap();
+    assert!(
+        !element::info(state, ctx.project.id, id)
+            .await
+            .unwrap()
+            .deleted
+    );
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_delete_referenced_element(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let module_id = create_module(ctx, ModuleType::Element).await;
+    let id = element::create(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        create_request(module_id, "#submit"),
+    )
+    .await
+    .unwrap();
+    let case_id = case::create_functional_case(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        CreateFunctionalCaseRequest {
+            name: Faker.fake::<String>(),
+            module_id: 1,
+            template_id: 1,
+            tags: None,
+            description: None,
+            fields: (1..=7)
+                .map(|id| SelectedField {
+                    id,
+                    value: match id {
+                        2 => FieldValue::Select(1),
+                        _ => FieldValue::Input(Faker.fake::<String>()),
+                    },
+                })
+                .collect(),
+        },
+    )
+    .await
+    .unwrap();
+    /* the script file is not generated, only its record and element binding */
+    {
+        let client = state.pool.get().await.unwrap();
+        let script_id: i32 = client
+            .query_one(
+                "INSERT INTO script (case_id, environment, path, created_by) VALUES ($1, $2, $3, $4) RETURNING id",
+                &[&case_id, &"test", &Faker.fake::<String>(), &admin.uuid],
+            )
+            .await
+            .unwrap()
+            .get(0);
+        let element_dao = ElementDao::new(&client);
+        let option_id = element_dao.get_operation_options(None).await.unwrap()[0].id;
+        element_dao
+            .bind_element_operation(&id, &option_id)
+            .await
+            .unwrap();
+        CaseDao::new(&client)
+            .insert_script_element_relation(
+                &script_id,
+                "STEP".to_string(),
+                &vec![Step {
+                    position: 1,
+                    element_id: id,
+                    option_id,
+                    attach_info: None,
+                }],
+            )
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        element::references(state, ctx.project.id, id)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let err = element::delete(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        DeleteElementRequest { id, force: false },
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+    assert!(
+        !element::info(state, ctx.project.id, id)
+            .await
+            .unwrap()
+            .deleted
+    );
+
+    /* forced, the scripts no longer reference the deleted element */
+    element::delete(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        DeleteElementRequest { id, force: true },
+    )
+    .await
+    .unwrap();
+    assert!(
+        element::info(state, ctx.project.id, id)
+            .await
+            .unwrap()
+            .deleted
+    );
+    assert!(element::references(state, ctx.project.id, id)
+        .await
+        .unwrap()
+        .is_empty());
+}
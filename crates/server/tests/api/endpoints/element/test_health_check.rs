@@ -0,0 +1,124 @@
+use crate::{context::seeder::SeedDbTestContext, helper::user::Role};
+use fake::{Fake, Faker};
+use server::{
+    dao::{element::ElementDao, file::FileDao},
+    dto::request::{CreateElementRequest, HealthCheckRequest, ListQueryParam},
+    entity::file::{FileModule, ModuleType},
+    errors::AppError,
+    service::element,
+};
+use test_context::test_context;
+use uuid::Uuid;
+
+const PAGE: &str = r#"
+<html>
+  <body>
+    <input class="field" name="username" />
+    <input class="field" name="password" type="password" />
+    <button id="submit">Sign in</button>
+  </body>
+</html>
+"#;
+
+async fn create_element_module(ctx: &SeedDbTestContext, uid: Uuid) -> i32 {
+    let client = ctx.app.state.pool.get().await.unwrap();
+    FileDao::new(&client)
+        .insert_file_module(
+            &uid,
+            ctx.project.id,
+            &FileModule {
+                id: 0,
+                name: Faker.fake::<String>(),
+                module_type: ModuleType::Element,
+                position: 1,
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap()
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_health_check_results_are_listed(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let module_id = create_element_module(ctx, admin.uuid).await;
+    let mut element_ids = vec![];
+    for value in ["#submit", "#forgot-password", "input.field"] {
+        let request = CreateElementRequest {
+            name: Faker.fake::<String>(),
+            module_id,
+            value: value.to_string(),
+            element_type: "button".to_string(),
+            description: None,
+        };
+        let id = element::create(state, admin.uuid, ctx.project.id, request)
+            .await
+            .unwrap();
+        element_ids.push(id);
+    }
+
+    let check_id = {
+        let client = state.pool.get().await.unwrap();
+        ElementDao::new(&client)
+            .create_health_check(&module_id, "https://example.com/login", None, &admin.uuid)
+            .await
+            .unwrap()
+    };
+    element::save_health_check(state, check_id, module_id, PAGE)
+        .await
+        .unwrap();
+
+    let check = element::get_health_check(state, ctx.project.id, check_id)
+        .await
+        .unwrap();
+    assert_eq!(check.results.len(), 3);
+    let param = ListQueryParam {
+        module_ids: Some(module_id.to_string()),
+        deleted: None,
+        page_num: None,
+        page_size: None,
+        page_token: None,
+    };
+    let resp = element::get_element_list(state, &ctx.project.id, param)
+        .await
+        .unwrap();
+    let statuses = resp
+        .list
+        .iter()
+        .map(|e| (e.id, e.health_status.clone(), e.health_checked_at.is_some()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        statuses,
+        vec![
+            (element_ids[0], Some("FOUND".to_string()), true),
+            (element_ids[1], Some("MISSING".to_string()), true),
+            (element_ids[2], Some("AMBIGUOUS".to_string()), true),
+        ]
+    );
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_health_check_refuses_internal_page_url(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let module_id = create_element_module(ctx, admin.uuid).await;
+
+    for page_url in [
+        ctx.app.mock_server.uri(),
+        "http://169.254.169.254/latest/meta-data".to_string(),
+        "file:///etc/passwd".to_string(),
+    ] {
+        let request = HealthCheckRequest {
+            module_id,
+            page_url,
+            machine_id: None,
+        };
+        let err = element::health_check(state, admin.uuid, ctx.project.id, request)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use crate::{context::seeder::SeedDbTestContext, helper::user::Role};
+use fake::{Fake, Faker};
+use server::{
+    dao::{element::ElementDao, entity::Step, file::FileDao},
+    dto::request::{
+        case::{CreateFunctionalCaseRequest, FieldValue, SelectedField},
+        CreateElementRequest, CreateOperationOptionRequest, CreateScriptRequest,
+        OperationOptionBindingRequest,
+    },
+    entity::file::{FileModule, ModuleType},
+    errors::AppError,
+    service::{
+        case, element,
+        engine::{render_step_templates, StepInfo},
+    },
+};
+use test_context::test_context;
+use uuid::Uuid;
+
+struct Fixture {
+    case_id: i32,
+    element_id: i32,
+    element_type: String,
+    option_id: i32,
+}
+
+/* an element of a fresh type and a custom option nothing is bound to yet */
+async fn fixture(ctx: &SeedDbTestContext) -> Fixture {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let module_id = {
+        let client = state.pool.get().await.unwrap();
+        FileDao::new(&client)
+            .insert_file_module(
+                &admin.uuid,
+                ctx.project.id,
+                &FileModule {
+                    id: 0,
+                    name: Faker.fake::<String>(),
+                    module_type: ModuleType::Element,
+                    position: 1,
+                    parent_id: None,
+                },
+            )
+            .await
+            .unwrap()
+    };
+    let element_type = format!("type-{}", Uuid::new_v4().simple());
+    let element_id = element::create(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        CreateElementRequest {
+            name: Faker.fake::<String>(),
+            module_id,
+            value: "#submit".to_string(),
+            element_type: element_type.clone(),
+            description: None,
+        },
+    )
+    .await
+    .unwrap();
+    let option_id = element::create_operation_option(
+        state,
+        admin.uuid,
+        CreateOperationOptionRequest {
+            name: Faker.fake::<String>(),
+            exec: format!("HOVER_{}", Uuid::new_v4().simple()),
+            template: Some(
+                "cy.get('{{ selector }}').trigger('{{ attach_info.event }}');".to_string(),
+            ),
+        },
+    )
+    .await
+    .unwrap();
+    let case_id = case::create_functional_case(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        CreateFunctionalCaseRequest {
+            name: Faker.fake::<String>(),
+            module_id: 1,
+            template_id: 1,
+            tags: None,
+            description: None,
+            fields: (1..=7)
+                .map(|id| SelectedField {
+                    id,
+                    value: match id {
+                        2 => FieldValue::Select(1),
+                        _ => FieldValue::Input(Faker.fake::<String>()),
+                    },
+                })
+                .collect(),
+        },
+    )
+    .await
+    .unwrap();
+    Fixture {
+        case_id,
+        element_id,
+        element_type,
+        option_id,
+    }
+}
+
+fn script_request(fixture: &Fixture) -> CreateScriptRequest {
+    CreateScriptRequest {
+        name: Faker.fake::<String>(),
+        case_id: fixture.case_id,
+        environment: "test".to_string(),
+        pre_processors: vec![],
+        steps: vec![Step {
+            position: 1,
+            element_id: fixture.element_id,
+            option_id: fixture.option_id,
+            attach_info: Some(HashMap::from([(
+                "event".to_string(),
+                "mouseover".to_string(),
+            )])),
+        }],
+        after_processors: vec![],
+    }
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_script_step_requires_bound_operation(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let fixture = fixture(ctx).await;
+    let request = script_request(&fixture);
+
+    let err = case::check_script_request(state, ctx.project.id, &request)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+
+    element::bind_operation_options(
+        state,
+        admin.uuid,
+        OperationOptionBindingRequest {
+            element_type: fixture.element_type.clone(),
+            option_ids: vec![fixture.option_id],
+        },
+    )
+    .await
+    .unwrap();
+    case::check_script_request(state, ctx.project.id, &request)
+        .await
+        .unwrap();
+
+    element::unbind_operation_options(
+        state,
+        OperationOptionBindingRequest {
+            element_type: fixture.element_type.clone(),
+            option_ids: vec![fixture.option_id],
+        },
+    )
+    .await
+    .unwrap();
+    let err = case::check_script_request(state, ctx.project.id, &request)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_render_custom_operation_template(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let fixture = fixture(ctx).await;
+    element::bind_operation_options(
+        state,
+        admin.uuid,
+        OperationOptionBindingRequest {
+            element_type: fixture.element_type.clone(),
+            option_ids: vec![fixture.option_id],
+        },
+    )
+    .await
+    .unwrap();
+
+    let info = {
+        let client = state.pool.get().await.unwrap();
+        ElementDao::new(&client)
+            .get_element(fixture.element_id, fixture.option_id)
+            .await
+            .unwrap()
+    };
+    assert!(info.allowed);
+    let step = &script_request(&fixture).steps[0];
+    let mut steps = vec![StepInfo {
+        position: step.position,
+        action: info.action,
+        template: info.template,
+        selector: info.selector,
+        attach_info: step.attach_info.clone(),
+    }];
+    render_step_templates(&mut steps).unwrap();
+    assert_eq!(
+        steps[0].template.as_deref(),
+        Some("cy.get('#submit').trigger('mouseover');")
+    );
+}
@@ -1,3 +1,4 @@
+pub mod test_cross_project;
 pub mod test_field_ceate;
 pub mod test_field_delete;
 pub mod test_field_get;
@@ -5,6 +6,7 @@ pub mod test_field_update;
 pub mod test_functional_case_create;
 pub mod test_functional_case_get;
 pub mod test_functional_case_update;
+pub mod test_issue_report;
 pub mod test_module_create;
 pub mod test_module_delete;
 pub mod test_module_get;
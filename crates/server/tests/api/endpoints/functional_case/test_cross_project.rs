@@ -0,0 +1,269 @@
+use crate::{
+    assert_err,
+    context::seeder::SeedDbTestContext,
+    helper::{project::TestProject, user::Role},
+};
+use fake::{Fake, Faker};
+use server::{
+    dao::{case::CaseDao, file::FileDao, plan::PlanDao},
+    dto::request::{
+        case::{
+            CreateFieldRequest, CreateFunctionalCaseRequest, CreateRequirementRequest, FieldValue,
+            RequirementInfoParam, SelectedField, UpdateFieldRequest,
+        },
+        user::LoginRequest,
+    },
+    entity::{
+        case::FieldOption,
+        file::{FileModule, ModuleType},
+        project::Plan,
+    },
+    errors::{AppError, AppResponseError},
+    service::{case, plan, requirement},
+};
+use test_context::test_context;
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_forbidden_create_functional_case_in_other_project_module(
+    ctx: &mut SeedDbTestContext,
+) {
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+
+    let req: LoginRequest = LoginRequest {
+        username: admin.username.clone(),
+        password: admin.password.clone(),
+    };
+    let token = ctx.app.api.get_token(&req).await.unwrap();
+
+    let system = ctx.users.get(&Role::System).unwrap();
+    let other = TestProject::create_project(&ctx.app.state.pool, system.uuid)
+        .await
+        .unwrap();
+    assert_ne!(other.id, ctx.project.id);
+    let client = ctx.app.state.pool.get().await.unwrap();
+    let module_id = FileDao::new(&client)
+        .insert_file_module(
+            &system.uuid,
+            other.id,
+            &FileModule {
+                id: 0,
+                name: Faker.fake::<String>(),
+                module_type: ModuleType::Case,
+                position: 1,
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let req: CreateFunctionalCaseRequest = CreateFunctionalCaseRequest {
+        name: Faker.fake::<String>(),
+        module_id,
+        template_id: 1,
+        tags: Some(Faker.fake::<String>()),
+        description: None,
+        fields: vec![SelectedField {
+            id: 2,
+            value: FieldValue::Select(1),
+        }],
+    };
+
+    let (status, resp) = ctx
+        .app
+        .api
+        .create_functional_case(&token.access_token, ctx.project.id, &req)
+        .await
+        .unwrap();
+
+    assert_eq!(status, reqwest::StatusCode::FORBIDDEN);
+    assert_err!(resp, |e: &AppResponseError| e.kind == "FORBIDDEN_ERROR");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_forbidden_create_functional_case_with_other_project_template(
+    ctx: &mut SeedDbTestContext,
+) {
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let system = ctx.users.get(&Role::System).unwrap();
+    let other = TestProject::create_project(&ctx.app.state.pool, system.uuid)
+        .await
+        .unwrap();
+    let client = ctx.app.state.pool.get().await.unwrap();
+    let template_id: i32 = client
+        .query_one(
+            "INSERT INTO template (name, project_id, created_by) VALUES ($1, $2, $3) RETURNING id",
+            &[&Faker.fake::<String>(), &other.id, &system.uuid],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    let module_id = FileDao::new(&client)
+        .insert_file_module(
+            &admin.uuid,
+            ctx.project.id,
+            &FileModule {
+                id: 0,
+                name: Faker.fake::<String>(),
+                module_type: ModuleType::Case,
+                position: 1,
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let err = case::create_functional_case(
+        &ctx.app.state,
+        admin.uuid,
+        ctx.project.id,
+        CreateFunctionalCaseRequest {
+            name: Faker.fake::<String>(),
+            module_id,
+            template_id,
+            tags: None,
+            description: None,
+            fields: vec![],
+        },
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::ForbiddenError(_)), "{err:?}");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_update_field_rejects_option_of_other_field(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let system = ctx.users.get(&Role::System).unwrap();
+    let other = TestProject::create_project(&state.pool, system.uuid)
+        .await
+        .unwrap();
+    let select_field = |project_id| {
+        case::create_field(
+            state,
+            admin.uuid,
+            project_id,
+            CreateFieldRequest {
+                name: Faker.fake::<String>(),
+                field_type: "SELECT".to_string(),
+                remark: None,
+                options: Some(vec![FieldOption {
+                    id: 0,
+                    field_id: 0,
+                    value: "P0".to_string(),
+                    position: 1,
+                }]),
+            },
+        )
+    };
+    let field_id = select_field(ctx.project.id).await.unwrap().id;
+    let other_field_id = select_field(other.id).await.unwrap().id;
+    let client = state.pool.get().await.unwrap();
+    let other_option = CaseDao::new(&client)
+        .get_field_by_id(other_field_id)
+        .await
+        .unwrap()
+        .options
+        .remove(0);
+
+    let err = case::update_field(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        UpdateFieldRequest {
+            id: field_id,
+            name: Faker.fake::<String>(),
+            field_type: "SELECT".to_string(),
+            remark: None,
+            options: Some(vec![FieldOption {
+                value: "hijacked".to_string(),
+                ..other_option
+            }]),
+        },
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+    let option = CaseDao::new(&client)
+        .get_field_option_by_id(other_option.id)
+        .await
+        .unwrap();
+    assert_eq!(option.value, "P0");
+
+    /* the field of the other project can not be reached through this one */
+    let err = case::update_field(
+        state,
+        admin.uuid,
+        ctx.project.id,
+        UpdateFieldRequest {
+            id: other_field_id,
+            name: Faker.fake::<String>(),
+            field_type: "INPUT".to_string(),
+            remark: None,
+            options: None,
+        },
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::ForbiddenError(_)), "{err:?}");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_forbidden_requirement_and_plan_of_other_project(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let system = ctx.users.get(&Role::System).unwrap();
+    let other = TestProject::create_project(&state.pool, system.uuid)
+        .await
+        .unwrap();
+
+    let requirement_id = requirement::create(
+        state,
+        system.uuid,
+        other.id,
+        CreateRequirementRequest {
+            name: Faker.fake::<String>(),
+            description: None,
+        },
+    )
+    .await
+    .unwrap()
+    .id;
+    let err = requirement::get(state, ctx.project.id, requirement_id)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::ForbiddenError(_)), "{err:?}");
+
+    let client = state.pool.get().await.unwrap();
+    let plan_id = PlanDao::new(&client)
+        .create(Plan::new(
+            &Faker.fake::<String>(),
+            other.id,
+            1,
+            system.uuid,
+            None,
+            None,
+            None,
+        ))
+        .await
+        .unwrap();
+    let err = plan::get_schedule(state, ctx.project.id, plan_id)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::ForbiddenError(_)), "{err:?}");
+
+    /* coverage of this project can not be computed against the plan of another one */
+    let err = requirement::info(
+        state,
+        &ctx.project.id,
+        RequirementInfoParam {
+            plan_id: Some(plan_id),
+        },
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::ForbiddenError(_)), "{err:?}");
+}
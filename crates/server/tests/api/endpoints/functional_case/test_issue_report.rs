@@ -0,0 +1,91 @@
+use crate::{context::seeder::SeedDbTestContext, helper::user::Role};
+use fake::{Fake, Faker};
+use server::{
+    dao::{case::CaseDao, file::FileDao},
+    entity::{
+        case::{CaseResult, FunctionalCase, IssueSource, TrackerIssue},
+        file::{FileModule, ModuleType},
+    },
+};
+use test_context::test_context;
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_execute_record_is_claimed_once_for_reporting(ctx: &mut SeedDbTestContext) {
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let client = ctx.app.state.pool.get().await.unwrap();
+    let case_dao = CaseDao::new(&client);
+    let file_dao = FileDao::new(&client);
+    let module_id = file_dao
+        .insert_file_module(
+            &admin.uuid,
+            ctx.project.id,
+            &FileModule {
+                id: 0,
+                name: Faker.fake::<String>(),
+                module_type: ModuleType::Case,
+                position: 1,
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    let module = file_dao.get_module_by_id(module_id).await.unwrap();
+    let case = FunctionalCase::new(&Faker.fake::<String>(), module, 1, vec![]);
+    let case_id = case_dao
+        .insert_functional_case(case, admin.uuid)
+        .await
+        .unwrap();
+    let record_id = case_dao
+        .insert_execute_record(&case_id, &CaseResult::Failed, None, &admin.uuid)
+        .await
+        .unwrap();
+
+    /* a second report waits for the first one to finish or fail */
+    assert!(case_dao
+        .claim_execute_record_report(&record_id)
+        .await
+        .unwrap());
+    assert!(!case_dao
+        .claim_execute_record_report(&record_id)
+        .await
+        .unwrap());
+
+    /* a failed tracker call hands the record back */
+    case_dao
+        .release_execute_record_report(&record_id)
+        .await
+        .unwrap();
+    assert!(case_dao
+        .claim_execute_record_report(&record_id)
+        .await
+        .unwrap());
+
+    let relation_id = case_dao
+        .insert_case_issue_relation(
+            &case_id,
+            &IssueSource::Jira,
+            &TrackerIssue {
+                issue_id: "DT-1".to_string(),
+                title: Faker.fake::<String>(),
+                status: "Open".to_string(),
+                uri: "https://jira.example.com/browse/DT-1".to_string(),
+            },
+            &admin.uuid,
+        )
+        .await
+        .unwrap();
+    case_dao
+        .bind_execute_record_issue(&record_id, &relation_id, &admin.uuid)
+        .await
+        .unwrap();
+    /* reported records stay reported */
+    case_dao
+        .release_execute_record_report(&record_id)
+        .await
+        .unwrap();
+    assert!(!case_dao
+        .claim_execute_record_report(&record_id)
+        .await
+        .unwrap());
+}
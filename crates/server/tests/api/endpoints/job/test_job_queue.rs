@@ -0,0 +1,184 @@
+use crate::{context::seeder::SeedDbTestContext, helper::user::Role};
+use chrono::{DateTime, Utc};
+use server::{
+    dao::job::JobDao,
+    entity::job::{JobKind, JobStatus},
+    errors::AppError,
+    service::job,
+};
+use test_context::test_context;
+
+/* jobs of other tests stay pending, run_at of the epoch puts the new one in front of them */
+const DUE_FIRST: &str = "UPDATE job SET run_at = 'epoch' WHERE id = $1";
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_claimed_job_is_held_by_one_worker(ctx: &mut SeedDbTestContext) {
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let mut client = ctx.app.state.pool.get().await.unwrap();
+    /* rolled back at the end, the claims never touch jobs of other tests */
+    let transaction = client.transaction().await.unwrap();
+    let job_dao = JobDao::new(&transaction);
+    let id = job_dao
+        .create(JobKind::Unknown, "{}", 3, &admin.uuid)
+        .await
+        .unwrap();
+    transaction.execute(DUE_FIRST, &[&id]).await.unwrap();
+
+    let claimed = job_dao.claim("worker-1").await.unwrap().unwrap();
+    assert_eq!((claimed.id, claimed.attempts), (id, 1));
+    let other = job_dao.claim("worker-2").await.unwrap();
+    assert_ne!(other.map(|job| job.id), Some(id));
+
+    assert!(!job_dao.heartbeat(&id, "worker-2").await.unwrap());
+    assert!(job_dao.heartbeat(&id, "worker-1").await.unwrap());
+    let finished = job_dao
+        .finish(&id, "worker-2", JobStatus::Failed, None, None)
+        .await
+        .unwrap();
+    assert!(!finished);
+    let finished = job_dao
+        .finish(
+            &id,
+            "worker-1",
+            JobStatus::Succeeded,
+            Some("{}".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(finished);
+    let job = job_dao.get_job_by_id(&id).await.unwrap();
+    assert_eq!(job.status, JobStatus::Succeeded.to_string());
+    assert_eq!(job.progress, 100);
+    assert_eq!(job.locked_by, None);
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_failed_job_is_retried_until_max_attempts(ctx: &mut SeedDbTestContext) {
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let mut client = ctx.app.state.pool.get().await.unwrap();
+    let transaction = client.transaction().await.unwrap();
+    let job_dao = JobDao::new(&transaction);
+    let id = job_dao
+        .create(JobKind::Unknown, "{}", 2, &admin.uuid)
+        .await
+        .unwrap();
+    transaction.execute(DUE_FIRST, &[&id]).await.unwrap();
+
+    let claimed = job_dao.claim("worker-1").await.unwrap().unwrap();
+    assert_eq!(claimed.id, id);
+    /* only the worker holding the job can put it back */
+    assert!(!job_dao
+        .retry(&id, "worker-2", "failed", DateTime::<Utc>::UNIX_EPOCH)
+        .await
+        .unwrap());
+    assert!(job_dao
+        .retry(&id, "worker-1", "failed", DateTime::<Utc>::UNIX_EPOCH)
+        .await
+        .unwrap());
+    let job = job_dao.get_job_by_id(&id).await.unwrap();
+    assert_eq!(job.status, JobStatus::Pending.to_string());
+    assert_eq!(job.error.as_deref(), Some("failed"));
+
+    let claimed = job_dao.claim("worker-2").await.unwrap().unwrap();
+    assert_eq!((claimed.id, claimed.attempts), (id, 2));
+    /* the second worker is lost on the last attempt, the job fails */
+    let requeued = job_dao
+        .requeue_stale(Utc::now() + chrono::Duration::hours(1))
+        .await
+        .unwrap();
+    assert!(requeued >= 1);
+    let job = job_dao.get_job_by_id(&id).await.unwrap();
+    assert_eq!(job.status, JobStatus::Failed.to_string());
+    assert_eq!(job.error.as_deref(), Some("worker lost"));
+    assert!(!job_dao
+        .finish(&id, "worker-2", JobStatus::Succeeded, None, None)
+        .await
+        .unwrap());
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_cancel_running_job(ctx: &mut SeedDbTestContext) {
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let mut client = ctx.app.state.pool.get().await.unwrap();
+    let transaction = client.transaction().await.unwrap();
+    let job_dao = JobDao::new(&transaction);
+    let id = job_dao
+        .create(JobKind::Unknown, "{}", 3, &admin.uuid)
+        .await
+        .unwrap();
+    transaction.execute(DUE_FIRST, &[&id]).await.unwrap();
+    let claimed = job_dao.claim("worker-1").await.unwrap().unwrap();
+    assert_eq!(claimed.id, id);
+
+    let status = job_dao.cancel(&id).await.unwrap();
+    assert_eq!(status, Some(JobStatus::Running.to_string()));
+    /* the running job sees the request at its next progress report */
+    assert!(job_dao.update_progress(&id, 50).await.unwrap());
+    assert!(!job_dao
+        .retry(&id, "worker-1", "failed", DateTime::<Utc>::UNIX_EPOCH)
+        .await
+        .unwrap());
+    assert!(job_dao
+        .finish(&id, "worker-1", JobStatus::Cancelled, None, None)
+        .await
+        .unwrap());
+    let job = job_dao.get_job_by_id(&id).await.unwrap();
+    assert_eq!(job.status, JobStatus::Cancelled.to_string());
+    assert!(job.cancel_requested);
+    assert_eq!(job_dao.cancel(&id).await.unwrap(), None);
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_lost_job_asked_to_cancel_is_not_requeued(ctx: &mut SeedDbTestContext) {
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let mut client = ctx.app.state.pool.get().await.unwrap();
+    let transaction = client.transaction().await.unwrap();
+    let job_dao = JobDao::new(&transaction);
+    let id = job_dao
+        .create(JobKind::Unknown, "{}", 3, &admin.uuid)
+        .await
+        .unwrap();
+    transaction.execute(DUE_FIRST, &[&id]).await.unwrap();
+    let claimed = job_dao.claim("worker-1").await.unwrap().unwrap();
+    assert_eq!(claimed.id, id);
+    job_dao.cancel(&id).await.unwrap();
+
+    /* the worker dies before it sees the request, attempts are left but the job ends */
+    job_dao
+        .requeue_stale(Utc::now() + chrono::Duration::hours(1))
+        .await
+        .unwrap();
+    let job = job_dao.get_job_by_id(&id).await.unwrap();
+    assert_eq!(job.status, JobStatus::Cancelled.to_string());
+    assert!(job.finished_at.is_some());
+    assert_ne!(
+        job_dao.claim("worker-2").await.unwrap().map(|job| job.id),
+        Some(id)
+    );
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_cancel_pending_job(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let user = ctx.users.get(&Role::User).unwrap();
+    /* unknown kind, a worker would only fail it */
+    let id = job::enqueue(state, admin.uuid, JobKind::Unknown, &())
+        .await
+        .unwrap();
+
+    let err = job::cancel(state, user.uuid, id).await.unwrap_err();
+    assert!(matches!(err, AppError::ForbiddenError(_)), "{err:?}");
+    job::cancel(state, admin.uuid, id).await.unwrap();
+    let job = job::get_job(state, admin.uuid, id).await.unwrap();
+    assert_eq!(job.status, JobStatus::Cancelled.to_string());
+    assert!(job.finished_at.is_some());
+    let err = job::cancel(state, admin.uuid, id).await.unwrap_err();
+    assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+}
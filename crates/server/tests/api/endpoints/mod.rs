@@ -1,4 +1,9 @@
+pub mod element;
 pub mod functional_case;
+pub mod job;
 pub mod permission;
+pub mod plan;
+pub mod project;
+pub mod requirement;
 pub mod role;
 pub mod user;
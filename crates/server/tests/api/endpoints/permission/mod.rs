@@ -1,2 +1,3 @@
+pub mod test_api_token;
 pub mod test_role_permission;
 pub mod test_user_permission;
@@ -0,0 +1,196 @@
+use crate::{
+    context::seeder::SeedDbTestContext,
+    helper::{
+        project::TestProject,
+        user::{Role, TestUser},
+    },
+};
+use chrono::{Duration, Utc};
+use reqwest::StatusCode;
+use server::{
+    constant::MANAGEMENT_FUNCTIONAL_CASE,
+    dao::user::UserDao,
+    dto::request::{
+        case::CreateRequirementRequest,
+        user::{CreateApiTokenRequest, LoginRequest},
+    },
+    service::{api_token, permission},
+};
+use test_context::test_context;
+
+/* project token of the admin carrying only the read permission of functional cases */
+async fn create_case_read_token(
+    ctx: &SeedDbTestContext,
+    admin: &TestUser,
+    expires_at: Option<chrono::DateTime<Utc>>,
+) -> (i32, String) {
+    let permission_list = admin
+        .permission
+        .iter()
+        .filter(|p| p.module == MANAGEMENT_FUNCTIONAL_CASE && p.scope == "READ")
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+    assert!(!permission_list.is_empty());
+    let resp = api_token::create(
+        &ctx.app.state,
+        admin.uuid,
+        CreateApiTokenRequest {
+            name: "case reader".to_string(),
+            project_id: Some(ctx.project.id),
+            permission_list,
+            expires_at,
+        },
+    )
+    .await
+    .unwrap();
+    (resp.id, resp.token)
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_api_token_is_limited_to_its_project_and_permissions(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let system = ctx.users.get(&Role::System).unwrap();
+    let (id, token) = create_case_read_token(ctx, admin, None).await;
+
+    let (status, _) = ctx
+        .app
+        .api
+        .get_functional_case_list(&token, ctx.project.id, &None)
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    let used = api_token::list(state, admin.uuid)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|t| t.id == id)
+        .unwrap();
+    assert!(used.last_used_at.is_some());
+
+    /* write access of another module is not carried by the token */
+    let (status, _) = ctx
+        .app
+        .api
+        .create_requirement(
+            &token,
+            ctx.project.id,
+            &CreateRequirementRequest {
+                name: "from api token".to_string(),
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    /* routes open to every member or every user are out of reach */
+    let (status, _) = ctx
+        .app
+        .api
+        .get_project_info(&token, ctx.project.id)
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = ctx.app.api.list_sessions(&token).await.unwrap();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    /* the owner is a member of another project, the token still is not */
+    let other = TestProject::create_project(&state.pool, system.uuid)
+        .await
+        .unwrap();
+    assert_ne!(other.id, ctx.project.id);
+    {
+        let client = state.pool.get().await.unwrap();
+        let user_dao = UserDao::new(&client);
+        if user_dao
+            .get_member_role(&admin.uuid, &other.id)
+            .await
+            .unwrap()
+            .is_none()
+        {
+            user_dao
+                .insert_user_role_relation(admin.uuid, admin.role_id, other.id, system.uuid)
+                .await
+                .unwrap();
+        }
+    }
+    permission::invalidate_member(state, &admin.uuid, &other.id)
+        .await
+        .unwrap();
+    let jwt = ctx
+        .app
+        .api
+        .get_token(&LoginRequest {
+            username: admin.username.clone(),
+            password: admin.password.clone(),
+        })
+        .await
+        .unwrap();
+    let (status, _) = ctx
+        .app
+        .api
+        .get_functional_case_list(&jwt.access_token, other.id, &None)
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    let (status, _) = ctx
+        .app
+        .api
+        .get_functional_case_list(&token, other.id, &None)
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_revoked_api_token_is_rejected(ctx: &mut SeedDbTestContext) {
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let (id, token) = create_case_read_token(ctx, admin, None).await;
+
+    api_token::revoke(&ctx.app.state, admin.uuid, id)
+        .await
+        .unwrap();
+    let (status, _) = ctx
+        .app
+        .api
+        .get_functional_case_list(&token, ctx.project.id, &None)
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_expired_api_token_is_rejected(ctx: &mut SeedDbTestContext) {
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let (id, token) =
+        create_case_read_token(ctx, admin, Some(Utc::now() + Duration::minutes(5))).await;
+
+    let (status, _) = ctx
+        .app
+        .api
+        .get_functional_case_list(&token, ctx.project.id, &None)
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+
+    /* tokens can not be created already expired, move the expiry into the past instead */
+    let client = ctx.app.state.pool.get().await.unwrap();
+    client
+        .execute(
+            "UPDATE api_token SET expires_at = NOW() - INTERVAL '1 minute' WHERE id = $1",
+            &[&id],
+        )
+        .await
+        .unwrap();
+    let (status, _) = ctx
+        .app
+        .api
+        .get_functional_case_list(&token, ctx.project.id, &None)
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
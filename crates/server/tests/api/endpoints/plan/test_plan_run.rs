@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use crate::{context::seeder::SeedDbTestContext, helper::user::Role};
+use fake::{Fake, Faker};
+use server::{
+    dao::{job::JobDao, plan::PlanDao},
+    dto::request::PlanRunQueryParam,
+    entity::{
+        job::{JobKind, JobStatus},
+        plan::{PlanRun, RunStatus, RunTrigger},
+        project::Plan,
+    },
+    errors::AppError,
+    service::plan,
+    state::AppState,
+};
+use test_context::test_context;
+
+async fn get_runs(state: &AppState, project_id: i32, plan_id: i32) -> Vec<PlanRun> {
+    plan::get_runs(
+        state,
+        project_id,
+        plan_id,
+        PlanRunQueryParam { limit: None },
+    )
+    .await
+    .unwrap()
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_fired_run_is_queued_and_expired_once_lost(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let client = state.pool.get().await.unwrap();
+    let plan_dao = PlanDao::new(&client);
+    let job_dao = JobDao::new(&client);
+    let plan_id = plan_dao
+        .create(Plan::new(
+            &Faker.fake::<String>(),
+            ctx.project.id,
+            1,
+            admin.uuid,
+            None,
+            None,
+            None,
+        ))
+        .await
+        .unwrap();
+    /* due every second, the machine is never reached without job workers */
+    plan_dao
+        .upsert_schedule(&plan_id, None, "* * * * * *", &0, true, &admin.uuid)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    plan::fire_due_schedules(state).await.unwrap();
+    let runs = get_runs(state, ctx.project.id, plan_id).await;
+    assert_eq!(runs.len(), 1);
+    let run = &runs[0];
+    assert_eq!(run.status, RunStatus::Running.to_string());
+    assert_eq!(run.trigger_type, RunTrigger::Schedule.to_string());
+    let job = job_dao.get_job_by_id(&run.job_id.unwrap()).await.unwrap();
+    assert_eq!(job.kind, JobKind::PlanRun.to_string());
+    assert_eq!(job.status, JobStatus::Pending.to_string());
+
+    /* later slots are skipped while the run is in progress */
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    plan::fire_due_schedules(state).await.unwrap();
+    assert_eq!(get_runs(state, ctx.project.id, plan_id).await.len(), 1);
+    let err = plan::run_now(state, admin.uuid, ctx.project.id, plan_id)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+
+    /* the job is gone without finishing the run, it no longer blocks the plan */
+    job_dao.cancel(&job.id).await.unwrap();
+    plan::expire_lost_runs(state).await.unwrap();
+    let runs = get_runs(state, ctx.project.id, plan_id).await;
+    assert_eq!(runs[0].status, RunStatus::Error.to_string());
+    assert!(runs[0].finished_at.is_some());
+    let run_id = plan::run_now(state, admin.uuid, ctx.project.id, plan_id)
+        .await
+        .unwrap();
+
+    plan_dao
+        .soft_delete_schedule(&plan_id, &admin.uuid)
+        .await
+        .unwrap();
+    let runs = get_runs(state, ctx.project.id, plan_id).await;
+    let manual = runs.iter().find(|run| run.id == run_id).unwrap();
+    assert_eq!(manual.trigger_type, RunTrigger::Manual.to_string());
+    job_dao.cancel(&manual.job_id.unwrap()).await.unwrap();
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_concurrent_run_now_starts_one_run(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let client = state.pool.get().await.unwrap();
+    let plan_dao = PlanDao::new(&client);
+    let plan_id = plan_dao
+        .create(Plan::new(
+            &Faker.fake::<String>(),
+            ctx.project.id,
+            1,
+            admin.uuid,
+            None,
+            None,
+            None,
+        ))
+        .await
+        .unwrap();
+    /* disabled, only started by hand */
+    plan_dao
+        .upsert_schedule(&plan_id, None, "0 0 0 1 1 *", &0, false, &admin.uuid)
+        .await
+        .unwrap();
+
+    let (first, second) = tokio::join!(
+        plan::run_now(state, admin.uuid, ctx.project.id, plan_id),
+        plan::run_now(state, admin.uuid, ctx.project.id, plan_id),
+    );
+    let errors = [&first, &second]
+        .into_iter()
+        .filter_map(|result| result.as_ref().err())
+        .collect::<Vec<_>>();
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(
+        matches!(errors[0], AppError::BadRequestError(_)),
+        "{errors:?}"
+    );
+    let runs = get_runs(state, ctx.project.id, plan_id).await;
+    assert_eq!(runs.len(), 1);
+
+    /* the running run also blocks a plain insert, whatever the caller checked before */
+    let err = plan_dao
+        .create_run(&plan_id, None, None, RunTrigger::Manual, &admin.uuid)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::DatabaseError(_)), "{err:?}");
+
+    JobDao::new(&client)
+        .cancel(&runs[0].job_id.unwrap())
+        .await
+        .unwrap();
+}
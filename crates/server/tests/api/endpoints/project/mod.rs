@@ -0,0 +1,2 @@
+pub mod test_project_member;
+pub mod test_project_name;
@@ -0,0 +1,157 @@
+use crate::{
+    assert_err,
+    context::seeder::SeedDbTestContext,
+    helper::user::{Role, TestUser},
+};
+use fake::{Fake, Faker};
+use reqwest::StatusCode;
+use server::{
+    constant::PROJECT_ADMIN_ROLE,
+    dao::user::UserDao,
+    dto::request::{
+        user::LoginRequest, AddMemberRequest, CreateProjectRequest, UpdateMemberRequest,
+    },
+    errors::AppResponseError,
+    service::project,
+};
+use test_context::test_context;
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_project_admin_can_not_grant_internal_roles(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    /* the creator only holds the project admin role of the new project */
+    let project_id = project::create(
+        state,
+        admin.uuid,
+        CreateProjectRequest {
+            name: Faker.fake::<String>(),
+            description: None,
+            modules: None,
+        },
+    )
+    .await
+    .unwrap();
+    let member = TestUser::create_user_with_permission(&state.pool, vec![1])
+        .await
+        .unwrap();
+    let token = ctx
+        .app
+        .api
+        .get_token(&LoginRequest {
+            username: admin.username.clone(),
+            password: admin.password.clone(),
+        })
+        .await
+        .unwrap();
+
+    for role in ["ADMIN", "SYSTEM"] {
+        let (status, resp) = ctx
+            .app
+            .api
+            .add_project_member(
+                &token.access_token,
+                project_id,
+                &AddMemberRequest {
+                    uid: member.id,
+                    role: role.to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::BAD_REQUEST, "role: {role}");
+        assert_err!(resp, |e: &AppResponseError| e.kind == "BAD_REQUEST_ERROR");
+    }
+    let client = state.pool.get().await.unwrap();
+    assert!(UserDao::new(&client)
+        .get_member_role(&member.uuid, &project_id)
+        .await
+        .unwrap()
+        .is_none());
+
+    let (status, _) = ctx
+        .app
+        .api
+        .add_project_member(
+            &token.access_token,
+            project_id,
+            &AddMemberRequest {
+                uid: member.id,
+                role: PROJECT_ADMIN_ROLE.to_string(),
+            },
+        )
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+
+    /* an existing member can not be promoted to them either, not even the caller */
+    for uid in [member.id, admin.id] {
+        let (status, resp) = ctx
+            .app
+            .api
+            .update_project_member(
+                &token.access_token,
+                project_id,
+                &UpdateMemberRequest {
+                    uid,
+                    role: "ADMIN".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_err!(resp, |e: &AppResponseError| e.kind == "BAD_REQUEST_ERROR");
+    }
+    let role = UserDao::new(&client)
+        .get_member_role(&member.uuid, &project_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(role.name, PROJECT_ADMIN_ROLE);
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_project_admin_role_can_not_be_renamed_or_deleted(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let system = ctx.users.get(&Role::System).unwrap();
+    let role_id = {
+        let client = state.pool.get().await.unwrap();
+        UserDao::new(&client)
+            .get_role_id_by_name(PROJECT_ADMIN_ROLE)
+            .await
+            .unwrap()
+    };
+
+    let err = user::update_role(
+        state,
+        UpdateRoleRequest {
+            id: role_id,
+            name: "renamed admin".to_string(),
+            description: None,
+            permission_list: vec![1],
+        },
+        system.uuid,
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::ForbiddenError(_)), "{err:?}");
+    let err = user::delete_role(state, vec![role_id], system.uuid)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::ForbiddenError(_)), "{err:?}");
+
+    /* projects still get their admin */
+    project::create(
+        state,
+        system.uuid,
+        CreateProjectRequest {
+            name: Faker.fake::<String>(),
+            description: None,
+            modules: None,
+        },
+    )
+    .await
+    .unwrap();
+}
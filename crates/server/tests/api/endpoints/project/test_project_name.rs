@@ -0,0 +1,97 @@
+use crate::{context::seeder::SeedDbTestContext, helper::user::Role};
+use fake::{Fake, Faker};
+use server::{
+    dto::request::{CreateProjectRequest, DeleteEntityRequest, UpdateProjectRequest},
+    errors::AppError,
+    service::project,
+};
+use test_context::test_context;
+
+fn create_request(name: &str) -> CreateProjectRequest {
+    CreateProjectRequest {
+        name: name.to_string(),
+        description: None,
+        modules: None,
+    }
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_name_of_deleted_project_can_be_reused(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let system = ctx.users.get(&Role::System).unwrap();
+    let name = Faker.fake::<String>();
+
+    let id = project::create(state, system.uuid, create_request(&name))
+        .await
+        .unwrap();
+    let err = project::create(state, system.uuid, create_request(&name))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+
+    project::delete(state, system.uuid, DeleteEntityRequest { id })
+        .await
+        .unwrap();
+    let new_id = project::create(state, system.uuid, create_request(&name))
+        .await
+        .unwrap();
+    assert_ne!(new_id, id);
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_rename_to_live_project_name_is_rejected(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let system = ctx.users.get(&Role::System).unwrap();
+    let (name, other_name) = (Faker.fake::<String>(), Faker.fake::<String>());
+    let id = project::create(state, system.uuid, create_request(&name))
+        .await
+        .unwrap();
+    let other_id = project::create(state, system.uuid, create_request(&other_name))
+        .await
+        .unwrap();
+
+    let rename = |project_id| {
+        project::update(
+            state,
+            system.uuid,
+            project_id,
+            UpdateProjectRequest {
+                name: name.clone(),
+                description: None,
+            },
+        )
+    };
+    let err = rename(other_id).await.unwrap_err();
+    assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+
+    /* once deleted the name is free again */
+    project::delete(state, system.uuid, DeleteEntityRequest { id })
+        .await
+        .unwrap();
+    rename(other_id).await.unwrap();
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_concurrent_creates_with_same_name(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let system = ctx.users.get(&Role::System).unwrap();
+    let name = Faker.fake::<String>();
+
+    let (first, second) = tokio::join!(
+        project::create(state, system.uuid, create_request(&name)),
+        project::create(state, system.uuid, create_request(&name)),
+    );
+    /* whichever loses the race gets the same error as the checked path */
+    let errors = [first, second]
+        .into_iter()
+        .filter_map(Result::err)
+        .collect::<Vec<_>>();
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(
+        matches!(errors[0], AppError::BadRequestError(_)),
+        "{errors:?}"
+    );
+}
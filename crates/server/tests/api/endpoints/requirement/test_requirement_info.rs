@@ -0,0 +1,175 @@
+use crate::{context::seeder::SeedDbTestContext, helper::user::Role, unwrap};
+use fake::{Fake, Faker};
+use server::{
+    dao::{case::CaseDao, file::FileDao, plan::PlanDao},
+    dto::request::{
+        case::{CreateRequirementRequest, RequirementCaseRequest, RequirementInfoParam},
+        user::LoginRequest,
+    },
+    entity::{
+        case::{CaseResult, FunctionalCase},
+        file::{FileModule, ModuleType},
+        project::Plan,
+    },
+};
+use test_context::test_context;
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_success_get_requirement_coverage(ctx: &mut SeedDbTestContext) {
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+
+    let req: LoginRequest = LoginRequest {
+        username: admin.username.clone(),
+        password: admin.password.clone(),
+    };
+    let token = ctx.app.api.get_token(&req).await.unwrap();
+
+    /* records are inserted outside a transaction so their created_at keeps the insert order */
+    let client = ctx.app.state.pool.get().await.unwrap();
+    let case_dao = CaseDao::new(&client);
+    let file_dao = FileDao::new(&client);
+    let plan_dao = PlanDao::new(&client);
+    let module_id = file_dao
+        .insert_file_module(
+            &admin.uuid,
+            ctx.project.id,
+            &FileModule {
+                id: 0,
+                name: Faker.fake::<String>(),
+                module_type: ModuleType::Case,
+                position: 1,
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    let mut case_ids = Vec::new();
+    for _ in 0..3 {
+        let module = file_dao.get_module_by_id(module_id).await.unwrap();
+        let case = FunctionalCase::new(&Faker.fake::<String>(), module, 1, vec![]);
+        case_ids.push(
+            case_dao
+                .insert_functional_case(case, admin.uuid)
+                .await
+                .unwrap(),
+        );
+    }
+    let mut plan_ids = Vec::new();
+    for _ in 0..2 {
+        let plan = Plan::new(
+            &Faker.fake::<String>(),
+            ctx.project.id,
+            1,
+            admin.uuid,
+            None,
+            None,
+            None,
+        );
+        plan_ids.push(plan_dao.create(plan).await.unwrap());
+    }
+    let (plan_id, other_plan_id) = (plan_ids[0], plan_ids[1]);
+    /* only the latest record of each case within the plan counts */
+    for (case_id, result, plan_id) in [
+        (case_ids[0], CaseResult::Failed, plan_id),
+        (case_ids[0], CaseResult::Passed, plan_id),
+        (case_ids[1], CaseResult::Passed, plan_id),
+        (case_ids[1], CaseResult::Failed, plan_id),
+        (case_ids[1], CaseResult::Passed, other_plan_id),
+        (case_ids[2], CaseResult::Passed, plan_id),
+    ] {
+        case_dao
+            .insert_execute_record(&case_id, &result, Some(plan_id), &admin.uuid)
+            .await
+            .unwrap();
+    }
+
+    let (status, resp) = ctx
+        .app
+        .api
+        .create_requirement(
+            &token.access_token,
+            ctx.project.id,
+            &CreateRequirementRequest {
+                name: "covered requirement".to_string(),
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    let covered = unwrap!(resp);
+
+    let (status, resp) = ctx
+        .app
+        .api
+        .create_requirement(
+            &token.access_token,
+            ctx.project.id,
+            &CreateRequirementRequest {
+                name: "uncovered requirement".to_string(),
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    let uncovered = unwrap!(resp);
+
+    let (status, _resp) = ctx
+        .app
+        .api
+        .link_requirement_cases(
+            &token.access_token,
+            ctx.project.id,
+            &RequirementCaseRequest {
+                requirement_id: covered.id,
+                case_ids: case_ids[..2].to_vec(),
+            },
+        )
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+
+    let (status, resp) = ctx
+        .app
+        .api
+        .get_requirement_info(
+            &token.access_token,
+            ctx.project.id,
+            &RequirementInfoParam {
+                plan_id: Some(plan_id),
+            },
+        )
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    let info = unwrap!(resp);
+    assert_eq!(info.plan_id, Some(plan_id));
+    let item = info.list.iter().find(|r| r.id == covered.id).unwrap();
+    assert_eq!(item.case_count, 2);
+    assert_eq!(item.passed_count, 1);
+    let item = info.list.iter().find(|r| r.id == uncovered.id).unwrap();
+    assert_eq!(item.case_count, 0);
+    assert_eq!(item.passed_count, 0);
+    assert!(info.uncovered.iter().any(|r| r.id == uncovered.id));
+    assert!(!info.uncovered.iter().any(|r| r.id == covered.id));
+
+    /* the other plan only executed the second case and it passed */
+    let (_, resp) = ctx
+        .app
+        .api
+        .get_requirement_info(
+            &token.access_token,
+            ctx.project.id,
+            &RequirementInfoParam {
+                plan_id: Some(other_plan_id),
+            },
+        )
+        .await
+        .unwrap();
+    let info = unwrap!(resp);
+    let item = info.list.iter().find(|r| r.id == covered.id).unwrap();
+    assert_eq!(item.case_count, 2);
+    assert_eq!(item.passed_count, 1);
+}
@@ -6,7 +6,7 @@ use crate::{
 };
 use fake::{Fake, Faker};
 use server::{
-    constant::ACCESS_TOKEN_ENCODE_KEY,
+    constant::ACCESS_TOKEN_KEYS,
     dto::{
         request::{user::LoginRequest, CreateRoleRequest},
         response::CreateEntityResponse,
@@ -189,7 +189,7 @@ pub async fn test_expired_token_create_role(ctx: &mut SeedDbTestContext) {
         .await
         .unwrap();
     let expired_token = UserClaims::new(Duration::from_secs(0), admin.uuid, session_id)
-        .encode(&ACCESS_TOKEN_ENCODE_KEY)
+        .encode(&ACCESS_TOKEN_KEYS)
         .unwrap();
     let req: CreateRoleRequest = CreateRoleRequest {
         name: Faker.fake::<String>(),
@@ -1,6 +1,11 @@
+pub mod test_invitation;
 pub mod test_login;
 pub mod test_logout;
+pub mod test_mfa;
+pub mod test_refresh_token;
 pub mod test_register;
+pub mod test_session;
+pub mod test_sso;
 pub mod test_user_delete;
 pub mod test_user_get;
 pub mod test_user_update;
@@ -0,0 +1,51 @@
+use crate::{assert_err, context::seeder::SeedDbTestContext, helper::user::Role, unwrap};
+use server::{
+    dao::user::UserDao,
+    dto::{
+        request::user::{AcceptInvitationRequest, LoginRequest},
+        response::user::LoginResponse,
+    },
+    errors::AppResponseError,
+};
+use test_context::test_context;
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_password_change_required_on_first_login(ctx: &mut SeedDbTestContext) {
+    let user = ctx.users.get(&Role::User).unwrap();
+    let client = ctx.app.state.pool.get().await.unwrap();
+    UserDao::new(&client)
+        .set_password_change_required(&user.uuid, true)
+        .await
+        .unwrap();
+
+    let req = LoginRequest {
+        username: user.username.clone(),
+        password: user.password.clone(),
+    };
+    let (status, resp) = ctx.app.api.login(&req).await.unwrap();
+    assert!(status.is_success(), "status: {status}");
+    let LoginResponse::PasswordChangeRequired { ticket } = unwrap!(resp) else {
+        panic!("expected password change to be required");
+    };
+
+    let accept = AcceptInvitationRequest {
+        token: ticket,
+        password: "new_test_password".to_string(),
+    };
+    let (status, resp) = ctx.app.api.accept_invitation(&accept).await.unwrap();
+    assert!(status.is_success(), "status: {status}");
+    assert!(matches!(unwrap!(resp), LoginResponse::Token(_)));
+
+    /* the ticket is single use */
+    let (status, resp) = ctx.app.api.accept_invitation(&accept).await.unwrap();
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+    assert_err!(resp, |e: &AppResponseError| e.kind == "BAD_REQUEST_ERROR");
+
+    let req = LoginRequest {
+        username: user.username.clone(),
+        password: "new_test_password".to_string(),
+    };
+    let token = ctx.app.api.get_token(&req).await.unwrap();
+    assert!(!token.access_token.is_empty());
+}
@@ -28,7 +28,7 @@ pub async fn test_success_login(ctx: &mut SeedDbTestContext) {
             assert!(!token.access_token.is_empty());
             assert!(!token.refresh_token.is_empty());
         }
-        LoginResponse::Code { .. } => {
+        _ => {
             panic!("not expected to receive message.");
         }
     }
@@ -90,3 +90,26 @@ pub async fn test_short_password_login(ctx: &mut SeedDbTestContext) {
     assert!(status.is_client_error(), "status: {status}");
     assert_err!(resp, |e: &AppResponseError| e.kind == "INVALID_INPUT_ERROR");
 }
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_repeated_failed_login_is_throttled(ctx: &mut SeedDbTestContext) {
+    let user = ctx.users.get(&Role::User).unwrap();
+    let wrong = LoginRequest {
+        username: user.username.clone(),
+        password: "wrong_password".to_string(),
+    };
+    for _ in 0..2 {
+        let (status, _resp) = ctx.app.api.login(&wrong).await.unwrap();
+        assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+    }
+    /* even the right password waits for the backoff */
+    let req = LoginRequest {
+        username: user.username.clone(),
+        password: user.password.clone(),
+    };
+    let (status, resp) = ctx.app.api.login(&req).await.unwrap();
+    assert_eq!(status, reqwest::StatusCode::TOO_MANY_REQUESTS);
+    assert_err!(resp, |e: &AppResponseError| e.kind
+        == "TOO_MANY_REQUESTS_ERROR");
+}
@@ -3,7 +3,7 @@ use fake::{Fake, Faker};
 
 use server::{
     utils::claim::UserClaims,
-    {constant::ACCESS_TOKEN_DECODE_KEY, dto::request::user::LoginRequest},
+    {constant::ACCESS_TOKEN_KEYS, dto::request::user::LoginRequest},
 };
 use test_context::test_context;
 
@@ -58,7 +58,7 @@ pub async fn test_logout_session(ctx: &mut SeedDbTestContext) {
     assert!(status.is_success(), "status: {status}");
 
     /* TODO: check redis key whether still exist or not*/
-    let user_claims = UserClaims::decode(&token.access_token, &ACCESS_TOKEN_DECODE_KEY).unwrap();
+    let user_claims = UserClaims::decode(&token.access_token, &ACCESS_TOKEN_KEYS).unwrap();
     assert!(
         server::service::session::check(&ctx.app.state.redis, &user_claims.claims)
             .await
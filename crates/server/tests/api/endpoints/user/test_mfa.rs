@@ -0,0 +1,281 @@
+use std::time::Duration;
+
+use crate::{
+    assert_err,
+    context::seeder::SeedDbTestContext,
+    helper::user::{Role, TestUser},
+    unwrap,
+};
+use server::{
+    dao::mfa::MfaDao,
+    dto::{
+        request::user::{LoginRequest, MfaCodeRequest, MfaEnrollChallengeRequest, MfaLoginRequest},
+        response::user::{LoginResponse, MfaEnrollResponse},
+    },
+    errors::{AppError, AppResponseError},
+    service::mfa,
+    utils::header::ClientInfo,
+};
+use test_context::test_context;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+fn current_code(enroll: &MfaEnrollResponse) -> String {
+    let secret = Secret::Encoded(enroll.secret.clone()).to_bytes().unwrap();
+    TOTP::new_unchecked(Algorithm::SHA1, 6, 0, 30, secret, None, String::new())
+        .generate_current()
+        .unwrap()
+}
+
+/* a fresh user with mfa enabled, the enrollment is not confirmed so no step is used yet */
+async fn create_mfa_user(ctx: &SeedDbTestContext) -> (TestUser, MfaEnrollResponse) {
+    let user = TestUser::create_user_with_permission(&ctx.app.state.pool, vec![1])
+        .await
+        .unwrap();
+    let enroll = mfa::enroll(&ctx.app.state, user.uuid).await.unwrap();
+    let client = ctx.app.state.pool.get().await.unwrap();
+    assert!(MfaDao::new(&client).enable(&user.uuid).await.unwrap());
+    (user, enroll)
+}
+
+async fn login_challenge(ctx: &SeedDbTestContext, user: &TestUser) -> String {
+    let (status, resp) = ctx
+        .app
+        .api
+        .login(&LoginRequest {
+            username: user.username.clone(),
+            password: user.password.clone(),
+        })
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    match unwrap!(resp) {
+        LoginResponse::MfaRequired { challenge } => challenge,
+        other => panic!("unexpected login response: {other:?}"),
+    }
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_success_login_with_totp_code(ctx: &mut SeedDbTestContext) {
+    let (user, enroll) = create_mfa_user(ctx).await;
+
+    let challenge = login_challenge(ctx, &user).await;
+    let (status, resp) = ctx
+        .app
+        .api
+        .login_mfa(&MfaLoginRequest {
+            challenge: challenge.clone(),
+            code: current_code(&enroll),
+        })
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    let LoginResponse::Token(token) = unwrap!(resp) else {
+        panic!("token expected");
+    };
+    let (status, _) = ctx
+        .app
+        .api
+        .list_sessions(&token.access_token)
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+
+    /* the challenge is used up together with the code */
+    let (status, _) = ctx
+        .app
+        .api
+        .login_mfa(&MfaLoginRequest {
+            challenge,
+            code: enroll.recovery_codes[0].clone(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_replayed_totp_code_is_rejected(ctx: &mut SeedDbTestContext) {
+    let (user, enroll) = create_mfa_user(ctx).await;
+    let code = current_code(&enroll);
+
+    let challenge = login_challenge(ctx, &user).await;
+    let (status, _) = ctx
+        .app
+        .api
+        .login_mfa(&MfaLoginRequest {
+            challenge,
+            code: code.clone(),
+        })
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+
+    let challenge = login_challenge(ctx, &user).await;
+    let (status, resp) = ctx
+        .app
+        .api
+        .login_mfa(&MfaLoginRequest { challenge, code })
+        .await
+        .unwrap();
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+    assert_err!(resp, |e: &AppResponseError| e.kind == "BAD_REQUEST_ERROR");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_recovery_code_works_once(ctx: &mut SeedDbTestContext) {
+    let (user, enroll) = create_mfa_user(ctx).await;
+    /* accepted without the dash and in upper case */
+    let code = enroll.recovery_codes[0].replace('-', "").to_uppercase();
+
+    let challenge = login_challenge(ctx, &user).await;
+    let (status, resp) = ctx
+        .app
+        .api
+        .login_mfa(&MfaLoginRequest {
+            challenge,
+            code: code.clone(),
+        })
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    assert!(matches!(unwrap!(resp), LoginResponse::Token(_)));
+
+    let challenge = login_challenge(ctx, &user).await;
+    let (status, resp) = ctx
+        .app
+        .api
+        .login_mfa(&MfaLoginRequest {
+            challenge: challenge.clone(),
+            code,
+        })
+        .await
+        .unwrap();
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+    assert_err!(resp, |e: &AppResponseError| e.kind == "BAD_REQUEST_ERROR");
+
+    /* the other codes are still valid */
+    let (status, _) = ctx
+        .app
+        .api
+        .login_mfa(&MfaLoginRequest {
+            challenge,
+            code: enroll.recovery_codes[1].clone(),
+        })
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_role_requires_mfa_enrollment(ctx: &mut SeedDbTestContext) {
+    let system = ctx.users.get(&Role::System).unwrap();
+    let user = TestUser::create_user_with_permission(&ctx.app.state.pool, vec![1])
+        .await
+        .unwrap();
+    let client = ctx.app.state.pool.get().await.unwrap();
+    assert!(MfaDao::new(&client)
+        .set_role_required(&user.role_id, true, &system.uuid)
+        .await
+        .unwrap());
+
+    let (status, resp) = ctx
+        .app
+        .api
+        .login(&LoginRequest {
+            username: user.username.clone(),
+            password: user.password.clone(),
+        })
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    let LoginResponse::MfaEnrollmentRequired { challenge } = unwrap!(resp) else {
+        panic!("enrollment expected");
+    };
+
+    /* no session before the second factor is set up */
+    let (status, _) = ctx
+        .app
+        .api
+        .login_mfa(&MfaLoginRequest {
+            challenge: challenge.clone(),
+            code: "000000".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+
+    let (status, resp) = ctx
+        .app
+        .api
+        .login_mfa_enroll(&MfaEnrollChallengeRequest {
+            challenge: challenge.clone(),
+        })
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    let enroll = unwrap!(resp);
+    let (status, resp) = ctx
+        .app
+        .api
+        .login_mfa(&MfaLoginRequest {
+            challenge,
+            code: current_code(&enroll),
+        })
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    assert!(matches!(unwrap!(resp), LoginResponse::Token(_)));
+
+    /* enrolled users of the role are asked for the code and can not opt out */
+    login_challenge(ctx, &user).await;
+    let err = mfa::disable(
+        &ctx.app.state,
+        user.uuid,
+        MfaCodeRequest {
+            code: enroll.recovery_codes[0].clone(),
+        },
+        &ClientInfo::default(),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::ForbiddenError(_)), "{err:?}");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_repeated_bad_codes_lock_out_mfa_login(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let (user, enroll) = create_mfa_user(ctx).await;
+    let challenge = login_challenge(ctx, &user).await;
+
+    let config = &state.config.lockout;
+    /* without a client ip only the user counter is involved, other tests keep their ip budget */
+    let client_info = &ClientInfo::default();
+    let attempt = |code: &str| {
+        mfa::login(
+            state,
+            MfaLoginRequest {
+                challenge: challenge.clone(),
+                code: code.to_string(),
+            },
+            client_info,
+        )
+    };
+
+    for failures in 0..config.max_failures {
+        /* wait out the backoff so every attempt reaches the code check */
+        tokio::time::sleep(Duration::from_millis(config.backoff(failures) * 1000 + 100)).await;
+        let err = attempt("abcde-abcde").await.unwrap_err();
+        assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+    }
+    let err = attempt("abcde-abcde").await.unwrap_err();
+    assert!(matches!(err, AppError::TooManyRequestsError(_)), "{err:?}");
+
+    /* a valid code does not get through the lockout either */
+    let err = attempt(&current_code(&enroll)).await.unwrap_err();
+    assert!(matches!(err, AppError::TooManyRequestsError(_)), "{err:?}");
+}
@@ -0,0 +1,70 @@
+use crate::{context::seeder::SeedDbTestContext, helper::user::Role, unwrap};
+use server::{
+    constant::{ACCESS_TOKEN_KEYS, EXPIRE_REFRESH_TOKEN_SECS, REFRESH_TOKEN_KEYS},
+    dto::{
+        request::{user::LoginRequest, RefreshTokenRequest},
+        response::user::LoginResponse,
+    },
+    utils::claim::UserClaims,
+};
+use test_context::test_context;
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_refresh_token_rotation_and_reuse(ctx: &mut SeedDbTestContext) {
+    let user = ctx.users.get(&Role::User).unwrap();
+    let req = LoginRequest {
+        username: user.username.clone(),
+        password: user.password.clone(),
+    };
+    let token = ctx.app.api.get_token(&req).await.unwrap();
+    let old = RefreshTokenRequest {
+        refresh_token: token.refresh_token.clone(),
+    };
+
+    let (status, resp) = ctx.app.api.refresh_token(&old).await.unwrap();
+    assert!(status.is_success(), "status: {status}");
+    let LoginResponse::Token(rotated) = unwrap!(resp) else {
+        panic!("expected new tokens");
+    };
+    assert_ne!(rotated.refresh_token, token.refresh_token);
+
+    /* the rotated token was used again, the whole session is revoked */
+    let (status, _resp) = ctx.app.api.refresh_token(&old).await.unwrap();
+    assert_eq!(status, reqwest::StatusCode::UNAUTHORIZED);
+    let new = RefreshTokenRequest {
+        refresh_token: rotated.refresh_token,
+    };
+    let (status, _resp) = ctx.app.api.refresh_token(&new).await.unwrap();
+    assert!(status.is_client_error(), "status: {status}");
+    let (status, _resp) = ctx.app.api.logout(&rotated.access_token).await.unwrap();
+    assert!(status.is_client_error(), "status: {status}");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_refresh_token_without_jti_is_rejected(ctx: &mut SeedDbTestContext) {
+    let user = ctx.users.get(&Role::User).unwrap();
+    let req = LoginRequest {
+        username: user.username.clone(),
+        password: user.password.clone(),
+    };
+    let token = ctx.app.api.get_token(&req).await.unwrap();
+    let claims = UserClaims::decode(&token.access_token, &ACCESS_TOKEN_KEYS)
+        .unwrap()
+        .claims;
+    /* issued for the live session before refresh tokens were rotated */
+    let legacy = UserClaims::new(EXPIRE_REFRESH_TOKEN_SECS, claims.uid, claims.sid)
+        .encode(&REFRESH_TOKEN_KEYS)
+        .unwrap();
+
+    let (status, _resp) = ctx
+        .app
+        .api
+        .refresh_token(&RefreshTokenRequest {
+            refresh_token: legacy,
+        })
+        .await
+        .unwrap();
+    assert_eq!(status, reqwest::StatusCode::UNAUTHORIZED);
+}
@@ -5,11 +5,19 @@ use crate::{
         user::{Role, TestUser},
     },
 };
-use fake::{Fake, Faker};
+use fake::{
+    faker::internet::en::{SafeEmail, Username},
+    Fake, Faker,
+};
 
-use server::dto::{
-    request::{user::LoginRequest, RegisterRequest, UserInfo},
-    response::MessageResponse,
+use server::{
+    dao::user::UserDao,
+    dto::{
+        request::{user::LoginRequest, RegisterRequest, UserInfo},
+        response::MessageResponse,
+    },
+    errors::AppError,
+    service::user,
 };
 use test_context::test_context;
 
@@ -157,4 +165,49 @@ pub async fn test_batch_users_within_invalid_register(_ctx: &mut SeedDbTestConte
 
 #[test_context(SeedDbTestContext)]
 #[tokio::test]
-pub async fn test_batch_users_within_already_exists_register(_ctx: &mut SeedDbTestContext) {}
+pub async fn test_batch_users_within_already_exists_register(ctx: &mut SeedDbTestContext) {
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let first: UserInfo = Faker.fake();
+    let second: UserInfo = Faker.fake();
+    let usernames = [first.username.clone(), second.username.clone()];
+    /* duplicates the first row within the batch */
+    let duplicated = UserInfo {
+        username: first.username.to_uppercase(),
+        email: SafeEmail().fake(),
+    };
+    /* collides with an existing user */
+    let existing = UserInfo {
+        username: Username().fake(),
+        email: admin.email.clone(),
+    };
+    let user_info_list = vec![first, second, duplicated, existing];
+
+    let report = match user::batch_register(
+        &ctx.app.state,
+        admin.uuid,
+        RegisterRequest { user_info_list },
+    )
+    .await
+    {
+        Err(AppError::InvalidInputError(report)) => report,
+        other => panic!("unexpected result: {other:?}"),
+    };
+    let paths = report
+        .iter()
+        .map(|(path, _)| path.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        vec![
+            garde::Path::new(2).join("username").to_string(),
+            garde::Path::new(3).join("email").to_string(),
+        ]
+    );
+
+    /* the valid rows are not inserted either */
+    let client = ctx.app.state.pool.get().await.unwrap();
+    let user_dao = UserDao::new(&client);
+    for username in usernames {
+        assert!(user_dao.find_by_username(username).await.is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{context::seeder::SeedDbTestContext, helper::user::Role, unwrap};
+use server::{
+    constant::ACCESS_TOKEN_KEYS, dto::request::user::LoginRequest, utils::claim::UserClaims,
+};
+use test_context::test_context;
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_revoke_one_of_two_sessions(ctx: &mut SeedDbTestContext) {
+    let user = ctx.users.get(&Role::User).unwrap();
+    let req = LoginRequest {
+        username: user.username.clone(),
+        password: user.password.clone(),
+    };
+    let first = ctx.app.api.get_token(&req).await.unwrap();
+    let second = ctx.app.api.get_token(&req).await.unwrap();
+    let first_sid = UserClaims::decode(&first.access_token, &ACCESS_TOKEN_KEYS)
+        .unwrap()
+        .claims
+        .sid;
+
+    let (status, resp) = ctx
+        .app
+        .api
+        .list_sessions(&second.access_token)
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    let sessions = unwrap!(resp);
+    assert!(sessions.list.iter().any(|s| s.sid == first_sid));
+    assert!(sessions.list.iter().any(|s| s.sid == sessions.current));
+
+    let (status, _resp) = ctx
+        .app
+        .api
+        .revoke_session(&second.access_token, &first_sid)
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+
+    /* only the revoked session is logged out */
+    let (status, _resp) = ctx
+        .app
+        .api
+        .list_sessions(&first.access_token)
+        .await
+        .unwrap();
+    assert_eq!(status, reqwest::StatusCode::UNAUTHORIZED);
+    let (status, resp) = ctx
+        .app
+        .api
+        .list_sessions(&second.access_token)
+        .await
+        .unwrap();
+    assert!(status.is_success(), "status: {status}");
+    assert!(unwrap!(resp).list.iter().all(|s| s.sid != first_sid));
+}
@@ -0,0 +1,310 @@
+use std::sync::Arc;
+
+use crate::context::seeder::SeedDbTestContext;
+use crate::helper::user::Role;
+use chrono::Utc;
+use jsonwebtoken::{Algorithm, Header};
+use reqwest::Url;
+use serde_json::json;
+use server::{
+    configure::sso::ConfigOIDC,
+    constant::{ACCESS_TOKEN_KEYS, CONFIG},
+    dao::user::UserDao,
+    dto::{
+        request::user::{LdapLoginRequest, OidcCallbackParam},
+        response::user::LoginResponse,
+    },
+    entity::user::User,
+    errors::{AppError, AppResult},
+    service::sso,
+    state::AppState,
+    utils::{
+        claim::UserClaims,
+        header::ClientInfo,
+        jwk::{KeySet, KeyStore, SigningKey},
+    },
+};
+use test_context::test_context;
+use uuid::Uuid;
+use wiremock::{
+    matchers::{body_string_contains, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+const CLIENT_ID: &str = "meter";
+
+fn issuer_keys() -> KeyStore {
+    let key = SigningKey::from_pem(
+        "issuer",
+        Some(&CONFIG.jwt.read_private_access_key().unwrap()),
+        &CONFIG.jwt.read_public_access_key().unwrap(),
+        None,
+    )
+    .unwrap();
+    KeyStore::new(KeySet::new("issuer", vec![key]).unwrap())
+}
+
+/* the app state with the mock server of the context as oidc issuer */
+async fn oidc_state(ctx: &SeedDbTestContext) -> AppState {
+    let server = &ctx.app.mock_server;
+    Mock::given(method("GET"))
+        .and(path("/.well-known/openid-configuration"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "issuer": server.uri(),
+            "authorization_endpoint": format!("{}/authorize", server.uri()),
+            "token_endpoint": format!("{}/token", server.uri()),
+            "jwks_uri": format!("{}/jwks", server.uri()),
+        })))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/jwks"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(issuer_keys().jwks()))
+        .mount(server)
+        .await;
+    let mut config = (*ctx.app.state.config).clone();
+    config.sso.oidc = Some(ConfigOIDC {
+        issuer: server.uri(),
+        client_id: CLIENT_ID.to_string(),
+        client_secret: "client_secret".to_string(),
+        redirect_uri: "http://localhost:3000/sso/callback".to_string(),
+        scopes: vec!["openid".to_string()],
+        groups_claim: "groups".to_string(),
+    });
+    AppState {
+        config: Arc::new(config),
+        ..ctx.app.state.clone()
+    }
+}
+
+/* state, nonce and browser binding of a new authorization */
+async fn authorize(state: &AppState) -> (String, String, String) {
+    let (resp, binding) = sso::oidc_authorize(state).await.unwrap();
+    let url = Url::parse(&resp.url).unwrap();
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.to_string())
+            .unwrap()
+    };
+    (param("state"), param("nonce"), binding)
+}
+
+/* code the issuer exchanges for an id token of `sub` */
+async fn issue_code(server: &MockServer, nonce: &str, sub: &str, username: &str) -> String {
+    let now = Utc::now().timestamp();
+    let claims = json!({
+        "iss": server.uri(),
+        "aud": CLIENT_ID,
+        "sub": sub,
+        "nonce": nonce,
+        "preferred_username": username,
+        "email": format!("{sub}@example.com"),
+        "iat": now,
+        "exp": now + 300,
+    });
+    let id_token = issuer_keys()
+        .encode(&Header::new(Algorithm::RS256), &claims)
+        .unwrap();
+    let code = Uuid::new_v4().simple().to_string();
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(body_string_contains(format!("code={code}")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id_token": id_token })))
+        .mount(server)
+        .await;
+    code
+}
+
+async fn callback(
+    state: &AppState,
+    csrf_state: &str,
+    code: &str,
+    binding: Option<&str>,
+) -> AppResult<LoginResponse> {
+    let param = OidcCallbackParam {
+        code: code.to_string(),
+        state: csrf_state.to_string(),
+    };
+    sso::oidc_callback(state, param, binding, &ClientInfo::default()).await
+}
+
+async fn oidc_login(
+    state: &AppState,
+    server: &MockServer,
+    sub: &str,
+    username: &str,
+) -> AppResult<LoginResponse> {
+    let (csrf_state, nonce, binding) = authorize(state).await;
+    let code = issue_code(server, &nonce, sub, username).await;
+    callback(state, &csrf_state, &code, Some(&binding)).await
+}
+
+fn logged_in_uid(resp: LoginResponse) -> Uuid {
+    let LoginResponse::Token(token) = resp else {
+        panic!("expected a token, got {resp:?}");
+    };
+    UserClaims::decode(&token.access_token, &ACCESS_TOKEN_KEYS)
+        .unwrap()
+        .claims
+        .uid
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_oidc_login_links_identity_by_subject(ctx: &mut SeedDbTestContext) {
+    let state = oidc_state(ctx).await;
+    let server = &ctx.app.mock_server;
+    let sub = Uuid::new_v4().to_string();
+    let username = format!("oidc-{sub}");
+
+    let uid = logged_in_uid(oidc_login(&state, server, &sub, &username).await.unwrap());
+    /* the name claim may change, the subject keeps pointing at the same user */
+    let renamed = format!("renamed-{sub}");
+    let again = logged_in_uid(oidc_login(&state, server, &sub, &renamed).await.unwrap());
+    assert_eq!(again, uid);
+
+    /* another subject claiming the name of the provisioned user is refused */
+    let other = Uuid::new_v4().to_string();
+    let err = oidc_login(&state, server, &other, &username)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::ForbiddenError(_)), "{err:?}");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_oidc_login_refuses_local_account(ctx: &mut SeedDbTestContext) {
+    let state = oidc_state(ctx).await;
+    let admin = ctx.users.get(&Role::Admin).unwrap();
+    let sub = Uuid::new_v4().to_string();
+
+    let err = oidc_login(&state, &ctx.app.mock_server, &sub, &admin.username)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::ForbiddenError(_)), "{err:?}");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_oidc_state_is_bound_to_browser_and_single_use(ctx: &mut SeedDbTestContext) {
+    let state = oidc_state(ctx).await;
+    let server = &ctx.app.mock_server;
+    let sub = Uuid::new_v4().to_string();
+    let username = format!("oidc-{sub}");
+
+    for other_browser in [None, Some("another browser")] {
+        let (csrf_state, nonce, _binding) = authorize(&state).await;
+        let code = issue_code(server, &nonce, &sub, &username).await;
+        let err = callback(&state, &csrf_state, &code, other_browser)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+    }
+
+    let (csrf_state, nonce, binding) = authorize(&state).await;
+    let code = issue_code(server, &nonce, &sub, &username).await;
+    callback(&state, &csrf_state, &code, Some(&binding))
+        .await
+        .unwrap();
+    let err = callback(&state, &csrf_state, &code, Some(&binding))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::BadRequestError(_)), "{err:?}");
+}
+
+fn ldap_request(username: &str, password: &str) -> LdapLoginRequest {
+    LdapLoginRequest {
+        username: username.to_string(),
+        password: password.to_string(),
+    }
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_ldap_login_links_identity_by_dn(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let client_info = ClientInfo::default();
+
+    let resp = sso::ldap_login(
+        state,
+        ldap_request("ldap.tester", "ldap_password"),
+        &client_info,
+    )
+    .await
+    .unwrap();
+    let uid = logged_in_uid(resp);
+    let resp = sso::ldap_login(
+        state,
+        ldap_request("ldap.tester", "ldap_password"),
+        &client_info,
+    )
+    .await
+    .unwrap();
+    assert_eq!(logged_in_uid(resp), uid);
+
+    let err = sso::ldap_login(state, ldap_request("ldap.tester", "wrong"), &client_info)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::UnauthorizedError(_)), "{err:?}");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_ldap_login_refuses_local_account(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let system = ctx.users.get(&Role::System).unwrap();
+    let client = state.pool.get().await.unwrap();
+    let user_dao = UserDao::new(&client);
+    if user_dao
+        .find_by_username("ldap.local".to_string())
+        .await
+        .is_err()
+    {
+        let user = User::new(
+            "ldap.local",
+            "not a password hash",
+            "ldap.local@example.com",
+            system.uuid,
+            true,
+        );
+        user_dao.insert(&user).await.unwrap();
+    }
+
+    let err = sso::ldap_login(
+        state,
+        ldap_request("ldap.local", "ldap_password"),
+        &ClientInfo::default(),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::ForbiddenError(_)), "{err:?}");
+}
+
+#[test_context(SeedDbTestContext)]
+#[tokio::test]
+pub async fn test_ldap_login_locks_out_repeated_failures(ctx: &mut SeedDbTestContext) {
+    let state = &ctx.app.state;
+    let username = format!("ldap.{}", Uuid::new_v4().simple());
+    let max_failures = state.config.lockout.max_failures;
+
+    let mut errors = vec![];
+    for _ in 0..=max_failures {
+        let err = sso::ldap_login(
+            state,
+            ldap_request(&username, "wrong"),
+            &ClientInfo::default(),
+        )
+        .await
+        .unwrap_err();
+        errors.push(err);
+    }
+    assert!(
+        matches!(errors[0], AppError::UnauthorizedError(_)),
+        "{errors:?}"
+    );
+    assert!(
+        matches!(errors.last(), Some(AppError::TooManyRequestsError(_))),
+        "{errors:?}"
+    );
+}
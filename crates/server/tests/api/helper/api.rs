@@ -14,7 +14,10 @@ use server::{
         request::{
             case::*,
             file::{CreateModuleRequest, DeleteModuleRequest, QueryModuleParam},
-            user::{DeleteUserRequest, LoginRequest, UpdateUserStatusRequest},
+            user::{
+                AcceptInvitationRequest, DeleteUserRequest, LoginRequest,
+                MfaEnrollChallengeRequest, MfaLoginRequest, UpdateUserStatusRequest,
+            },
             *,
         },
         response::{case::FunctionalCaseResponse, user::*, *},
@@ -76,10 +79,53 @@ impl Api {
         let resp = unwrap!(resp);
         match resp {
             LoginResponse::Token(token) => Ok(token),
-            LoginResponse::Code { .. } => Err(anyhow::anyhow!("Get token failed...")),
+            _ => Err(anyhow::anyhow!("Get token failed...")),
         }
     }
 
+    #[logfn(Info)]
+    pub async fn login_mfa(
+        &self,
+        req: &MfaLoginRequest,
+    ) -> anyhow::Result<(StatusCode, AppResponseResult<LoginResponse>)> {
+        let resp = HTTP
+            .post_request(&format!("{}/auth/login/mfa", self.addr), req)
+            .await?;
+        Ok((resp.status(), resp.json().await?))
+    }
+
+    #[logfn(Info)]
+    pub async fn login_mfa_enroll(
+        &self,
+        req: &MfaEnrollChallengeRequest,
+    ) -> anyhow::Result<(StatusCode, AppResponseResult<MfaEnrollResponse>)> {
+        let resp = HTTP
+            .post_request(&format!("{}/auth/login/mfa/enroll", self.addr), req)
+            .await?;
+        Ok((resp.status(), resp.json().await?))
+    }
+
+    pub async fn accept_invitation(
+        &self,
+        req: &AcceptInvitationRequest,
+    ) -> anyhow::Result<(StatusCode, AppResponseResult<LoginResponse>)> {
+        let resp = HTTP
+            .post_request(&format!("{}/auth/invitation/accept", self.addr), req)
+            .await?;
+        Ok((resp.status(), resp.json().await?))
+    }
+
+    #[logfn(Info)]
+    pub async fn refresh_token(
+        &self,
+        req: &RefreshTokenRequest,
+    ) -> anyhow::Result<(StatusCode, AppResponseResult<LoginResponse>)> {
+        let resp = HTTP
+            .post_request(&format!("{}/auth/token/refresh", self.addr), req)
+            .await?;
+        Ok((resp.status(), resp.json().await?))
+    }
+
     #[logfn(Info)]
     pub async fn logout(&self, token: &str) -> anyhow::Result<(StatusCode, AppResponseResult)> {
         let resp = HTTP
@@ -90,6 +136,97 @@ impl Api {
         Ok((resp.status(), resp.json().await?))
     }
 
+    #[logfn(Info)]
+    pub async fn list_sessions(
+        &self,
+        token: &str,
+    ) -> anyhow::Result<(StatusCode, AppResponseResult<ListSessionResponse>)> {
+        let resp = HTTP
+            .get(format!("{}/user/sessions", self.addr))
+            .header(reqwest::header::AUTHORIZATION, format!("Bearer {token}"))
+            .send()
+            .await?;
+        Ok((resp.status(), resp.json().await?))
+    }
+
+    #[logfn(Info)]
+    pub async fn revoke_session(
+        &self,
+        token: &str,
+        sid: &uuid::Uuid,
+    ) -> anyhow::Result<(StatusCode, AppResponseResult)> {
+        let resp = HTTP
+            .delete(format!("{}/user/sessions/{sid}", self.addr))
+            .header(reqwest::header::AUTHORIZATION, format!("Bearer {token}"))
+            .send()
+            .await?;
+        Ok((resp.status(), resp.json().await?))
+    }
+
+    #[logfn(Info)]
+    pub async fn get_project_info(
+        &self,
+        token: &str,
+        project_id: i32,
+    ) -> anyhow::Result<(StatusCode, AppResponseResult<ProjectInfoResponse>)> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.append(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {token}").parse()?,
+        );
+        headers.append(PROJECT_ID, project_id.to_string().parse()?);
+        let resp = HTTP
+            .get(format!("{}/management/project/{project_id}", self.addr))
+            .headers(headers)
+            .send()
+            .await?;
+        Ok((resp.status(), resp.json().await?))
+    }
+
+    #[logfn(Info)]
+    pub async fn add_project_member(
+        &self,
+        token: &str,
+        project_id: i32,
+        req: &AddMemberRequest,
+    ) -> anyhow::Result<(StatusCode, AppResponseResult<MessageResponse>)> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.append(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {token}").parse()?,
+        );
+        headers.append(PROJECT_ID, project_id.to_string().parse()?);
+        let resp = HTTP
+            .post(format!("{}/management/project/member", self.addr))
+            .headers(headers)
+            .json(req)
+            .send()
+            .await?;
+        Ok((resp.status(), resp.json().await?))
+    }
+
+    #[logfn(Info)]
+    pub async fn update_project_member(
+        &self,
+        token: &str,
+        project_id: i32,
+        req: &UpdateMemberRequest,
+    ) -> anyhow::Result<(StatusCode, AppResponseResult<MessageResponse>)> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.append(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {token}").parse()?,
+        );
+        headers.append(PROJECT_ID, project_id.to_string().parse()?);
+        let resp = HTTP
+            .put(format!("{}/management/project/member", self.addr))
+            .headers(headers)
+            .json(req)
+            .send()
+            .await?;
+        Ok((resp.status(), resp.json().await?))
+    }
+
     #[logfn(Info)]
     pub async fn create_role(
         &self,
@@ -571,4 +708,73 @@ impl Api {
 
         Ok((resp.status(), resp.json().await?))
     }
+
+    #[logfn(Info)]
+    pub async fn create_requirement(
+        &self,
+        token: &str,
+        project_id: i32,
+        req: &CreateRequirementRequest,
+    ) -> anyhow::Result<(StatusCode, AppResponseResult<CreateEntityResponse>)> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.append(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {token}").parse()?,
+        );
+        headers.append(PROJECT_ID, project_id.to_string().parse()?);
+        let resp = HTTP
+            .post(format!("{}/management/requirement", self.addr))
+            .headers(headers)
+            .json(req)
+            .send()
+            .await?;
+
+        Ok((resp.status(), resp.json().await?))
+    }
+
+    #[logfn(Info)]
+    pub async fn link_requirement_cases(
+        &self,
+        token: &str,
+        project_id: i32,
+        req: &RequirementCaseRequest,
+    ) -> anyhow::Result<(StatusCode, AppResponseResult)> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.append(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {token}").parse()?,
+        );
+        headers.append(PROJECT_ID, project_id.to_string().parse()?);
+        let resp = HTTP
+            .post(format!("{}/management/requirement/case", self.addr))
+            .headers(headers)
+            .json(req)
+            .send()
+            .await?;
+
+        Ok((resp.status(), resp.json().await?))
+    }
+
+    #[logfn(Info)]
+    pub async fn get_requirement_info(
+        &self,
+        token: &str,
+        project_id: i32,
+        params: &RequirementInfoParam,
+    ) -> anyhow::Result<(StatusCode, AppResponseResult<RequirementInfoResponse>)> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.append(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {token}").parse()?,
+        );
+        headers.append(PROJECT_ID, project_id.to_string().parse()?);
+        let resp = HTTP
+            .get(format!("{}/management/case/info/requirement", self.addr))
+            .headers(headers)
+            .query(params)
+            .send()
+            .await?;
+
+        Ok((resp.status(), resp.json().await?))
+    }
 }
@@ -114,6 +114,22 @@ impl TestUser {
         Ok(users)
     }
 
+    /* a user of its own role, for tests that change the account or role settings */
+    #[allow(dead_code)]
+    pub async fn create_user_with_permission(
+        pool: &db::Pool,
+        permission_list: Vec<i32>,
+    ) -> AppResult<TestUser> {
+        let mut client = pool.get().await?;
+        let transaction = client.transaction().await?;
+        let user_dao = UserDao::new(&transaction);
+        let perm_dao = PermissionDao::new(&transaction);
+        let role_id = create_role(permission_list, &user_dao, &perm_dao).await?;
+        let user = create_user_with_role(role_id, &user_dao, &perm_dao).await?;
+        transaction.commit().await?;
+        Ok(user)
+    }
+
     pub async fn disable_user(pool: &db::Pool, id: i32) -> AppResult {
         let client = pool.get().await?;
         let user_dao = UserDao::new(&client);
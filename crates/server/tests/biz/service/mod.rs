@@ -1 +1,20 @@
+mod test_api_permission;
+mod test_api_token;
+mod test_client_ip;
+mod test_issue_tracker;
+mod test_job_queue;
+mod test_lockout;
+mod test_mfa;
+mod test_notification;
+mod test_page_token;
+mod test_password_policy;
+mod test_plan_schedule;
+mod test_project_module;
+mod test_public_url;
+mod test_role_permission;
+mod test_selector_health;
+mod test_scheduler;
 mod test_script_gen;
+mod test_signing_key;
+mod test_sso;
+mod test_user_csv;
@@ -0,0 +1,29 @@
+use std::collections::HashSet;
+
+use server::{api, constant::MANAGEMENT_FUNCTIONAL_CASE, entity::permission::Access};
+
+#[test]
+pub fn test_access_by_route_template() {
+    assert_eq!(
+        api::access("GET", "/management/case/functional-case/{case_id}"),
+        Some(Access::read(MANAGEMENT_FUNCTIONAL_CASE))
+    );
+    assert_eq!(api::access("POST", "/auth/login"), Some(Access::Open));
+    assert_eq!(
+        api::access("GET", "/management/case/functional-case/123"),
+        None
+    );
+}
+
+#[test]
+pub fn test_permissions_declared_once() {
+    let mut routes = HashSet::new();
+    for permission in api::PERMISSIONS.iter() {
+        assert!(
+            routes.insert((permission.method, permission.path.as_str())),
+            "duplicate permission for {} {}",
+            permission.method,
+            permission.path
+        );
+    }
+}
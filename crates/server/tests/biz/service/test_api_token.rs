@@ -0,0 +1,32 @@
+use chrono::{TimeZone, Utc};
+use server::{
+    entity::api_token::ApiTokenScope, service::api_token::hash_token, utils::claim::UserClaims,
+};
+use uuid::Uuid;
+
+#[test]
+pub fn test_hash_token() {
+    let hash = hash_token("mtk_0123456789abcdef");
+    assert_eq!(hash.len(), 64);
+    assert_eq!(hash, hash_token("mtk_0123456789abcdef"));
+    assert_ne!(hash, hash_token("mtk_0123456789abcdee"));
+}
+
+#[test]
+pub fn test_claims_for_api_token() {
+    let expires_at = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+    let mut scope = ApiTokenScope {
+        id: 1,
+        uid: Uuid::new_v4(),
+        project_id: Some(1),
+        permissions: vec![1, 2],
+        expires_at: Some(expires_at),
+    };
+    let claims = UserClaims::for_api_token(&scope);
+    assert_eq!(claims.uid, scope.uid);
+    assert_eq!(claims.sid, Uuid::nil());
+    assert_eq!(claims.exp, expires_at.timestamp());
+
+    scope.expires_at = None;
+    assert_eq!(UserClaims::for_api_token(&scope).exp, i64::MAX);
+}
@@ -0,0 +1,40 @@
+use axum::http::{HeaderMap, HeaderValue};
+use server::utils::header::forwarded_client_ip;
+
+fn forwarded(values: &[&str]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for value in values {
+        headers.append("x-forwarded-for", HeaderValue::from_str(value).unwrap());
+    }
+    headers
+}
+
+#[test]
+pub fn test_forwarded_for_is_ignored_without_trusted_proxies() {
+    let headers = forwarded(&["203.0.113.7"]);
+    assert_eq!(forwarded_client_ip(&headers, 0), None);
+}
+
+#[test]
+pub fn test_client_can_not_pick_its_own_ip() {
+    /* the client sent `1.1.1.1`, the proxy appended the address it was reached from */
+    let headers = forwarded(&["1.1.1.1, 203.0.113.7"]);
+    assert_eq!(
+        forwarded_client_ip(&headers, 1).as_deref(),
+        Some("203.0.113.7")
+    );
+
+    /* a second proxy in front, possibly sending the list as another header line */
+    let headers = forwarded(&["1.1.1.1, 203.0.113.7", "10.0.0.2"]);
+    assert_eq!(
+        forwarded_client_ip(&headers, 2).as_deref(),
+        Some("203.0.113.7")
+    );
+}
+
+#[test]
+pub fn test_short_forwarded_chain_falls_back_to_peer() {
+    let headers = forwarded(&["203.0.113.7"]);
+    assert_eq!(forwarded_client_ip(&headers, 2), None);
+    assert_eq!(forwarded_client_ip(&HeaderMap::new(), 1), None);
+}
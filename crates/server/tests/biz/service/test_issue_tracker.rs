@@ -0,0 +1,137 @@
+use server::{
+    configure::tracker::ConfigTrackerItem,
+    entity::case::IssueSource,
+    errors::{AppError, ResourceType},
+    service::issue::{GitHubTracker, GitLabTracker, IssueTracker, JiraTracker, NewIssue},
+    utils::http::HttpClient,
+};
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn tracker_config(server: &MockServer, project: &str) -> ConfigTrackerItem {
+    ConfigTrackerItem {
+        base_url: server.uri(),
+        username: "bot@example.com".to_string(),
+        token: "test_token".to_string(),
+        project: project.to_string(),
+        issue_type: None,
+    }
+}
+
+#[tokio::test]
+pub async fn test_success_gitlab_get_issue() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v4/projects/42/issues/7"))
+        .and(header("PRIVATE-TOKEN", "test_token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "iid": 7,
+            "title": "login button not clickable",
+            "state": "opened",
+            "web_url": "https://gitlab.example.com/group/project/-/issues/7"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tracker = GitLabTracker::new(HttpClient::new(), tracker_config(&server, "42"));
+    let issue = tracker.get_issue("7").await.unwrap();
+
+    assert_eq!(tracker.source(), IssueSource::GitLab);
+    assert_eq!(issue.issue_id, "7");
+    assert_eq!(issue.title, "login button not clickable");
+    assert_eq!(issue.status, "opened");
+}
+
+#[tokio::test]
+pub async fn test_github_issue_not_found() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repos/owner/repo/issues/404"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let tracker = GitHubTracker::new(HttpClient::new(), tracker_config(&server, "owner/repo"));
+    let err = tracker.get_issue("404").await.unwrap_err();
+
+    assert!(
+        matches!(err, AppError::NotFoundError(ref r) if r.resource_type == ResourceType::Issue),
+        "error: {err:?}"
+    );
+}
+
+#[tokio::test]
+pub async fn test_success_jira_create_issue() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/api/2/issue"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "10001",
+            "key": "TEST-1"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/issue/TEST-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "key": "TEST-1",
+            "fields": {
+                "summary": "[login] 用例执行失败",
+                "status": { "name": "To Do" }
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tracker = JiraTracker::new(HttpClient::new(), tracker_config(&server, "TEST"));
+    let issue = tracker
+        .create_issue(&NewIssue {
+            title: "[login] 用例执行失败".to_string(),
+            description: "execute record: 1".to_string(),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(issue.issue_id, "TEST-1");
+    assert_eq!(issue.status, "To Do");
+    assert_eq!(issue.uri, format!("{}/browse/TEST-1", server.uri()));
+}
+
+#[tokio::test]
+pub async fn test_issue_id_outside_tracker_format_is_rejected() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let jira = JiraTracker::new(HttpClient::new(), tracker_config(&server, "TEST"));
+    let gitlab = GitLabTracker::new(HttpClient::new(), tracker_config(&server, "42"));
+    let github = GitHubTracker::new(HttpClient::new(), tracker_config(&server, "owner/repo"));
+
+    for issue_id in ["", "../../users", "TEST-1?expand=all", "TEST-1/comment"] {
+        let err = jira.get_issue(issue_id).await.unwrap_err();
+        assert!(
+            matches!(err, AppError::BadRequestError(_)),
+            "error: {err:?}"
+        );
+    }
+    for issue_id in ["", "7/notes", "../../../users", "TEST-1", "7%2F"] {
+        let err = gitlab.get_issue(issue_id).await.unwrap_err();
+        assert!(
+            matches!(err, AppError::BadRequestError(_)),
+            "error: {err:?}"
+        );
+        let err = github.get_issue(issue_id).await.unwrap_err();
+        assert!(
+            matches!(err, AppError::BadRequestError(_)),
+            "error: {err:?}"
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use std::time::Duration;
+
+use server::{constant::JOB_MAX_BACKOFF, service::job::backoff};
+
+#[test]
+pub fn test_job_backoff_doubles_per_attempt() {
+    assert_eq!(backoff(10, 1), Duration::from_secs(10));
+    assert_eq!(backoff(10, 2), Duration::from_secs(20));
+    assert_eq!(backoff(10, 4), Duration::from_secs(80));
+}
+
+#[test]
+pub fn test_job_backoff_is_capped() {
+    assert_eq!(backoff(10, 30), Duration::from_secs(JOB_MAX_BACKOFF));
+    assert_eq!(backoff(u64::MAX, 2), Duration::from_secs(JOB_MAX_BACKOFF));
+}
@@ -0,0 +1,20 @@
+use server::configure::lockout::ConfigLockout;
+
+#[test]
+pub fn test_backoff_grows_exponentially() {
+    let config = ConfigLockout::default();
+    assert_eq!(config.backoff(0), 0);
+    assert_eq!(config.backoff(1), 0);
+    assert_eq!(config.backoff(2), 1);
+    assert_eq!(config.backoff(3), 2);
+    assert_eq!(config.backoff(4), 4);
+}
+
+#[test]
+pub fn test_backoff_is_capped_by_lockout() {
+    let config = ConfigLockout {
+        lockout_secs: 60,
+        ..ConfigLockout::default()
+    };
+    assert_eq!(config.backoff(100), 60);
+}
@@ -0,0 +1,8 @@
+use server::service::mfa::normalize_recovery_code;
+
+#[test]
+pub fn test_recovery_code_ignores_case_and_separators() {
+    assert_eq!(normalize_recovery_code("abcde-23456"), "abcde23456");
+    assert_eq!(normalize_recovery_code(" ABCDE 23456 "), "abcde23456");
+    assert_eq!(normalize_recovery_code("abcde23456"), "abcde23456");
+}
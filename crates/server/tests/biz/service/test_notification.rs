@@ -0,0 +1,192 @@
+use std::sync::Arc;
+
+use hmac::{Hmac, Mac};
+use server::{
+    constant::CONFIG,
+    entity::notification::{Notification, NotificationEvent, NotificationPreference},
+    service::notification::{
+        targets, DingTalkNotifier, EmailNotifier, FeishuNotifier, NotificationTarget, Notifier,
+        SlackNotifier, WebhookNotifier, EVENT_HEADER, SIGNATURE_HEADER,
+    },
+    utils::{http::HttpClient, smtp::email_client_builder},
+};
+use sha2::Sha256;
+use wiremock::{
+    matchers::{body_partial_json, header, method, path, query_param_is_missing},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn notification() -> Notification {
+    Notification {
+        event_type: NotificationEvent::PlanRunFinished,
+        title: "Plan smoke finished: PASSED".to_string(),
+        content: "Run 7: 3 of 3 scripts passed, 0 failed".to_string(),
+        link: None,
+    }
+}
+
+#[test]
+pub fn test_default_preference_notifies_by_email_only() {
+    let preference = NotificationPreference::default_for(NotificationEvent::ExecutionFailed);
+    assert_eq!(targets(&preference), vec![NotificationTarget::Email]);
+
+    let preference = NotificationPreference {
+        event_type: NotificationEvent::ExecutionFailed,
+        email: false,
+        channel_ids: vec![3, 5],
+    };
+    assert_eq!(
+        targets(&preference),
+        vec![
+            NotificationTarget::Channel(3),
+            NotificationTarget::Channel(5)
+        ]
+    );
+}
+
+#[tokio::test]
+pub async fn test_webhook_signs_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .and(header(EVENT_HEADER, "PLAN_RUN_FINISHED"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/hook", server.uri());
+    WebhookNotifier::new(HttpClient::new(), &url, Some("webhook_secret"))
+        .send(&notification())
+        .await
+        .unwrap();
+
+    let request = &server.received_requests().await.unwrap()[0];
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"webhook_secret").unwrap();
+    mac.update(&request.body);
+    let expected = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+    assert_eq!(
+        request
+            .headers
+            .get(SIGNATURE_HEADER)
+            .unwrap()
+            .to_str()
+            .unwrap(),
+        expected
+    );
+}
+
+#[tokio::test]
+pub async fn test_failed_webhook_delivery_is_retryable() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+
+    let err = WebhookNotifier::new(HttpClient::new(), &server.uri(), None)
+        .send(&notification())
+        .await
+        .unwrap_err();
+    assert!(err.response().0.is_server_error());
+}
+
+#[tokio::test]
+pub async fn test_slack_posts_text() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/services/T0/B0/x"))
+        .and(body_partial_json(serde_json::json!({
+            "text": notification().text()
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/services/T0/B0/x", server.uri());
+    SlackNotifier::new(HttpClient::new(), &url)
+        .send(&notification())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+pub async fn test_dingtalk_error_code_fails_delivery() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/robot/send"))
+        .and(body_partial_json(serde_json::json!({ "msgtype": "text" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "errcode": 310000,
+            "errmsg": "sign not match"
+        })))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/robot/send?access_token=abc", server.uri());
+    let notifier = DingTalkNotifier::new(HttpClient::new(), &url, Some("SEC000"));
+    let signed = notifier.signed_url(1700000000000).unwrap();
+    let query: Vec<(String, String)> = signed.query_pairs().into_owned().collect();
+    assert_eq!(query[0], ("access_token".to_string(), "abc".to_string()));
+    assert_eq!(
+        query[1],
+        ("timestamp".to_string(), "1700000000000".to_string())
+    );
+    assert_eq!(query[2].0, "sign");
+
+    let err = notifier.send(&notification()).await.unwrap_err();
+    assert!(err.to_string().contains("sign not match"));
+}
+
+#[tokio::test]
+pub async fn test_dingtalk_without_secret_is_not_signed() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(query_param_is_missing("sign"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "errcode": 0,
+            "errmsg": "ok"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    DingTalkNotifier::new(HttpClient::new(), &server.uri(), None)
+        .send(&notification())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+pub async fn test_feishu_signs_body() {
+    let notifier = FeishuNotifier::new(HttpClient::new(), "http://localhost", Some("SEC000"));
+    let body = notifier.body(&notification(), 1700000000);
+    assert_eq!(body["timestamp"], "1700000000");
+    assert_eq!(body["msg_type"], "text");
+    assert!(!body["sign"].as_str().unwrap().is_empty());
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({ "msg_type": "text" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "code": 0,
+            "msg": "success"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    FeishuNotifier::new(HttpClient::new(), &server.uri(), None)
+        .send(&notification())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+pub async fn test_email_notification_sent_to_local_smtp() {
+    let client = Arc::new(email_client_builder(&CONFIG.smtp));
+    EmailNotifier::new(client, "tester", "tester@example.com")
+        .send(&notification())
+        .await
+        .unwrap();
+}
@@ -0,0 +1,48 @@
+use chrono::Utc;
+use server::{
+    constant::{PAGE_DECODE_KEY, PAGE_ENCODE_KEY},
+    utils::claim::{PageClaims, PageFilter, PageKind},
+};
+use std::time::Duration;
+
+fn page() -> PageClaims {
+    let filter = PageFilter {
+        kind: PageKind::Plan,
+        project_id: 1,
+        module_ids: vec![1, 2],
+        deleted: false,
+    };
+    PageClaims::new(Duration::from_secs(60), filter, 10, 2, 42)
+}
+
+#[test]
+pub fn test_page_token_keeps_filter_and_cursor() {
+    let token = page().encode(&PAGE_ENCODE_KEY).unwrap();
+    let claims = PageClaims::decode(&token, &PAGE_DECODE_KEY).unwrap().claims;
+    assert_eq!(claims.filter, page().filter);
+    assert_eq!(
+        (claims.page_size, claims.page_num, claims.after),
+        (10, 2, 42)
+    );
+}
+
+#[test]
+pub fn test_expired_page_token_is_rejected() {
+    let mut claims = page();
+    claims.exp = Utc::now().timestamp() - 3600;
+    let token = claims.encode(&PAGE_ENCODE_KEY).unwrap();
+    assert!(PageClaims::decode(&token, &PAGE_DECODE_KEY).is_err());
+}
+
+#[test]
+pub fn test_page_token_with_changed_filter_is_rejected() {
+    let token = page().encode(&PAGE_ENCODE_KEY).unwrap();
+    let mut other = page();
+    other.filter.project_id = 2;
+    let other = other.encode(&PAGE_ENCODE_KEY).unwrap();
+    /* payload of the other token under the signature of this one */
+    let parts: Vec<&str> = token.split('.').collect();
+    let payload = other.split('.').nth(1).unwrap();
+    let forged = [parts[0], payload, parts[2]].join(".");
+    assert!(PageClaims::decode(&forged, &PAGE_DECODE_KEY).is_err());
+}
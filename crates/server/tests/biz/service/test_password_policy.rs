@@ -0,0 +1,33 @@
+use server::{configure::password::ConfigPassword, utils::password::check_strength};
+
+#[test]
+pub fn test_default_policy_only_checks_length() {
+    let config = ConfigPassword::default();
+    assert!(check_strength(&config, "short").is_err());
+    assert!(check_strength(&config, "longenough").is_ok());
+}
+
+#[test]
+pub fn test_configured_policy() {
+    let config = ConfigPassword {
+        min_length: 10,
+        require_uppercase: true,
+        require_lowercase: true,
+        require_digit: true,
+        require_symbol: true,
+    };
+    assert!(check_strength(&config, "Abcdefgh1!").is_ok());
+    for weak in [
+        "Abcdef1!",
+        "abcdefgh1!",
+        "ABCDEFGH1!",
+        "Abcdefghi!",
+        "Abcdefgh12",
+    ] {
+        let err = check_strength(&config, weak).unwrap_err();
+        assert!(
+            err.to_string().contains("Password Exception"),
+            "{weak}: {err}"
+        );
+    }
+}
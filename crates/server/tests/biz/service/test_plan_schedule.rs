@@ -0,0 +1,26 @@
+use chrono::{TimeZone, Utc};
+
+use server::service::plan::due_slot;
+
+#[test]
+pub fn test_due_slot_not_due_before_next_fire() {
+    let from = Utc.with_ymd_and_hms(2024, 12, 20, 2, 0, 0).unwrap();
+    let now = Utc.with_ymd_and_hms(2024, 12, 20, 23, 59, 59).unwrap();
+    assert_eq!(due_slot("0 0 2 * * *", from, now).unwrap(), None);
+}
+
+#[test]
+pub fn test_due_slot_collapses_missed_slots() {
+    let from = Utc.with_ymd_and_hms(2024, 12, 20, 2, 0, 0).unwrap();
+    let now = Utc.with_ymd_and_hms(2024, 12, 23, 3, 0, 0).unwrap();
+    assert_eq!(
+        due_slot("0 0 2 * * *", from, now).unwrap(),
+        Some(Utc.with_ymd_and_hms(2024, 12, 23, 2, 0, 0).unwrap())
+    );
+}
+
+#[test]
+pub fn test_due_slot_rejects_invalid_cron() {
+    let now = Utc::now();
+    assert!(due_slot("every night", now, now).is_err());
+}
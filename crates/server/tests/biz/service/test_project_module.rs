@@ -0,0 +1,34 @@
+use server::{
+    api,
+    constant::{MANAGEMENT_PROJECT, SYSTEM_USER},
+    entity::{permission::Access, project::ProjectModule},
+};
+use strum::IntoEnumIterator;
+
+#[test]
+pub fn test_default_module_setting() {
+    let modules = ProjectModule::iter().collect::<Vec<_>>();
+    assert_eq!(
+        serde_json::to_string(&modules).unwrap(),
+        r#"["bugManagement","caseManagement","apiTest","testPlan"]"#
+    );
+    assert_eq!(ProjectModule::ApiTest.to_string(), "apiTest");
+}
+
+#[test]
+pub fn test_management_routes_belong_to_module() {
+    for permission in api::PERMISSIONS.iter() {
+        let Access::Require { module, .. } = permission.access else {
+            continue;
+        };
+        let switchable = ProjectModule::of(module).is_some();
+        let always_on = module == MANAGEMENT_PROJECT || module.starts_with("SYSTEM:");
+        assert!(
+            switchable != always_on,
+            "{} {} is neither switchable nor always on",
+            permission.method,
+            permission.path
+        );
+    }
+    assert_eq!(ProjectModule::of(SYSTEM_USER), None);
+}
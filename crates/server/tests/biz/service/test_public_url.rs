@@ -0,0 +1,63 @@
+use std::net::IpAddr;
+
+use server::{
+    constant::CONFIG,
+    utils::http::{build_public_client, check_public_url, is_public_ip},
+};
+use wiremock::{matchers::any, Mock, MockServer, ResponseTemplate};
+
+#[test]
+pub fn test_internal_addresses_are_not_public() {
+    for ip in [
+        "127.0.0.1",
+        "10.1.2.3",
+        "172.16.0.1",
+        "192.168.1.1",
+        "169.254.169.254",
+        "100.64.0.1",
+        "0.0.0.0",
+        "::1",
+        "fd00:ec2::254",
+        "fe80::1",
+        "::ffff:10.0.0.1",
+    ] {
+        assert!(!is_public_ip(ip.parse::<IpAddr>().unwrap()), "{ip}");
+    }
+    for ip in ["93.184.216.34", "2606:2800:220:1::1"] {
+        assert!(is_public_ip(ip.parse::<IpAddr>().unwrap()), "{ip}");
+    }
+}
+
+#[tokio::test]
+pub async fn test_check_public_url_rejects_internal_hosts() {
+    for url in [
+        "file:///etc/passwd",
+        "gopher://93.184.216.34/",
+        "http://127.0.0.1:8080/hook",
+        "http://localhost/hook",
+        "http://[::1]/hook",
+        "http://169.254.169.254/latest/meta-data/",
+        "http://192.168.1.10/hook",
+    ] {
+        assert!(check_public_url(url).await.is_err(), "{url}");
+    }
+    assert!(check_public_url("https://93.184.216.34/hook").await.is_ok());
+}
+
+#[tokio::test]
+pub async fn test_public_client_does_not_connect_to_loopback_names() {
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+    let url = format!("http://localhost:{}/hook", server.address().port());
+    let err = build_public_client(&CONFIG)
+        .unwrap()
+        .post(url)
+        .send()
+        .await
+        .unwrap_err();
+    assert!(err.is_connect(), "{err:?}");
+}
@@ -0,0 +1,20 @@
+use server::{dto::response::UpdateRoleResponse, service::permission::diff_permissions};
+
+#[test]
+pub fn test_diff_permissions() {
+    let diff = diff_permissions(&[1, 2, 3], &[3, 4, 2, 5]);
+    assert_eq!(
+        diff,
+        UpdateRoleResponse {
+            added: vec![4, 5],
+            removed: vec![1],
+        }
+    );
+}
+
+#[test]
+pub fn test_diff_permissions_unchanged() {
+    let diff = diff_permissions(&[2, 1], &[1, 2, 2]);
+    assert!(diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+}
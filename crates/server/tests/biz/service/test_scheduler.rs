@@ -0,0 +1,54 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use server::service::schedule::{AsyncScheduler, Job, JobScheduler};
+
+fn counting_job(config: &str, counter: Arc<AtomicUsize>) -> Job {
+    let config: toml::Table = config.parse().unwrap();
+    Job::with_config_async(&config, move || {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    })
+}
+
+#[tokio::test]
+pub async fn test_scheduler_respects_remaining_ticks() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let mut scheduler =
+        JobScheduler::new().with_shutdown(tokio::time::sleep(Duration::from_millis(3500)));
+    scheduler.add_job(counting_job(
+        r#"
+        cron = "* * * * * *"
+        immediate = true
+        ticks = 2
+        "#,
+        counter.clone(),
+    ));
+    scheduler.run().await.unwrap();
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+pub async fn test_scheduler_skips_disabled_job() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let mut scheduler =
+        JobScheduler::new().with_shutdown(tokio::time::sleep(Duration::from_millis(1500)));
+    scheduler.add_job(counting_job(
+        r#"
+        cron = "* * * * * *"
+        immediate = true
+        disabled = true
+        "#,
+        counter.clone(),
+    ));
+    scheduler.run().await.unwrap();
+    assert_eq!(counter.load(Ordering::SeqCst), 0);
+}
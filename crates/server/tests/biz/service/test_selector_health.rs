@@ -0,0 +1,37 @@
+use server::{entity::element::SelectorStatus, service::engine::check_selectors};
+
+const PAGE: &str = r#"
+<html>
+  <body>
+    <form id="login">
+      <input class="field" name="username" />
+      <input class="field" name="password" type="password" />
+      <button id="submit">Sign in</button>
+    </form>
+  </body>
+</html>
+"#;
+
+#[test]
+pub fn test_check_selectors_status() {
+    let selectors = vec![
+        (1, "#submit".to_string()),
+        (2, "#forgot-password".to_string()),
+        (3, "input.field".to_string()),
+        (4, "button[".to_string()),
+    ];
+    let checks = check_selectors(PAGE, &selectors);
+    let statuses = checks
+        .iter()
+        .map(|c| (c.element_id, c.status, c.match_count))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        statuses,
+        vec![
+            (1, SelectorStatus::Found, 1),
+            (2, SelectorStatus::Missing, 0),
+            (3, SelectorStatus::Ambiguous, 2),
+            (4, SelectorStatus::Invalid, 0),
+        ]
+    );
+}
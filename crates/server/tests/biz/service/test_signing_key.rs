@@ -0,0 +1,69 @@
+use chrono::Utc;
+use jsonwebtoken::decode_header;
+use server::{
+    constant::CONFIG,
+    utils::{
+        claim::UserClaims,
+        jwk::{KeySet, KeyStore, SigningKey},
+    },
+};
+use std::time::Duration;
+use uuid::Uuid;
+
+fn key(kid: &str, private: bool, expired: bool) -> SigningKey {
+    let (private_pem, public_pem) = if kid == "old" {
+        (
+            CONFIG.jwt.read_private_access_key().unwrap(),
+            CONFIG.jwt.read_public_access_key().unwrap(),
+        )
+    } else {
+        (
+            CONFIG.jwt.read_private_refresh_key().unwrap(),
+            CONFIG.jwt.read_public_refresh_key().unwrap(),
+        )
+    };
+    SigningKey::from_pem(
+        kid,
+        private.then_some(private_pem.as_str()),
+        &public_pem,
+        expired.then(Utc::now),
+    )
+    .unwrap()
+}
+
+#[test]
+pub fn test_token_signed_by_retired_key_verifies_until_expiry() {
+    let store = KeyStore::new(KeySet::new("old", vec![key("old", true, false)]).unwrap());
+    let claims = UserClaims::new(Duration::from_secs(60), Uuid::new_v4(), Uuid::new_v4());
+    let token = claims.encode(&store).unwrap();
+    assert_eq!(decode_header(&token).unwrap().kid.as_deref(), Some("old"));
+
+    store.replace(
+        KeySet::new(
+            "new",
+            vec![key("new", true, false), key("old", false, false)],
+        )
+        .unwrap(),
+    );
+    assert_eq!(UserClaims::decode(&token, &store).unwrap().claims, claims);
+    let rotated = claims.encode(&store).unwrap();
+    assert_eq!(decode_header(&rotated).unwrap().kid.as_deref(), Some("new"));
+    assert_eq!(store.jwks().keys.len(), 2);
+
+    store.replace(
+        KeySet::new(
+            "new",
+            vec![key("new", true, false), key("old", false, true)],
+        )
+        .unwrap(),
+    );
+    assert!(UserClaims::decode(&token, &store).is_err());
+    assert!(UserClaims::decode(&rotated, &store).is_ok());
+    assert_eq!(store.jwks().keys.len(), 1);
+}
+
+#[test]
+pub fn test_primary_key_requires_private_key() {
+    assert!(KeySet::new("old", vec![key("old", false, false)]).is_err());
+    assert!(KeySet::new("new", vec![key("old", true, false)]).is_err());
+}
@@ -0,0 +1,52 @@
+use server::{
+    configure::sso::{ConfigGroupMapping, ConfigSSO},
+    service::sso::map_groups,
+};
+
+fn mapping(group: &str, project_id: i32, role: &str) -> ConfigGroupMapping {
+    ConfigGroupMapping {
+        group: group.to_string(),
+        project_id,
+        role: role.to_string(),
+    }
+}
+
+#[test]
+pub fn test_map_groups_first_match_per_project() {
+    let mappings = vec![
+        mapping("qa-lead", 1, "admin"),
+        mapping("qa", 1, "member"),
+        mapping("qa", 2, "member"),
+        mapping("dev", 3, "member"),
+    ];
+    let groups = vec!["qa".to_string(), "qa-lead".to_string()];
+    assert_eq!(
+        map_groups(&mappings, &groups),
+        vec![(1, "admin".to_string()), (2, "member".to_string())]
+    );
+    assert!(map_groups(&mappings, &[]).is_empty());
+}
+
+#[test]
+pub fn test_sso_config_defaults() {
+    let config: ConfigSSO = toml::from_str(
+        r#"
+        [ldap]
+        url = "ldap://localhost:389"
+        bind_dn = "cn=admin,dc=example,dc=com"
+        bind_password = "admin"
+        base_dn = "ou=people,dc=example,dc=com"
+
+        [[group_mapping]]
+        group = "cn=qa,ou=groups,dc=example,dc=com"
+        project_id = 1
+        role = "admin"
+        "#,
+    )
+    .unwrap();
+    let ldap = config.ldap.unwrap();
+    assert_eq!(ldap.user_filter, "(uid={username})");
+    assert_eq!(ldap.group_attribute, "memberOf");
+    assert!(config.oidc.is_none());
+    assert_eq!(config.group_mapping.len(), 1);
+}
@@ -0,0 +1,31 @@
+use server::{errors::AppError, service::user::parse_user_csv};
+
+#[test]
+pub fn test_parse_user_csv_skips_header_and_blank_lines() {
+    let content = "username,email\n\nalice, alice@example.com\n\"bob\",\"bob@example.com\"\n";
+    let users = parse_user_csv(content).unwrap();
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[0].username, "alice");
+    assert_eq!(users[0].email, "alice@example.com");
+    assert_eq!(users[1].username, "bob");
+    assert_eq!(users[1].email, "bob@example.com");
+}
+
+#[test]
+pub fn test_parse_user_csv_reports_every_malformed_line() {
+    let content = "alice,alice@example.com\nbob\ncarol,carol@example.com,extra\n";
+    match parse_user_csv(content) {
+        Err(AppError::InvalidInputError(report)) => assert_eq!(report.iter().count(), 2),
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+pub fn test_parse_user_csv_keeps_quoted_commas() {
+    let content = "\"smith, john\",john@example.com\r\n\"o\"\"neil\",oneil@example.com\n";
+    let users = parse_user_csv(content).unwrap();
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[0].username, "smith, john");
+    assert_eq!(users[0].email, "john@example.com");
+    assert_eq!(users[1].username, "o\"neil");
+}
@@ -38,6 +38,8 @@ cy.get('{{ raw.selector }}').click()
             body: {{ value }},
         {% endif %}
         {% endfor %} })
+{% elif raw.template %}
+    {{ raw.template }}
     {% endif %}
     {% if raw.expected %}
     {% for key, value in raw.expected %}